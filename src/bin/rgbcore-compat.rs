@@ -0,0 +1,39 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+
+use rgbcore::compat::{FixtureSet, COMPAT_FIXTURES_DIR};
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let root = args
+        .next()
+        .unwrap_or_else(|| COMPAT_FIXTURES_DIR.to_owned());
+    let set = match args.next() {
+        Some(version) => FixtureSet::new(root, version),
+        None => FixtureSet::current(root),
+    };
+    set.write_all()
+        .expect("unable to write compatibility fixtures");
+    println!("Fixtures for version {} written to {}", set.version(), set.dir().display());
+}
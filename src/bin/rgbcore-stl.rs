@@ -27,6 +27,7 @@ use aluvm::stl::aluvm_stl;
 use bp::stl::bp_core_stl;
 use commit_verify::stl::commit_verify_stl;
 use commit_verify::CommitmentLayout;
+use rgbcore::layout::{ConsensusLayout, LAYOUT_SNAPSHOT};
 use rgbcore::stl::bp_tx_stl;
 use rgbcore::{Schema, Transition, TransitionBundle};
 use strict_types::stl::{std_stl, strict_types_stl};
@@ -98,6 +99,9 @@ fn main() {
 
     let dir = dir.unwrap_or_else(|| ".".to_owned());
 
+    fs::write(format!("{dir}/{LAYOUT_SNAPSHOT}"), ConsensusLayout::current().to_string())
+        .expect("unable to write to the file");
+
     let mut file = fs::File::create(format!("{dir}/Schema.vesper")).unwrap();
     writeln!(
         file,
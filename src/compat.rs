@@ -0,0 +1,696 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backward-compatibility checks of the consensus data encoding against golden
+//! fixtures.
+//!
+//! Fixtures are organized into per-release directories (named after the crate
+//! version which has produced them) under a common root. Each fixture consists
+//! of a strict-encoded consensus object (`<Name>.sto` file) and its consensus
+//! id in textual form (`<Name>.id` file). Checking a fixture set means decoding
+//! each of the objects with the current version of the library and ensuring
+//! that neither its id nor its serialization have changed.
+//!
+//! Fixtures of a release are generated once, by the `rgbcore-compat` binary
+//! built from that release tag, and are never overwritten afterwards.
+//!
+//! Downstream crates may pin the commitment ids of their own sample objects
+//! with [`CommitIdVector`]s, using [`commit_id_vector!`](crate::commit_id_vector)
+//! macro.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+use aluvm::library::{LibId, LibSite};
+use amplify::confinement::{self, Confined, SmallBlob};
+use amplify::num::u24;
+use amplify::hex::ToHex;
+use amplify::ByteArray;
+use bp::dbc::Method;
+use bp::Txid;
+use commit_verify::{CommitId, CommitmentId, Conceal};
+use strict_encoding::{
+    DeserializeError, SerializeError, StrictDeserialize, StrictDumb, StrictSerialize,
+};
+use strict_types::SemId;
+
+use crate::{
+    AltLayer1, AltLayer1Set, AssetTag, AssetTags, Assign, AssignmentType, AttachId, BlindingFactor,
+    DataState, Extension, ExtensionSchema, ExtensionType, FungibleType, Genesis, GenesisSchema,
+    GenesisSeal, GlobalStateSchema, GlobalStateType, GraphSeal, Identity, Input, InputMap, Inputs,
+    MediaType, MetaType, MetaValue, Occurrences, Operation, Opout, OwnedStateSchema, Redeemed,
+    RevealedAttach, RevealedData, RevealedValue, Schema, Transition, TransitionBundle,
+    TransitionSchema, TransitionType, TypedAssigns, Valencies, ValencyType, Vin, VoidState, XChain,
+};
+
+/// Name of the directory inside the crate root containing golden fixtures.
+pub const COMPAT_FIXTURES_DIR: &str = "compat";
+
+/// Maximal size of a single fixture file.
+pub const COMPAT_FIXTURE_MAX_LEN: usize = 0xFF_FFFF;
+
+/// Consensus data type which can be stored as a golden fixture.
+pub trait CompatFixture: StrictSerialize + StrictDeserialize + StrictDumb {
+    /// Name of the fixture, used as a base file name.
+    const FIXTURE_NAME: &'static str;
+
+    /// Textual representation of the consensus id for the object.
+    fn fixture_id(&self) -> String;
+}
+
+impl CompatFixture for Schema {
+    const FIXTURE_NAME: &'static str = "Schema";
+    fn fixture_id(&self) -> String { self.schema_id().to_string() }
+}
+
+impl CompatFixture for Genesis {
+    const FIXTURE_NAME: &'static str = "Genesis";
+    fn fixture_id(&self) -> String { self.id().to_string() }
+}
+
+impl CompatFixture for Transition {
+    const FIXTURE_NAME: &'static str = "Transition";
+    fn fixture_id(&self) -> String { self.id().to_string() }
+}
+
+impl CompatFixture for Extension {
+    const FIXTURE_NAME: &'static str = "Extension";
+    fn fixture_id(&self) -> String { self.id().to_string() }
+}
+
+impl CompatFixture for TransitionBundle {
+    const FIXTURE_NAME: &'static str = "TransitionBundle";
+    fn fixture_id(&self) -> String { self.bundle_id().to_string() }
+}
+
+/// Errors happening during generation or verification of the golden fixtures.
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum CompatError {
+    /// I/O error accessing compatibility fixtures. Details: {0}
+    #[from]
+    Io(io::Error),
+
+    /// fixture {0} exceeds maximal allowed size.
+    #[from]
+    TooLarge(confinement::Error),
+
+    /// unable to serialize fixture. Details: {0}
+    #[from]
+    Serialize(SerializeError),
+
+    /// fixture {name} from version {version} can't be decoded. Details: {err}
+    Decode {
+        version: String,
+        name: &'static str,
+        err: DeserializeError,
+    },
+
+    /// fixture {name} from version {version} has id {found} instead of the
+    /// expected {expected}.
+    IdMismatch {
+        version: String,
+        name: &'static str,
+        expected: String,
+        found: String,
+    },
+
+    /// fixture {name} from version {version} is serialized differently by the
+    /// current library version.
+    Reencode { version: String, name: &'static str },
+
+    /// fixture {name} from version {version} already exists; golden fixtures
+    /// must never be overwritten.
+    Exists { version: String, name: &'static str },
+
+    /// sample {name} has commitment id {found} instead of the recorded
+    /// {expected}.
+    CommitIdMismatch {
+        name: &'static str,
+        expected: String,
+        found: String,
+    },
+}
+
+/// Test vector of a commitment id: strict serialization of a sample object
+/// together with the tag and the value of its commitment id.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CommitIdVector {
+    pub name: &'static str,
+    pub tag: &'static str,
+    pub id: String,
+    pub data: Vec<u8>,
+}
+
+impl CommitIdVector {
+    /// Constructs the test vector for the sample object.
+    pub fn new<T: CommitId + StrictSerialize>(
+        name: &'static str,
+        sample: &T,
+    ) -> Result<Self, CompatError>
+    where
+        T::CommitmentId: Display,
+    {
+        let data = sample.to_strict_serialized::<COMPAT_FIXTURE_MAX_LEN>()?;
+        Ok(CommitIdVector {
+            name,
+            tag: T::CommitmentId::TAG,
+            id: sample.commit_id().to_string(),
+            data: data.release(),
+        })
+    }
+
+    /// Checks that the commitment id matches the recorded one.
+    pub fn check(&self, expected: &str) -> Result<(), CompatError> {
+        if self.id != expected {
+            return Err(CompatError::CommitIdMismatch {
+                name: self.name,
+                expected: expected.to_owned(),
+                found: self.id.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Renders the vector as a text fixture: the tag, the commitment id and
+    /// the hex-encoded serialized data, each on a separate line.
+    pub fn to_fixture(&self) -> String {
+        format!("{}\n{}\n{}\n", self.tag, self.id, self.data.to_hex())
+    }
+}
+
+/// Defines a test checking that the commitment id of a sample object matches
+/// the recorded constant. The test prints the fixture of the sample (see
+/// [`CommitIdVector::to_fixture`]), such that the new vector can be recorded
+/// after an intended change of the consensus encoding.
+///
+/// ```ignore
+/// commit_id_vector!(schema_id, Samples::new().schema, "rgb:sch:...");
+/// ```
+#[macro_export]
+macro_rules! commit_id_vector {
+    ($name:ident, $sample:expr, $id:expr $(,)?) => {
+        #[test]
+        fn $name() {
+            let vector = $crate::compat::CommitIdVector::new(stringify!($name), &$sample)
+                .expect("unable to serialize the sample");
+            println!("{}", vector.to_fixture());
+            if let Err(err) = vector.check($id) {
+                panic!("{err}");
+            }
+        }
+    };
+}
+
+/// Set of golden fixtures produced by a specific release of the library.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FixtureSet {
+    version: String,
+    dir: PathBuf,
+}
+
+impl FixtureSet {
+    /// Constructs fixture set for a given release version located inside the
+    /// `root` directory. Doesn't access the file system.
+    pub fn new(root: impl AsRef<Path>, version: impl ToString) -> Self {
+        let version = version.to_string();
+        let dir = root.as_ref().join(&version);
+        FixtureSet { version, dir }
+    }
+
+    /// Constructs fixture set for the current version of the library.
+    pub fn current(root: impl AsRef<Path>) -> Self { Self::new(root, env!("CARGO_PKG_VERSION")) }
+
+    /// Lists all fixture sets present in the `root` directory, sorted by their
+    /// version name.
+    pub fn list(root: impl AsRef<Path>) -> Result<Vec<Self>, CompatError> {
+        let mut sets = vec![];
+        for entry in fs::read_dir(root.as_ref())? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let version = entry.file_name().to_string_lossy().into_owned();
+            sets.push(FixtureSet::new(root.as_ref(), version));
+        }
+        sets.sort_by(|a, b| a.version.cmp(&b.version));
+        Ok(sets)
+    }
+
+    /// Release version which has produced the fixtures.
+    pub fn version(&self) -> &str { &self.version }
+
+    /// Directory containing the fixtures.
+    pub fn dir(&self) -> &Path { &self.dir }
+
+    fn data_path(&self, name: &str) -> PathBuf { self.dir.join(format!("{name}.sto")) }
+
+    fn id_path(&self, name: &str) -> PathBuf { self.dir.join(format!("{name}.id")) }
+
+    /// Stores the object as a fixture. Fails if the fixture is already
+    /// present in the set.
+    pub fn write<T: CompatFixture>(&self, obj: &T) -> Result<(), CompatError> {
+        if self.data_path(T::FIXTURE_NAME).exists() {
+            return Err(CompatError::Exists {
+                version: self.version.clone(),
+                name: T::FIXTURE_NAME,
+            });
+        }
+        fs::create_dir_all(&self.dir)?;
+        let data = obj.to_strict_serialized::<COMPAT_FIXTURE_MAX_LEN>()?;
+        fs::write(self.data_path(T::FIXTURE_NAME), data.as_slice())?;
+        fs::write(self.id_path(T::FIXTURE_NAME), obj.fixture_id())?;
+        Ok(())
+    }
+
+    /// Loads the fixture and checks that its id and serialization match the
+    /// ones produced by the current version of the library. Returns `None` if
+    /// the fixture is absent from the set.
+    pub fn check<T: CompatFixture>(&self) -> Result<Option<T>, CompatError> {
+        let name = T::FIXTURE_NAME;
+        let data_path = self.data_path(name);
+        if !data_path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read(data_path)?;
+        let expected = fs::read_to_string(self.id_path(name))?.trim().to_owned();
+
+        let obj =
+            T::from_strict_serialized::<COMPAT_FIXTURE_MAX_LEN>(Confined::try_from(data.clone())?)
+                .map_err(|err| CompatError::Decode {
+                    version: self.version.clone(),
+                    name,
+                    err,
+                })?;
+
+        let found = obj.fixture_id();
+        if found != expected {
+            return Err(CompatError::IdMismatch {
+                version: self.version.clone(),
+                name,
+                expected,
+                found,
+            });
+        }
+        if obj
+            .to_strict_serialized::<COMPAT_FIXTURE_MAX_LEN>()?
+            .as_slice()
+            != data.as_slice()
+        {
+            return Err(CompatError::Reencode {
+                version: self.version.clone(),
+                name,
+            });
+        }
+        Ok(Some(obj))
+    }
+
+    /// Writes fixtures for all supported consensus types, using [`Samples`].
+    pub fn write_all(&self) -> Result<(), CompatError> {
+        let samples = Samples::new();
+        self.write(&samples.schema)?;
+        self.write(&samples.genesis)?;
+        self.write(&samples.transition)?;
+        self.write(&samples.extension)?;
+        self.write(&samples.bundle)?;
+        Ok(())
+    }
+
+    /// Checks fixtures for all supported consensus types, returning the number
+    /// of fixtures present in the set.
+    pub fn check_all(&self) -> Result<usize, CompatError> {
+        let count = [
+            self.check::<Schema>()?.is_some(),
+            self.check::<Genesis>()?.is_some(),
+            self.check::<Transition>()?.is_some(),
+            self.check::<Extension>()?.is_some(),
+            self.check::<TransitionBundle>()?.is_some(),
+        ]
+        .into_iter()
+        .filter(|present| *present)
+        .count();
+        Ok(count)
+    }
+}
+
+/// Consensus objects used as the fixtures of a release.
+///
+/// The objects are populated with all kinds of the metadata, global and owned
+/// state, with revealed and concealed seals on different layer1s, such that
+/// the fixtures cover the encoding of each of the consensus types. Objects are
+/// deterministic: the same library version always produces the same samples.
+#[derive(Clone, Debug)]
+pub struct Samples {
+    pub schema: Schema,
+    pub genesis: Genesis,
+    pub extension: Extension,
+    pub transition: Transition,
+    pub bundle: TransitionBundle,
+}
+
+impl Default for Samples {
+    fn default() -> Self { Self::new() }
+}
+
+impl Samples {
+    const META: MetaType = MetaType::with(1);
+    const GLOBAL: GlobalStateType = GlobalStateType::with(2000);
+    const DECLARATIVE: AssignmentType = AssignmentType::with(3000);
+    const FUNGIBLE: AssignmentType = AssignmentType::with(3001);
+    const STRUCTURED: AssignmentType = AssignmentType::with(3002);
+    const ATTACHMENT: AssignmentType = AssignmentType::with(3003);
+    const VALENCY: ValencyType = ValencyType::with(4000);
+    const EXTENSION: ExtensionType = ExtensionType::with(5);
+    const TRANSITION: TransitionType = TransitionType::with(10_000);
+
+    /// Constructs samples of all supported consensus types.
+    pub fn new() -> Self {
+        let schema = Self::schema();
+        let genesis = Self::genesis(&schema);
+        let extension = Self::extension(&genesis);
+        let transition = Self::transition(&genesis);
+        let bundle = Self::bundle(&transition);
+        Samples {
+            schema,
+            genesis,
+            extension,
+            transition,
+            bundle,
+        }
+    }
+
+    fn sem_id(no: u8) -> SemId { SemId::from_byte_array([no; 32]) }
+
+    fn blob(data: &[u8]) -> SmallBlob {
+        SmallBlob::try_from(data.to_vec()).expect("sample data are small")
+    }
+
+    fn tag() -> AssetTag { AssetTag::from([0xA7; 32]) }
+
+    fn fungible(value: u64, blinding: u8) -> RevealedValue {
+        let blinding = BlindingFactor::from_str(&format!("{blinding:02x}").repeat(32))
+            .expect("valid blinding factor");
+        RevealedValue::with_blinding(value, blinding, Self::tag())
+    }
+
+    fn genesis_seal(no: u32, liquid: bool) -> XChain<GenesisSeal> {
+        let seal = GenesisSeal::with_blinding(
+            Method::TapretFirst,
+            Txid::from_byte_array([0x11; 32]),
+            no,
+            1000 + no as u64,
+        );
+        if liquid {
+            XChain::Liquid(seal)
+        } else {
+            XChain::Bitcoin(seal)
+        }
+    }
+
+    fn graph_seal(no: u32, liquid: bool) -> XChain<GraphSeal> {
+        let seal = if no % 2 == 0 {
+            GraphSeal::with_blinding(
+                Method::OpretFirst,
+                Txid::from_byte_array([0x22; 32]),
+                no,
+                2000 + no as u64,
+            )
+        } else {
+            GraphSeal::with_blinded_vout(Method::OpretFirst, no, 2000 + no as u64)
+        };
+        if liquid {
+            XChain::Liquid(seal)
+        } else {
+            XChain::Bitcoin(seal)
+        }
+    }
+
+    fn schema() -> Schema {
+        let lib = LibSite::with(0x10, LibId::from_byte_array([0xA1; 32]));
+        let mut schema = Schema::strict_dumb();
+        schema.name = tn!("CompatFixture");
+        schema.timestamp = 1_710_000_000;
+        schema.developer = Identity::from("ssi:compat-fixture");
+        schema.meta_types = tiny_bmap! { Self::META => Self::sem_id(1) };
+        schema.global_types = tiny_bmap! {
+            Self::GLOBAL => GlobalStateSchema {
+                kind: default!(),
+                sem_id: Self::sem_id(2),
+                max_items: u24::with(10),
+            }
+        };
+        schema.owned_types = tiny_bmap! {
+            Self::DECLARATIVE => OwnedStateSchema::Declarative,
+            Self::FUNGIBLE => OwnedStateSchema::Fungible(FungibleType::Unsigned64Bit),
+            Self::STRUCTURED => OwnedStateSchema::Structured(Self::sem_id(3)),
+            Self::ATTACHMENT => OwnedStateSchema::Attachment(MediaType::Any),
+        };
+        schema.valency_types = tiny_bset! { Self::VALENCY };
+        schema.genesis = GenesisSchema {
+            metadata: tiny_bset! { Self::META },
+            globals: tiny_bmap! { Self::GLOBAL => Occurrences::Once },
+            assignments: tiny_bmap! {
+                Self::DECLARATIVE => Occurrences::NoneOrMore,
+                Self::FUNGIBLE => Occurrences::OnceOrMore,
+                Self::STRUCTURED => Occurrences::NoneOrOnce,
+                Self::ATTACHMENT => Occurrences::OnceOrUpTo(4),
+            },
+            valencies: tiny_bset! { Self::VALENCY },
+            validator: Some(lib),
+        };
+        schema.extensions = tiny_bmap! {
+            Self::EXTENSION => ExtensionSchema {
+                metadata: none!(),
+                globals: tiny_bmap! { Self::GLOBAL => Occurrences::NoneOrOnce },
+                redeems: tiny_bset! { Self::VALENCY },
+                assignments: tiny_bmap! { Self::FUNGIBLE => Occurrences::Once },
+                valencies: none!(),
+                validator: None,
+            }
+        };
+        schema.transitions = tiny_bmap! {
+            Self::TRANSITION => TransitionSchema {
+                metadata: tiny_bset! { Self::META },
+                globals: none!(),
+                inputs: tiny_bmap! { Self::FUNGIBLE => Occurrences::OnceOrMore },
+                assignments: tiny_bmap! {
+                    Self::FUNGIBLE => Occurrences::OnceOrMore,
+                    Self::DECLARATIVE => Occurrences::Exactly(2),
+                },
+                valencies: none!(),
+                validator: Some(lib),
+            }
+        };
+        schema
+    }
+
+    fn genesis(schema: &Schema) -> Genesis {
+        let mut genesis = Genesis::strict_dumb();
+        genesis.schema_id = schema.schema_id();
+        genesis.timestamp = 1_710_000_100;
+        genesis.issuer = Identity::from("ssi:compat-issuer");
+        genesis.testnet = true;
+        genesis.alt_layers1 = AltLayer1Set::from(tiny_bset! { AltLayer1::Liquid });
+        genesis.asset_tags = AssetTags::from(tiny_bmap! { Self::FUNGIBLE => Self::tag() });
+        genesis
+            .metadata
+            .add_value(Self::META, MetaValue::from(Self::blob(&[1, 2, 3])))
+            .expect("single metadata value");
+        genesis
+            .globals
+            .add_state(Self::GLOBAL, DataState::from(Self::blob(b"compat")))
+            .expect("single global state value");
+        genesis.assignments = tiny_bmap! {
+            Self::FUNGIBLE => TypedAssigns::Fungible(small_vec![
+                Assign::revealed(Self::genesis_seal(0, false), Self::fungible(1_000_000, 1)),
+                Assign::revealed(Self::genesis_seal(1, true), Self::fungible(500, 2)),
+                Assign::ConfidentialSeal {
+                    seal: Self::genesis_seal(2, false).conceal(),
+                    state: Self::fungible(7, 3),
+                    lock: none!(),
+                },
+            ]),
+            Self::DECLARATIVE => TypedAssigns::Declarative(small_vec![
+                Assign::revealed(Self::genesis_seal(3, false), VoidState::default()),
+                Assign::revealed(Self::genesis_seal(4, true), VoidState::default()).conceal(),
+            ]),
+            Self::STRUCTURED => TypedAssigns::Structured(small_vec![Assign::revealed(
+                Self::genesis_seal(5, false),
+                RevealedData::with_salt(DataState::from(Self::blob(&[9; 9])), 0x5A17),
+            )]),
+            Self::ATTACHMENT => TypedAssigns::Attachment(small_vec![
+                Assign::revealed(
+                    Self::genesis_seal(6, false),
+                    RevealedAttach::with_salt(AttachId::from([0xAF; 32]), MediaType::Any, 0xA77A),
+                ),
+                Assign::revealed(
+                    Self::genesis_seal(7, false),
+                    RevealedAttach::with_salt(AttachId::from([0xAE; 32]), MediaType::Any, 0xA77B),
+                )
+                .conceal(),
+            ]),
+        }
+        .into();
+        genesis.valencies = Valencies::from(tiny_bset! { Self::VALENCY });
+        genesis
+    }
+
+    fn extension(genesis: &Genesis) -> Extension {
+        let mut extension = Extension::strict_dumb();
+        extension.contract_id = genesis.contract_id();
+        extension.nonce = 42;
+        extension.extension_type = Self::EXTENSION;
+        extension
+            .globals
+            .add_state(Self::GLOBAL, DataState::from(Self::blob(b"ext")))
+            .expect("single global state value");
+        extension.assignments = tiny_bmap! {
+            Self::FUNGIBLE => TypedAssigns::Fungible(small_vec![Assign::revealed(
+                Self::genesis_seal(8, false),
+                Self::fungible(100, 4),
+            )]),
+        }
+        .into();
+        extension.redeemed = Redeemed::from(tiny_bmap! { Self::VALENCY => genesis.id() });
+        extension
+    }
+
+    fn transition(genesis: &Genesis) -> Transition {
+        let mut transition = Transition::strict_dumb();
+        transition.contract_id = genesis.contract_id();
+        transition.nonce = 7;
+        transition.transition_type = Self::TRANSITION;
+        transition
+            .metadata
+            .add_value(Self::META, MetaValue::from(Self::blob(&[4, 5])))
+            .expect("single metadata value");
+        transition.inputs = Inputs::from(small_bset! {
+            Input::with(Opout::new(genesis.id(), Self::FUNGIBLE, 0)),
+            Input::with(Opout::new(genesis.id(), Self::FUNGIBLE, 1)),
+        });
+        transition.assignments = tiny_bmap! {
+            Self::FUNGIBLE => TypedAssigns::Fungible(small_vec![
+                Assign::revealed(Self::graph_seal(0, false), Self::fungible(999_500, 5)),
+                Assign::ConfidentialSeal {
+                    seal: Self::graph_seal(1, true).conceal(),
+                    state: Self::fungible(1000, 6),
+                    lock: none!(),
+                },
+            ]),
+            Self::DECLARATIVE => TypedAssigns::Declarative(small_vec![
+                Assign::revealed(Self::graph_seal(2, false), VoidState::default()),
+                Assign::revealed(Self::graph_seal(3, false), VoidState::default()),
+            ]),
+        }
+        .into();
+        transition
+    }
+
+    fn bundle(transition: &Transition) -> TransitionBundle {
+        let opid = transition.id();
+        TransitionBundle {
+            close_method: Method::OpretFirst,
+            input_map: InputMap::from(
+                Confined::try_from(bmap! {
+                    Vin::from_u32(0) => opid,
+                    Vin::from_u32(3) => opid,
+                })
+                .expect("non-empty input map"),
+            ),
+            known_transitions: Confined::try_from(bmap! { opid => transition.clone() })
+                .expect("single known transition"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, process};
+
+    use super::*;
+    use crate::BuiltinTag;
+
+    commit_id_vector!(
+        schema_id,
+        Samples::new().schema,
+        "rgb:sch:eb7P5baY1fgm8tnbz$Bavf9uDcAulW6sdbQWgSLwOd8#shallow-charlie-harmony"
+    );
+    commit_id_vector!(
+        genesis_id,
+        Samples::new().genesis,
+        "dfd4db69ca51a9374d005c665aadfca69d065cb24e7cc828ee516dd22095b19e"
+    );
+    commit_id_vector!(
+        bundle_id,
+        Samples::new().bundle,
+        "8e5696b1b7f143d8e1ba61c97799ec032b84b65397d3b260ebf2f9e1c48b1e4d"
+    );
+
+    #[test]
+    fn commit_id_mismatch() {
+        let vector = CommitIdVector::new("transition", &Samples::new().transition).unwrap();
+        assert_eq!(vector.tag, "urn:lnp-bp:rgb:operation#2024-02-03");
+        assert!(BuiltinTag::is_builtin(vector.tag));
+        assert!(vector.to_fixture().starts_with(&format!("{}\n{}\n", vector.tag, vector.id)));
+        assert!(matches!(
+            vector.check("00"),
+            Err(CompatError::CommitIdMismatch { name: "transition", .. })
+        ));
+    }
+
+    #[test]
+    fn golden_fixtures() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(COMPAT_FIXTURES_DIR);
+        let sets = FixtureSet::list(root).unwrap();
+        assert!(!sets.is_empty());
+        for set in sets {
+            let count = set.check_all().unwrap_or_else(|err| panic!("{err}"));
+            assert!(count > 0, "empty fixture set for version {}", set.version());
+        }
+    }
+
+    #[test]
+    fn write_check() {
+        let root = env::temp_dir().join(format!("rgbcore-compat-{}", process::id()));
+        let set = FixtureSet::new(&root, "test");
+        set.write_all().unwrap();
+        assert_eq!(set.check_all().unwrap(), 5);
+        assert!(matches!(set.write(&Schema::strict_dumb()), Err(CompatError::Exists { .. })));
+
+        // Samples not using features added after 0.11.0-beta.8 are encoded exactly as by that
+        // release
+        let release = FixtureSet::new(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join(COMPAT_FIXTURES_DIR),
+            "0.11.0-beta.8",
+        );
+        for name in ["Schema", "Genesis", "Transition", "Extension", "TransitionBundle"] {
+            assert_eq!(
+                fs::read(set.data_path(name)).unwrap(),
+                fs::read(release.data_path(name)).unwrap(),
+                "{name}"
+            );
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,444 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Panic-free decoding of untrusted data.
+//!
+//! All strict decoding implementations in the library are required to report
+//! malformed data with [`DecodeError`] and never panic. [`TryStrictDecode`]
+//! provides a decoding surface for services processing data from untrusted
+//! sources, which additionally limits the size and the nesting depth of the
+//! processed data (see [`DecodeLimits`]) and reports all failures with a typed
+//! error.
+
+use std::io;
+
+use strict_encoding::{
+    DecodeError, DeserializeError, FieldName, ReadRaw, ReadStruct, ReadTuple, ReadUnion,
+    StrictDecode, StrictEnum, StrictStruct, StrictSum, StrictTuple, StrictUnion, StreamReader,
+    TypedRead, VariantName,
+};
+
+/// Default maximal size of untrusted data accepted by
+/// [`TryStrictDecode::try_strict_decode`], equal to 10 MiB.
+pub const UNTRUSTED_DATA_MAX_LEN: usize = 10 * 1024 * 1024;
+
+/// Default maximal nesting depth of untrusted data accepted by
+/// [`TryStrictDecode::try_strict_decode`].
+pub const UNTRUSTED_DATA_MAX_DEPTH: usize = 64;
+
+/// Limits applied by [`TryStrictDecode`] to the decoded untrusted data, which
+/// can be set from the service configuration.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase", default)
+)]
+pub struct DecodeLimits {
+    /// Maximal size of the data, in bytes.
+    pub max_len: usize,
+    /// Maximal nesting depth of the data.
+    pub max_depth: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            max_len: UNTRUSTED_DATA_MAX_LEN,
+            max_depth: UNTRUSTED_DATA_MAX_DEPTH,
+        }
+    }
+}
+
+impl DecodeLimits {
+    /// Sets maximal size of the data, in bytes.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Sets maximal nesting depth of the data.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+/// Errors decoding untrusted data.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum TryDecodeError {
+    /// data of {len} bytes exceed the maximal allowed size of {max} bytes.
+    TooLarge { len: usize, max: usize },
+
+    /// data nesting exceeds the maximal allowed depth of {max}.
+    TooDeep { max: usize },
+
+    #[display(inner)]
+    #[from]
+    Deserialize(DeserializeError),
+}
+
+/// Panic-free strict decoding of data coming from untrusted sources.
+pub trait TryStrictDecode: StrictDecode {
+    /// Decodes the data, which must be no longer than
+    /// [`UNTRUSTED_DATA_MAX_LEN`], nested no deeper than
+    /// [`UNTRUSTED_DATA_MAX_DEPTH`] and consumed entirely.
+    fn try_strict_decode(data: impl AsRef<[u8]>) -> Result<Self, TryDecodeError> {
+        Self::try_strict_decode_with(data, DecodeLimits::default())
+    }
+
+    /// Decodes the data, which must be no longer than `MAX` bytes, nested no
+    /// deeper than [`UNTRUSTED_DATA_MAX_DEPTH`] and consumed entirely.
+    fn try_strict_decode_limited<const MAX: usize>(
+        data: impl AsRef<[u8]>,
+    ) -> Result<Self, TryDecodeError> {
+        Self::try_strict_decode_with(data, DecodeLimits::default().with_max_len(MAX))
+    }
+
+    /// Decodes the data, which must be no longer than `MAX` bytes, nested no
+    /// deeper than `max_depth` and consumed entirely.
+    fn try_strict_decode_nested<const MAX: usize>(
+        data: impl AsRef<[u8]>,
+        max_depth: usize,
+    ) -> Result<Self, TryDecodeError> {
+        Self::try_strict_decode_with(data, DecodeLimits { max_len: MAX, max_depth })
+    }
+
+    /// Decodes the data, which must fit into the provided `limits` and be
+    /// consumed entirely.
+    fn try_strict_decode_with(
+        data: impl AsRef<[u8]>,
+        limits: DecodeLimits,
+    ) -> Result<Self, TryDecodeError> {
+        let DecodeLimits { max_len, max_depth } = limits;
+        let data = data.as_ref();
+        let len = data.len();
+        if len > max_len {
+            return Err(TryDecodeError::TooLarge { len, max: max_len });
+        }
+        // The data size is already checked, so the reader needs no own limit
+        let mut context = DecodeContext::in_memory::<{ usize::MAX }>(data, max_depth);
+        let res = Self::strict_decode(&mut context);
+        if context.is_exceeded() {
+            return Err(TryDecodeError::TooDeep { max: max_depth });
+        }
+        let me = res.map_err(DeserializeError::from)?;
+        if context.into_cursor().position() != len as u64 {
+            return Err(DeserializeError::DataNotEntirelyConsumed.into());
+        }
+        Ok(me)
+    }
+}
+
+impl<T: StrictDecode> TryStrictDecode for T {}
+
+/// Strict decoding context limiting the nesting depth of the decoded data,
+/// which prevents stack exhaustion by maliciously nested input.
+///
+/// Each struct, tuple and union variant with fields enters a new nesting
+/// level; collections and primitive values don't increase the depth.
+#[derive(Clone, Debug)]
+pub struct DecodeContext<R: ReadRaw> {
+    reader: R,
+    depth: usize,
+    max_depth: usize,
+    exceeded: bool,
+}
+
+impl<T: AsRef<[u8]>> DecodeContext<StreamReader<io::Cursor<T>>> {
+    /// Constructs context for decoding in-memory data of at most `MAX` bytes.
+    pub fn in_memory<const MAX: usize>(data: T, max_depth: usize) -> Self {
+        Self::with(StreamReader::in_memory::<MAX>(data), max_depth)
+    }
+
+    /// Releases the cursor over the decoded data.
+    pub fn into_cursor(self) -> io::Cursor<T> { self.reader.into_cursor() }
+}
+
+impl<R: ReadRaw> DecodeContext<R> {
+    /// Constructs context decoding data from the `reader`.
+    pub fn with(reader: R, max_depth: usize) -> Self {
+        DecodeContext {
+            reader,
+            depth: 0,
+            max_depth,
+            exceeded: false,
+        }
+    }
+
+    /// Returns maximal nesting depth allowed by the context.
+    pub fn max_depth(&self) -> usize { self.max_depth }
+
+    /// Detects whether the decoding has failed since the data were nested
+    /// deeper than allowed.
+    pub fn is_exceeded(&self) -> bool { self.exceeded }
+
+    /// Releases the underlying reader.
+    pub fn unbox(self) -> R { self.reader }
+
+    fn enter(&mut self) -> Result<NestedReader<'_, R>, DecodeError> {
+        if self.depth >= self.max_depth {
+            self.exceeded = true;
+            return Err(DecodeError::DataIntegrityError(format!(
+                "data nesting exceeds the maximal allowed depth of {}",
+                self.max_depth
+            )));
+        }
+        self.depth += 1;
+        Ok(NestedReader(self))
+    }
+}
+
+impl<R: ReadRaw> TypedRead for DecodeContext<R> {
+    type TupleReader<'parent> = NestedReader<'parent, R> where Self: 'parent;
+    type StructReader<'parent> = NestedReader<'parent, R> where Self: 'parent;
+    type UnionReader = Self;
+    type RawReader = R;
+
+    unsafe fn raw_reader(&mut self) -> &mut Self::RawReader { &mut self.reader }
+
+    fn read_union<T: StrictUnion>(
+        &mut self,
+        inner: impl FnOnce(VariantName, &mut Self::UnionReader) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        let name = T::strict_name().unwrap_or_else(|| tn!("__unnamed"));
+        let tag = u8::strict_decode(self)?;
+        let variant_name = T::variant_name_by_tag(tag)
+            .ok_or(DecodeError::UnionTagNotKnown(name.to_string(), tag))?;
+        inner(variant_name, self)
+    }
+
+    fn read_enum<T: StrictEnum>(&mut self) -> Result<T, DecodeError>
+    where u8: From<T> {
+        let name = T::strict_name().unwrap_or_else(|| tn!("__unnamed"));
+        let tag = u8::strict_decode(self)?;
+        T::try_from(tag).map_err(|_| DecodeError::EnumTagNotKnown(name.to_string(), tag))
+    }
+
+    fn read_tuple<'parent, 'me, T: StrictTuple>(
+        &'me mut self,
+        inner: impl FnOnce(&mut Self::TupleReader<'parent>) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError>
+    where
+        Self: 'parent,
+        'me: 'parent,
+    {
+        inner(&mut self.enter()?)
+    }
+
+    fn read_struct<'parent, 'me, T: StrictStruct>(
+        &'me mut self,
+        inner: impl FnOnce(&mut Self::StructReader<'parent>) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError>
+    where
+        Self: 'parent,
+        'me: 'parent,
+    {
+        inner(&mut self.enter()?)
+    }
+}
+
+impl<R: ReadRaw> ReadUnion for DecodeContext<R> {
+    type TupleReader<'parent> = NestedReader<'parent, R> where Self: 'parent;
+    type StructReader<'parent> = NestedReader<'parent, R> where Self: 'parent;
+
+    fn read_tuple<'parent, 'me, T: StrictSum>(
+        &'me mut self,
+        inner: impl FnOnce(&mut Self::TupleReader<'parent>) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError>
+    where
+        Self: 'parent,
+        'me: 'parent,
+    {
+        inner(&mut self.enter()?)
+    }
+
+    fn read_struct<'parent, 'me, T: StrictSum>(
+        &'me mut self,
+        inner: impl FnOnce(&mut Self::StructReader<'parent>) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError>
+    where
+        Self: 'parent,
+        'me: 'parent,
+    {
+        inner(&mut self.enter()?)
+    }
+}
+
+/// Reader of the fields of a nested struct or tuple, which leaves the nesting
+/// level of the [`DecodeContext`] when dropped.
+#[derive(Debug)]
+pub struct NestedReader<'parent, R: ReadRaw>(&'parent mut DecodeContext<R>);
+
+impl<R: ReadRaw> Drop for NestedReader<'_, R> {
+    fn drop(&mut self) { self.0.depth -= 1; }
+}
+
+impl<R: ReadRaw> ReadTuple for NestedReader<'_, R> {
+    fn read_field<T: StrictDecode>(&mut self) -> Result<T, DecodeError> {
+        T::strict_decode(self.0)
+    }
+}
+
+impl<R: ReadRaw> ReadStruct for NestedReader<'_, R> {
+    fn read_field<T: StrictDecode>(&mut self, _field: FieldName) -> Result<T, DecodeError> {
+        T::strict_decode(self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+    use strict_encoding::{
+        StreamWriter, StrictDeserialize, StrictDumb, StrictEncode, StrictSerialize,
+    };
+
+    use super::*;
+    use crate::validation::EAnchor;
+    use crate::{Extension, Genesis, Schema, Transition, TransitionBundle};
+
+    /// Deterministic xorshift generator, so the failures are reproducible.
+    struct Noise(u64);
+
+    impl Noise {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn bytes(&mut self, len: usize) -> Vec<u8> { (0..len).map(|_| self.next() as u8).collect() }
+    }
+
+    /// Decodes the data with and without the nesting depth limit, which must
+    /// agree on the data validity.
+    fn decode<T: StrictDeserialize>(data: Vec<u8>) {
+        let limited = T::try_strict_decode(&data);
+        let unlimited =
+            T::from_strict_serialized::<UNTRUSTED_DATA_MAX_LEN>(Confined::try_from(data).unwrap());
+        assert_eq!(limited.is_ok(), unlimited.is_ok());
+    }
+
+    fn fuzz<T: StrictSerialize + StrictDeserialize + StrictDumb>(noise: &mut Noise) {
+        // Decoders are called directly, so any panic fails the test
+        let valid = T::strict_dumb()
+            .to_strict_serialized::<UNTRUSTED_DATA_MAX_LEN>()
+            .unwrap()
+            .release();
+        assert!(T::from_strict_serialized::<UNTRUSTED_DATA_MAX_LEN>(
+            Confined::try_from(valid.clone()).unwrap()
+        )
+        .is_ok());
+
+        for _ in 0..1000 {
+            let len = noise.next() as usize % 4096;
+            decode::<T>(noise.bytes(len));
+
+            let mut mutated = valid.clone();
+            for _ in 0..(1 + noise.next() % 4) {
+                let pos = noise.next() as usize % mutated.len();
+                mutated[pos] = noise.next() as u8;
+            }
+            decode::<T>(mutated);
+        }
+
+        decode::<T>(noise.bytes(UNTRUSTED_DATA_MAX_LEN));
+    }
+
+    #[test]
+    fn fuzz_consensus_types() {
+        let mut noise = Noise(0x2545_F491_4F6C_DD1D);
+        fuzz::<Schema>(&mut noise);
+        fuzz::<Genesis>(&mut noise);
+        fuzz::<Transition>(&mut noise);
+        fuzz::<Extension>(&mut noise);
+        fuzz::<TransitionBundle>(&mut noise);
+    }
+
+    #[test]
+    fn too_large() {
+        let data = vec![0u8; UNTRUSTED_DATA_MAX_LEN + 1];
+        assert_eq!(Genesis::try_strict_decode(data).unwrap_err(), TryDecodeError::TooLarge {
+            len: UNTRUSTED_DATA_MAX_LEN + 1,
+            max: UNTRUSTED_DATA_MAX_LEN
+        });
+        assert!(matches!(
+            Schema::try_strict_decode_limited::<16>([0u8; 17]),
+            Err(TryDecodeError::TooLarge { len: 17, max: 16 })
+        ));
+    }
+
+    #[test]
+    fn too_deep() {
+        let genesis = Genesis::strict_dumb();
+        let data = genesis.to_strict_serialized::<UNTRUSTED_DATA_MAX_LEN>().unwrap();
+        assert_eq!(Genesis::try_strict_decode(&data), Ok(genesis.clone()));
+        assert_eq!(
+            Genesis::try_strict_decode_nested::<UNTRUSTED_DATA_MAX_LEN>(&data, 2),
+            Ok(genesis)
+        );
+        assert_eq!(
+            Genesis::try_strict_decode_nested::<UNTRUSTED_DATA_MAX_LEN>(&data, 1),
+            Err(TryDecodeError::TooDeep { max: 1 })
+        );
+
+        // Anchors are not deserializable on their own, but still can be decoded
+        let anchor = <EAnchor>::strict_dumb();
+        let mut data = vec![];
+        anchor
+            .strict_write(StreamWriter::new::<UNTRUSTED_DATA_MAX_LEN>(&mut data))
+            .unwrap();
+        assert_eq!(<EAnchor>::try_strict_decode(&data), Ok(anchor));
+        assert_eq!(
+            <EAnchor>::try_strict_decode_nested::<UNTRUSTED_DATA_MAX_LEN>(&data, 4),
+            Err(TryDecodeError::TooDeep { max: 4 })
+        );
+        data.push(0);
+        assert_eq!(
+            <EAnchor>::try_strict_decode(&data),
+            Err(DeserializeError::DataNotEntirelyConsumed.into())
+        );
+    }
+
+    #[test]
+    fn configured_limits() {
+        let genesis = Genesis::strict_dumb();
+        let data = genesis.to_strict_serialized::<UNTRUSTED_DATA_MAX_LEN>().unwrap();
+        let len = data.len();
+        let limits = DecodeLimits::default();
+        assert_eq!(Genesis::try_strict_decode_with(&data, limits), Ok(genesis.clone()));
+        assert_eq!(
+            Genesis::try_strict_decode_with(&data, limits.with_max_len(len)),
+            Ok(genesis.clone())
+        );
+        assert_eq!(
+            Genesis::try_strict_decode_with(&data, limits.with_max_len(len - 1)),
+            Err(TryDecodeError::TooLarge { len, max: len - 1 })
+        );
+        assert_eq!(
+            Genesis::try_strict_decode_with(&data, limits.with_max_depth(1)),
+            Err(TryDecodeError::TooDeep { max: 1 })
+        );
+    }
+}
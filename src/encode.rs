@@ -0,0 +1,116 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Single-pass strict encoding into an output stream together with the
+//! computation of the commitment id of the encoded data.
+//!
+//! Computing an id and then writing the data to disk or network requires
+//! serializing the data twice. [`HashingWriter`] feeds all the data written to
+//! the underlying stream into the tagged hasher of the commitment id, such that
+//! for the types committing to their strict serialization (see
+//! [`CommitsSerialized`]) the id is produced by the same pass which writes the
+//! data.
+
+use std::io;
+use std::marker::PhantomData;
+
+use commit_verify::{CommitId, CommitmentId, DigestExt, Sha256};
+use strict_encoding::{StreamWriter, StrictEncode};
+
+use crate::{OpCommitment, Schema};
+
+/// Writer passing all the data to the `inner` writer and to the tagged hasher
+/// of the `Id` commitment.
+#[derive(Clone, Debug)]
+pub struct HashingWriter<W: io::Write, Id: CommitmentId> {
+    inner: W,
+    hasher: Sha256,
+    _id: PhantomData<Id>,
+}
+
+impl<W: io::Write, Id: CommitmentId> HashingWriter<W, Id> {
+    /// Constructs writer wrapping the `inner` writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::from_tag(Id::TAG),
+            _id: PhantomData,
+        }
+    }
+
+    /// Completes hashing, returning the inner writer and the commitment id of
+    /// all the data written.
+    pub fn finish(self) -> (W, Id) { (self.inner, self.hasher.into()) }
+}
+
+impl<W: io::Write, Id: CommitmentId> io::Write for HashingWriter<W, Id> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.input_raw(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+/// Marker trait for the types whose commitment id is the tagged hash of their
+/// strict serialization, like the types using `strict` commitment strategy.
+///
+/// Implementing the trait for a type committing to something else than its
+/// serialization results in [`CommitsSerialized::strict_write_hashed`]
+/// returning wrong ids.
+pub trait CommitsSerialized: CommitId + StrictEncode {
+    /// Strict-encodes the value into the `writer`, returning the writer back
+    /// together with the commitment id of the value, computed in the same
+    /// pass.
+    fn strict_write_hashed<W: io::Write>(
+        &self,
+        writer: W,
+    ) -> io::Result<(W, Self::CommitmentId)> {
+        let mut writer = HashingWriter::<W, Self::CommitmentId>::new(writer);
+        self.strict_write(StreamWriter::new::<{ usize::MAX }>(&mut writer))?;
+        Ok(writer.finish())
+    }
+}
+
+impl CommitsSerialized for Schema {}
+impl CommitsSerialized for OpCommitment {}
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::{StrictDumb, StrictSerialize};
+
+    use super::*;
+    use crate::{Genesis, Operation};
+
+    #[test]
+    fn single_pass() {
+        let schema = Schema::strict_dumb();
+        let (data, id) = schema.strict_write_hashed(vec![]).unwrap();
+        assert_eq!(id, schema.schema_id());
+        assert_eq!(data, schema.to_strict_serialized::<{ usize::MAX }>().unwrap().release());
+
+        let genesis = Genesis::strict_dumb();
+        let (_, id) = genesis.commit().strict_write_hashed(io::sink()).unwrap();
+        assert_eq!(id, genesis.id());
+    }
+}
@@ -0,0 +1,152 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Wire layout snapshot of the consensus data types.
+//!
+//! The layout lists every type of the RGB strict type libraries together with
+//! its semantic id and definition, which includes field order, enum and union
+//! tags and confinement bounds. Since semantic ids commit to the definitions of
+//! all nested types, any change to the consensus encoding changes the layout.
+//!
+//! The snapshot is committed to the repository (see [`LAYOUT_SNAPSHOT`]) and
+//! is regenerated by the `rgbcore-stl` binary; tests fail if the current
+//! layout differs from the committed one.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+use strict_encoding::{StrictDecode, StrictDumb, StrictEncode};
+use strict_types::TypeLib;
+
+use crate::stl::{rgb_commit_stl, rgb_logic_stl};
+use crate::{
+    Extension, Genesis, OpCommitment, Schema, Transition, TransitionBundle, LIB_NAME_RGB_COMMIT,
+    LIB_NAME_RGB_LOGIC,
+};
+
+/// File name of the committed layout snapshot inside the `stl` directory.
+pub const LAYOUT_SNAPSHOT: &str = "ConsensusLayout.txt";
+
+/// Ensures at compile time that the top-level consensus types derive all of
+/// the strict encoding traits.
+const _: fn() = || {
+    fn strict<T: StrictEncode + StrictDecode + StrictDumb>() {}
+    strict::<Schema>();
+    strict::<Genesis>();
+    strict::<Transition>();
+    strict::<Extension>();
+    strict::<TransitionBundle>();
+    strict::<OpCommitment>();
+};
+
+/// Wire layout of all consensus data types.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ConsensusLayout {
+    /// Layout lines (semantic id and definition) for each type, keyed by the
+    /// fully qualified type name.
+    types: BTreeMap<String, String>,
+}
+
+impl ConsensusLayout {
+    /// Collects layout of the types from the RGB strict type libraries.
+    pub fn current() -> Self {
+        let mut layout = ConsensusLayout { types: empty!() };
+        layout.add_lib(LIB_NAME_RGB_COMMIT, &rgb_commit_stl());
+        layout.add_lib(LIB_NAME_RGB_LOGIC, &rgb_logic_stl());
+        layout
+    }
+
+    /// Parses layout from its textual representation, as produced by
+    /// [`Display`].
+    pub fn parse(s: &str) -> Self {
+        let types = s
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with("--"))
+            .filter_map(|line| line.split_once(' '))
+            .map(|(name, layout)| (name.to_owned(), layout.to_owned()))
+            .collect();
+        ConsensusLayout { types }
+    }
+
+    fn add_lib(&mut self, lib_name: &str, lib: &TypeLib) {
+        for (name, ty) in &lib.types {
+            let sem_id = ty.sem_id_named(name);
+            let ty = ty.to_string().split_whitespace().collect::<Vec<_>>().join(" ");
+            self.types
+                .insert(format!("{lib_name}.{name}"), format!("{sem_id} {ty}"));
+        }
+    }
+
+    /// Lists names of the types whose layout differs between `self` and
+    /// `other`, including types present only in one of them.
+    pub fn diff<'a>(&'a self, other: &'a Self) -> Vec<&'a str> {
+        let mut diff = self
+            .types
+            .iter()
+            .filter(|(name, layout)| other.types.get(*name) != Some(layout))
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        diff.extend(
+            other
+                .types
+                .keys()
+                .filter(|name| !self.types.contains_key(*name))
+                .map(String::as_str),
+        );
+        diff.sort();
+        diff
+    }
+}
+
+impl Display for ConsensusLayout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "-- Wire layout of RGB consensus data types; generated by rgbcore-stl")?;
+        for (name, layout) in &self.types {
+            writeln!(f, "{name} {layout}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn layout_snapshot() {
+        let snapshot = ConsensusLayout::parse(include_str!("../stl/ConsensusLayout.txt"));
+        let current = ConsensusLayout::current();
+        let diff = current.diff(&snapshot);
+        assert!(
+            diff.is_empty(),
+            "consensus encoding of the following types has changed: {}; if this is intended, \
+             regenerate the layout snapshot with rgbcore-stl",
+            diff.join(", ")
+        );
+    }
+
+    #[test]
+    fn layout_roundtrip() {
+        let layout = ConsensusLayout::current();
+        assert_eq!(ConsensusLayout::parse(&layout.to_string()), layout);
+    }
+}
@@ -35,24 +35,41 @@ extern crate commit_verify;
 extern crate serde_crate as serde;
 extern crate core;
 
+use baid64::{Baid64Display, Baid64ParseError, DisplayBaid64, FromBaid64Str};
+
+mod decode;
+mod encode;
 mod operation;
+mod tags;
 pub mod schema;
 pub mod validation;
 #[macro_use]
 pub mod vm;
 #[cfg(feature = "stl")]
 pub mod stl;
+#[cfg(feature = "stl")]
+pub mod layout;
+#[cfg(feature = "compat")]
+pub mod compat;
 
 pub mod prelude {
     pub use commit_verify::ReservedBytes;
+    pub use decode::{
+        DecodeContext, DecodeLimits, NestedReader, TryDecodeError, TryStrictDecode,
+        UNTRUSTED_DATA_MAX_DEPTH, UNTRUSTED_DATA_MAX_LEN,
+    };
+    pub use encode::{CommitsSerialized, HashingWriter};
     pub use operation::*;
     pub use schema::*;
+    pub use tags::{BuiltinTag, BUILTIN_TAGS};
     pub use vm::XWitnessId;
 
     #[cfg(feature = "stl")]
     pub use super::stl;
+    #[cfg(feature = "stl")]
+    pub use super::stl::consensus_lib_id;
     use super::*;
-    pub use super::{schema, validation, vm};
+    pub use super::{schema, validation, vm, CompactId};
 }
 
 pub use prelude::*;
@@ -93,6 +110,28 @@ mod _ffv {
     }
 }
 
+/// Compact armored form of the identifiers, suitable for QR codes and
+/// invoices.
+///
+/// Unlike the default string representation, the compact form never uses
+/// chunking or mnemonic suffixes; instead it always includes the type prefix
+/// and embeds a 4-byte checksum into the Base64 payload, such that any typo or
+/// mistaken identifier type is detected on parsing.
+pub trait CompactId: DisplayBaid64 + FromBaid64Str
+where <Self as TryFrom<[u8; 32]>>::Error: std::error::Error
+{
+    /// Produces compact string with a type prefix and embedded checksum.
+    fn to_compact_string(&self) -> String {
+        Baid64Display::with(Self::HRI, self.to_baid64_payload(), false, true, false, true)
+            .to_string()
+    }
+
+    /// Parses compact string, verifying type prefix and embedded checksum.
+    ///
+    /// Also accepts any other valid Baid64 representation of the identifier.
+    fn from_compact_str(s: &str) -> Result<Self, Baid64ParseError> { Self::from_baid64_str(s) }
+}
+
 #[macro_export]
 macro_rules! impl_serde_baid64 {
     ($ty:ty) => {
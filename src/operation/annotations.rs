@@ -0,0 +1,107 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Annotations are transport-level key-value data travelling together with a
+//! consignment (like its creation timestamp, software which had produced it or
+//! notes for the receiver). They are never committed to: no contract,
+//! operation or bundle id depends on them, and they do not affect the
+//! validation result.
+
+use std::collections::btree_map;
+
+use amplify::confinement::{self, SmallOrdMap};
+use amplify::Wrapper;
+use strict_encoding::{FieldName, StrictDeserialize, StrictSerialize};
+
+use crate::{MetaValue, LIB_NAME_RGB_COMMIT};
+
+/// Non-committed annotations to a consignment.
+///
+/// The type intentionally doesn't implement [`commit_verify::CommitEncode`],
+/// such that it can't be made a part of any commitment id.
+#[derive(Wrapper, WrapperMut, Clone, PartialEq, Eq, Hash, Default, Debug, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct Annotations(SmallOrdMap<FieldName, MetaValue>);
+
+impl StrictSerialize for Annotations {}
+impl StrictDeserialize for Annotations {}
+
+impl Annotations {
+    /// Adds annotation, returning the previous value under the same key, if
+    /// any.
+    pub fn annotate(
+        &mut self,
+        key: FieldName,
+        value: MetaValue,
+    ) -> Result<Option<MetaValue>, confinement::Error> {
+        self.0.insert(key, value)
+    }
+
+    /// Returns annotation value under the given key.
+    pub fn annotation(&self, key: &FieldName) -> Option<&MetaValue> { self.0.get(key) }
+
+    /// Removes annotation under the given key, returning its value.
+    pub fn remove_annotation(&mut self, key: &FieldName) -> Option<MetaValue> {
+        self.0.remove(key).ok().flatten()
+    }
+}
+
+impl<'a> IntoIterator for &'a Annotations {
+    type Item = (&'a FieldName, &'a MetaValue);
+    type IntoIter = btree_map::Iter<'a, FieldName, MetaValue>;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.iter() }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::SmallBlob;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let mut annotations = Annotations::default();
+        let key = fname!("createdAt");
+        let value =
+            MetaValue::from(SmallBlob::try_from(1_700_000_000u64.to_le_bytes().to_vec()).unwrap());
+        assert_eq!(annotations.annotate(key.clone(), value.clone()).unwrap(), None);
+        assert_eq!(annotations.annotation(&key), Some(&value));
+
+        let data = annotations
+            .to_strict_serialized::<{ u16::MAX as usize }>()
+            .unwrap();
+        let decoded = Annotations::from_strict_serialized::<{ u16::MAX as usize }>(data).unwrap();
+        assert_eq!(decoded, annotations);
+
+        assert_eq!(annotations.remove_annotation(&key), Some(value));
+        assert!(annotations.is_empty());
+    }
+}
@@ -25,15 +25,16 @@ use core::fmt::Debug;
 use std::collections::{btree_map, BTreeSet};
 use std::hash::Hash;
 
-use amplify::confinement::{Confined, SmallVec, TinyOrdMap};
+use amplify::confinement::{Confined, TinyOrdMap};
 use commit_verify::{Conceal, ReservedBytes};
 use strict_encoding::{StrictDumb, StrictEncode};
 
-use super::ExposedState;
+use super::{ConfidentialState, ExposedState};
 use crate::operation::seal::GenesisSeal;
 use crate::{
-    AssignmentType, ExposedSeal, GraphSeal, RevealedAttach, RevealedData, RevealedValue,
-    SecretSeal, StateType, VoidState, XChain, LIB_NAME_RGB_COMMIT,
+    AssignList, AssignmentType, ConcealedState, ExposedSeal, GraphSeal, OpId, Opout,
+    RevealedAttach, RevealedData, RevealedState, RevealedValue, Schema, SecretSeal, StateType,
+    VoidState, XChain, LIB_NAME_RGB_COMMIT,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Display, Error)]
@@ -177,6 +178,13 @@ impl<State: ExposedState, Seal: ExposedSeal> Assign<State, Seal> {
         }
     }
 
+    pub fn as_revealed_seal(&self) -> Option<&XChain<Seal>> {
+        match self {
+            Assign::Revealed { seal, .. } | Assign::ConfidentialState { seal, .. } => Some(seal),
+            Assign::Confidential { .. } | Assign::ConfidentialSeal { .. } => None,
+        }
+    }
+
     pub fn to_confidential_state(&self) -> State::Confidential {
         match self {
             Assign::Revealed { state, .. } | Assign::ConfidentialSeal { state, .. } => {
@@ -227,6 +235,24 @@ impl<State: ExposedState, Seal: ExposedSeal> Assign<State, Seal> {
             _ => None,
         }
     }
+
+    /// Conceals state of the assignment, keeping the seal unchanged. The
+    /// commitment to the assignment is preserved.
+    pub fn conceal_state(&self) -> Self {
+        match self {
+            Assign::Confidential { .. } | Assign::ConfidentialState { .. } => self.clone(),
+            Assign::Revealed { seal, state, lock } => Assign::ConfidentialState {
+                seal: *seal,
+                state: state.conceal(),
+                lock: *lock,
+            },
+            Assign::ConfidentialSeal { seal, state, lock } => Assign::Confidential {
+                seal: *seal,
+                state: state.conceal(),
+                lock: *lock,
+            },
+        }
+    }
 }
 
 impl<State: ExposedState, Seal: ExposedSeal> Conceal for Assign<State, Seal>
@@ -300,13 +326,13 @@ impl<State: ExposedState> Assign<State, GenesisSeal> {
 pub enum TypedAssigns<Seal: ExposedSeal> {
     // TODO: Consider using non-empty variants
     #[strict_type(tag = 0x00)]
-    Declarative(SmallVec<AssignRights<Seal>>),
+    Declarative(AssignList<AssignRights<Seal>>),
     #[strict_type(tag = 0x01)]
-    Fungible(SmallVec<AssignFungible<Seal>>),
+    Fungible(AssignList<AssignFungible<Seal>>),
     #[strict_type(tag = 0x02)]
-    Structured(SmallVec<AssignData<Seal>>),
+    Structured(AssignList<AssignData<Seal>>),
     #[strict_type(tag = 0xFF)]
-    Attachment(SmallVec<AssignAttach<Seal>>),
+    Attachment(AssignList<AssignAttach<Seal>>),
 }
 
 impl<Seal: ExposedSeal> Conceal for TypedAssigns<Seal> {
@@ -315,22 +341,22 @@ impl<Seal: ExposedSeal> Conceal for TypedAssigns<Seal> {
         match self {
             TypedAssigns::Declarative(s) => {
                 let concealed_iter = s.iter().map(AssignRights::<Seal>::conceal);
-                let inner = SmallVec::try_from_iter(concealed_iter).expect("same size");
+                let inner = AssignList::try_from_iter(concealed_iter).expect("same size");
                 TypedAssigns::Declarative(inner)
             }
             TypedAssigns::Fungible(s) => {
                 let concealed_iter = s.iter().map(AssignFungible::<Seal>::conceal);
-                let inner = SmallVec::try_from_iter(concealed_iter).expect("same size");
+                let inner = AssignList::try_from_iter(concealed_iter).expect("same size");
                 TypedAssigns::Fungible(inner)
             }
             TypedAssigns::Structured(s) => {
                 let concealed_iter = s.iter().map(AssignData::<Seal>::conceal);
-                let inner = SmallVec::try_from_iter(concealed_iter).expect("same size");
+                let inner = AssignList::try_from_iter(concealed_iter).expect("same size");
                 TypedAssigns::Structured(inner)
             }
             TypedAssigns::Attachment(s) => {
                 let concealed_iter = s.iter().map(AssignAttach::<Seal>::conceal);
-                let inner = SmallVec::try_from_iter(concealed_iter).expect("same size");
+                let inner = AssignList::try_from_iter(concealed_iter).expect("same size");
                 TypedAssigns::Attachment(inner)
             }
         }
@@ -411,7 +437,7 @@ impl<Seal: ExposedSeal> TypedAssigns<Seal> {
     }
 
     #[inline]
-    pub fn as_declarative_mut(&mut self) -> Option<&mut SmallVec<AssignRights<Seal>>> {
+    pub fn as_declarative_mut(&mut self) -> Option<&mut AssignList<AssignRights<Seal>>> {
         match self {
             TypedAssigns::Declarative(set) => Some(set),
             _ => None,
@@ -419,7 +445,7 @@ impl<Seal: ExposedSeal> TypedAssigns<Seal> {
     }
 
     #[inline]
-    pub fn as_fungible_mut(&mut self) -> Option<&mut SmallVec<AssignFungible<Seal>>> {
+    pub fn as_fungible_mut(&mut self) -> Option<&mut AssignList<AssignFungible<Seal>>> {
         match self {
             TypedAssigns::Fungible(set) => Some(set),
             _ => None,
@@ -427,7 +453,7 @@ impl<Seal: ExposedSeal> TypedAssigns<Seal> {
     }
 
     #[inline]
-    pub fn as_structured_mut(&mut self) -> Option<&mut SmallVec<AssignData<Seal>>> {
+    pub fn as_structured_mut(&mut self) -> Option<&mut AssignList<AssignData<Seal>>> {
         match self {
             TypedAssigns::Structured(set) => Some(set),
             _ => None,
@@ -435,7 +461,7 @@ impl<Seal: ExposedSeal> TypedAssigns<Seal> {
     }
 
     #[inline]
-    pub fn as_attachment_mut(&mut self) -> Option<&mut SmallVec<AssignAttach<Seal>>> {
+    pub fn as_attachment_mut(&mut self) -> Option<&mut AssignList<AssignAttach<Seal>>> {
         match self {
             TypedAssigns::Attachment(set) => Some(set),
             _ => None,
@@ -466,6 +492,27 @@ impl<Seal: ExposedSeal> TypedAssigns<Seal> {
         })
     }
 
+    /// Conceals state of the assignment at `index`, keeping its seal unchanged
+    /// (see [`Assign::conceal_state`]). Returns whether the state was revealed
+    /// before, or [`UnknownDataError`] if the assignment does not exist.
+    pub fn conceal_state_at(&mut self, index: u16) -> Result<bool, UnknownDataError> {
+        fn conceal<State: ExposedState, Seal: ExposedSeal>(
+            vec: &mut AssignList<Assign<State, Seal>>,
+            index: u16,
+        ) -> Result<bool, UnknownDataError> {
+            let assign = vec.get_mut(index as usize).ok_or(UnknownDataError)?;
+            let revealed = assign.as_revealed_state().is_some();
+            *assign = assign.conceal_state();
+            Ok(revealed)
+        }
+        match self {
+            TypedAssigns::Declarative(vec) => conceal(vec, index),
+            TypedAssigns::Fungible(vec) => conceal(vec, index),
+            TypedAssigns::Structured(vec) => conceal(vec, index),
+            TypedAssigns::Attachment(vec) => conceal(vec, index),
+        }
+    }
+
     pub fn to_confidential_seals(&self) -> Vec<XChain<SecretSeal>> {
         match self {
             TypedAssigns::Declarative(s) => s
@@ -544,6 +591,106 @@ impl<Seal: ExposedSeal> TypedAssigns<Seal> {
     }
 }
 
+/// Reference to the revealed state data of an assignment, independent from the
+/// state type.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum AssignedState<'a> {
+    Void,
+    Fungible(&'a RevealedValue),
+    Structured(&'a RevealedData),
+    Attachment(&'a RevealedAttach),
+}
+
+impl AssignedState<'_> {
+    pub fn state_type(&self) -> StateType {
+        match self {
+            AssignedState::Void => StateType::Void,
+            AssignedState::Fungible(_) => StateType::Fungible,
+            AssignedState::Structured(_) => StateType::Structured,
+            AssignedState::Attachment(_) => StateType::Attachment,
+        }
+    }
+}
+
+impl From<AssignedState<'_>> for RevealedState {
+    fn from(state: AssignedState<'_>) -> Self {
+        match state {
+            AssignedState::Void => RevealedState::Void,
+            AssignedState::Fungible(value) => RevealedState::Fungible(*value),
+            AssignedState::Structured(data) => RevealedState::Structured(data.clone()),
+            AssignedState::Attachment(attach) => RevealedState::Attachment(attach.clone()),
+        }
+    }
+}
+
+fn revealed_at<'a, State: ExposedState, Seal: ExposedSeal>(
+    vec: &'a AssignList<Assign<State, Seal>>,
+    index: u16,
+    f: impl FnOnce(&'a State) -> AssignedState<'a>,
+) -> Option<(&'a XChain<Seal>, AssignedState<'a>)> {
+    let (seal, state) = vec.get(index as usize)?.as_revealed()?;
+    Some((seal, f(state)))
+}
+
+fn concealed_at<State: ExposedState, Seal: ExposedSeal>(
+    vec: &AssignList<Assign<State, Seal>>,
+    index: u16,
+) -> Option<(XChain<SecretSeal>, ConcealedState)> {
+    let assign = vec.get(index as usize)?;
+    if assign.as_revealed().is_some() {
+        return None;
+    }
+    let state = assign.to_confidential_state().state_commitment();
+    Some((assign.to_confidential_seal(), state))
+}
+
+impl<Seal: ExposedSeal> TypedAssigns<Seal> {
+    /// Iterates over assignments having both seal and state revealed, returning
+    /// their index, seal and state without cloning.
+    pub fn iter_revealed(&self) -> impl Iterator<Item = (u16, &XChain<Seal>, AssignedState<'_>)> {
+        (0..self.len_u16()).filter_map(move |no| {
+            let (seal, state) = match self {
+                TypedAssigns::Declarative(vec) => revealed_at(vec, no, |_| AssignedState::Void),
+                TypedAssigns::Fungible(vec) => revealed_at(vec, no, AssignedState::Fungible),
+                TypedAssigns::Structured(vec) => revealed_at(vec, no, AssignedState::Structured),
+                TypedAssigns::Attachment(vec) => revealed_at(vec, no, AssignedState::Attachment),
+            }?;
+            Some((no, seal, state))
+        })
+    }
+
+    /// Iterates over assignments having seal, state or both concealed,
+    /// returning their index, concealed seal and concealed state.
+    pub fn iter_concealed(
+        &self,
+    ) -> impl Iterator<Item = (u16, XChain<SecretSeal>, ConcealedState)> + '_ {
+        (0..self.len_u16()).filter_map(move |no| {
+            let (seal, state) = match self {
+                TypedAssigns::Declarative(vec) => concealed_at(vec, no),
+                TypedAssigns::Fungible(vec) => concealed_at(vec, no),
+                TypedAssigns::Structured(vec) => concealed_at(vec, no),
+                TypedAssigns::Attachment(vec) => concealed_at(vec, no),
+            }?;
+            Some((no, seal, state))
+        })
+    }
+
+    /// Iterates over seal definitions, returning assignment index and seal,
+    /// if it is revealed.
+    pub fn iter_seals(&self) -> impl Iterator<Item = (u16, Option<&XChain<Seal>>)> {
+        (0..self.len_u16()).map(move |no| {
+            let index = no as usize;
+            let seal = match self {
+                TypedAssigns::Declarative(vec) => vec[index].as_revealed_seal(),
+                TypedAssigns::Fungible(vec) => vec[index].as_revealed_seal(),
+                TypedAssigns::Structured(vec) => vec[index].as_revealed_seal(),
+                TypedAssigns::Attachment(vec) => vec[index].as_revealed_seal(),
+            };
+            (no, seal)
+        })
+    }
+}
+
 impl TypedAssigns<GenesisSeal> {
     pub fn transmutate_seals(&self) -> TypedAssigns<GraphSeal> {
         match self {
@@ -597,6 +744,63 @@ impl Assignments<GenesisSeal> {
     }
 }
 
+impl<Seal: ExposedSeal> Assignments<Seal> {
+    /// Iterates over assignments of a given type having both seal and state
+    /// revealed, returning their index, seal and state.
+    pub fn iter_revealed_of_type(
+        &self,
+        t: AssignmentType,
+    ) -> impl Iterator<Item = (u16, &XChain<Seal>, AssignedState<'_>)> {
+        self.get(&t)
+            .into_iter()
+            .flat_map(TypedAssigns::iter_revealed)
+    }
+
+    /// Iterates over assignments of a given type having seal, state or both
+    /// concealed, returning their index, concealed seal and concealed state.
+    pub fn iter_concealed_of_type(
+        &self,
+        t: AssignmentType,
+    ) -> impl Iterator<Item = (u16, XChain<SecretSeal>, ConcealedState)> + '_ {
+        self.get(&t)
+            .into_iter()
+            .flat_map(TypedAssigns::iter_concealed)
+    }
+
+    /// Iterates over all seal definitions, returning assignment type and index
+    /// together with the seal, if it is revealed.
+    pub fn iter_seal_definitions(
+        &self,
+    ) -> impl Iterator<Item = (AssignmentType, u16, Option<&XChain<Seal>>)> {
+        self.iter()
+            .flat_map(|(ty, assigns)| assigns.iter_seals().map(move |(no, seal)| (*ty, no, seal)))
+    }
+
+    /// Prunes data of the assignments of operation `opid` which are listed as
+    /// `superseded` and have prunable state type according to the `schema`
+    /// (see [`crate::RetentionSchema`]), concealing their state. Since the
+    /// commitments to the assignments are preserved, the operation id doesn't
+    /// change.
+    ///
+    /// Returns number of the assignments which state was pruned.
+    pub fn prune(&mut self, opid: OpId, schema: &Schema, superseded: &BTreeSet<Opout>) -> usize {
+        let mut pruned = 0;
+        for (ty, assigns) in self.keyed_values_mut() {
+            if !schema.retention(*ty).is_prunable() {
+                continue;
+            }
+            for opout in
+                superseded.range(Opout::new(opid, *ty, 0)..=Opout::new(opid, *ty, u16::MAX))
+            {
+                if let Ok(true) = assigns.conceal_state_at(opout.no) {
+                    pruned += 1;
+                }
+            }
+        }
+        pruned
+    }
+}
+
 impl<Seal: ExposedSeal> IntoIterator for Assignments<Seal> {
     type Item = (AssignmentType, TypedAssigns<Seal>);
     type IntoIter = btree_map::IntoIter<AssignmentType, TypedAssigns<Seal>>;
@@ -651,3 +855,112 @@ impl AssignmentsRef<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use bp::dbc::Method;
+    use bp::Txid;
+
+    use super::*;
+    use crate::{ConcealedState, DataState};
+
+    fn seal(no: u32) -> XChain<GraphSeal> {
+        XChain::Bitcoin(GraphSeal::with_blinding(Method::TapretFirst, Txid::from([1u8; 32]), no, 0))
+    }
+
+    #[test]
+    fn iterators() {
+        let rights_ty = AssignmentType::with(1);
+        let data_ty = AssignmentType::with(2);
+        let data = (0u8..5)
+            .map(|no| RevealedData::with_salt(DataState::from(small_blob![no]), 0))
+            .collect::<Vec<_>>();
+
+        let mut assignments = Assignments::<GraphSeal>::default();
+        assignments
+            .insert(
+                rights_ty,
+                TypedAssigns::Declarative(small_vec![Assign::revealed(
+                    seal(10),
+                    VoidState::default()
+                )]),
+            )
+            .unwrap();
+        assignments
+            .insert(
+                data_ty,
+                TypedAssigns::Structured(small_vec![
+                    Assign::revealed(seal(0), data[0].clone()),
+                    Assign::ConfidentialState {
+                        seal: seal(1),
+                        state: data[1].conceal(),
+                        lock: default!(),
+                    },
+                    Assign::revealed(seal(2), data[2].clone()).conceal(),
+                    Assign::revealed(seal(3), data[3].clone()),
+                    Assign::ConfidentialSeal {
+                        seal: seal(4).conceal(),
+                        state: data[4].clone(),
+                        lock: default!(),
+                    },
+                ]),
+            )
+            .unwrap();
+
+        assert_eq!(
+            assignments
+                .iter_revealed_of_type(data_ty)
+                .collect::<Vec<_>>(),
+            vec![
+                (0, &seal(0), AssignedState::Structured(&data[0])),
+                (3, &seal(3), AssignedState::Structured(&data[3])),
+            ]
+        );
+        assert_eq!(
+            assignments
+                .iter_revealed_of_type(rights_ty)
+                .collect::<Vec<_>>(),
+            vec![(0, &seal(10), AssignedState::Void)]
+        );
+        assert_eq!(
+            assignments
+                .iter_revealed_of_type(AssignmentType::with(3))
+                .count(),
+            0
+        );
+
+        assert_eq!(
+            assignments
+                .iter_concealed_of_type(data_ty)
+                .collect::<Vec<_>>(),
+            [1u16, 2, 4]
+                .into_iter()
+                .map(|no| {
+                    let state = ConcealedState::Structured(data[no as usize].conceal());
+                    (no, seal(no as u32).conceal(), state)
+                })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(assignments.iter_concealed_of_type(rights_ty).count(), 0);
+
+        assert_eq!(assignments.iter_seal_definitions().collect::<Vec<_>>(), vec![
+            (rights_ty, 0, Some(&seal(10))),
+            (data_ty, 0, Some(&seal(0))),
+            (data_ty, 1, Some(&seal(1))),
+            (data_ty, 2, None),
+            (data_ty, 3, Some(&seal(3))),
+            (data_ty, 4, None),
+        ]);
+    }
+
+    #[test]
+    fn revealed_state_ref() {
+        let value = RevealedValue::strict_dumb();
+        let data = RevealedData::strict_dumb();
+        let attach = RevealedAttach::strict_dumb();
+        assert_eq!(AssignedState::Void.state_type(), StateType::Void);
+        assert_eq!(AssignedState::Fungible(&value).state_type(), StateType::Fungible);
+        assert_eq!(AssignedState::Structured(&data).state_type(), StateType::Structured);
+        assert_eq!(AssignedState::Attachment(&attach).state_type(), StateType::Attachment);
+    }
+}
@@ -20,9 +20,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use std::{fmt, io};
 
 use amplify::{ByteArray, Bytes32};
 use baid64::{Baid64ParseError, DisplayBaid64, FromBaid64Str};
@@ -65,6 +65,52 @@ impl Display for AttachId {
 
 impl_serde_baid64!(AttachId);
 
+impl AttachId {
+    /// Size of the chunks in which [`AttachId::from_reader`] reads the data.
+    pub const CHUNK_LEN: usize = 64 * 1024;
+
+    /// Computes id of the attachment with the given content.
+    pub fn digest(data: impl AsRef<[u8]>) -> Self {
+        let mut hasher = Sha256::default();
+        hasher.input_raw(data.as_ref());
+        hasher.finish().into()
+    }
+
+    /// Computes id of the attachment content read from the `reader`.
+    ///
+    /// The content is hashed chunk by chunk, such that attachments of any size
+    /// can be processed without keeping them in memory.
+    pub fn from_reader(mut reader: impl io::Read) -> io::Result<Self> {
+        let mut hasher = Sha256::default();
+        let mut chunk = vec![0u8; Self::CHUNK_LEN];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(len) => hasher.input_raw(&chunk[..len]),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(hasher.finish().into())
+    }
+}
+
+/// Errors verifying attachment content against the contract state.
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum AttachError {
+    /// I/O error reading attachment content. Details: {0}
+    #[from]
+    Io(io::Error),
+
+    /// attachment content has id {actual} instead of the expected {expected}.
+    IdMismatch { expected: AttachId, actual: AttachId },
+
+    /// attachment {0} with the provided media type and salt doesn't match the
+    /// concealed state.
+    ConcealedMismatch(AttachId),
+}
+
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Display)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = LIB_NAME_RGB_COMMIT)]
@@ -133,6 +179,19 @@ impl RevealedAttach {
             salt,
         }
     }
+
+    /// Verifies that the attachment content read from the `reader` matches the
+    /// attachment id.
+    pub fn verify_stream(&self, reader: impl io::Read) -> Result<(), AttachError> {
+        let actual = AttachId::from_reader(reader)?;
+        if actual != self.file.id {
+            return Err(AttachError::IdMismatch {
+                expected: self.file.id,
+                actual,
+            });
+        }
+        Ok(())
+    }
 }
 
 impl ExposedState for RevealedAttach {
@@ -165,6 +224,27 @@ pub struct ConcealedAttach(
     Bytes32,
 );
 
+impl ConcealedAttach {
+    /// Verifies that the attachment content read from the `reader`, together
+    /// with the media type and salt, matches the concealed state.
+    ///
+    /// The content is hashed on the fly (see [`AttachId::from_reader`]).
+    /// Returns the revealed state on success.
+    pub fn verify_stream(
+        &self,
+        reader: impl io::Read,
+        media_type: impl Into<MediaType>,
+        salt: u64,
+    ) -> Result<RevealedAttach, AttachError> {
+        let id = AttachId::from_reader(reader)?;
+        let revealed = RevealedAttach::with_salt(id, media_type, salt);
+        if revealed.conceal() != *self {
+            return Err(AttachError::ConcealedMismatch(id));
+        }
+        Ok(revealed)
+    }
+}
+
 impl ConfidentialState for ConcealedAttach {
     fn state_type(&self) -> StateType { StateType::Attachment }
     fn state_commitment(&self) -> ConcealedState { ConcealedState::Attachment(*self) }
@@ -207,4 +287,37 @@ mod test {
             AttachId::from_str("rgb:fs:bGxsbGxs-bGxsbGx-sbGxsbG-xsbGxsb-GxsbGxs-bGxsbGw").unwrap()
         );
     }
+
+    #[test]
+    fn verify_stream() {
+        let data = (0..AttachId::CHUNK_LEN * 2 + 17)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let id = AttachId::digest(&data);
+        assert_eq!(AttachId::from_reader(data.as_slice()).unwrap(), id);
+
+        let revealed = RevealedAttach::with_salt(id, MediaType::Any, 0xA77A);
+        let concealed = revealed.conceal();
+        assert!(revealed.verify_stream(data.as_slice()).is_ok());
+        assert_eq!(
+            concealed
+                .verify_stream(data.as_slice(), MediaType::Any, 0xA77A)
+                .unwrap(),
+            revealed
+        );
+
+        let tampered = &data[1..];
+        assert!(matches!(
+            revealed.verify_stream(tampered),
+            Err(AttachError::IdMismatch { expected, .. }) if expected == id
+        ));
+        assert!(matches!(
+            concealed.verify_stream(tampered, MediaType::Any, 0xA77A),
+            Err(AttachError::ConcealedMismatch(_))
+        ));
+        assert!(matches!(
+            concealed.verify_stream(data.as_slice(), MediaType::Any, 0),
+            Err(AttachError::ConcealedMismatch(found)) if found == id
+        ));
+    }
 }
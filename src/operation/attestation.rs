@@ -0,0 +1,218 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Issuer attestation is a signature by the published identity key of the
+//! issuer, linking the key to the contract. It is embedded into the genesis as
+//! an auxiliary proof of the type defined by the schema (see
+//! [`crate::IssuerProofSchema`]).
+//!
+//! Since the auxiliary proofs are committed into the contract id, the issuer
+//! signs the id which the contract would have without the attestation.
+
+use amplify::confinement::{self, U16};
+use amplify::{Bytes64, Wrapper};
+use bp::secp256k1::{schnorr, Keypair, Message, SECP256K1};
+use bp::XOnlyPk;
+use commit_verify::{DigestExt, Sha256};
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+use crate::schema::AuxProofType;
+use crate::{AuxProof, ContractId, Genesis, Operation, LIB_NAME_RGB_COMMIT};
+
+/// Tag used for the domain separation of the issuer attestation messages.
+pub const ISSUER_ATTESTATION_TAG: &str = "urn:lnp-bp:rgb:issuer-attestation#2026-10-16";
+
+/// Errors verifying issuer attestation.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum IssuerAttestationError {
+    /// genesis doesn't contain issuer attestation.
+    Absent,
+
+    /// genesis contains multiple issuer attestations.
+    Multiple,
+
+    /// issuer attestation data can't be decoded.
+    Undecodable,
+
+    /// issuer attestation signature by {0} is invalid.
+    InvalidSignature(XOnlyPk),
+}
+
+/// BIP-340 signature by the issuer identity key over the attested contract id
+/// (see [`IssuerAttestation::attested_id`]).
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct IssuerAttestation {
+    pub issuer: XOnlyPk,
+    pub sig: Bytes64,
+}
+
+impl StrictSerialize for IssuerAttestation {}
+impl StrictDeserialize for IssuerAttestation {}
+
+impl IssuerAttestation {
+    /// Computes id of the contract defined by the genesis with all proofs of
+    /// the attestation `proof_type` removed.
+    pub fn attested_id(genesis: &Genesis, proof_type: AuxProofType) -> ContractId {
+        let mut genesis = genesis.clone();
+        genesis
+            .aux_proofs
+            .remove(&proof_type)
+            .expect("the map has no minimal size");
+        genesis.contract_id()
+    }
+
+    /// Computes message signed by the issuer of the contract with the given
+    /// attested id.
+    pub fn message(attested_id: ContractId) -> Message {
+        let mut hasher = Sha256::from_tag(ISSUER_ATTESTATION_TAG);
+        hasher.input_raw(attested_id.as_slice());
+        Message::from_digest(hasher.finish())
+    }
+
+    /// Signs the genesis with the issuer identity key. The genesis must not
+    /// yet contain the attestation.
+    pub fn sign(genesis: &Genesis, proof_type: AuxProofType, keypair: &Keypair) -> Self {
+        let msg = Self::message(Self::attested_id(genesis, proof_type));
+        let sig = SECP256K1.sign_schnorr(&msg, keypair);
+        IssuerAttestation {
+            issuer: XOnlyPk::from(keypair.x_only_public_key().0),
+            sig: Bytes64::from_byte_array(*sig.as_ref()),
+        }
+    }
+
+    /// Adds the attestation to the genesis auxiliary proofs.
+    pub fn embed(
+        &self,
+        genesis: &mut Genesis,
+        proof_type: AuxProofType,
+    ) -> Result<(), confinement::Error> {
+        let data = self
+            .to_strict_serialized::<U16>()
+            .expect("attestation has a fixed size");
+        genesis.aux_proofs.add_proof(proof_type, AuxProof::from(data))
+    }
+
+    /// Extracts the attestation from the genesis auxiliary proofs, without
+    /// verifying it.
+    pub fn extract(
+        genesis: &Genesis,
+        proof_type: AuxProofType,
+    ) -> Result<Self, IssuerAttestationError> {
+        let proofs = genesis
+            .aux_proofs
+            .get(&proof_type)
+            .ok_or(IssuerAttestationError::Absent)?;
+        let [proof] = proofs.as_slice() else {
+            return Err(IssuerAttestationError::Multiple);
+        };
+        Self::from_strict_serialized::<U16>(proof.to_inner())
+            .map_err(|_| IssuerAttestationError::Undecodable)
+    }
+
+    /// Verifies that the attestation is a valid signature of the genesis by
+    /// the issuer key.
+    pub fn verify(
+        &self,
+        genesis: &Genesis,
+        proof_type: AuxProofType,
+    ) -> Result<(), IssuerAttestationError> {
+        let msg = Self::message(Self::attested_id(genesis, proof_type));
+        schnorr::Signature::from_slice(self.sig.as_slice())
+            .and_then(|sig| SECP256K1.verify_schnorr(&sig, &msg, &self.issuer))
+            .map_err(|_| IssuerAttestationError::InvalidSignature(self.issuer))
+    }
+
+    /// Extracts the attestation from the genesis and verifies it, returning
+    /// the issuer key.
+    pub fn verify_genesis(
+        genesis: &Genesis,
+        proof_type: AuxProofType,
+    ) -> Result<XOnlyPk, IssuerAttestationError> {
+        let attestation = Self::extract(genesis, proof_type)?;
+        attestation.verify(genesis, proof_type)?;
+        Ok(attestation.issuer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bp::secp256k1::rand::thread_rng;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+
+    #[test]
+    fn attest_verify() {
+        let ty = AuxProofType::with(1);
+        let keypair = Keypair::new(SECP256K1, &mut thread_rng());
+        let issuer = XOnlyPk::from(keypair.x_only_public_key().0);
+        let mut genesis = Genesis::strict_dumb();
+        assert_eq!(
+            IssuerAttestation::verify_genesis(&genesis, ty),
+            Err(IssuerAttestationError::Absent)
+        );
+
+        let attested_id = genesis.contract_id();
+        let attestation = IssuerAttestation::sign(&genesis, ty, &keypair);
+        attestation.embed(&mut genesis, ty).unwrap();
+        assert_ne!(genesis.contract_id(), attested_id);
+        assert_eq!(IssuerAttestation::attested_id(&genesis, ty), attested_id);
+        assert_eq!(IssuerAttestation::extract(&genesis, ty), Ok(attestation));
+        assert_eq!(IssuerAttestation::verify_genesis(&genesis, ty), Ok(issuer));
+
+        let mut forged = genesis.clone();
+        forged.timestamp += 1;
+        assert_eq!(
+            IssuerAttestation::verify_genesis(&forged, ty),
+            Err(IssuerAttestationError::InvalidSignature(issuer))
+        );
+
+        attestation.embed(&mut genesis, ty).unwrap();
+        assert_eq!(
+            IssuerAttestation::verify_genesis(&genesis, ty),
+            Err(IssuerAttestationError::Multiple)
+        );
+
+        let mut genesis = Genesis::strict_dumb();
+        genesis
+            .aux_proofs
+            .add_proof(ty, AuxProof::strict_dumb())
+            .unwrap();
+        assert_eq!(
+            IssuerAttestation::verify_genesis(&genesis, ty),
+            Err(IssuerAttestationError::Undecodable)
+        );
+    }
+}
@@ -23,13 +23,14 @@
 use std::collections::{btree_map, BTreeMap};
 
 use amplify::confinement::{Confined, U16 as U16MAX};
-use amplify::{Bytes32, Wrapper};
+use amplify::{ByteArray, Bytes32, Wrapper};
+use baid64::{DisplayBaid64, FromBaid64Str};
 use bp::seals::txout::CloseMethod;
 use bp::Vout;
 use commit_verify::{mpc, CommitEncode, CommitEngine, CommitId, CommitmentId, DigestExt, Sha256};
-use strict_encoding::{StrictDumb, StrictEncode};
+use strict_encoding::{StrictDeserialize, StrictDumb, StrictEncode, StrictSerialize};
 
-use crate::{OpId, Transition, LIB_NAME_RGB_COMMIT};
+use crate::{BundleOps, CompactId, OpId, Transition, LIB_NAME_RGB_COMMIT};
 
 pub type Vin = Vout;
 
@@ -58,6 +59,17 @@ impl CommitmentId for BundleId {
     const TAG: &'static str = "urn:lnp-bp:rgb:bundle#2024-02-03";
 }
 
+impl DisplayBaid64 for BundleId {
+    const HRI: &'static str = "rgb:bdl";
+    const CHUNKING: bool = false;
+    const PREFIX: bool = true;
+    const EMBED_CHECKSUM: bool = false;
+    const MNEMONIC: bool = true;
+    fn to_baid64_payload(&self) -> [u8; 32] { self.to_byte_array() }
+}
+impl FromBaid64Str for BundleId {}
+impl CompactId for BundleId {}
+
 impl From<BundleId> for mpc::Message {
     fn from(id: BundleId) -> Self { mpc::Message::from_inner(id.into_inner()) }
 }
@@ -111,7 +123,7 @@ impl<'a> IntoIterator for &'a InputMap {
 pub struct TransitionBundle {
     pub close_method: CloseMethod,
     pub input_map: InputMap,
-    pub known_transitions: Confined<BTreeMap<OpId, Transition>, 1, U16MAX>,
+    pub known_transitions: BundleOps<Transition>,
 }
 
 impl CommitEncode for TransitionBundle {
@@ -123,6 +135,9 @@ impl CommitEncode for TransitionBundle {
     }
 }
 
+impl StrictSerialize for TransitionBundle {}
+impl StrictDeserialize for TransitionBundle {}
+
 impl StrictDumb for TransitionBundle {
     fn strict_dumb() -> Self {
         Self {
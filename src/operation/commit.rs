@@ -25,7 +25,7 @@ use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use std::{fmt, vec};
 
-use amplify::confinement::{Confined, MediumOrdMap, U16 as U16MAX};
+use amplify::confinement::{Confined, MediumOrdMap};
 use amplify::hex::{FromHex, ToHex};
 use amplify::num::u256;
 use amplify::{hex, ByteArray, Bytes32, FromSliceError, Wrapper};
@@ -34,14 +34,14 @@ use commit_verify::{
     mpc, CommitEncode, CommitEngine, CommitId, CommitmentId, Conceal, DigestExt, MerkleHash,
     MerkleLeaves, ReservedBytes, Sha256, StrictHash,
 };
-use strict_encoding::StrictDumb;
+use strict_encoding::{StrictDumb, StrictSerialize};
 
 use crate::{
-    impl_serde_baid64, Assign, AssignmentType, Assignments, BundleId, ConcealedAttach,
+    impl_serde_baid64, Assign, AssignmentType, Assignments, BundleId, CompactId, ConcealedAttach,
     ConcealedData, ConcealedState, ConfidentialState, DataState, ExposedSeal, ExposedState,
-    Extension, ExtensionType, Ffv, Genesis, GlobalState, GlobalStateType, Operation,
+    Extension, ExtensionType, Ffv, Genesis, GlobalState, GlobalStateType, Metadata, Operation,
     PedersenCommitment, Redeemed, SchemaId, SecretSeal, Transition, TransitionBundle,
-    TransitionType, TypedAssigns, XChain, LIB_NAME_RGB_COMMIT,
+    TransitionType, TypedAssigns, XChain, LIB_NAME_RGB_COMMIT, MAX_OPS_PER_BUNDLE,
 };
 
 /// Unique contract identifier equivalent to the contract genesis commitment
@@ -93,6 +93,8 @@ impl From<ContractId> for mpc::ProtocolId {
     fn from(id: ContractId) -> Self { mpc::ProtocolId::from_inner(id.into_inner()) }
 }
 
+impl CompactId for ContractId {}
+
 impl_serde_baid64!(ContractId);
 
 /// Unique operation (genesis, extensions & state transition) identifier
@@ -169,6 +171,64 @@ impl DiscloseHash {
     }
 }
 
+/// Digest of the contract metadata and global state defined by the genesis.
+///
+/// Can be used by asset registries and explorers to detect tampering with
+/// contract metadata (like asset name or ticker) without performing full
+/// consignment validation.
+#[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, From)]
+#[wrapper(Deref, BorrowSlice, Hex, Index, RangeOps)]
+#[display(Self::to_hex)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct MetadataDigest(
+    #[from]
+    #[from([u8; 32])]
+    Bytes32,
+);
+
+impl From<Sha256> for MetadataDigest {
+    fn from(hasher: Sha256) -> Self { hasher.finish().into() }
+}
+
+impl CommitmentId for MetadataDigest {
+    const TAG: &'static str = "urn:lnp-bp:rgb:genesis-metadata#2026-10-16";
+}
+
+impl FromStr for MetadataDigest {
+    type Err = hex::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::from_hex(s) }
+}
+
+/// Commitment to the genesis metadata and global state, producing
+/// [`MetadataDigest`].
+///
+/// Both metadata and global state are strict-serialized, which provides their
+/// canonical representation: the entries are ordered by their type, and the
+/// global state values of the same type retain the order defined by the
+/// genesis.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct GenesisMetadata<'genesis> {
+    pub schema_id: SchemaId,
+    pub metadata: &'genesis Metadata,
+    pub globals: &'genesis GlobalState,
+}
+
+impl CommitEncode for GenesisMetadata<'_> {
+    type CommitmentId = MetadataDigest;
+
+    fn commit_encode(&self, e: &mut CommitEngine) {
+        e.commit_to_serialized(&self.schema_id);
+        e.commit_to_serialized(self.metadata);
+        e.commit_to_serialized(self.globals);
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = LIB_NAME_RGB_COMMIT)]
@@ -201,7 +261,7 @@ pub struct OpDisclose {
 #[commit_encode(strategy = strict, id = DiscloseHash)]
 pub struct BundleDisclosure {
     pub id: BundleId,
-    pub known_transitions: Confined<BTreeSet<DiscloseHash>, 1, U16MAX>,
+    pub known_transitions: Confined<BTreeSet<DiscloseHash>, 1, MAX_OPS_PER_BUNDLE>,
 }
 
 impl StrictDumb for BundleDisclosure {
@@ -271,10 +331,60 @@ pub struct OpCommitment {
     pub redeemed: StrictHash,
     pub valencies: StrictHash,
     pub witness: MerkleHash,
-    pub validator: StrictHash,
+    pub aux_proofs: StrictHash,
+}
+
+impl OpCommitment {
+    /// Returns the exact data hashed into the operation id.
+    pub fn preimage(&self) -> CommitmentPreimage {
+        let data = self
+            .to_strict_serialized::<{ usize::MAX }>()
+            .expect("in-memory encoding")
+            .release();
+        CommitmentPreimage {
+            tag: OpId::TAG,
+            data,
+        }
+    }
+}
+
+impl StrictSerialize for OpCommitment {}
+
+/// Pre-image of an operation id, allowing external verification of the
+/// commitment scheme.
+///
+/// The id is the tagged SHA256 hash of the `data`, i.e.
+/// `SHA256(SHA256(tag) || SHA256(tag) || data)`, where `data` is the strict
+/// serialization of the [`OpCommitment`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CommitmentPreimage {
+    /// Tag of the tagged hash.
+    pub tag: &'static str,
+    /// Data hashed under the tag.
+    pub data: Vec<u8>,
+}
+
+impl CommitmentPreimage {
+    /// Computes the operation id from the pre-image.
+    pub fn op_id(&self) -> OpId {
+        let mut hasher = Sha256::from_tag(self.tag);
+        hasher.input_raw(&self.data);
+        OpId::from(hasher)
+    }
 }
 
 impl Genesis {
+    /// Computes digest of the contract metadata and global state defined by
+    /// the genesis. See [`MetadataDigest`] for the details.
+    pub fn metadata_digest(&self) -> MetadataDigest {
+        GenesisMetadata {
+            schema_id: self.schema_id,
+            metadata: &self.metadata,
+            globals: &self.globals,
+        }
+        .commit_id()
+    }
+
     pub fn commit(&self) -> OpCommitment {
         let base = BaseCommitment {
             flags: self.flags,
@@ -296,7 +406,7 @@ impl Genesis {
             redeemed: Redeemed::default().commit_id(),
             valencies: self.valencies.commit_id(),
             witness: MerkleHash::void(0, u256::ZERO),
-            validator: self.validator.commit_id(),
+            aux_proofs: self.aux_proofs.commit_id(),
         }
     }
 
@@ -316,7 +426,7 @@ impl Transition {
             redeemed: Redeemed::default().commit_id(),
             valencies: self.valencies.commit_id(),
             witness: MerkleHash::void(0, u256::ZERO),
-            validator: self.validator.commit_id(),
+            aux_proofs: self.aux_proofs.commit_id(),
         }
     }
 }
@@ -334,7 +444,7 @@ impl Extension {
             redeemed: self.redeemed.commit_id(),
             valencies: self.valencies.commit_id(),
             witness: MerkleHash::void(0, u256::ZERO),
-            validator: self.validator.commit_id(),
+            aux_proofs: self.aux_proofs.commit_id(),
         }
     }
 }
@@ -444,3 +554,68 @@ impl MerkleLeaves for GlobalState {
             .into_iter()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::{DataState, MetaType, MetaValue};
+
+    #[test]
+    fn metadata_digest() {
+        let mut genesis = Genesis::strict_dumb();
+        assert_eq!(
+            genesis.metadata_digest().to_string(),
+            "3a9140f1d6385f4aaac8fdb9303879b512a2472b8cc29079e7f79e5e50a2590a"
+        );
+
+        genesis
+            .metadata
+            .add_value(MetaType::with(1), MetaValue::from_hex("cafe").unwrap())
+            .unwrap();
+        let digest = genesis.metadata_digest();
+        assert_eq!(
+            digest.to_string(),
+            "f160ad45f72dc4eee2c153466be44f6b3441ff5bb1e2af8cd6fb6d13ad84fc09"
+        );
+        assert_eq!(MetadataDigest::from_str(&digest.to_string()).unwrap(), digest);
+
+        // Changes to the rest of the genesis must not affect the digest
+        genesis.timestamp = 1;
+        genesis.testnet = !genesis.testnet;
+        assert_eq!(genesis.metadata_digest(), digest);
+
+        let mut other = genesis.clone();
+        other.metadata = none!();
+        other
+            .metadata
+            .add_value(MetaType::with(1), MetaValue::from_hex("cafd").unwrap())
+            .unwrap();
+        assert_ne!(other.metadata_digest(), digest);
+
+        let mut other = genesis.clone();
+        other
+            .globals
+            .add_state(GlobalStateType::with(1), DataState::from_hex("cafe").unwrap())
+            .unwrap();
+        assert_ne!(other.metadata_digest(), digest);
+
+        let mut other = genesis.clone();
+        other.schema_id = SchemaId::from_byte_array([1u8; 32]);
+        assert_ne!(other.metadata_digest(), digest);
+    }
+
+    #[test]
+    fn commitment_preimage() {
+        let genesis = Genesis::strict_dumb();
+        let preimage = genesis.commitment_preimage();
+        assert_eq!(preimage.tag, OpId::TAG);
+        assert_eq!(preimage.op_id(), genesis.id());
+
+        let mut other = genesis.clone();
+        other.timestamp = 1;
+        assert_ne!(other.commitment_preimage().data, preimage.data);
+        assert_eq!(other.commitment_preimage().op_id(), other.id());
+    }
+}
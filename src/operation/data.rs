@@ -29,6 +29,9 @@ use amplify::{Bytes32, Wrapper};
 use bp::secp256k1::rand::{random, Rng, RngCore};
 use commit_verify::{CommitId, CommitmentId, Conceal, DigestExt, Sha256};
 use strict_encoding::{StrictSerialize, StrictType};
+use strict_types::decode::Error as DecodeError;
+use strict_types::value::{EnumTag, StrictNum};
+use strict_types::{SemId, StrictVal, TypeSystem};
 
 use super::{ConfidentialState, ExposedState};
 use crate::{ConcealedState, RevealedState, StateType, LIB_NAME_RGB_COMMIT};
@@ -122,6 +125,19 @@ impl RevealedData {
             salt,
         }
     }
+
+    /// Decodes the state data according to the semantic type from the type
+    /// system, which is usually the type declared for the state in the schema.
+    ///
+    /// Fails in the same cases in which the validator reports the state as not
+    /// matching its semantic type.
+    pub fn interpret(&self, types: &TypeSystem, sem_id: SemId) -> Result<TypedValue, StateDataError> {
+        let value = types
+            .strict_deserialize_type(sem_id, self.value.as_slice())
+            .map_err(|err| StateDataError::InvalidValue(sem_id, err))?
+            .unbox();
+        Ok(TypedValue { sem_id, value })
+    }
 }
 
 impl ExposedState for RevealedData {
@@ -160,6 +176,93 @@ impl Debug for RevealedData {
     }
 }
 
+/// Errors interpreting structured state data.
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum StateDataError {
+    /// state data do not match semantic type id {0}. Details: {1}
+    InvalidValue(SemId, DecodeError),
+
+    /// state data of semantic type {0} can't be interpreted as {1}.
+    TypeMismatch(SemId, &'static str),
+}
+
+/// Structured state data decoded according to its semantic type (see
+/// [`RevealedData::interpret`]).
+///
+/// Typed getters see through newtype wrappers, such that a value of a schema
+/// type like `Price(U64)` or `Percent(U8)` can be read as a plain integer.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TypedValue {
+    sem_id: SemId,
+    value: StrictVal,
+}
+
+impl TypedValue {
+    /// Semantic type of the value.
+    pub fn sem_id(&self) -> SemId { self.sem_id }
+
+    /// Decoded value.
+    pub fn as_val(&self) -> &StrictVal { &self.value }
+
+    /// Releases the decoded value.
+    pub fn unbox(self) -> StrictVal { self.value }
+
+    fn mismatch(&self, expected: &'static str) -> StateDataError {
+        StateDataError::TypeMismatch(self.sem_id, expected)
+    }
+
+    /// Interprets the value as an unsigned integer.
+    pub fn to_u64(&self) -> Result<u64, StateDataError> {
+        match self.value.skip_wrapper() {
+            StrictVal::Number(StrictNum::Uint(val)) => {
+                u64::try_from(*val).map_err(|_| self.mismatch("u64"))
+            }
+            _ => Err(self.mismatch("u64")),
+        }
+    }
+
+    /// Interprets the value as a signed integer.
+    pub fn to_i64(&self) -> Result<i64, StateDataError> {
+        match self.value.skip_wrapper() {
+            StrictVal::Number(StrictNum::Int(val)) => {
+                i64::try_from(*val).map_err(|_| self.mismatch("i64"))
+            }
+            StrictVal::Number(StrictNum::Uint(val)) => {
+                i64::try_from(*val).map_err(|_| self.mismatch("i64"))
+            }
+            _ => Err(self.mismatch("i64")),
+        }
+    }
+
+    /// Interprets the value as a boolean.
+    pub fn to_bool(&self) -> Result<bool, StateDataError> {
+        match self.value.skip_wrapper() {
+            StrictVal::Enum(EnumTag::Ord(0)) => Ok(false),
+            StrictVal::Enum(EnumTag::Ord(1)) => Ok(true),
+            StrictVal::Enum(EnumTag::Name(name)) if name.as_str() == "false" => Ok(false),
+            StrictVal::Enum(EnumTag::Name(name)) if name.as_str() == "true" => Ok(true),
+            _ => Err(self.mismatch("bool")),
+        }
+    }
+
+    /// Interprets the value as a string.
+    pub fn as_str(&self) -> Result<&str, StateDataError> {
+        match self.value.skip_wrapper() {
+            StrictVal::String(s) => Ok(s),
+            _ => Err(self.mismatch("string")),
+        }
+    }
+
+    /// Interprets the value as a byte string.
+    pub fn as_bytes(&self) -> Result<&[u8], StateDataError> {
+        match self.value.skip_wrapper() {
+            StrictVal::Bytes(bytes) => Ok(bytes),
+            _ => Err(self.mismatch("bytes")),
+        }
+    }
+}
+
 /// Confidential version of an structured state data.
 ///
 /// See also revealed version [`RevealedData`].
@@ -190,3 +293,33 @@ impl From<Sha256> for ConcealedData {
 impl CommitmentId for ConcealedData {
     const TAG: &'static str = "urn:lnp-bp:rgb:state-data#2024-02-12";
 }
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+    use strict_types::Ty;
+
+    use super::*;
+
+    #[test]
+    fn interpret_u64() {
+        let ty = Ty::<SemId>::U64;
+        let sem_id = ty.sem_id_unnamed();
+        let types = TypeSystem::from(Confined::from_checked(bmap! { sem_id => ty }));
+
+        let blob = SmallBlob::from_checked(100500u64.to_le_bytes().to_vec());
+        let data = RevealedData::with_salt(blob, 0);
+        let value = data.interpret(&types, sem_id).unwrap();
+        assert_eq!(value.sem_id(), sem_id);
+        assert_eq!(value.to_u64(), Ok(100500));
+        assert_eq!(value.to_i64(), Ok(100500));
+        assert_eq!(value.to_bool(), Err(StateDataError::TypeMismatch(sem_id, "bool")));
+        assert!(value.as_str().is_err());
+
+        let data = RevealedData::with_salt(SmallBlob::from_checked(vec![1u8; 4]), 0);
+        assert!(matches!(
+            data.interpret(&types, sem_id),
+            Err(StateDataError::InvalidValue(id, _)) if id == sem_id
+        ));
+    }
+}
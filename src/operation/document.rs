@@ -0,0 +1,121 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Notarization of external documents with the global state of
+//! [`crate::GlobalStateKind::Document`] kind.
+
+use amplify::confinement::U16 as U16MAX;
+use amplify::hex::ToHex;
+use amplify::{Bytes32, Wrapper};
+use strict_encoding::{DeserializeError, SerializeError, StrictDeserialize, StrictSerialize};
+
+use crate::{DataState, LIB_NAME_RGB_COMMIT};
+
+/// Digest of a notarized external document.
+#[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, From)]
+#[wrapper(Deref, BorrowSlice, Hex, Index, RangeOps)]
+#[display(Self::to_hex)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct DocumentDigest(
+    #[from]
+    #[from([u8; 32])]
+    Bytes32,
+);
+
+/// Revision of a notarized external document.
+///
+/// The document itself is not a part of the contract; only its digest is
+/// committed to. A new revision of the document references the digest of the
+/// previous revision, such that revisions form a chain starting from the
+/// revision which doesn't have a previous one.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct DocumentRevision {
+    /// Digest of the document revision.
+    pub digest: DocumentDigest,
+    /// Digest of the previous revision of the same document, if any.
+    pub prev: Option<DocumentDigest>,
+}
+
+impl StrictSerialize for DocumentRevision {}
+impl StrictDeserialize for DocumentRevision {}
+
+impl DocumentRevision {
+    /// Constructs the first revision of a document.
+    pub fn new(digest: impl Into<DocumentDigest>) -> Self {
+        DocumentRevision {
+            digest: digest.into(),
+            prev: None,
+        }
+    }
+
+    /// Constructs next revision of the same document.
+    pub fn revise(&self, digest: impl Into<DocumentDigest>) -> Self {
+        DocumentRevision {
+            digest: digest.into(),
+            prev: Some(self.digest),
+        }
+    }
+
+    /// Detects whether the revision is the first one in its chain.
+    pub fn is_initial(&self) -> bool { self.prev.is_none() }
+
+    /// Parses document revision from the global state data.
+    pub fn from_data(data: &DataState) -> Result<Self, DeserializeError> {
+        Self::from_strict_serialized::<U16MAX>(data.as_inner().clone())
+    }
+
+    /// Serializes document revision into the global state data.
+    pub fn to_data(&self) -> Result<DataState, SerializeError> {
+        self.to_strict_serialized::<U16MAX>().map(DataState::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn data_roundtrip() {
+        let first = DocumentRevision::new([1u8; 32]);
+        let second = first.revise([2u8; 32]);
+        assert!(first.is_initial());
+        assert_eq!(second.prev, Some(first.digest));
+
+        for revision in [first, second] {
+            let data = revision.to_data().unwrap();
+            assert_eq!(DocumentRevision::from_data(&data).unwrap(), revision);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Endorsements are signatures of an operation id by the keys declared in the
+//! contract genesis. They are not a part of the operation commitment, and are
+//! required by the schema for specific state transition types.
+
+use amplify::confinement::{self, SmallOrdMap};
+use amplify::{Bytes64, Wrapper};
+use bp::secp256k1::{schnorr, Keypair, Message, SECP256K1};
+use bp::XOnlyPk;
+use commit_verify::{DigestExt, Sha256};
+
+use crate::{OpId, LIB_NAME_RGB_COMMIT};
+
+/// Tag used for the domain separation of the endorsed messages.
+pub const ENDORSEMENT_TAG: &str = "urn:lnp-bp:rgb:endorsement#2026-10-16";
+
+/// Errors verifying operation endorsements.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum EndorsementError {
+    /// operation is endorsed by {0} which is not a known endorser.
+    UnknownEndorser(XOnlyPk),
+
+    /// endorsement signature by {0} is invalid.
+    InvalidSignature(XOnlyPk),
+}
+
+/// Set of endorsements of an operation, mapping each endorser key to its
+/// BIP-340 signature.
+#[derive(Wrapper, WrapperMut, Clone, PartialEq, Eq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct Endorsements(SmallOrdMap<XOnlyPk, Bytes64>);
+
+impl Endorsements {
+    /// Computes message signed by endorsers of the operation with the given
+    /// id.
+    pub fn message(opid: OpId) -> Message {
+        let mut hasher = Sha256::from_tag(ENDORSEMENT_TAG);
+        hasher.input_raw(opid.as_slice());
+        Message::from_digest(hasher.finish())
+    }
+
+    /// Signs operation with the provided endorser key, adding the signature to
+    /// the set of endorsements.
+    pub fn endorse(&mut self, opid: OpId, keypair: &Keypair) -> Result<(), confinement::Error> {
+        let sig = SECP256K1.sign_schnorr(&Self::message(opid), keypair);
+        let endorser = XOnlyPk::from(keypair.x_only_public_key().0);
+        self.0
+            .insert(endorser, Bytes64::from_byte_array(*sig.as_ref()))
+            .map(|_| ())
+    }
+
+    /// Verifies that all endorsements are valid signatures of the operation
+    /// made by some of the `endorsers`.
+    pub fn verify(&self, opid: OpId, endorsers: &[XOnlyPk]) -> Result<(), EndorsementError> {
+        let msg = Self::message(opid);
+        for (endorser, sig) in &self.0 {
+            if !endorsers.contains(endorser) {
+                return Err(EndorsementError::UnknownEndorser(*endorser));
+            }
+            schnorr::Signature::from_slice(sig.as_slice())
+                .and_then(|sig| SECP256K1.verify_schnorr(&sig, &msg, endorser))
+                .map_err(|_| EndorsementError::InvalidSignature(*endorser))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::ByteArray;
+    use bp::secp256k1::rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn endorse_verify() {
+        let keypair = Keypair::new(SECP256K1, &mut thread_rng());
+        let other = Keypair::new(SECP256K1, &mut thread_rng());
+        let endorser = XOnlyPk::from(keypair.x_only_public_key().0);
+        let opid = OpId::from_byte_array([0xAD; 32]);
+
+        let mut endorsements = Endorsements::default();
+        endorsements.endorse(opid, &keypair).unwrap();
+        assert_eq!(endorsements.verify(opid, &[endorser]), Ok(()));
+        assert_eq!(
+            endorsements.verify(OpId::from_byte_array([0xAE; 32]), &[endorser]),
+            Err(EndorsementError::InvalidSignature(endorser))
+        );
+
+        endorsements.endorse(opid, &other).unwrap();
+        let unknown = XOnlyPk::from(other.x_only_public_key().0);
+        assert_eq!(
+            endorsements.verify(opid, &[endorser]),
+            Err(EndorsementError::UnknownEndorser(unknown))
+        );
+    }
+}
@@ -33,7 +33,7 @@
 use core::cmp::Ordering;
 use core::fmt::Debug;
 use core::num::ParseIntError;
-use core::ops::Deref;
+use core::ops::{Deref, RangeInclusive};
 use core::str::FromStr;
 use std::hash::Hash;
 use std::io;
@@ -148,6 +148,79 @@ impl FungibleState {
     }
 
     pub fn as_u64(&self) -> u64 { (*self).into() }
+
+    /// Returns decimal order of magnitude of the value.
+    pub fn magnitude(&self) -> Magnitude { Magnitude::of(*self) }
+}
+
+/// Decimal order of magnitude of a fungible value, i.e. the number of decimal
+/// digits in its integer representation (zero for the zero value).
+///
+/// Used for revealing approximate amounts while keeping the exact value
+/// concealed (see [`PartiallyConcealedValue`]).
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, Default)]
+#[display("{0} digits")]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", try_from = "u8", into = "u8")
+)]
+pub struct Magnitude(u8);
+
+/// decimal order of magnitude {0} exceeds the maximal order of magnitude for
+/// 64-bit values.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Display, Error)]
+#[display(doc_comments)]
+pub struct MagnitudeOverflow(u8);
+
+impl TryFrom<u8> for Magnitude {
+    type Error = MagnitudeOverflow;
+    fn try_from(digits: u8) -> Result<Self, Self::Error> {
+        if digits > Self::MAX.0 {
+            return Err(MagnitudeOverflow(digits));
+        }
+        Ok(Self(digits))
+    }
+}
+
+impl From<Magnitude> for u8 {
+    fn from(magnitude: Magnitude) -> Self { magnitude.0 }
+}
+
+impl Magnitude {
+    /// Order of magnitude of [`u64::MAX`].
+    pub const MAX: Magnitude = Magnitude(20);
+
+    /// Detects order of magnitude for a given value.
+    pub fn of(value: impl Into<FungibleState>) -> Self {
+        match value.into().as_u64() {
+            0 => Self(0),
+            value => Self(value.ilog10() as u8 + 1),
+        }
+    }
+
+    /// Number of decimal digits in the values of this order of magnitude.
+    pub const fn digits(self) -> u8 { self.0 }
+
+    /// Minimal value having this order of magnitude.
+    pub fn min_value(self) -> u64 {
+        match self.0 {
+            0 => 0,
+            digits => 10u64.pow(digits as u32 - 1),
+        }
+    }
+
+    /// Maximal value having this order of magnitude.
+    pub fn max_value(self) -> u64 {
+        match self.0 {
+            0 => 0,
+            digits if digits >= Self::MAX.0 => u64::MAX,
+            digits => 10u64.pow(digits as u32) - 1,
+        }
+    }
+
+    /// Checks whether the value belongs to this order of magnitude.
+    pub fn contains(self, value: impl Into<FungibleState>) -> bool { Self::of(value) == self }
 }
 
 /// value provided for a blinding factor overflows prime field order for
@@ -171,6 +244,28 @@ pub enum BlindingParseError {
     InvalidFieldElement,
 }
 
+/// Errors computing blinding factor balancing amounts of a state transition.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum BlindingCorrectionError {
+    /// no input state is provided for the blinding factor correction.
+    NoInputs,
+
+    /// no output amounts are provided for the split.
+    NoOutputs,
+
+    /// fungible state with different asset tags can't be balanced.
+    AssetTagMismatch,
+
+    /// sum of output amounts {outputs} exceeds sum of input amounts {inputs}.
+    AmountMismatch { inputs: u128, outputs: u128 },
+
+    /// blinding factors of the provided state can't be balanced, since they
+    /// are either already balanced or some of them are inverses of the others.
+    #[from(InvalidFieldElement)]
+    Unbalanceable,
+}
+
 /// Blinding factor used in creating Pedersen commitment to an [`AtomicValue`].
 ///
 /// Knowledge of the blinding factor is important to reproduce the commitment
@@ -252,6 +347,32 @@ impl BlindingFactor {
         Ok(blinding_correction.into())
     }
 
+    /// Computes blinding factor for the closing output of a state transition,
+    /// making the sum of Pedersen commitments of the `inputs` equal to the sum
+    /// of commitments of the `outputs` and the closing output.
+    ///
+    /// # Errors
+    ///
+    /// * if `inputs` are empty;
+    /// * if the provided state uses different asset tags;
+    /// * if the blinding factors can't be balanced (see [`Self::zero_balanced`]).
+    pub fn zero_sum_correction<'state>(
+        inputs: impl IntoIterator<Item = &'state RevealedValue>,
+        outputs: impl IntoIterator<Item = &'state RevealedValue>,
+    ) -> Result<Self, BlindingCorrectionError> {
+        let inputs = inputs.into_iter().collect::<Vec<_>>();
+        let outputs = outputs.into_iter().collect::<Vec<_>>();
+        let tag = inputs.first().ok_or(BlindingCorrectionError::NoInputs)?.tag;
+        if inputs.iter().chain(&outputs).any(|state| state.tag != tag) {
+            return Err(BlindingCorrectionError::AssetTagMismatch);
+        }
+        Self::zero_balanced(
+            inputs.into_iter().map(|state| state.blinding),
+            outputs.into_iter().map(|state| state.blinding),
+        )
+        .map_err(BlindingCorrectionError::from)
+    }
+
     fn to_secret_key(self) -> secp256k1_zkp::SecretKey {
         secp256k1_zkp::SecretKey::from_slice(self.0.as_slice())
             .expect("blinding factor is an invalid secret key")
@@ -311,6 +432,71 @@ impl RevealedValue {
         Self::with_blinding(value, BlindingFactor::random_custom(rng), tag)
     }
 
+    /// Constructs the closing output of a state transition, which receives the
+    /// change left after spending `inputs` into `outputs`. The blinding factor
+    /// is computed such that the transition amounts balance (see
+    /// [`BlindingFactor::zero_sum_correction`]).
+    pub fn closing<'state>(
+        inputs: impl IntoIterator<Item = &'state RevealedValue>,
+        outputs: impl IntoIterator<Item = &'state RevealedValue>,
+    ) -> Result<Self, BlindingCorrectionError> {
+        let inputs = inputs.into_iter().collect::<Vec<_>>();
+        let outputs = outputs.into_iter().collect::<Vec<_>>();
+        let blinding =
+            BlindingFactor::zero_sum_correction(inputs.iter().copied(), outputs.iter().copied())?;
+        let sum = |state: &[&RevealedValue]| -> u128 {
+            state.iter().map(|s| s.value.as_u64() as u128).sum()
+        };
+        let (sum_inputs, sum_outputs) = (sum(&inputs), sum(&outputs));
+        let change = sum_inputs
+            .checked_sub(sum_outputs)
+            .and_then(|change| u64::try_from(change).ok())
+            .ok_or(BlindingCorrectionError::AmountMismatch {
+                inputs: sum_inputs,
+                outputs: sum_outputs,
+            })?;
+        Ok(Self::with_blinding(change, blinding, inputs[0].tag))
+    }
+
+    /// Splits the state into outputs with the provided `amounts`, which must
+    /// sum up to the value of the state. All outputs except the last one get
+    /// random blinding factors; the blinding factor of the last output is
+    /// computed such that the commitments of the outputs sum up to the
+    /// commitment of the state.
+    pub fn split(
+        &self,
+        amounts: impl IntoIterator<Item = impl Into<FungibleState>>,
+    ) -> Result<Vec<Self>, BlindingCorrectionError> {
+        let amounts = amounts.into_iter().map(Into::into).collect::<Vec<_>>();
+        let (last, rest) = amounts
+            .split_last()
+            .ok_or(BlindingCorrectionError::NoOutputs)?;
+        let sum = amounts.iter().map(|a| a.as_u64() as u128).sum::<u128>();
+        if sum != self.value.as_u64() as u128 {
+            return Err(BlindingCorrectionError::AmountMismatch {
+                inputs: self.value.as_u64() as u128,
+                outputs: sum,
+            });
+        }
+        let mut outputs = rest
+            .iter()
+            .map(|amount| Self::new_random_blinding(*amount, self.tag))
+            .collect::<Vec<_>>();
+        let closing = Self::closing([self], &outputs)?;
+        debug_assert_eq!(closing.value, *last);
+        outputs.push(closing);
+        Ok(outputs)
+    }
+
+    /// Merges multiple states into a single one, with the value equal to the
+    /// sum of the values and the commitment equal to the sum of the
+    /// commitments of the merged states.
+    pub fn merge<'state>(
+        states: impl IntoIterator<Item = &'state RevealedValue>,
+    ) -> Result<Self, BlindingCorrectionError> {
+        Self::closing(states, [])
+    }
+
     /// Convenience constructor.
     pub fn with_blinding(
         value: impl Into<FungibleState>,
@@ -456,7 +642,7 @@ impl StrictEncode for RangeProof {
 
 impl StrictDecode for RangeProof {
     fn strict_decode(_: &mut impl TypedRead) -> Result<Self, DecodeError> {
-        panic!("bulletproofs dummies must never be read")
+        Err(DecodeError::DataIntegrityError(s!("bulletproofs dummies must never be read")))
     }
 }
 
@@ -505,6 +691,13 @@ impl CommitVerify<RevealedValue, PedersenProtocol> for ConcealedValue {
 
 /// Errors verifying range proofs.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Display, Error)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = order, dumb = Self::BulletproofsAbsent)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
 #[display(doc_comments)]
 pub enum RangeProofError {
     /// invalid blinding factor {0}.
@@ -524,6 +717,82 @@ impl ConcealedValue {
     }
 }
 
+/// Errors verifying revealed value against its partially concealed form.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum PartialRevealError {
+    /// revealed value doesn't match the Pedersen commitment.
+    CommitmentMismatch,
+
+    /// revealed value has {found} while the partially concealed value claims
+    /// {claimed}.
+    MagnitudeMismatch {
+        claimed: Magnitude,
+        found: Magnitude,
+    },
+}
+
+/// Fungible value with the exact amount kept concealed, but its decimal order
+/// of magnitude revealed.
+///
+/// Allows to show the receiver approximate amount before the full reveal. The
+/// range proof of the concealed value must prove that the committed value
+/// belongs to the revealed order of magnitude.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct PartiallyConcealedValue {
+    /// Concealed value.
+    pub concealed: ConcealedValue,
+    /// Revealed order of magnitude of the value.
+    pub magnitude: Magnitude,
+}
+
+impl PartiallyConcealedValue {
+    /// Range of the values which may be committed to.
+    pub fn value_range(&self) -> RangeInclusive<u64> {
+        self.magnitude.min_value()..=self.magnitude.max_value()
+    }
+
+    /// Verifies that the range proof restricts the committed value to the
+    /// revealed order of magnitude.
+    pub fn verify_range_proof(&self) -> Result<bool, RangeProofError> {
+        // Range proofs are not yet supported, so we fail the same way as for
+        // fully concealed values
+        self.concealed.verify_range_proof()
+    }
+
+    /// Verifies that the fully revealed value matches both the commitment and
+    /// the claimed order of magnitude.
+    pub fn verify_reveal(&self, revealed: &RevealedValue) -> Result<(), PartialRevealError> {
+        if ConcealedValue::commit(revealed) != self.concealed {
+            return Err(PartialRevealError::CommitmentMismatch);
+        }
+        let found = revealed.value.magnitude();
+        if found != self.magnitude {
+            return Err(PartialRevealError::MagnitudeMismatch {
+                claimed: self.magnitude,
+                found,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl RevealedValue {
+    /// Conceals the exact value, keeping its decimal order of magnitude
+    /// revealed.
+    pub fn conceal_partially(&self) -> PartiallyConcealedValue {
+        PartiallyConcealedValue {
+            concealed: self.conceal(),
+            magnitude: self.value.magnitude(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use amplify::ByteArray;
@@ -633,4 +902,105 @@ mod test {
 
         assert!(secp256k1_zkp::verify_commitments_sum_to_equal(SECP256K1, &[a, b], &[c, d]))
     }
+
+    #[test]
+    fn closing_output() {
+        let tag = AssetTag::from_byte_array([1u8; 32]);
+        let inputs = [
+            RevealedValue::new_random_blinding(15, tag),
+            RevealedValue::new_random_blinding(7, tag),
+        ];
+        let outputs = [RevealedValue::new_random_blinding(13, tag)];
+
+        let closing = RevealedValue::closing(&inputs, &outputs).unwrap();
+        assert_eq!(closing.value.as_u64(), 9);
+
+        let commit = |state: &RevealedValue| PedersenCommitment::commit(state).into_inner();
+        let a = inputs.iter().map(commit).collect::<Vec<_>>();
+        let b = outputs
+            .iter()
+            .chain([&closing])
+            .map(commit)
+            .collect::<Vec<_>>();
+        assert!(secp256k1_zkp::verify_commitments_sum_to_equal(SECP256K1, &a, &b));
+
+        assert_eq!(
+            RevealedValue::closing(&outputs, &inputs),
+            Err(BlindingCorrectionError::AmountMismatch {
+                inputs: 13,
+                outputs: 22
+            })
+        );
+        assert_eq!(
+            BlindingFactor::zero_sum_correction([], &outputs),
+            Err(BlindingCorrectionError::NoInputs)
+        );
+        let other = [RevealedValue::new_random_blinding(13, AssetTag::from_byte_array([2u8; 32]))];
+        assert_eq!(
+            BlindingFactor::zero_sum_correction(&inputs, &other),
+            Err(BlindingCorrectionError::AssetTagMismatch)
+        );
+    }
+
+    #[test]
+    fn magnitude() {
+        assert_eq!(Magnitude::of(0u64).digits(), 0);
+        assert_eq!(Magnitude::of(9u64).digits(), 1);
+        assert_eq!(Magnitude::of(10u64).digits(), 2);
+        assert_eq!(Magnitude::of(u64::MAX), Magnitude::MAX);
+        assert_eq!(Magnitude::MAX.max_value(), u64::MAX);
+        assert_eq!(Magnitude::try_from(21), Err(MagnitudeOverflow(21)));
+
+        let m = Magnitude::of(1234u64);
+        assert_eq!((m.min_value(), m.max_value()), (1000, 9999));
+        assert!(m.contains(1000u64) && m.contains(9999u64));
+        assert!(!m.contains(999u64) && !m.contains(10000u64));
+    }
+
+    #[test]
+    fn partial_reveal() {
+        let tag = AssetTag::from_byte_array([1u8; 32]);
+        let revealed = RevealedValue::new_random_blinding(1234, tag);
+        let partial = revealed.conceal_partially();
+        assert_eq!(partial.value_range(), 1000..=9999);
+        assert_eq!(partial.verify_reveal(&revealed), Ok(()));
+
+        let other = RevealedValue::with_blinding(1235, revealed.blinding, tag);
+        assert_eq!(partial.verify_reveal(&other), Err(PartialRevealError::CommitmentMismatch));
+
+        let mut forged = revealed.conceal_partially();
+        forged.magnitude = Magnitude::try_from(5).unwrap();
+        assert_eq!(
+            forged.verify_reveal(&revealed),
+            Err(PartialRevealError::MagnitudeMismatch {
+                claimed: forged.magnitude,
+                found: partial.magnitude
+            })
+        );
+    }
+
+    #[test]
+    fn split_merge() {
+        let tag = AssetTag::from_byte_array([1u8; 32]);
+        let commit = |state: &RevealedValue| PedersenCommitment::commit(state).into_inner();
+
+        let input = RevealedValue::new_random_blinding(22, tag);
+        let outputs = input.split([10u64, 5, 7]).unwrap();
+        assert_eq!(outputs.iter().map(|o| o.value.as_u64()).collect::<Vec<_>>(), vec![10, 5, 7]);
+        let b = outputs.iter().map(commit).collect::<Vec<_>>();
+        assert!(secp256k1_zkp::verify_commitments_sum_to_equal(SECP256K1, &[commit(&input)], &b));
+
+        let merged = RevealedValue::merge(&outputs).unwrap();
+        assert_eq!(merged.value.as_u64(), 22);
+        assert!(secp256k1_zkp::verify_commitments_sum_to_equal(SECP256K1, &b, &[commit(&merged)]));
+
+        assert_eq!(
+            input.split([10u64, 5]),
+            Err(BlindingCorrectionError::AmountMismatch {
+                inputs: 22,
+                outputs: 15
+            })
+        );
+        assert_eq!(input.split(Vec::<u64>::new()), Err(BlindingCorrectionError::NoOutputs));
+    }
 }
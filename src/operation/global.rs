@@ -23,11 +23,11 @@
 use std::collections::btree_map;
 use std::vec;
 
-use amplify::confinement::{Confined, TinyOrdMap, U16};
+use amplify::confinement::{Confined, TinyOrdMap};
 use amplify::{confinement, Wrapper};
 use strict_encoding::StrictDumb;
 
-use crate::{schema, DataState, LIB_NAME_RGB_COMMIT};
+use crate::{schema, DataState, GlobalList, LIB_NAME_RGB_COMMIT};
 
 #[derive(Wrapper, WrapperMut, Clone, PartialEq, Eq, Hash, Debug, From)]
 #[wrapper(Deref)]
@@ -39,7 +39,7 @@ use crate::{schema, DataState, LIB_NAME_RGB_COMMIT};
     derive(Serialize, Deserialize),
     serde(crate = "serde_crate", transparent)
 )]
-pub struct GlobalValues(Confined<Vec<DataState>, 1, U16>);
+pub struct GlobalValues(GlobalList<DataState>);
 
 impl StrictDumb for GlobalValues {
     fn strict_dumb() -> Self { Self(Confined::with(DataState::strict_dumb())) }
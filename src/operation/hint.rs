@@ -0,0 +1,181 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Seal hints allow a receiver to test whether a consignment assigns state to
+//! one of its concealed seals without validating the consignment.
+//!
+//! A hint is a truncated HMAC-SHA256 of the concealed seal under a key which
+//! the receiver shares with the sender (for instance, as a part of an invoice).
+//! Senders put the hints into the consignment [`Annotations`]; since the hints
+//! are keyed, third parties can't link them to the seals.
+
+use amplify::confinement::{self, SmallBlob};
+use amplify::{ByteArray, Bytes, Bytes32};
+use bp::secp256k1::rand::random;
+use commit_verify::{DigestExt, Sha256};
+use strict_encoding::{FieldName, StreamWriter, StrictEncode};
+
+use crate::{Annotations, MetaValue, SecretSeal, XChain};
+
+/// Name of the annotation containing seal hints.
+pub const SEAL_HINTS_ANNOTATION: &str = "sealHints";
+
+const HMAC_BLOCK_LEN: usize = 64;
+
+fn seal_hints_key() -> FieldName { FieldName::from(SEAL_HINTS_ANNOTATION) }
+
+/// HMAC-SHA256 (RFC 2104) with a key shorter than the hash block.
+fn hmac(key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let mut pad = [0u8; HMAC_BLOCK_LEN];
+    pad[..32].copy_from_slice(key);
+    let keyed = |byte: u8| {
+        let mut engine = Sha256::default();
+        engine.input_raw(&pad.map(|b| b ^ byte));
+        engine
+    };
+    let mut inner = keyed(0x36);
+    inner.input_raw(data);
+    let mut outer = keyed(0x5c);
+    outer.input_raw(&inner.finish());
+    outer.finish()
+}
+
+/// Secret key of the receiver used to produce and match seal hints.
+#[derive(Wrapper, Copy, Clone, Eq, PartialEq, Hash, Debug, From)]
+#[wrapper(Deref, BorrowSlice, Hex, Index, RangeOps)]
+pub struct SealHintKey(
+    #[from]
+    #[from([u8; 32])]
+    Bytes32,
+);
+
+impl SealHintKey {
+    /// Generates new random key.
+    pub fn random() -> Self { Self(Bytes32::from_byte_array(random::<[u8; 32]>())) }
+
+    /// Produces hint for the concealed seal.
+    pub fn hint(&self, seal: XChain<SecretSeal>) -> SealHint {
+        let mut data = vec![];
+        seal.strict_write(StreamWriter::new::<{ usize::MAX }>(&mut data))
+            .expect("in-memory encoding");
+        let mac = hmac(&self.0.to_byte_array(), &data);
+
+        let mut hint = [0u8; SealHint::LEN];
+        hint.copy_from_slice(&mac[..SealHint::LEN]);
+        SealHint(Bytes::from_byte_array(hint))
+    }
+
+    /// Detects whether the annotations contain a hint for any of the seals.
+    pub fn matches(
+        &self,
+        annotations: &Annotations,
+        seals: impl IntoIterator<Item = XChain<SecretSeal>>,
+    ) -> bool {
+        let hints = annotations.seal_hints().collect::<Vec<_>>();
+        !hints.is_empty() && seals.into_iter().any(|seal| hints.contains(&self.hint(seal)))
+    }
+}
+
+/// Short keyed tag of a concealed seal (see [`SealHintKey::hint`]).
+#[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, From)]
+#[wrapper(Deref, BorrowSlice, Hex, Index, RangeOps)]
+#[display(LowerHex)]
+pub struct SealHint(
+    #[from]
+    #[from([u8; 8])]
+    Bytes<8>,
+);
+
+impl SealHint {
+    /// Length of the hint, in bytes.
+    pub const LEN: usize = 8;
+}
+
+impl Annotations {
+    /// Adds seal hints to the ones already present in the annotations.
+    pub fn add_seal_hints(
+        &mut self,
+        hints: impl IntoIterator<Item = SealHint>,
+    ) -> Result<(), confinement::Error> {
+        let key = seal_hints_key();
+        let mut data = self
+            .annotation(&key)
+            .map(|value| value.to_vec())
+            .unwrap_or_default();
+        data.extend(hints.into_iter().flat_map(|hint| hint.to_byte_array()));
+        self.annotate(key, MetaValue::from(SmallBlob::try_from(data)?))
+            .map(|_| ())
+    }
+
+    /// Iterates over the seal hints present in the annotations. Malformed
+    /// trailing bytes are ignored.
+    pub fn seal_hints(&self) -> impl Iterator<Item = SealHint> + '_ {
+        self.annotation(&seal_hints_key())
+            .into_iter()
+            .flat_map(|value| value.chunks_exact(SealHint::LEN))
+            .map(SealHint::from_slice_unsafe)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::hex::FromHex;
+    use bp::dbc::Method;
+
+    use super::*;
+    use crate::GraphSeal;
+
+    fn seal(vout: u32) -> XChain<SecretSeal> {
+        XChain::Bitcoin(GraphSeal::with_blinded_vout(Method::TapretFirst, vout, 0)).to_secret_seal()
+    }
+
+    #[test]
+    fn rfc4231() {
+        // Test case 2: keys shorter than the block are zero-padded by HMAC
+        let mut key = [0u8; 32];
+        key[..4].copy_from_slice(b"Jefe");
+        assert_eq!(
+            hmac(&key, b"what do ya want for nothing?").to_vec(),
+            Vec::<u8>::from_hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn matching() {
+        let key = SealHintKey::random();
+        let other = SealHintKey::random();
+        assert_ne!(key.hint(seal(0)), other.hint(seal(0)));
+        assert_ne!(key.hint(seal(0)), key.hint(seal(1)));
+        assert_eq!(key.hint(seal(0)), key.hint(seal(0)));
+
+        let mut annotations = Annotations::default();
+        assert!(!key.matches(&annotations, [seal(0)]));
+        annotations.add_seal_hints([key.hint(seal(0))]).unwrap();
+        annotations.add_seal_hints([other.hint(seal(2))]).unwrap();
+        assert_eq!(annotations.seal_hints().count(), 2);
+
+        assert!(key.matches(&annotations, [seal(1), seal(0)]));
+        assert!(!key.matches(&annotations, [seal(1), seal(2)]));
+        assert!(other.matches(&annotations, [seal(2)]));
+    }
+}
@@ -0,0 +1,255 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Core invoice primitive shared by wallet-level invoicing implementations.
+
+use std::fmt::{self, Display, Formatter};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use amplify::hex::FromHex;
+use amplify::Wrapper;
+use baid64::Baid64ParseError;
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+use crate::{
+    AssignmentType, ContractId, DataState, FungibleState, SecretSeal, XChain, XGraphSeal,
+    LIB_NAME_RGB_COMMIT,
+};
+
+/// Errors parsing [`InvoiceCore`] string representation.
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum InvoiceParseError {
+    /// invalid invoice structure; the invoice must have form of
+    /// `<contract id>/<assignment type>/<state>/<beneficiary>`.
+    WrongStructure,
+
+    /// invalid contract id in the invoice - {0}
+    #[from]
+    ContractId(Baid64ParseError),
+
+    /// invalid assignment type in the invoice - {0}
+    #[from]
+    AssignmentType(ParseIntError),
+
+    /// invalid requested state '{0}'.
+    State(String),
+
+    /// invalid beneficiary '{0}'.
+    Beneficiary(String),
+}
+
+/// State requested by an invoice.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = order)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum InvoiceState {
+    /// Any state of the requested type.
+    #[default]
+    #[strict_type(dumb)]
+    Any,
+    /// Declarative (void) state.
+    Void,
+    /// Fungible state of the given amount.
+    Amount(FungibleState),
+    /// Structured state with the given data.
+    Data(DataState),
+}
+
+impl Display for InvoiceState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InvoiceState::Any => f.write_str("any"),
+            InvoiceState::Void => f.write_str("void"),
+            InvoiceState::Amount(amount) => Display::fmt(amount, f),
+            InvoiceState::Data(data) => write!(f, "0x{data}"),
+        }
+    }
+}
+
+impl FromStr for InvoiceState {
+    type Err = InvoiceParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(InvoiceState::Any),
+            "void" => Ok(InvoiceState::Void),
+            s if s.starts_with("0x") => DataState::from_hex(&s[2..])
+                .map(InvoiceState::Data)
+                .map_err(|_| InvoiceParseError::State(s.to_owned())),
+            s => FungibleState::from_str(s)
+                .map(InvoiceState::Amount)
+                .map_err(|_| InvoiceParseError::State(s.to_owned())),
+        }
+    }
+}
+
+/// Beneficiary of an invoice, defining the single-use seal which will own the
+/// requested state.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, From)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = order, dumb = Self::BlindedSeal(strict_dumb!()))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum Beneficiary {
+    /// Concealed seal defined over an existing transaction output.
+    #[from]
+    BlindedSeal(XChain<SecretSeal>),
+    /// Seal template, which may be defined over an output of the witness
+    /// transaction.
+    #[from]
+    SealTemplate(XGraphSeal),
+}
+
+impl Display for Beneficiary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Beneficiary::BlindedSeal(seal) => Display::fmt(seal, f),
+            Beneficiary::SealTemplate(seal) => Display::fmt(seal, f),
+        }
+    }
+}
+
+impl FromStr for Beneficiary {
+    type Err = InvoiceParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        XChain::<SecretSeal>::from_str(s)
+            .map(Beneficiary::BlindedSeal)
+            .or_else(|_| XGraphSeal::from_str(s).map(Beneficiary::SealTemplate))
+            .map_err(|_| InvoiceParseError::Beneficiary(s.to_owned()))
+    }
+}
+
+/// Core invoice data, binding together the contract, type and value of the
+/// requested state and the beneficiary.
+///
+/// The invoice has a stable string representation in form of
+/// `<contract id>/<assignment type>/<state>/<beneficiary>`, where the
+/// assignment type is a decimal number.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct InvoiceCore {
+    pub contract_id: ContractId,
+    pub assignment_type: AssignmentType,
+    pub state: InvoiceState,
+    pub beneficiary: Beneficiary,
+}
+
+impl StrictSerialize for InvoiceCore {}
+impl StrictDeserialize for InvoiceCore {}
+
+impl Display for InvoiceCore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}/{}",
+            self.contract_id,
+            self.assignment_type.to_inner(),
+            self.state,
+            self.beneficiary
+        )
+    }
+}
+
+impl FromStr for InvoiceCore {
+    type Err = InvoiceParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.splitn(4, '/');
+        match (split.next(), split.next(), split.next(), split.next()) {
+            (Some(contract_id), Some(assignment_type), Some(state), Some(beneficiary)) => {
+                Ok(InvoiceCore {
+                    contract_id: ContractId::from_str(contract_id)?,
+                    assignment_type: AssignmentType::with(u16::from_str(assignment_type)?),
+                    state: state.parse()?,
+                    beneficiary: beneficiary.parse()?,
+                })
+            }
+            _ => Err(InvoiceParseError::WrongStructure),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::ByteArray;
+    use bp::dbc::Method;
+    use commit_verify::Conceal;
+
+    use super::*;
+    use crate::GraphSeal;
+
+    #[test]
+    fn display_from_str() {
+        let contract_id = ContractId::from_byte_array([0xA5; 32]);
+        let template = XGraphSeal::Bitcoin(GraphSeal::with_blinded_vout(
+            Method::TapretFirst,
+            1,
+            0x31bbed7e7b2d,
+        ));
+        let invoices = [
+            InvoiceCore {
+                contract_id,
+                assignment_type: AssignmentType::with(4000),
+                state: InvoiceState::Amount(FungibleState::Bits64(100)),
+                beneficiary: Beneficiary::SealTemplate(template),
+            },
+            InvoiceCore {
+                contract_id,
+                assignment_type: AssignmentType::with(1),
+                state: InvoiceState::Data(DataState::from_hex("cafe").unwrap()),
+                beneficiary: Beneficiary::BlindedSeal(template.conceal()),
+            },
+            InvoiceCore {
+                contract_id,
+                assignment_type: AssignmentType::with(1),
+                state: InvoiceState::Any,
+                beneficiary: Beneficiary::BlindedSeal(template.conceal()),
+            },
+        ];
+        for invoice in invoices {
+            let s = invoice.to_string();
+            assert_eq!(InvoiceCore::from_str(&s).unwrap(), invoice, "{s}");
+        }
+
+        assert!(matches!(
+            InvoiceCore::from_str("rgb:abc/1/any"),
+            Err(InvoiceParseError::WrongStructure)
+        ));
+    }
+}
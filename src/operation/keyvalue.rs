@@ -0,0 +1,111 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Records of the dictionary-like global state of
+//! [`crate::GlobalStateKind::KeyValue`] kind.
+
+use amplify::confinement::{SmallBlob, TinyString, U16 as U16MAX};
+use amplify::Wrapper;
+use strict_encoding::{DeserializeError, SerializeError, StrictDeserialize, StrictSerialize};
+
+use crate::{DataState, LIB_NAME_RGB_COMMIT};
+
+/// Value stored under a key of the dictionary-like global state.
+#[derive(Wrapper, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, From)]
+#[wrapper(Deref, AsSlice, BorrowSlice, Hex)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct RecordValue(SmallBlob);
+
+/// Update of the dictionary-like global state.
+///
+/// A record with a value sets the value under the key, replacing the previous
+/// one; a record without a value deletes the key. A single operation may
+/// update each key only once.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct KeyValueRecord {
+    pub key: TinyString,
+    pub value: Option<RecordValue>,
+}
+
+impl StrictSerialize for KeyValueRecord {}
+impl StrictDeserialize for KeyValueRecord {}
+
+impl KeyValueRecord {
+    /// Constructs record setting the value under the key.
+    pub fn set(key: TinyString, value: impl Into<RecordValue>) -> Self {
+        KeyValueRecord {
+            key,
+            value: Some(value.into()),
+        }
+    }
+
+    /// Constructs record deleting the key.
+    pub fn delete(key: TinyString) -> Self { KeyValueRecord { key, value: None } }
+
+    /// Detects whether the record deletes the key.
+    pub fn is_delete(&self) -> bool { self.value.is_none() }
+
+    /// Parses the record from the global state data.
+    pub fn from_data(data: &DataState) -> Result<Self, DeserializeError> {
+        Self::from_strict_serialized::<U16MAX>(data.as_inner().clone())
+    }
+
+    /// Serializes the record into the global state data.
+    pub fn to_data(&self) -> Result<DataState, SerializeError> {
+        self.to_strict_serialized::<U16MAX>().map(DataState::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+
+    use super::*;
+
+    #[test]
+    fn data_roundtrip() {
+        let key = TinyString::try_from(s!("example.rgb")).unwrap();
+        let value = RecordValue::from(Confined::try_from(vec![1u8, 2, 3]).unwrap());
+        let set = KeyValueRecord::set(key.clone(), value);
+        let delete = KeyValueRecord::delete(key);
+        assert!(!set.is_delete());
+        assert!(delete.is_delete());
+
+        for record in [set, delete] {
+            let data = record.to_data().unwrap();
+            assert_eq!(KeyValueRecord::from_data(&data).unwrap(), record);
+        }
+    }
+}
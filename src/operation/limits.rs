@@ -0,0 +1,72 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Consensus limits on the size of the contract data collections.
+//!
+//! The limits are part of the consensus: they define the maximal size of the
+//! length prefixes in the strict encoding, and the collections are confined to
+//! them, such that the data exceeding the limits are rejected already at the
+//! decoding time.
+
+use std::collections::BTreeMap;
+
+use amplify::confinement::{Confined, U16};
+
+use crate::OpId;
+
+/// Maximal number of state transitions in a single transition bundle.
+pub const MAX_OPS_PER_BUNDLE: usize = U16;
+
+/// Maximal number of assignments of a single type in an operation.
+pub const MAX_ASSIGNMENTS_PER_OP: usize = U16;
+
+/// Maximal number of global state items of a single type in an operation.
+pub const MAX_GLOBAL_ITEMS: usize = U16;
+
+/// Non-empty collection of the bundle operations, indexed by their ids.
+pub type BundleOps<T> = Confined<BTreeMap<OpId, T>, 1, MAX_OPS_PER_BUNDLE>;
+
+/// List of assignments of a single type in an operation.
+pub type AssignList<T> = Confined<Vec<T>, 0, MAX_ASSIGNMENTS_PER_OP>;
+
+/// Non-empty list of global state items of a single type in an operation.
+pub type GlobalList<T> = Confined<Vec<T>, 1, MAX_GLOBAL_ITEMS>;
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::{StrictDecode, StrictReader};
+
+    use super::*;
+    use crate::GlobalValues;
+
+    #[test]
+    fn decode_enforced() {
+        // Empty list with the u16 length prefix
+        let data = vec![0u8, 0];
+        assert!(GlobalValues::strict_decode(&mut StrictReader::in_memory::<0xFF>(data)).is_err());
+
+        // Length prefix exceeding the limit can't be even encoded
+        assert!(GlobalList::<u8>::try_from(vec![0u8; MAX_GLOBAL_ITEMS + 1]).is_err());
+        assert!(AssignList::<u8>::try_from(vec![0u8; MAX_ASSIGNMENTS_PER_OP + 1]).is_err());
+        assert!(AssignList::<u8>::try_from(vec![]).is_ok());
+    }
+}
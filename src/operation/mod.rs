@@ -20,7 +20,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod limits;
 mod meta;
+mod proofs;
 mod global;
 mod data;
 mod fungible;
@@ -32,33 +34,59 @@ mod operations;
 mod bundle;
 mod xchain;
 mod commit;
+mod endorsement;
+mod attestation;
+mod annotations;
+mod document;
+mod keyvalue;
+mod hint;
+mod invoice;
+mod short_id;
 
+pub use annotations::Annotations;
 pub use assignments::{
-    Assign, AssignAttach, AssignData, AssignFungible, AssignRights, Assignments, AssignmentsRef,
-    TypedAssigns,
+    Assign, AssignAttach, AssignData, AssignFungible, AssignRights, AssignedState, Assignments,
+    AssignmentsRef, TypedAssigns,
 };
-pub use attachment::{AttachId, AttachState, ConcealedAttach, RevealedAttach};
+pub use attachment::{AttachError, AttachId, AttachState, ConcealedAttach, RevealedAttach};
+pub use attestation::{IssuerAttestation, IssuerAttestationError, ISSUER_ATTESTATION_TAG};
 pub use bundle::{BundleId, InputMap, TransitionBundle, Vin};
 pub use commit::{
-    AssignmentCommitment, AssignmentIndex, BaseCommitment, BundleDisclosure, ContractId,
-    DiscloseHash, GlobalCommitment, OpCommitment, OpDisclose, OpId, TypeCommitment,
+    AssignmentCommitment, AssignmentIndex, BaseCommitment, BundleDisclosure, CommitmentPreimage,
+    ContractId, DiscloseHash, GenesisMetadata, GlobalCommitment, MetadataDigest, OpCommitment,
+    OpDisclose, OpId, TypeCommitment,
 };
-pub use data::{ConcealedData, DataState, RevealedData, VoidState};
+pub use data::{ConcealedData, StateDataError, DataState, RevealedData, TypedValue, VoidState};
+pub use document::{DocumentDigest, DocumentRevision};
+pub use endorsement::{EndorsementError, Endorsements, ENDORSEMENT_TAG};
 pub use fungible::{
-    AssetTag, BlindingFactor, BlindingParseError, ConcealedValue, FungibleState,
-    InvalidFieldElement, NoiseDumb, PedersenCommitment, RangeProof, RangeProofError, RevealedValue,
+    AssetTag, BlindingCorrectionError, BlindingFactor, BlindingParseError, ConcealedValue,
+    FungibleState, InvalidFieldElement, Magnitude, MagnitudeOverflow, NoiseDumb,
+    PartialRevealError, PartiallyConcealedValue, PedersenCommitment, RangeProof, RangeProofError,
+    RevealedValue,
 };
 pub use global::{GlobalState, GlobalValues};
+pub use hint::{SealHint, SealHintKey, SEAL_HINTS_ANNOTATION};
+pub use invoice::{Beneficiary, InvoiceCore, InvoiceParseError, InvoiceState};
+pub use keyvalue::{KeyValueRecord, RecordValue};
+pub use limits::{
+    AssignList, BundleOps, GlobalList, MAX_ASSIGNMENTS_PER_OP, MAX_GLOBAL_ITEMS, MAX_OPS_PER_BUNDLE,
+};
 pub use meta::{MetaValue, Metadata, MetadataError};
 pub use operations::{
     AssetTags, Extension, Genesis, Identity, Input, Inputs, Operation, Opout, OpoutParseError,
     Redeemed, Transition, Valencies,
 };
+pub use proofs::{AuxProof, AuxProofValues, AuxProofs};
 pub use seal::{
+    combine_blinding, split_blinding, split_blinding_with_rng, BlindingShare, BlindingShareError,
     ExposedSeal, GenesisSeal, GraphSeal, OutputSeal, SecretSeal, TxoSeal, XGenesisSeal, XGraphSeal,
     XOutputSeal,
 };
-pub use state::{ConcealedState, ConfidentialState, ExposedState, RevealedState, StateType};
+pub use short_id::{Disambiguator, ShortId};
+pub use state::{
+    ConcealedState, ConfidentialState, ExposedState, RevealedState, StateType, StateTypeMismatch,
+};
 pub use xchain::{
     AltLayer1, AltLayer1Set, Impossible, Layer1, XChain, XChainParseError, XOutpoint,
     XCHAIN_BITCOIN_PREFIX, XCHAIN_LIQUID_PREFIX,
@@ -38,9 +38,9 @@ use strict_encoding::{RString, StrictDeserialize, StrictEncode, StrictSerialize}
 use crate::schema::{self, ExtensionType, OpFullType, OpType, SchemaId, TransitionType};
 use crate::{
     AltLayer1Set, AssetTag, Assign, AssignmentIndex, AssignmentType, Assignments, AssignmentsRef,
-    ConcealedAttach, ConcealedData, ConcealedValue, ContractId, DiscloseHash, ExposedState, Ffv,
-    GenesisSeal, GlobalState, GraphSeal, Metadata, OpDisclose, OpId, SecretSeal, TypedAssigns,
-    VoidState, XChain, LIB_NAME_RGB_COMMIT,
+    AuxProofs, CommitmentPreimage, ConcealedAttach, ConcealedData, ConcealedValue, ContractId,
+    DiscloseHash, Endorsements, ExposedState, Ffv, GenesisSeal, GlobalState, GraphSeal, Metadata,
+    OpDisclose, OpId, SecretSeal, TypedAssigns, VoidState, XChain, LIB_NAME_RGB_COMMIT,
 };
 
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
@@ -224,6 +224,10 @@ pub trait Operation {
     /// serialization
     fn id(&self) -> OpId;
 
+    /// Returns the exact data hashed into the [`OpId`], for the external
+    /// verification of the operation commitment.
+    fn commitment_preimage(&self) -> CommitmentPreimage;
+
     /// Returns [`ContractId`] this operation belongs to.
     fn contract_id(&self) -> ContractId;
 
@@ -242,12 +246,15 @@ pub trait Operation {
     /// Returns metadata associated with the operation, if any.
     fn metadata(&self) -> &Metadata;
 
+    /// Returns auxiliary proofs carried by the operation.
+    fn aux_proofs(&self) -> &AuxProofs;
+
     /// Returns reference to a full set of metadata (in form of [`GlobalState`]
     /// wrapper structure) for the contract operation.
     fn globals(&self) -> &GlobalState;
     fn valencies(&self) -> &Valencies;
 
-    fn assignments(&self) -> AssignmentsRef;
+    fn assignments(&self) -> AssignmentsRef<'_>;
 
     fn assignments_by_type(&self, t: AssignmentType) -> Option<TypedAssigns<GraphSeal>>;
 
@@ -366,7 +373,7 @@ pub struct Genesis {
     pub globals: GlobalState,
     pub assignments: Assignments<GenesisSeal>,
     pub valencies: Valencies,
-    pub validator: ReservedBytes<1, 0>,
+    pub aux_proofs: AuxProofs,
 }
 
 impl StrictSerialize for Genesis {}
@@ -390,7 +397,7 @@ pub struct Extension {
     pub assignments: Assignments<GenesisSeal>,
     pub redeemed: Redeemed,
     pub valencies: Valencies,
-    pub validator: ReservedBytes<1, 0>,
+    pub aux_proofs: AuxProofs,
     pub witness: ReservedBytes<2, 0>,
 }
 
@@ -423,8 +430,8 @@ pub struct Transition {
     pub inputs: Inputs,
     pub assignments: Assignments<GraphSeal>,
     pub valencies: Valencies,
-    pub validator: ReservedBytes<1, 0>,
-    pub witness: ReservedBytes<2, 0>,
+    pub aux_proofs: AuxProofs,
+    pub endorsements: Endorsements,
 }
 
 impl StrictSerialize for Transition {}
@@ -515,6 +522,9 @@ impl Operation for Genesis {
     #[inline]
     fn id(&self) -> OpId { self.commit_id() }
 
+    #[inline]
+    fn commitment_preimage(&self) -> CommitmentPreimage { self.commit().preimage() }
+
     #[inline]
     fn contract_id(&self) -> ContractId { ContractId::from_inner(self.id().into_inner()) }
 
@@ -530,6 +540,9 @@ impl Operation for Genesis {
     #[inline]
     fn metadata(&self) -> &Metadata { &self.metadata }
 
+    #[inline]
+    fn aux_proofs(&self) -> &AuxProofs { &self.aux_proofs }
+
     #[inline]
     fn globals(&self) -> &GlobalState { &self.globals }
 
@@ -537,7 +550,7 @@ impl Operation for Genesis {
     fn valencies(&self) -> &Valencies { &self.valencies }
 
     #[inline]
-    fn assignments(&self) -> AssignmentsRef { (&self.assignments).into() }
+    fn assignments(&self) -> AssignmentsRef<'_> { (&self.assignments).into() }
 
     #[inline]
     fn assignments_by_type(&self, t: AssignmentType) -> Option<TypedAssigns<GraphSeal>> {
@@ -560,6 +573,9 @@ impl Operation for Extension {
     #[inline]
     fn id(&self) -> OpId { self.commit_id() }
 
+    #[inline]
+    fn commitment_preimage(&self) -> CommitmentPreimage { self.commit().preimage() }
+
     #[inline]
     fn contract_id(&self) -> ContractId { self.contract_id }
 
@@ -575,6 +591,9 @@ impl Operation for Extension {
     #[inline]
     fn metadata(&self) -> &Metadata { &self.metadata }
 
+    #[inline]
+    fn aux_proofs(&self) -> &AuxProofs { &self.aux_proofs }
+
     #[inline]
     fn globals(&self) -> &GlobalState { &self.globals }
 
@@ -582,7 +601,7 @@ impl Operation for Extension {
     fn valencies(&self) -> &Valencies { &self.valencies }
 
     #[inline]
-    fn assignments(&self) -> AssignmentsRef { (&self.assignments).into() }
+    fn assignments(&self) -> AssignmentsRef<'_> { (&self.assignments).into() }
 
     #[inline]
     fn assignments_by_type(&self, t: AssignmentType) -> Option<TypedAssigns<GraphSeal>> {
@@ -605,6 +624,9 @@ impl Operation for Transition {
     #[inline]
     fn id(&self) -> OpId { self.commit_id() }
 
+    #[inline]
+    fn commitment_preimage(&self) -> CommitmentPreimage { self.commit().preimage() }
+
     #[inline]
     fn contract_id(&self) -> ContractId { self.contract_id }
 
@@ -620,6 +642,9 @@ impl Operation for Transition {
     #[inline]
     fn metadata(&self) -> &Metadata { &self.metadata }
 
+    #[inline]
+    fn aux_proofs(&self) -> &AuxProofs { &self.aux_proofs }
+
     #[inline]
     fn globals(&self) -> &GlobalState { &self.globals }
 
@@ -627,7 +652,7 @@ impl Operation for Transition {
     fn valencies(&self) -> &Valencies { &self.valencies }
 
     #[inline]
-    fn assignments(&self) -> AssignmentsRef { (&self.assignments).into() }
+    fn assignments(&self) -> AssignmentsRef<'_> { (&self.assignments).into() }
 
     #[inline]
     fn assignments_by_type(&self, t: AssignmentType) -> Option<TypedAssigns<GraphSeal>> {
@@ -641,8 +666,56 @@ impl Operation for Transition {
 mod test {
     use amplify::ByteArray;
     use baid64::DisplayBaid64;
+    use strict_encoding::StrictDumb;
 
     use super::*;
+    use crate::{AltLayer1, OpCommitment, TypeCommitment};
+
+    impl StrictSerialize for TypeCommitment {}
+
+    #[test]
+    fn op_kind_domain_separation() {
+        let genesis = Genesis::strict_dumb();
+        let contract_id = genesis.contract_id();
+
+        let mut transition = Transition::strict_dumb();
+        transition.contract_id = contract_id;
+        transition.transition_type = TransitionType::with(0);
+        let mut extension = Extension::strict_dumb();
+        extension.contract_id = contract_id;
+        extension.extension_type = ExtensionType::with(0);
+        extension.nonce = transition.nonce;
+
+        // Transition and extension with the same contract, type number and
+        // state must not share the id
+        assert_eq!(transition.metadata, extension.metadata);
+        assert_eq!(transition.globals, extension.globals);
+        let ids = [genesis.id(), transition.id(), extension.id()];
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[0], ids[2]);
+        assert_ne!(ids[1], ids[2]);
+        assert_eq!(genesis.id(), contract_id);
+
+        // Each kind of operation is committed with a distinct type tag
+        let tag = |c: OpCommitment| c.op_type.to_strict_serialized::<1024>().unwrap()[0];
+        assert_eq!(tag(genesis.commit()), 0);
+        assert_eq!(tag(transition.commit()), 1);
+        assert_eq!(tag(extension.commit()), 2);
+    }
+
+    #[test]
+    fn contract_id_chain_binding() {
+        let genesis = Genesis::strict_dumb();
+        let contract_id = genesis.contract_id();
+
+        let mut testnet = genesis.clone();
+        testnet.testnet = !genesis.testnet;
+        assert_ne!(testnet.contract_id(), contract_id);
+
+        let mut liquid = genesis.clone();
+        liquid.alt_layers1.push(AltLayer1::Liquid).unwrap();
+        assert_ne!(liquid.contract_id(), contract_id);
+    }
 
     #[test]
     fn contract_id_display() {
@@ -0,0 +1,154 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Auxiliary proofs are strictly typed byte blobs, like zero-knowledge proofs
+//! or accumulator witnesses, which are committed into the operation id and
+//! are accessible to the validation scripts. Their types, sizes and number are
+//! defined by the schema (see [`crate::AuxProofSchema`]).
+
+use std::collections::btree_map;
+
+use amplify::confinement::{self, Confined, SmallBlob, TinyOrdMap, U8};
+use commit_verify::StrictHash;
+use strict_encoding::StrictDumb;
+
+use crate::{schema, LIB_NAME_RGB_COMMIT};
+
+#[derive(
+    Wrapper, WrapperMut, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, Default, From
+)]
+#[display(LowerHex)]
+#[wrapper(Deref, AsSlice, BorrowSlice, Hex)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+pub struct AuxProof(SmallBlob);
+
+#[cfg(feature = "serde")]
+mod _serde {
+    use amplify::hex::FromHex;
+    use serde_crate::de::Error;
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    impl Serialize for AuxProof {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer {
+            serializer.serialize_str(&self.to_string())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for AuxProof {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de> {
+            let s = String::deserialize(deserializer)?;
+            Self::from_hex(&s).map_err(D::Error::custom)
+        }
+    }
+}
+
+/// Non-empty list of auxiliary proofs of the same type.
+#[derive(Wrapper, WrapperMut, Clone, PartialEq, Eq, Hash, Debug, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct AuxProofValues(Confined<Vec<AuxProof>, 1, U8>);
+
+impl StrictDumb for AuxProofValues {
+    fn strict_dumb() -> Self { Self(Confined::with(AuxProof::strict_dumb())) }
+}
+
+impl AuxProofValues {
+    pub fn with(proof: AuxProof) -> Self { AuxProofValues(Confined::with(proof)) }
+}
+
+/// Auxiliary proofs of an operation, grouped by their type.
+///
+/// An empty set of proofs is encoded as a single zero byte, matching the
+/// reserved byte which operations had in its place before, such that the ids
+/// of the operations without proofs are preserved.
+#[derive(Wrapper, WrapperMut, Clone, PartialEq, Eq, Hash, Default, Debug, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[derive(CommitEncode)]
+#[commit_encode(strategy = strict, id = StrictHash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct AuxProofs(TinyOrdMap<schema::AuxProofType, AuxProofValues>);
+
+impl AuxProofs {
+    /// Adds proof of a given type after the already present proofs of the
+    /// same type.
+    pub fn add_proof(
+        &mut self,
+        ty: schema::AuxProofType,
+        proof: AuxProof,
+    ) -> Result<(), confinement::Error> {
+        match self.0.get_mut(&ty) {
+            Some(proofs) => proofs.push(proof),
+            None => self.0.insert(ty, AuxProofValues::with(proof)).map(|_| ()),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a AuxProofs {
+    type Item = (&'a schema::AuxProofType, &'a AuxProofValues);
+    type IntoIter = btree_map::Iter<'a, schema::AuxProofType, AuxProofValues>;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.iter() }
+}
+
+#[cfg(test)]
+mod test {
+    use commit_verify::{CommitId, ReservedBytes};
+    use strict_encoding::StrictSerialize;
+
+    use super::*;
+    use crate::{Genesis, Operation};
+
+    #[test]
+    fn empty_compat() {
+        assert_eq!(AuxProofs::default().commit_id(), ReservedBytes::<1, 0>::default().commit_id());
+
+        let mut genesis = Genesis::strict_dumb();
+        let data = genesis.to_strict_serialized::<{ usize::MAX }>().unwrap();
+        let opid = genesis.id();
+        genesis
+            .aux_proofs
+            .add_proof(schema::AuxProofType::with(1), AuxProof::strict_dumb())
+            .unwrap();
+        assert_ne!(genesis.id(), opid);
+        assert_eq!(genesis.to_strict_serialized::<{ usize::MAX }>().unwrap().len(), data.len() + 5);
+    }
+}
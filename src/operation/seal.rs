@@ -21,6 +21,7 @@
 // limitations under the License.
 
 use core::fmt::Debug;
+use std::collections::BTreeSet;
 use std::hash::Hash;
 
 use bp::dbc::Method;
@@ -28,11 +29,12 @@ pub use bp::seals::txout::blind::{ChainBlindSeal, ParseError, SingleBlindSeal};
 pub use bp::seals::txout::TxoSeal;
 use bp::seals::txout::{BlindSeal, CloseMethod, ExplicitSeal, SealTxid};
 pub use bp::seals::SecretSeal;
+use bp::secp256k1::rand::{thread_rng, Rng, RngCore};
 use bp::{Outpoint, Txid, Vout};
 use commit_verify::Conceal;
-use strict_encoding::{StrictDecode, StrictDumb, StrictEncode};
+use strict_encoding::{StrictDecode, StrictDeserialize, StrictDumb, StrictEncode, StrictSerialize};
 
-use crate::{XChain, XOutpoint};
+use crate::{XChain, XOutpoint, LIB_NAME_RGB_COMMIT};
 
 pub type GenesisSeal = SingleBlindSeal<Method>;
 pub type GraphSeal = ChainBlindSeal<Method>;
@@ -136,6 +138,172 @@ impl<Id: SealTxid> XChain<BlindSeal<Id>> {
     pub fn to_secret_seal(&self) -> XChain<SecretSeal> { self.conceal() }
 }
 
+/// Share of a seal blinding secret, produced by [`split_blinding`].
+///
+/// The blinding secret is split using Shamir secret sharing over GF(2^8),
+/// applied independently to each byte of the secret. Any `threshold` shares
+/// with distinct indexes are sufficient to recover the secret with
+/// [`combine_blinding`], while a smaller number of shares reveals no
+/// information about it.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct BlindingShare {
+    /// Number of shares required to recover the secret.
+    pub threshold: u8,
+    /// Non-zero index of the share, used as the evaluation point.
+    pub index: u8,
+    /// Share data.
+    pub data: [u8; 8],
+}
+
+impl StrictSerialize for BlindingShare {}
+impl StrictDeserialize for BlindingShare {}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum BlindingShareError {
+    /// threshold must be at least one and must not exceed the number of shares.
+    InvalidThreshold,
+
+    /// only {0} shares are provided, while at least {1} are required to recover
+    /// the blinding secret.
+    NotEnoughShares(usize, u8),
+
+    /// shares belong to different splits having different thresholds.
+    ThresholdMismatch,
+
+    /// share index is zero or repeats.
+    InvalidIndex,
+}
+
+// Arithmetics in GF(2^8) operates on secret data, thus it must not branch on
+// the values of its operands and must take the same number of steps for any
+// input.
+fn gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (0x1B & carry);
+        b >>= 1;
+    }
+    product
+}
+
+fn gf256_inv(a: u8) -> u8 {
+    // a^-1 = a^254 = a^2 * a^4 * a^8 * a^16 * a^32 * a^64 * a^128
+    let mut result = 1u8;
+    let mut square = a;
+    for _ in 0..7 {
+        square = gf256_mul(square, square);
+        result = gf256_mul(result, square);
+    }
+    result
+}
+
+/// Splits seal blinding secret into `count` shares, any `threshold` of which
+/// are sufficient for recovering the secret.
+pub fn split_blinding(
+    blinding: u64,
+    threshold: u8,
+    count: u8,
+) -> Result<Vec<BlindingShare>, BlindingShareError> {
+    split_blinding_with_rng(blinding, threshold, count, &mut thread_rng())
+}
+
+/// Splits seal blinding secret into `count` shares, any `threshold` of which
+/// are sufficient for recovering the secret, using custom random number
+/// generator.
+pub fn split_blinding_with_rng<R: Rng + RngCore>(
+    blinding: u64,
+    threshold: u8,
+    count: u8,
+    rng: &mut R,
+) -> Result<Vec<BlindingShare>, BlindingShareError> {
+    if threshold == 0 || threshold > count {
+        return Err(BlindingShareError::InvalidThreshold);
+    }
+    let secret = blinding.to_le_bytes();
+    // Polynomial coefficients for each of the secret bytes; the free term is
+    // the secret byte itself.
+    let coefficients = secret.map(|byte| {
+        let mut poly = vec![byte];
+        poly.extend((1..threshold).map(|_| rng.gen::<u8>()));
+        poly
+    });
+    let shares = (1..=count)
+        .map(|index| {
+            let mut data = [0u8; 8];
+            for (byte, poly) in data.iter_mut().zip(&coefficients) {
+                *byte = poly
+                    .iter()
+                    .rev()
+                    .fold(0u8, |acc, coeff| gf256_mul(acc, index) ^ coeff);
+            }
+            BlindingShare {
+                threshold,
+                index,
+                data,
+            }
+        })
+        .collect();
+    Ok(shares)
+}
+
+/// Recovers seal blinding secret from the shares produced by
+/// [`split_blinding`].
+pub fn combine_blinding(shares: &[BlindingShare]) -> Result<u64, BlindingShareError> {
+    let Some(first) = shares.first() else {
+        return Err(BlindingShareError::NotEnoughShares(0, 1));
+    };
+    let threshold = first.threshold;
+    if shares.iter().any(|share| share.threshold != threshold) {
+        return Err(BlindingShareError::ThresholdMismatch);
+    }
+    if shares.len() < threshold as usize {
+        return Err(BlindingShareError::NotEnoughShares(shares.len(), threshold));
+    }
+    let shares = &shares[..threshold as usize];
+    let mut indexes = BTreeSet::new();
+    if shares
+        .iter()
+        .any(|share| share.index == 0 || !indexes.insert(share.index))
+    {
+        return Err(BlindingShareError::InvalidIndex);
+    }
+
+    let mut secret = [0u8; 8];
+    for share in shares {
+        // Lagrange basis polynomial for the share evaluated at zero
+        let mut basis = 1u8;
+        for other in shares.iter().filter(|other| other.index != share.index) {
+            basis = gf256_mul(basis, gf256_mul(other.index, gf256_inv(other.index ^ share.index)));
+        }
+        for (byte, data) in secret.iter_mut().zip(share.data) {
+            *byte ^= gf256_mul(data, basis);
+        }
+    }
+    Ok(u64::from_le_bytes(secret))
+}
+
+impl<Id: SealTxid> XChain<BlindSeal<Id>> {
+    /// Splits the seal blinding secret into `count` shares, any `threshold` of
+    /// which are sufficient for recovering the secret.
+    pub fn split_blinding(
+        &self,
+        threshold: u8,
+        count: u8,
+    ) -> Result<Vec<BlindingShare>, BlindingShareError> {
+        split_blinding(self.as_reduced_unsafe().blinding, threshold, count)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use amplify::hex::FromHex;
@@ -143,6 +311,54 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn gf256() {
+        // Test vectors from FIPS-197, section 4.2
+        assert_eq!(gf256_mul(0x57, 0x83), 0xC1);
+        assert_eq!(gf256_mul(0x57, 0x13), 0xFE);
+        assert_eq!(gf256_inv(0x53), 0xCA);
+        assert_eq!(gf256_inv(0), 0);
+        for a in 1..=0xFFu8 {
+            assert_eq!(gf256_mul(a, 1), a);
+            assert_eq!(gf256_mul(a, 0), 0);
+            assert_eq!(gf256_mul(a, gf256_inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn blinding_shares() {
+        let blinding = 54683213134637;
+        let shares = split_blinding(blinding, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert_eq!(combine_blinding(&shares).unwrap(), blinding);
+        assert_eq!(combine_blinding(&shares[2..]).unwrap(), blinding);
+        assert_eq!(combine_blinding(&[shares[4], shares[0], shares[2]]).unwrap(), blinding);
+        assert_eq!(
+            combine_blinding(&shares[..2]).unwrap_err(),
+            BlindingShareError::NotEnoughShares(2, 3)
+        );
+        assert_eq!(
+            combine_blinding(&[shares[0], shares[0], shares[1]]).unwrap_err(),
+            BlindingShareError::InvalidIndex
+        );
+
+        let share = BlindingShare::from_strict_serialized::<16>(
+            shares[1].to_strict_serialized::<16>().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(share, shares[1]);
+
+        assert_eq!(
+            split_blinding(blinding, 0, 5).unwrap_err(),
+            BlindingShareError::InvalidThreshold
+        );
+        assert_eq!(
+            split_blinding(blinding, 6, 5).unwrap_err(),
+            BlindingShareError::InvalidThreshold
+        );
+        assert_eq!(combine_blinding(&split_blinding(blinding, 1, 1).unwrap()).unwrap(), blinding);
+    }
+
     #[test]
     fn secret_seal_is_sha256d() {
         let reveal = XChain::Bitcoin(BlindSeal {
@@ -0,0 +1,217 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Short form of the operation and bundle ids for logs and visual debugging.
+//!
+//! A short id is a prefix of the hex representation of the id followed by a
+//! checksum character, like `1f2e3d4c#q`. The checksum is computed from the
+//! full id, such that ids sharing the displayed prefix are still likely to be
+//! told apart. To keep short ids unambiguous, [`Disambiguator`] extends the
+//! prefix for the ids colliding with others within some set, like all the ids
+//! of a consignment.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Bound;
+
+use amplify::ByteArray;
+use commit_verify::{DigestExt, Sha256};
+
+use crate::{BundleId, OpId};
+
+const CHECKSUM_ALPHABET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Short form of a 32-byte id (see the module documentation).
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+pub struct ShortId {
+    id: [u8; 32],
+    len: u8,
+}
+
+impl ShortId {
+    /// Default number of the hex characters in the short id.
+    pub const DEFAULT_LEN: usize = 8;
+
+    /// Maximal number of the hex characters, at which the short id displays
+    /// the whole id.
+    pub const MAX_LEN: usize = 64;
+
+    /// Returns short id displaying `len` hex characters of the id, bounded
+    /// by [`Self::DEFAULT_LEN`] and [`Self::MAX_LEN`].
+    pub fn with_len(mut self, len: usize) -> Self {
+        self.len = len.clamp(Self::DEFAULT_LEN, Self::MAX_LEN) as u8;
+        self
+    }
+
+    /// Number of the displayed hex characters.
+    pub fn hex_len(&self) -> usize { self.len as usize }
+
+    /// Returns the full id.
+    pub fn to_byte_array(&self) -> [u8; 32] { self.id }
+
+    /// Checksum character of the full id.
+    pub fn checksum(&self) -> char {
+        let mut engine = Sha256::default();
+        engine.input_raw(&self.id);
+        let hash = engine.finish();
+        CHECKSUM_ALPHABET[(hash[0] & 0x1F) as usize] as char
+    }
+}
+
+impl From<[u8; 32]> for ShortId {
+    fn from(id: [u8; 32]) -> Self {
+        ShortId {
+            id,
+            len: Self::DEFAULT_LEN as u8,
+        }
+    }
+}
+
+impl From<OpId> for ShortId {
+    fn from(id: OpId) -> Self { ShortId::from(id.to_byte_array()) }
+}
+
+impl From<BundleId> for ShortId {
+    fn from(id: BundleId) -> Self { ShortId::from(id.to_byte_array()) }
+}
+
+impl Display for ShortId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for pos in 0..self.hex_len() {
+            let byte = self.id[pos / 2];
+            let nibble = if pos % 2 == 0 { byte >> 4 } else { byte & 0x0F };
+            write!(f, "{nibble:x}")?;
+        }
+        write!(f, "#{}", self.checksum())
+    }
+}
+
+impl OpId {
+    /// Returns the short form of the id with the default length.
+    pub fn short_id(&self) -> ShortId { ShortId::from(*self) }
+}
+
+impl BundleId {
+    /// Returns the short form of the id with the default length.
+    pub fn short_id(&self) -> ShortId { ShortId::from(*self) }
+}
+
+/// Number of the leading hex characters shared by two ids.
+fn common_prefix(a: &[u8; 32], b: &[u8; 32]) -> usize {
+    for (pos, (a, b)) in a.iter().zip(b).enumerate() {
+        if a != b {
+            return pos * 2 + usize::from(a >> 4 == b >> 4);
+        }
+    }
+    ShortId::MAX_LEN
+}
+
+/// Set of ids, producing short ids long enough to tell apart each of the ids
+/// from all the others in the set.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Disambiguator(BTreeSet<[u8; 32]>);
+
+impl Disambiguator {
+    /// Constructs empty disambiguator.
+    pub fn new() -> Self { Self::default() }
+
+    /// Adds id to the set.
+    pub fn insert(&mut self, id: impl Into<ShortId>) { self.0.insert(id.into().id); }
+
+    /// Returns the shortest short id of at least [`ShortId::DEFAULT_LEN`]
+    /// characters which doesn't collide with any other id in the set. The
+    /// id doesn't have to be a part of the set.
+    pub fn short_id(&self, id: impl Into<ShortId>) -> ShortId {
+        let short = id.into();
+        let below = self.0.range(..short.id).next_back();
+        let above = self
+            .0
+            .range((Bound::Excluded(short.id), Bound::Unbounded))
+            .next();
+        let common = below
+            .into_iter()
+            .chain(above)
+            .map(|other| common_prefix(&short.id, other))
+            .max()
+            .unwrap_or_default();
+        short.with_len(common + 1)
+    }
+}
+
+impl<I: Into<ShortId>> FromIterator<I> for Disambiguator {
+    fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
+        let mut disambiguator = Disambiguator::new();
+        disambiguator.extend(iter);
+        disambiguator
+    }
+}
+
+impl<I: Into<ShortId>> Extend<I> for Disambiguator {
+    fn extend<T: IntoIterator<Item = I>>(&mut self, iter: T) {
+        self.0.extend(iter.into_iter().map(|id| id.into().id));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn id(prefix: &[u8]) -> [u8; 32] {
+        let mut id = [0xFFu8; 32];
+        id[..prefix.len()].copy_from_slice(prefix);
+        id
+    }
+
+    #[test]
+    fn display() {
+        let short = ShortId::from(id(&[0x1f, 0x2e, 0x3d, 0x4c, 0x5b]));
+        let checksum = short.checksum();
+        assert_eq!(short.to_string(), format!("1f2e3d4c#{checksum}"));
+        assert_eq!(short.with_len(9).to_string(), format!("1f2e3d4c5#{checksum}"));
+        assert_eq!(short.with_len(1).hex_len(), ShortId::DEFAULT_LEN);
+        assert_eq!(short.with_len(100).to_string().len(), ShortId::MAX_LEN + 2);
+        assert_ne!(ShortId::from(id(&[0x1f, 0x2e, 0x3d, 0x4c, 0x5a])), short);
+    }
+
+    #[test]
+    fn disambiguation() {
+        let a = id(&[0x1f, 0x2e, 0x3d, 0x4c, 0x5b, 0x6a]);
+        let b = id(&[0x1f, 0x2e, 0x3d, 0x4c, 0x5b, 0x6b]);
+        let c = id(&[0x1f, 0x2e, 0x3d, 0x4c, 0x6b]);
+        let d = id(&[0x20]);
+        let ids = [a, b, c, d].into_iter().collect::<Disambiguator>();
+
+        assert_eq!(ids.short_id(a).hex_len(), 12);
+        assert_eq!(ids.short_id(b).hex_len(), 12);
+        assert_eq!(ids.short_id(c).hex_len(), 9);
+        assert_eq!(ids.short_id(d).hex_len(), ShortId::DEFAULT_LEN);
+        // Ids out of the set are told apart from the set members
+        assert_eq!(ids.short_id(id(&[0x1f, 0x2e, 0x3d, 0x4c, 0x5b, 0x60])).hex_len(), 12);
+        assert_eq!(Disambiguator::new().short_id(a).hex_len(), ShortId::DEFAULT_LEN);
+
+        let shorts = [a, b, c, d]
+            .map(|id| ids.short_id(id).to_string())
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        assert_eq!(shorts.len(), 4);
+    }
+}
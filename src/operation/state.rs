@@ -28,6 +28,7 @@ use strict_encoding::{StrictDecode, StrictDumb, StrictEncode};
 
 use crate::{
     ConcealedAttach, ConcealedData, ConcealedValue, RevealedAttach, RevealedData, RevealedValue,
+    LIB_NAME_RGB_COMMIT,
 };
 
 /// Marker trait for types of state which are just a commitment to the actual
@@ -77,16 +78,25 @@ pub enum StateType {
 }
 
 /// Categories of the state
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = custom, dumb = Self::Void)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
     serde(crate = "serde_crate", rename_all = "camelCase", tag = "type")
 )]
 pub enum RevealedState {
+    #[strict_type(tag = 0x00)]
     Void,
+    #[from]
+    #[strict_type(tag = 0x01)]
     Fungible(RevealedValue),
+    #[from]
+    #[strict_type(tag = 0x02)]
     Structured(RevealedData),
+    #[from]
+    #[strict_type(tag = 0xFF)]
     Attachment(RevealedAttach),
 }
 
@@ -99,6 +109,83 @@ impl RevealedState {
             RevealedState::Attachment(_) => StateType::Attachment,
         }
     }
+
+    /// Detects whether the state is void.
+    pub fn is_void(&self) -> bool { matches!(self, RevealedState::Void) }
+
+    /// Returns fungible state, if the state is of fungible type.
+    pub fn as_fungible(&self) -> Option<&RevealedValue> {
+        match self {
+            RevealedState::Fungible(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns structured state, if the state is of structured type.
+    pub fn as_structured(&self) -> Option<&RevealedData> {
+        match self {
+            RevealedState::Structured(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Returns attachment state, if the state is of attachment type.
+    pub fn as_attachment(&self) -> Option<&RevealedAttach> {
+        match self {
+            RevealedState::Attachment(attach) => Some(attach),
+            _ => None,
+        }
+    }
+}
+
+/// Error converting state into a specific state type.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display("state of {found} type can't be converted into {expected} state")]
+pub struct StateTypeMismatch {
+    pub expected: StateType,
+    pub found: StateType,
+}
+
+impl TryFrom<RevealedState> for RevealedValue {
+    type Error = StateTypeMismatch;
+
+    fn try_from(state: RevealedState) -> Result<Self, Self::Error> {
+        match state {
+            RevealedState::Fungible(value) => Ok(value),
+            other => Err(StateTypeMismatch {
+                expected: StateType::Fungible,
+                found: other.state_type(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<RevealedState> for RevealedData {
+    type Error = StateTypeMismatch;
+
+    fn try_from(state: RevealedState) -> Result<Self, Self::Error> {
+        match state {
+            RevealedState::Structured(data) => Ok(data),
+            other => Err(StateTypeMismatch {
+                expected: StateType::Structured,
+                found: other.state_type(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<RevealedState> for RevealedAttach {
+    type Error = StateTypeMismatch;
+
+    fn try_from(state: RevealedState) -> Result<Self, Self::Error> {
+        match state {
+            RevealedState::Attachment(attach) => Ok(attach),
+            other => Err(StateTypeMismatch {
+                expected: StateType::Attachment,
+                found: other.state_type(),
+            }),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -126,3 +213,76 @@ impl ConfidentialState for ConcealedState {
     }
     fn state_commitment(&self) -> ConcealedState { *self }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::DataState;
+
+    #[test]
+    fn revealed_state_accessors() {
+        let value = RevealedValue::strict_dumb();
+        let data = RevealedData::with_salt(DataState::from(small_blob![0xca, 0xfe]), 0);
+        let attach = RevealedAttach::strict_dumb();
+
+        let void = RevealedState::Void;
+        assert!(void.is_void());
+        assert_eq!(void.as_fungible(), None);
+        assert_eq!(void.as_structured(), None);
+        assert_eq!(void.as_attachment(), None);
+
+        let fungible = RevealedState::from(value);
+        assert_eq!(fungible, RevealedState::Fungible(value));
+        assert!(!fungible.is_void());
+        assert_eq!(fungible.as_fungible(), Some(&value));
+        assert_eq!(fungible.as_structured(), None);
+        assert_eq!(fungible.as_attachment(), None);
+
+        let structured = RevealedState::from(data.clone());
+        assert_eq!(structured, RevealedState::Structured(data.clone()));
+        assert_eq!(structured.as_fungible(), None);
+        assert_eq!(structured.as_structured(), Some(&data));
+        assert_eq!(structured.as_attachment(), None);
+
+        let attachment = RevealedState::from(attach.clone());
+        assert_eq!(attachment, RevealedState::Attachment(attach.clone()));
+        assert_eq!(attachment.as_fungible(), None);
+        assert_eq!(attachment.as_structured(), None);
+        assert_eq!(attachment.as_attachment(), Some(&attach));
+    }
+
+    #[test]
+    fn revealed_state_conversions() {
+        let value = RevealedValue::strict_dumb();
+        let data = RevealedData::with_salt(DataState::from(small_blob![0xca, 0xfe]), 0);
+        let attach = RevealedAttach::strict_dumb();
+
+        assert_eq!(RevealedValue::try_from(RevealedState::from(value)), Ok(value));
+        assert_eq!(RevealedData::try_from(RevealedState::from(data.clone())), Ok(data.clone()));
+        assert_eq!(RevealedAttach::try_from(RevealedState::from(attach.clone())), Ok(attach));
+
+        assert_eq!(
+            RevealedValue::try_from(RevealedState::Void),
+            Err(StateTypeMismatch {
+                expected: StateType::Fungible,
+                found: StateType::Void
+            })
+        );
+        assert_eq!(
+            RevealedAttach::try_from(RevealedState::from(data)),
+            Err(StateTypeMismatch {
+                expected: StateType::Attachment,
+                found: StateType::Structured
+            })
+        );
+        let err = RevealedData::try_from(RevealedState::from(value)).unwrap_err();
+        assert_eq!(err, StateTypeMismatch {
+            expected: StateType::Structured,
+            found: StateType::Fungible
+        });
+        assert_eq!(
+            err.to_string(),
+            "state of fungible type can't be converted into structured state"
+        );
+    }
+}
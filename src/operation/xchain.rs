@@ -30,9 +30,9 @@ use amplify::confinement::TinyOrdSet;
 use bp::{Bp, Outpoint};
 use commit_verify::{Conceal, StrictHash};
 use strict_encoding::{
-    DecodeError, DefineUnion, ReadTuple, ReadUnion, StrictDecode, StrictDumb, StrictEncode,
-    StrictEnum, StrictSum, StrictType, StrictUnion, TypedRead, TypedWrite, VariantError,
-    WriteUnion,
+    DecodeError, DefineUnion, ReadTuple, ReadUnion, StrictDecode, StrictDeserialize, StrictDumb,
+    StrictEncode, StrictEnum, StrictSerialize, StrictSum, StrictType, StrictUnion, TypedRead,
+    TypedWrite, VariantError, WriteUnion,
 };
 
 use crate::{OutputSeal, XOutputSeal, LIB_NAME_RGB_COMMIT};
@@ -68,6 +68,9 @@ pub enum Layer1 {
 #[strict_type(lib = LIB_NAME_RGB_COMMIT)]
 pub struct XOutpoint(XChain<Outpoint>);
 
+impl StrictSerialize for XOutpoint {}
+impl StrictDeserialize for XOutpoint {}
+
 impl From<XOutputSeal> for XOutpoint {
     #[inline]
     fn from(seal: XOutputSeal) -> Self { seal.to_outpoint() }
@@ -168,7 +171,7 @@ impl StrictEncode for Impossible {
 }
 impl StrictDecode for Impossible {
     fn strict_decode(_reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
-        panic!("must not be deserialized")
+        Err(DecodeError::DataIntegrityError(s!("impossible value must not be deserialized")))
     }
 }
 
@@ -289,7 +292,7 @@ where T: StrictDumb + StrictDecode
                 r.read_tuple(|r| r.read_field().map(Self::Bitcoin))
             }
             x if x == Self::ALL_VARIANTS[1].1 => r.read_tuple(|r| r.read_field().map(Self::Liquid)),
-            _ => unreachable!(),
+            x => Err(DecodeError::DataIntegrityError(format!("unknown XChain variant `{x}`"))),
         })
     }
 }
@@ -321,6 +324,33 @@ impl<T> XChain<T, Impossible> {
         }
     }
 
+    /// Converts from `&XChain<T>` to `XChain<&T>`.
+    pub fn as_ref(&self) -> XChain<&T> {
+        match self {
+            XChain::Bitcoin(t) => XChain::Bitcoin(t),
+            XChain::Liquid(t) => XChain::Liquid(t),
+            XChain::Other(_) => unreachable!(),
+        }
+    }
+
+    /// Converts from `&mut XChain<T>` to `XChain<&mut T>`.
+    pub fn as_mut(&mut self) -> XChain<&mut T> {
+        match self {
+            XChain::Bitcoin(t) => XChain::Bitcoin(t),
+            XChain::Liquid(t) => XChain::Liquid(t),
+            XChain::Other(_) => unreachable!(),
+        }
+    }
+
+    /// Splits the value into the layer 1 it belongs to and the inner data.
+    pub fn into_parts(self) -> (Layer1, T) {
+        match self {
+            XChain::Bitcoin(t) => (Layer1::Bitcoin, t),
+            XChain::Liquid(t) => (Layer1::Liquid, t),
+            XChain::Other(_) => unreachable!(),
+        }
+    }
+
     pub fn as_reduced_unsafe(&self) -> &T {
         match self {
             XChain::Bitcoin(t) | XChain::Liquid(t) => t,
@@ -513,6 +543,26 @@ impl<T> XChain<Option<T>, Impossible> {
     }
 }
 
+impl<T, E> XChain<Result<T, E>, Impossible> {
+    pub fn transpose(self) -> Result<XChain<T>, E> {
+        match self {
+            XChain::Bitcoin(inner) => inner.map(XChain::Bitcoin),
+            XChain::Liquid(inner) => inner.map(XChain::Liquid),
+            XChain::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl<T> From<(Layer1, T)> for XChain<T> {
+    #[inline]
+    fn from((layer1, data): (Layer1, T)) -> Self { XChain::with(layer1, data) }
+}
+
+impl<T> From<&XChain<T>> for Layer1 {
+    #[inline]
+    fn from(xchain: &XChain<T>) -> Self { xchain.layer1() }
+}
+
 impl<I: Iterator> Iterator for XChain<I, Impossible> {
     type Item = XChain<<I as Iterator>::Item>;
 
@@ -530,6 +580,9 @@ pub enum XChainParseError<E: Debug + Display> {
     #[display("unknown chain prefix '{0}'; only 'bc:' and 'lq:' are currently supported")]
     UnknownPrefix(String),
 
+    #[display("chain prefix is absent; 'bc:' or 'lq:' prefix is required")]
+    NoPrefix,
+
     #[from]
     #[display(inner)]
     Inner(E),
@@ -578,3 +631,40 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn layer1_conversions() {
+        let bc = XChain::<u8>::from((Layer1::Bitcoin, 1));
+        let lq = XChain::<u8>::from((Layer1::Liquid, 2));
+        assert_eq!(bc, XChain::Bitcoin(1));
+        assert_eq!(lq, XChain::Liquid(2));
+        assert_eq!(Layer1::from(&bc), Layer1::Bitcoin);
+        assert_eq!(Layer1::from(&lq), Layer1::Liquid);
+        assert_eq!(bc.into_parts(), (Layer1::Bitcoin, 1));
+        assert_eq!(lq.into_parts(), (Layer1::Liquid, 2));
+        assert!(bc.is_bitcoin() && !bc.is_liquid());
+        assert!(lq.is_liquid() && !lq.is_bitcoin());
+    }
+
+    #[test]
+    fn combinators() {
+        let mut x = XChain::<u8>::Liquid(2);
+        if let XChain::Liquid(v) = x.as_mut() {
+            *v += 1;
+        }
+        assert_eq!(x.as_ref().map(|v| *v), XChain::Liquid(3));
+        assert_eq!(x.map(u16::from), XChain::Liquid(3u16));
+        assert_eq!(x.map_ref(|v| v * 2), XChain::Liquid(6));
+        assert_eq!(x.try_map(|v| u8::try_from(v as u16 * 10)), Ok(XChain::Liquid(30)));
+        assert!(x.try_map(|v| u8::try_from(v as u16 * 100)).is_err());
+
+        assert_eq!(XChain::Bitcoin(Some(1u8)).transpose(), Some(XChain::Bitcoin(1)));
+        assert_eq!(XChain::<Option<u8>>::Liquid(None).transpose(), None);
+        assert_eq!(XChain::<Result<u8, ()>>::Liquid(Ok(1)).transpose(), Ok(XChain::Liquid(1)));
+        assert_eq!(XChain::<Result<u8, ()>>::Bitcoin(Err(())).transpose(), Err(()));
+    }
+}
@@ -20,16 +20,76 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::RangeInclusive;
+
+/// Range of the schema type ids assigned to the standard interfaces.
+pub const STANDARD_TYPE_IDS: RangeInclusive<u16> = 0x0000..=0x7FFF;
+/// Range of the schema type ids left for the experimental use; they never
+/// collide with the ids of the standard interfaces.
+pub const EXPERIMENTAL_TYPE_IDS: RangeInclusive<u16> = 0x8000..=0xFEFF;
+/// Range of the schema type ids reserved by the consensus (like
+/// [`TransitionType::BLANK`]), which can't be declared by schemas.
+pub const RESERVED_TYPE_IDS: RangeInclusive<u16> = 0xFF00..=0xFFFF;
+
+macro_rules! type_id_ranges {
+    ($ty:ident) => {
+        impl $ty {
+            /// Constructs type id for the standard interfaces.
+            ///
+            /// # Panics
+            ///
+            /// If `no` is outside of [`STANDARD_TYPE_IDS`](crate::schema::STANDARD_TYPE_IDS).
+            pub const fn standard(no: u16) -> Self {
+                assert!(no <= *$crate::schema::STANDARD_TYPE_IDS.end(), "non-standard type id");
+                Self::with(no)
+            }
+
+            /// Constructs `no`-th type id from the
+            /// [`EXPERIMENTAL_TYPE_IDS`](crate::schema::EXPERIMENTAL_TYPE_IDS) range.
+            ///
+            /// # Panics
+            ///
+            /// If `no` exceeds the size of the experimental range.
+            pub const fn experimental(no: u16) -> Self {
+                let start = *$crate::schema::EXPERIMENTAL_TYPE_IDS.start();
+                let end = *$crate::schema::EXPERIMENTAL_TYPE_IDS.end();
+                assert!(no <= end - start, "experimental type id out of range");
+                Self::with(start + no)
+            }
+
+            /// Detects whether the type id belongs to the standard interfaces.
+            pub fn is_standard(self) -> bool { $crate::schema::STANDARD_TYPE_IDS.contains(&self.0) }
+
+            /// Detects whether the type id is an experimental one.
+            pub fn is_experimental(self) -> bool {
+                $crate::schema::EXPERIMENTAL_TYPE_IDS.contains(&self.0)
+            }
+
+            /// Detects whether the type id is reserved by the consensus.
+            pub fn is_reserved(self) -> bool { $crate::schema::RESERVED_TYPE_IDS.contains(&self.0) }
+        }
+    };
+}
+
 mod operations;
 #[allow(clippy::module_inception)]
 mod schema;
 mod state;
 mod occurrences;
 
-pub use occurrences::{Occurrences, OccurrencesMismatch};
+pub use occurrences::{Occurrences, OccurrencesConfineError, OccurrencesMismatch};
 pub use operations::{
     AssignmentType, AssignmentsSchema, ExtensionSchema, GenesisSchema, GlobalSchema, MetaSchema,
-    OpFullType, OpSchema, OpType, TransitionSchema, ValencySchema, ValencyType,
+    OpFullType, OpSchema, OpType, StateRef, TransitionSchema, ValencySchema, ValencyType,
+};
+pub use schema::{
+    AuxProofDetails, AuxProofSchema, AuxProofType, ChainSupplySchema, ContractFeatures,
+    DustLimitSchema, EndorsementSchema, ExtensionType, FeatureSchema, FeeCollector, FeeSchema,
+    GlobalStateType, IssuerProofSchema, Layer1Cap, MetaType, RetentionClass, RetentionSchema,
+    Schema, SchemaExtension, SchemaExtensionType, SchemaExtensions, SchemaExtensionsVersion,
+    SchemaId, SchemaTypeNames, TransitionType,
+};
+pub use state::{
+    FungibleType, GlobalStateKind, GlobalStateSchema, KeyCharset, KeyDomain, KeyDomains, MediaType,
+    OwnedStateSchema,
 };
-pub use schema::{ExtensionType, GlobalStateType, MetaType, Schema, SchemaId, TransitionType};
-pub use state::{FungibleType, GlobalStateSchema, MediaType, OwnedStateSchema};
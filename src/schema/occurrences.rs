@@ -23,6 +23,7 @@
 use std::io;
 use std::ops::RangeInclusive;
 
+use amplify::confinement::{self, Collection, Confined};
 use strict_encoding::{
     DecodeError, ReadStruct, StrictDecode, StrictEncode, StrictProduct, StrictStruct, StrictType,
     TypeName, TypedRead, TypedWrite, WriteStruct,
@@ -73,6 +74,41 @@ impl Occurrences {
         }
     }
 
+    /// Returns range of the allowed number of occurrences.
+    pub fn bounds(&self) -> RangeInclusive<u16> { self.min_value()..=self.max_value() }
+
+    /// Detects whether a confined collection with bounds `MIN..=MAX` is able to
+    /// hold any number of elements allowed by the occurrences value.
+    pub fn fits_confinement<const MIN: usize, const MAX: usize>(&self) -> bool {
+        MIN <= self.min_value() as usize && self.max_value() as usize <= MAX
+    }
+
+    /// Constructs confined collection from the provided items, checking that
+    /// the number of items matches both the occurrences value and the
+    /// confinement bounds.
+    pub fn confine<C: Collection, const MIN: usize, const MAX: usize>(
+        &self,
+        col: C,
+    ) -> Result<Confined<C, MIN, MAX>, OccurrencesConfineError> {
+        let len = col.len();
+        let count = u16::try_from(len).map_err(|_| confinement::Error::Oversize {
+            len,
+            max_len: self.max_value() as usize,
+        })?;
+        self.check(count)?;
+        Ok(Confined::try_from(col)?)
+    }
+
+    /// Collects items from an iterator into a confined collection, checking
+    /// that the number of items matches both the occurrences value and the
+    /// confinement bounds.
+    pub fn confine_iter<C: Collection, const MIN: usize, const MAX: usize>(
+        &self,
+        iter: impl IntoIterator<Item = C::Item>,
+    ) -> Result<Confined<C, MIN, MAX>, OccurrencesConfineError> {
+        self.confine(iter.into_iter().collect::<C>())
+    }
+
     pub fn check(&self, count: u16) -> Result<(), OccurrencesMismatch> {
         let orig_count = count;
         match self {
@@ -105,6 +141,18 @@ pub enum OccurrencesError {
     MinExceedsMax,
 }
 
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(inner)]
+pub enum OccurrencesConfineError {
+    /// number of items doesn't match the occurrences value.
+    #[from]
+    Mismatch(OccurrencesMismatch),
+
+    /// number of items doesn't fit the collection confinement.
+    #[from]
+    Confinement(confinement::Error),
+}
+
 impl TryFrom<RangeInclusive<u16>> for Occurrences {
     type Error = OccurrencesError;
 
@@ -163,7 +211,37 @@ pub struct OccurrencesMismatch {
 
 #[cfg(test)]
 mod test {
-    use super::Occurrences;
+    use amplify::confinement::{TinyVec, U8};
+
+    use super::*;
+
+    #[test]
+    fn confine() {
+        let occurrence = Occurrences::OnceOrUpTo(3);
+        assert!(occurrence.fits_confinement::<1, { U8 }>());
+        assert!(!occurrence.fits_confinement::<2, { U8 }>());
+        assert!(!Occurrences::OnceOrMore.fits_confinement::<1, { U8 }>());
+        assert_eq!(occurrence.bounds(), 1..=3);
+
+        let col: TinyVec<u8> = occurrence.confine(vec![1, 2, 3]).unwrap();
+        assert_eq!(col.len(), 3);
+        let col: TinyVec<u8> = occurrence.confine_iter(1..=2).unwrap();
+        assert_eq!(col.len(), 2);
+        assert_eq!(
+            occurrence
+                .confine::<_, 0, { U8 }>(vec![1u8; 4])
+                .unwrap_err(),
+            OccurrencesConfineError::Mismatch(OccurrencesMismatch {
+                min: 1,
+                max: 3,
+                found: 4
+            })
+        );
+        assert!(matches!(
+            Occurrences::NoneOrMore.confine::<_, 0, 2>(vec![1u8; 3]),
+            Err(OccurrencesConfineError::Confinement(_))
+        ));
+    }
 
     #[test]
     fn test_once_check_count() {
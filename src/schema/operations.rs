@@ -44,6 +44,7 @@ impl AssignmentType {
     #[inline]
     pub fn to_le_bytes(&self) -> [u8; 2] { self.0.to_le_bytes() }
 }
+type_id_ranges!(AssignmentType);
 
 #[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From, Display)]
 #[wrapper(FromStr, LowerHex, UpperHex)]
@@ -60,6 +61,34 @@ impl ValencyType {
     pub const fn with(ty: u16) -> Self { Self(ty) }
 }
 
+/// Typed reference to a kind of contract state defined by a schema.
+///
+/// Used in reporting, where a plain state type number would be ambiguous,
+/// since global state, owned state and valency types share the same numeric
+/// space.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Display, From)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum StateRef {
+    /// Global state type.
+    #[from]
+    #[display("global state type {0}")]
+    Global(GlobalStateType),
+
+    /// Owned state (assignment) type.
+    #[from]
+    #[display("owned state type {0}")]
+    Owned(AssignmentType),
+
+    /// Valency type.
+    #[from]
+    #[display("valency type {0}")]
+    Valency(ValencyType),
+}
+
 pub type MetaSchema = TinyOrdSet<MetaType>;
 pub type GlobalSchema = TinyOrdMap<GlobalStateType, Occurrences>;
 pub type ValencySchema = TinyOrdSet<ValencyType>;
@@ -29,18 +29,21 @@ use amplify::confinement::{TinyOrdMap, TinyOrdSet};
 use amplify::{ByteArray, Bytes32};
 use baid64::{Baid64ParseError, DisplayBaid64, FromBaid64Str};
 use commit_verify::{
-    CommitEncode, CommitEngine, CommitId, CommitmentId, DigestExt, ReservedBytes, Sha256,
+    CommitEncode, CommitEngine, CommitId, CommitmentId, Conceal, DigestExt, ReservedBytes, Sha256,
 };
 use strict_encoding::{
-    StrictDecode, StrictDeserialize, StrictEncode, StrictSerialize, StrictType, TypeName,
+    DecodeError, FieldName, ReadStruct, StrictDecode, StrictDeserialize, StrictDumb, StrictEncode,
+    StrictSerialize, StrictType, TypeName, TypedRead,
 };
 use strict_types::SemId;
 
 use super::{
-    AssignmentType, ExtensionSchema, GenesisSchema, OwnedStateSchema, TransitionSchema, ValencyType,
+    AssignmentType, ExtensionSchema, GenesisSchema, KeyDomain, KeyDomains, OpFullType,
+    OwnedStateSchema, StateRef, TransitionSchema, ValencyType,
 };
 use crate::{
-    impl_serde_baid64, Ffv, GlobalStateSchema, Identity, Occurrences, LIB_NAME_RGB_COMMIT,
+    impl_serde_baid64, CompactId, ExposedSeal, Ffv, GlobalStateSchema, Identity, Layer1,
+    Occurrences, SecretSeal, XChain, XOutpoint, LIB_NAME_RGB_COMMIT,
 };
 
 #[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From, Display)]
@@ -58,6 +61,21 @@ impl MetaType {
     pub const fn with(ty: u16) -> Self { Self(ty) }
 }
 
+#[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From, Display)]
+#[wrapper(FromStr, LowerHex, UpperHex)]
+#[display("0x{0:04X}")]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct AuxProofType(u16);
+impl AuxProofType {
+    pub const fn with(ty: u16) -> Self { Self(ty) }
+}
+
 #[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From, Display)]
 #[wrapper(FromStr, LowerHex, UpperHex)]
 #[display("0x{0:04X}")]
@@ -72,6 +90,7 @@ pub struct GlobalStateType(u16);
 impl GlobalStateType {
     pub const fn with(ty: u16) -> Self { Self(ty) }
 }
+type_id_ranges!(GlobalStateType);
 
 #[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From, Display)]
 #[wrapper(FromStr, LowerHex, UpperHex)]
@@ -102,6 +121,7 @@ pub struct TransitionType(u16);
 impl TransitionType {
     pub const fn with(ty: u16) -> Self { Self(ty) }
 }
+type_id_ranges!(TransitionType);
 
 impl TransitionType {
     pub const BLANK: Self = TransitionType(u16::MAX);
@@ -147,6 +167,8 @@ impl Display for SchemaId {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.fmt_baid64(f) }
 }
 
+impl CompactId for SchemaId {}
+
 impl_serde_baid64!(SchemaId);
 
 #[derive(Clone, Eq, Debug)]
@@ -172,10 +194,595 @@ pub struct Schema {
     pub genesis: GenesisSchema,
     pub extensions: TinyOrdMap<ExtensionType, ExtensionSchema>,
     pub transitions: TinyOrdMap<TransitionType, TransitionSchema>,
+    /// Optional consensus rules added after the schema format was fixed.
+    pub ext: SchemaExtensions,
+}
+
+/// Version of the [`SchemaExtensions`] container encoding.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(lowercase)]
+#[repr(u8)]
+pub enum SchemaExtensionsVersion {
+    /// Initial version, where each of the extension types may be present at
+    /// most once.
+    #[default]
+    V0 = 0,
+}
+
+/// Type of an optional schema extension, which is the key of the
+/// [`SchemaExtensions`] container.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(lowercase)]
+#[repr(u8)]
+pub enum SchemaExtensionType {
+    #[default]
+    Endorsement = 0,
+    #[display("type-names")]
+    TypeNames = 1,
+    #[display("key-domains")]
+    KeyDomains = 2,
+    Fees = 3,
+    #[display("aux-proofs")]
+    AuxProofs = 4,
+    #[display("dust-limit")]
+    DustLimit = 5,
+    #[display("chain-supply")]
+    ChainSupply = 6,
+    Retention = 7,
+    #[display("issuer-proof")]
+    IssuerProof = 8,
+    Features = 9,
+}
+
+/// Optional schema extension.
+#[derive(Clone, PartialEq, Eq, Debug, From)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = order, dumb = Self::Endorsement(strict_dumb!()))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum SchemaExtension {
+    #[from]
+    Endorsement(EndorsementSchema),
+    #[from]
+    TypeNames(SchemaTypeNames),
+    #[from]
+    KeyDomains(KeyDomains),
+    #[from]
+    Fees(FeeSchema),
+    #[from]
+    AuxProofs(AuxProofSchema),
+    #[from]
+    DustLimit(DustLimitSchema),
+    #[from]
+    ChainSupply(ChainSupplySchema),
+    #[from]
+    Retention(RetentionSchema),
+    #[from]
+    IssuerProof(IssuerProofSchema),
+    #[from]
+    Features(FeatureSchema),
+}
+
+impl SchemaExtension {
+    /// Returns type of the extension, under which it is stored in
+    /// [`SchemaExtensions`].
+    pub const fn extension_type(&self) -> SchemaExtensionType {
+        match self {
+            SchemaExtension::Endorsement(_) => SchemaExtensionType::Endorsement,
+            SchemaExtension::TypeNames(_) => SchemaExtensionType::TypeNames,
+            SchemaExtension::KeyDomains(_) => SchemaExtensionType::KeyDomains,
+            SchemaExtension::Fees(_) => SchemaExtensionType::Fees,
+            SchemaExtension::AuxProofs(_) => SchemaExtensionType::AuxProofs,
+            SchemaExtension::DustLimit(_) => SchemaExtensionType::DustLimit,
+            SchemaExtension::ChainSupply(_) => SchemaExtensionType::ChainSupply,
+            SchemaExtension::Retention(_) => SchemaExtensionType::Retention,
+            SchemaExtension::IssuerProof(_) => SchemaExtensionType::IssuerProof,
+            SchemaExtension::Features(_) => SchemaExtensionType::Features,
+        }
+    }
+}
+
+/// Versioned container of the optional schema extensions, keyed by their
+/// type.
+///
+/// The container takes the place of the reserved bytes of the schemata
+/// created by RGB 0.11.0-beta.8 and earlier: an empty container is encoded
+/// with the same eight zero bytes, such that the ids of these schemata are
+/// preserved. New kinds of extensions are added as new
+/// [`SchemaExtensionType`] variants without changing the schema layout;
+/// incompatible changes to the container require a new
+/// [`SchemaExtensionsVersion`].
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[derive(StrictType, StrictEncode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct SchemaExtensions {
+    version: SchemaExtensionsVersion,
+    items: TinyOrdMap<SchemaExtensionType, SchemaExtension>,
+    reserved: ReservedBytes<6, 0>,
+}
+
+impl SchemaExtensions {
+    /// Returns version of the container.
+    pub const fn version(&self) -> SchemaExtensionsVersion { self.version }
+
+    /// Detects whether the container has no extensions.
+    pub fn is_empty(&self) -> bool { self.items.is_empty() }
+
+    /// Iterates over the extensions, ordered by their type.
+    pub fn iter(&self) -> impl Iterator<Item = &SchemaExtension> { self.items.values() }
+
+    /// Returns extension of a given type, if present.
+    pub fn get(&self, ty: SchemaExtensionType) -> Option<&SchemaExtension> { self.items.get(&ty) }
+
+    /// Adds extension to the container, returning previously present
+    /// extension of the same type, if any.
+    pub fn insert(&mut self, ext: impl Into<SchemaExtension>) -> Option<SchemaExtension> {
+        let ext = ext.into();
+        self.items
+            .insert(ext.extension_type(), ext)
+            .expect("number of extension types is below the container limit")
+    }
+
+    /// Removes extension of a given type from the container, returning it.
+    pub fn remove(&mut self, ty: SchemaExtensionType) -> Option<SchemaExtension> {
+        self.items
+            .remove(&ty)
+            .expect("the container has no minimal size")
+    }
+}
+
+macro_rules! extension_accessors {
+    ($($name:ident: $variant:ident($ty:ty)),+ $(,)?) => {
+        impl SchemaExtensions {
+            $(
+                #[doc = concat!("Returns [`", stringify!($ty), "`] extension, if present.")]
+                pub fn $name(&self) -> Option<&$ty> {
+                    match self.get(SchemaExtensionType::$variant)? {
+                        SchemaExtension::$variant(ext) => Some(ext),
+                        _ => None,
+                    }
+                }
+            )+
+        }
+    };
+}
+
+extension_accessors! {
+    endorsement: Endorsement(EndorsementSchema),
+    type_names: TypeNames(SchemaTypeNames),
+    key_domains: KeyDomains(KeyDomains),
+    fees: Fees(FeeSchema),
+    aux_proofs: AuxProofs(AuxProofSchema),
+    dust_limit: DustLimit(DustLimitSchema),
+    chain_supply: ChainSupply(ChainSupplySchema),
+    retention: Retention(RetentionSchema),
+    issuer_proof: IssuerProof(IssuerProofSchema),
+    features: Features(FeatureSchema),
+}
+
+impl StrictDecode for SchemaExtensions {
+    fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+        reader.read_struct(|r| {
+            let version = r.read_field(fname!("version"))?;
+            let items: TinyOrdMap<SchemaExtensionType, SchemaExtension> =
+                r.read_field(fname!("items"))?;
+            let reserved = r.read_field(fname!("reserved"))?;
+            if let Some((ty, ext)) = items.iter().find(|(ty, ext)| ext.extension_type() != **ty) {
+                return Err(DecodeError::DataIntegrityError(format!(
+                    "schema extension {} is stored under {ty} type",
+                    ext.extension_type()
+                )));
+            }
+            Ok(SchemaExtensions {
+                version,
+                items,
+                reserved,
+            })
+        })
+    }
+}
+
+/// Human-readable short names of the type ids declared by a schema.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct SchemaTypeNames {
+    pub meta: TinyOrdMap<MetaType, FieldName>,
+    pub globals: TinyOrdMap<GlobalStateType, FieldName>,
+    pub assignments: TinyOrdMap<AssignmentType, FieldName>,
+    pub valencies: TinyOrdMap<ValencyType, FieldName>,
+    pub extensions: TinyOrdMap<ExtensionType, FieldName>,
+    pub transitions: TinyOrdMap<TransitionType, FieldName>,
+}
+
+/// Requirements for the endorsement of state transitions by the keys declared
+/// in the contract genesis (see [`crate::Endorsements`]).
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct EndorsementSchema {
+    /// Type of the genesis global state listing x-only public keys of the
+    /// endorsers.
+    pub endorsers: GlobalStateType,
+    /// Types of the state transitions which must be endorsed.
+    pub transitions: TinyOrdSet<TransitionType>,
+}
+
+/// Fees which the state transitions must pay in the contract's own fungible
+/// state to the fee collectors defined in the contract genesis.
+///
+/// The fee is paid by assigning the fungible state with a revealed amount to
+/// seals matching one of the fee collector templates (see [`FeeCollector`]).
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct FeeSchema {
+    /// Type of the fungible owned state in which the fees are paid.
+    pub assignment: AssignmentType,
+    /// Type of the genesis global state containing fee collector seal
+    /// templates (see [`FeeCollector`]).
+    pub collector: GlobalStateType,
+    /// Minimal amount each of the state transitions must assign to the fee
+    /// collector.
+    pub min_amount: u64,
+    /// Types of the state transitions which must pay the fee.
+    pub transitions: TinyOrdSet<TransitionType>,
+}
+
+/// Template of the seals receiving fees required by [`FeeSchema`], stored in
+/// the contract genesis global state.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = order, dumb = Self::Outpoint(strict_dumb!()))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum FeeCollector {
+    /// Any revealed seal defined on the outpoint, whatever its blinding and
+    /// closing method are.
+    Outpoint(XOutpoint),
+    /// A single seal, which may be kept concealed by the fee-paying
+    /// assignments.
+    Concealed(XChain<SecretSeal>),
+}
+
+impl StrictSerialize for FeeCollector {}
+impl StrictDeserialize for FeeCollector {}
+
+impl FeeCollector {
+    /// Detects whether a revealed seal matches the template.
+    pub fn matches<Seal: ExposedSeal>(&self, seal: &XChain<Seal>) -> bool {
+        match self {
+            FeeCollector::Outpoint(outpoint) => seal
+                .maybe_map_ref(|seal| seal.outpoint())
+                .is_some_and(|seal| XOutpoint::from(seal) == *outpoint),
+            FeeCollector::Concealed(secret) => seal.conceal() == *secret,
+        }
+    }
+
+    /// Detects whether a concealed seal matches the template.
+    pub fn matches_concealed(&self, seal: &XChain<SecretSeal>) -> bool {
+        matches!(self, FeeCollector::Concealed(secret) if secret == seal)
+    }
+}
+
+/// Declaration of an auxiliary proof type (see [`crate::AuxProofs`]).
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct AuxProofDetails {
+    /// Strict type of the proof data.
+    pub sem_id: SemId,
+    /// Maximal size of a single proof, in bytes.
+    pub max_len: u16,
+}
+
+/// Auxiliary proofs which operations may carry, with the occurrence bounds for
+/// each of the operation types. Operations may not carry proofs of types not
+/// listed for them.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct AuxProofSchema {
+    pub types: TinyOrdMap<AuxProofType, AuxProofDetails>,
+    pub genesis: TinyOrdMap<AuxProofType, Occurrences>,
+    pub transitions: TinyOrdMap<TransitionType, TinyOrdMap<AuxProofType, Occurrences>>,
+    pub extensions: TinyOrdMap<ExtensionType, TinyOrdMap<AuxProofType, Occurrences>>,
+}
+
+impl AuxProofSchema {
+    /// Returns auxiliary proofs allowed for the operations of a given type.
+    pub fn op_proofs(
+        &self,
+        op_type: OpFullType,
+    ) -> Option<&TinyOrdMap<AuxProofType, Occurrences>> {
+        match op_type {
+            OpFullType::Genesis => Some(&self.genesis),
+            OpFullType::StateTransition(ty) => self.transitions.get(&ty),
+            OpFullType::StateExtension(ty) => self.extensions.get(&ty),
+        }
+    }
+}
+
+/// Minimal bitcoin value of the witness transaction outputs hosting the
+/// contract state, preventing assignment of the state to dust outputs.
+///
+/// The rule applies to the outputs of the witness transaction itself; the
+/// validator reports violations as failures or warnings depending on the
+/// [`crate::validation::ValidationPolicy`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct DustLimitSchema {
+    /// Minimal value of an output hosting assignments, in satoshis.
+    pub min_value: u64,
+}
+
+/// Fungible state issued on multiple layers 1 under a single contract id, with
+/// the supply on each of the layers 1 capped by the contract genesis.
+///
+/// The state moves between layers 1 only with pairs of the burn and release
+/// state transitions. A burn transition burns the state on the layer 1 of its
+/// witness by assigning it to seals defined on another layer 1; the state is
+/// released there by a transition of the paired release type spending these
+/// seals, which is valid only if the burn witness is mined. The amounts issued
+/// in genesis and moved by the burn transitions must be revealed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct ChainSupplySchema {
+    /// Type of the fungible owned state which supply is tracked per layer 1.
+    pub assignment: AssignmentType,
+    /// Type of the genesis global state containing supply caps (see
+    /// [`Layer1Cap`]). Layers 1 without a cap are not limited.
+    pub caps: GlobalStateType,
+    /// Types of the burn state transitions mapped to the types of the paired
+    /// release state transitions.
+    pub bridges: TinyOrdMap<TransitionType, TransitionType>,
+}
+
+/// Maximal supply of the state declared by [`ChainSupplySchema`] on a layer 1,
+/// stored in the contract genesis global state.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct Layer1Cap {
+    /// Layer 1 to which the cap applies.
+    pub layer1: Layer1,
+    /// Maximal supply on the layer 1.
+    pub max_supply: u64,
+}
+
+impl StrictSerialize for Layer1Cap {}
+impl StrictDeserialize for Layer1Cap {}
+
+/// Owned state types which data may be pruned from the consignments once the
+/// state is superseded, i.e. spent by a state transition.
+///
+/// Pruned state is concealed, keeping the seal revealed, such that the
+/// commitment to the state is preserved and the seal closing is still
+/// validated. Only structured and attachment state may be pruned.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct RetentionSchema {
+    /// Types of the owned state which may be pruned.
+    pub prunable: TinyOrdSet<AssignmentType>,
+}
+
+/// Class of the owned state data retention in the consignments (see
+/// [`RetentionSchema`]).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(lowercase)]
+pub enum RetentionClass {
+    /// State data must always be present in the consignments.
+    #[default]
+    Permanent,
+    /// State data may be pruned once the state is spent.
+    Prunable,
+}
+
+impl RetentionClass {
+    /// Detects whether the state data may be pruned.
+    pub fn is_prunable(self) -> bool { self == RetentionClass::Prunable }
+}
+
+/// Genesis auxiliary proof type which carries an attestation of the contract
+/// issuer identity (see [`crate::IssuerAttestation`]).
+///
+/// The attestation is not required for the contract validity; the validator
+/// reports its verification outcome only if requested by the
+/// [`crate::validation::ValidationPolicy`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct IssuerProofSchema {
+    /// Type of the genesis auxiliary proof holding the attestation.
+    pub proof_type: AuxProofType,
+}
+
+/// Optional features of a contract, which the contract genesis enables
+/// with [`ContractFeatures`].
+///
+/// The schema defines which operations use each of the features; the
+/// validator rejects operations using features not enabled by the contract
+/// genesis, giving issuers coarse-grained control over the contract without
+/// custom validation scripts.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct FeatureSchema {
+    /// Type of the genesis global state containing the enabled features (see
+    /// [`ContractFeatures`]). Contracts without this state have no features
+    /// enabled.
+    pub flags: GlobalStateType,
+    /// Type of the fungible owned state to which the features apply.
+    pub assignment: AssignmentType,
+    /// Types of the state transitions burning the state, which require
+    /// [`ContractFeatures::BURN`].
+    pub burns: TinyOrdSet<TransitionType>,
+    /// Types of the state transitions issuing additional state, which require
+    /// [`ContractFeatures::INFLATION`]. State extensions assigning the state
+    /// always require this feature.
+    pub inflation: TinyOrdSet<TransitionType>,
+}
+
+/// Set of the contract features declared by [`FeatureSchema`], stored in the
+/// contract genesis global state.
+#[derive(Wrapper, Copy, Clone, PartialEq, Eq, Hash, Debug, Default, From)]
+#[wrapper(LowerHex, UpperHex)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct ContractFeatures(u16);
+
+impl ContractFeatures {
+    /// No features enabled.
+    pub const NONE: Self = ContractFeatures(0);
+    /// Burning the state with the state transitions.
+    pub const BURN: Self = ContractFeatures(0x0001);
+    /// Issuing additional state after the genesis.
+    pub const INFLATION: Self = ContractFeatures(0x0002);
+    /// Amounts assigned to concealed seals, i.e. owned by parties other than
+    /// the consignment receiver, are expected to stay confidential. Since the
+    /// revealed amounts are not committed to, their disclosure is reported as
+    /// a validation warning and not as a consensus failure.
+    pub const CONFIDENTIAL_AMOUNTS: Self = ContractFeatures(0x0004);
+    /// All features known to this version of the library.
+    pub const ALL: Self = ContractFeatures(0x0007);
+
+    const NAMES: [(Self, &'static str); 3] = [
+        (Self::BURN, "burn"),
+        (Self::INFLATION, "inflation"),
+        (Self::CONFIDENTIAL_AMOUNTS, "confidential-amounts"),
+    ];
 
-    pub reserved: ReservedBytes<8, 0>,
+    pub const fn from_bits(bits: u16) -> Self { ContractFeatures(bits) }
+
+    pub const fn bits(self) -> u16 { self.0 }
+
+    /// Detects whether all the given features are enabled.
+    pub const fn contains(self, features: Self) -> bool { self.0 & features.0 == features.0 }
+
+    /// Returns features enabled either in `self` or in `other`.
+    pub const fn union(self, other: Self) -> Self { ContractFeatures(self.0 | other.0) }
+
+    /// Returns features enabled in `self` but not in `other`.
+    pub const fn difference(self, other: Self) -> Self { ContractFeatures(self.0 & !other.0) }
+
+    /// Detects whether the set contains only the features known to this
+    /// version of the library.
+    pub const fn is_known(self) -> bool { self.0 & !Self::ALL.0 == 0 }
+}
+
+impl Display for ContractFeatures {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut names = Self::NAMES
+            .iter()
+            .filter(|(feature, _)| self.contains(*feature))
+            .map(|(_, name)| name.to_string())
+            .collect::<Vec<_>>();
+        if !self.is_known() {
+            names.push(format!("0x{:04X}", self.0 & !Self::ALL.0));
+        }
+        if names.is_empty() {
+            return f.write_str("none");
+        }
+        f.write_str(&names.join(", "))
+    }
 }
 
+impl StrictSerialize for ContractFeatures {}
+impl StrictDeserialize for ContractFeatures {}
+
 impl CommitEncode for Schema {
     type CommitmentId = SchemaId;
 
@@ -194,8 +801,7 @@ impl CommitEncode for Schema {
         e.commit_to_serialized(&self.genesis);
         e.commit_to_map(&self.extensions);
         e.commit_to_map(&self.transitions);
-
-        e.commit_to_serialized(&self.reserved);
+        e.commit_to_serialized(&self.ext);
     }
 }
 
@@ -239,6 +845,59 @@ impl Schema {
             )
     }
 
+    /// Returns domain of the keys of [`crate::GlobalStateKind::KeyValue`]
+    /// global state type, if declared.
+    pub fn key_domain(&self, ty: GlobalStateType) -> Option<&KeyDomain> {
+        self.ext.key_domains()?.get(&ty)
+    }
+
+    /// Returns retention class of the owned state type.
+    pub fn retention(&self, ty: AssignmentType) -> RetentionClass {
+        match self.ext.retention() {
+            Some(retention) if retention.prunable.contains(&ty) => RetentionClass::Prunable,
+            _ => RetentionClass::Permanent,
+        }
+    }
+
+    /// Returns human-readable name of the metadata type, if defined.
+    pub fn meta_name(&self, ty: MetaType) -> Option<&FieldName> {
+        self.ext.type_names()?.meta.get(&ty)
+    }
+
+    /// Returns human-readable name of the global state type, if defined.
+    pub fn global_name(&self, ty: GlobalStateType) -> Option<&FieldName> {
+        self.ext.type_names()?.globals.get(&ty)
+    }
+
+    /// Returns human-readable name of the assignment type, if defined.
+    pub fn assignment_name(&self, ty: AssignmentType) -> Option<&FieldName> {
+        self.ext.type_names()?.assignments.get(&ty)
+    }
+
+    /// Returns human-readable name of the valency type, if defined.
+    pub fn valency_name(&self, ty: ValencyType) -> Option<&FieldName> {
+        self.ext.type_names()?.valencies.get(&ty)
+    }
+
+    /// Returns human-readable name of the state extension type, if defined.
+    pub fn extension_name(&self, ty: ExtensionType) -> Option<&FieldName> {
+        self.ext.type_names()?.extensions.get(&ty)
+    }
+
+    /// Returns human-readable name of the state transition type, if defined.
+    pub fn transition_name(&self, ty: TransitionType) -> Option<&FieldName> {
+        self.ext.type_names()?.transitions.get(&ty)
+    }
+
+    /// Returns human-readable name of the state type, if defined.
+    pub fn state_name(&self, state: StateRef) -> Option<&FieldName> {
+        match state {
+            StateRef::Global(ty) => self.global_name(ty),
+            StateRef::Owned(ty) => self.assignment_name(ty),
+            StateRef::Valency(ty) => self.valency_name(ty),
+        }
+    }
+
     pub fn libs(&self) -> impl Iterator<Item = LibId> + '_ {
         self.genesis
             .validator
@@ -252,9 +911,13 @@ impl Schema {
 
 #[cfg(test)]
 mod test {
-    use strict_encoding::StrictDumb;
+    use strict_encoding::{StreamWriter, StrictDumb, StrictReader};
+    use strict_types::TypeSystem;
 
     use super::*;
+    use crate::{
+        validation, AssignmentType, BundleId, ContractId, OpFullType, StateRef, ValencyType,
+    };
 
     #[test]
     fn display() {
@@ -283,4 +946,165 @@ mod test {
         );
         assert_eq!(&format!("{less_dumb:-#}"), "RVY0MzUwLSc0dndqJzQ7di13OTR3J2UndkZWVkRocHE");
     }
+
+    #[test]
+    fn type_id_ranges() {
+        assert_eq!(TransitionType::standard(1), TransitionType::with(1));
+        assert_eq!(TransitionType::experimental(1), TransitionType::with(0x8001));
+        assert_eq!(GlobalStateType::experimental(0xFEFF - 0x8000), GlobalStateType::with(0xFEFF));
+        assert!(AssignmentType::standard(0x7FFF).is_standard());
+        assert!(AssignmentType::experimental(0).is_experimental());
+        assert!(!AssignmentType::experimental(0).is_standard());
+        assert!(TransitionType::BLANK.is_reserved());
+        assert!(!GlobalStateType::with(0xFEFF).is_reserved());
+    }
+
+    #[test]
+    #[should_panic]
+    fn type_id_nonstandard() { TransitionType::standard(0x8000); }
+
+    #[test]
+    #[should_panic]
+    fn type_id_experimental_overflow() { TransitionType::experimental(0xFEFF - 0x8000 + 1); }
+
+    #[test]
+    fn state_refs() {
+        let global = StateRef::from(GlobalStateType::with(1));
+        let owned = StateRef::from(AssignmentType::with(1));
+        let valency = StateRef::from(ValencyType::with(1));
+        assert_eq!(global, StateRef::Global(GlobalStateType::with(1)));
+        assert_eq!(owned, StateRef::Owned(AssignmentType::with(1)));
+        assert_eq!(valency, StateRef::Valency(ValencyType::with(1)));
+        assert_eq!(global.to_string(), "global state type 0x0001");
+        assert_eq!(owned.to_string(), "owned state type 0x0001");
+        assert_eq!(valency.to_string(), "valency type 0x0001");
+
+        // The same type number used for different kinds of state must be reported
+        // separately, without ambiguity
+        let mut schema = Schema::strict_dumb();
+        schema
+            .genesis
+            .globals
+            .insert(GlobalStateType::with(1), Occurrences::Once)
+            .unwrap();
+        schema
+            .genesis
+            .assignments
+            .insert(AssignmentType::with(1), Occurrences::Once)
+            .unwrap();
+        schema.genesis.valencies.push(ValencyType::with(1)).unwrap();
+        let status = schema.verify(&TypeSystem::new());
+        for state in [global, owned, valency] {
+            assert!(status
+                .failures
+                .contains(&validation::Failure::SchemaOpStateTypeUnknown(
+                    OpFullType::Genesis,
+                    state
+                )));
+        }
+    }
+
+    #[test]
+    fn extensions() {
+        let encode = |ext: &SchemaExtensions| {
+            let mut data = vec![];
+            ext.strict_write(StreamWriter::new::<0xFF>(&mut data))
+                .unwrap();
+            data
+        };
+        let decode = |data: &[u8]| {
+            SchemaExtensions::strict_decode(&mut StrictReader::in_memory::<0xFF>(data.to_vec()))
+        };
+
+        // Empty container is encoded as the reserved bytes of RGB 0.11.0-beta.8 schemata
+        let mut schema = Schema::strict_dumb();
+        assert!(schema.ext.is_empty());
+        assert_eq!(encode(&schema.ext), vec![0u8; 8]);
+        assert_eq!(
+            schema.schema_id().to_string(),
+            "rgb:sch:2TGiQQuKAhtgOvwx2bg618eZl7JVjZNe7vPoRnaCYpk#tribal-sierra-economy"
+        );
+
+        let endorsement = EndorsementSchema {
+            endorsers: GlobalStateType::with(1),
+            transitions: tiny_bset![TransitionType::with(1)],
+        };
+        assert_eq!(schema.ext.insert(endorsement.clone()), None);
+        assert_eq!(schema.ext.endorsement(), Some(&endorsement));
+        assert_ne!(
+            schema.schema_id().to_string(),
+            "rgb:sch:2TGiQQuKAhtgOvwx2bg618eZl7JVjZNe7vPoRnaCYpk#tribal-sierra-economy"
+        );
+        let data = schema.to_strict_serialized::<0xFFFF>().unwrap();
+        let decoded = Schema::from_strict_serialized::<0xFFFF>(data).unwrap();
+        assert_eq!(decoded.ext, schema.ext);
+
+        // Extension must be stored under its own type
+        let mut data = encode(&schema.ext);
+        assert_eq!(data[..4], [0, 1, SchemaExtensionType::Endorsement as u8, 0]);
+        assert_eq!(decode(&data).unwrap(), schema.ext);
+        data[2] = 1;
+        assert!(decode(&data).is_err());
+
+        assert_eq!(
+            schema.ext.remove(SchemaExtensionType::Endorsement),
+            Some(SchemaExtension::Endorsement(endorsement))
+        );
+        assert!(schema.ext.is_empty());
+    }
+
+    #[test]
+    fn compact() {
+        let bytes = *b"EV4350-'4vwj'4;v-w94w'e'vFVVDhpq";
+
+        let schema_id = SchemaId::from_byte_array(bytes);
+        let compact = schema_id.to_compact_string();
+        assert!(compact.starts_with("rgb:sch:"));
+        assert!(!compact.contains('#') && !compact.contains('-'));
+        assert_eq!(SchemaId::from_compact_str(&compact).unwrap(), schema_id);
+        assert_eq!(SchemaId::from_compact_str(&schema_id.to_string()).unwrap(), schema_id);
+
+        let contract_id = ContractId::from_byte_array(bytes);
+        let compact = contract_id.to_compact_string();
+        assert!(compact.starts_with("rgb:"));
+        assert_eq!(ContractId::from_compact_str(&compact).unwrap(), contract_id);
+
+        let bundle_id = BundleId::from_byte_array(bytes);
+        let compact = bundle_id.to_compact_string();
+        assert!(compact.starts_with("rgb:bdl:"));
+        assert_eq!(BundleId::from_compact_str(&compact).unwrap(), bundle_id);
+
+        // Identifier of a different type must not be accepted
+        assert!(matches!(
+            SchemaId::from_compact_str(&compact),
+            Err(Baid64ParseError::InvalidHri(..))
+        ));
+
+        // Typo must be detected by the embedded checksum
+        let mut typo = schema_id.to_compact_string().into_bytes();
+        typo[10] = if typo[10] == b'A' { b'B' } else { b'A' };
+        let typo = String::from_utf8(typo).unwrap();
+        assert!(matches!(
+            SchemaId::from_compact_str(&typo),
+            Err(Baid64ParseError::InvalidChecksum(..))
+        ));
+    }
+
+    #[test]
+    fn contract_features() {
+        let features = ContractFeatures::BURN.union(ContractFeatures::CONFIDENTIAL_AMOUNTS);
+        assert!(features.contains(ContractFeatures::BURN));
+        assert!(!features.contains(ContractFeatures::ALL));
+        assert_eq!(ContractFeatures::ALL.difference(features), ContractFeatures::INFLATION);
+        assert_eq!(features.to_string(), "burn, confidential-amounts");
+        assert_eq!(ContractFeatures::NONE.to_string(), "none");
+
+        let unknown = ContractFeatures::from_bits(0x8002);
+        assert!(!unknown.is_known());
+        assert_eq!(unknown.to_string(), "inflation, 0x8000");
+
+        let data = features.to_strict_serialized::<2>().unwrap();
+        assert_eq!(data.as_slice(), &[0x05, 0x00]);
+        assert_eq!(ContractFeatures::from_strict_serialized::<2>(data).unwrap(), features);
+    }
 }
@@ -20,12 +20,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use amplify::confinement::TinyOrdMap;
 use amplify::num::u24;
-use commit_verify::ReservedBytes;
 use strict_encoding::Primitive;
 use strict_types::SemId;
 
-use crate::{StateType, LIB_NAME_RGB_COMMIT};
+use crate::{GlobalStateType, StateType, LIB_NAME_RGB_COMMIT};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Display)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
@@ -106,6 +106,116 @@ pub enum FungibleType {
     Unsigned64Bit = Primitive::U64.into_code(),
 }
 
+impl FungibleType {
+    /// Maximal amount which can be represented by the state of this type.
+    pub const fn max_value(self) -> u128 {
+        match self {
+            FungibleType::Unsigned64Bit => u64::MAX as u128,
+        }
+    }
+}
+
+/// Kind of the global state, defining additional consensus rules applied to
+/// it on top of the type system validation.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(lowercase)]
+#[repr(u8)]
+pub enum GlobalStateKind {
+    /// Plain global state, validated only against its semantic type.
+    #[default]
+    Plain = 0,
+
+    // TODO: Value 1 is reserved for computed state (RCP240327A).
+    /// Notarized external documents: each of the state items must be a
+    /// [`crate::DocumentRevision`], and revisions must form non-forking chains.
+    Document = 2,
+
+    /// Dictionary of records: each of the state items must be a
+    /// [`crate::KeyValueRecord`] setting or deleting a value under a key from
+    /// the domain declared in [`crate::SchemaExtensions::key_domains`].
+    KeyValue = 3,
+}
+
+/// Set of characters allowed in the keys of [`GlobalStateKind::KeyValue`]
+/// global state.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(lowercase)]
+#[repr(u8)]
+pub enum KeyCharset {
+    /// ASCII letters and digits.
+    #[default]
+    Alphanumeric = 0,
+    /// Lowercase ASCII letters, digits, `-` and `.`, like in domain names.
+    DomainName = 1,
+    /// Printable ASCII characters, excluding space.
+    Printable = 2,
+}
+
+impl KeyCharset {
+    /// Checks whether the character belongs to the charset.
+    pub fn contains(self, c: char) -> bool {
+        match self {
+            KeyCharset::Alphanumeric => c.is_ascii_alphanumeric(),
+            KeyCharset::DomainName => {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.'
+            }
+            KeyCharset::Printable => c.is_ascii_graphic(),
+        }
+    }
+}
+
+/// Domain of the keys and values of [`GlobalStateKind::KeyValue`] global state.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct KeyDomain {
+    pub charset: KeyCharset,
+    pub max_key_len: u8,
+    pub max_value_len: u16,
+}
+
+impl KeyDomain {
+    /// Checks whether the key is non-empty, fits the maximal key length and
+    /// consists of the charset characters.
+    pub fn contains_key(&self, key: &str) -> bool {
+        !key.is_empty()
+            && key.len() <= self.max_key_len as usize
+            && key.chars().all(|c| self.charset.contains(c))
+    }
+}
+
+/// Domains of the keys of [`GlobalStateKind::KeyValue`] global state types.
+#[derive(Wrapper, WrapperMut, Clone, PartialEq, Eq, Debug, Default, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct KeyDomains(TinyOrdMap<GlobalStateType, KeyDomain>);
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = LIB_NAME_RGB_COMMIT)]
@@ -115,9 +225,7 @@ pub enum FungibleType {
     serde(crate = "serde_crate", rename_all = "camelCase")
 )]
 pub struct GlobalStateSchema {
-    // TODO: Reserved for computed state (RCP240327A): will be used as an enum tag with computed
-    //       state having value 1.
-    pub reserved: ReservedBytes<1>,
+    pub kind: GlobalStateKind,
     pub sem_id: SemId,
     pub max_items: u24,
 }
@@ -125,7 +233,7 @@ pub struct GlobalStateSchema {
 impl GlobalStateSchema {
     pub fn once(sem_id: SemId) -> Self {
         GlobalStateSchema {
-            reserved: default!(),
+            kind: GlobalStateKind::Plain,
             sem_id,
             max_items: u24::ONE,
         }
@@ -133,9 +241,47 @@ impl GlobalStateSchema {
 
     pub fn many(sem_id: SemId) -> Self {
         GlobalStateSchema {
-            reserved: default!(),
+            kind: GlobalStateKind::Plain,
             sem_id,
             max_items: u24::MAX,
         }
     }
+
+    /// Constructs schema for the dictionary-like global state (see
+    /// [`GlobalStateKind::KeyValue`]).
+    pub fn key_value(sem_id: SemId) -> Self {
+        GlobalStateSchema {
+            kind: GlobalStateKind::KeyValue,
+            sem_id,
+            max_items: u24::MAX,
+        }
+    }
+
+    /// Constructs schema for the global state notarizing external documents
+    /// (see [`GlobalStateKind::Document`]).
+    pub fn documents(sem_id: SemId) -> Self {
+        GlobalStateSchema {
+            kind: GlobalStateKind::Document,
+            sem_id,
+            max_items: u24::MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn key_domain() {
+        let domain = KeyDomain {
+            charset: KeyCharset::DomainName,
+            max_key_len: 12,
+            max_value_len: 64,
+        };
+        assert!(domain.contains_key("example.rgb"));
+        assert!(!domain.contains_key(""));
+        assert!(!domain.contains_key("Example.rgb"));
+        assert!(!domain.contains_key("example.rgb.io"));
+    }
 }
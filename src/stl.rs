@@ -20,28 +20,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::OnceLock;
+
 pub use aluvm::stl::aluvm_stl;
 pub use bp::bc::stl::bp_tx_stl;
 pub use bp::stl::bp_core_stl;
 use commit_verify::stl::commit_verify_stl;
 use strict_types::stl::{std_stl, strict_types_stl};
 use strict_types::typelib::LibBuilder;
-use strict_types::{CompileError, TypeLib};
+use strict_types::{CompileError, TypeLib, TypeLibId};
 
 use crate::validation::DbcProof;
 use crate::vm::{GlobalOrd, XWitnessId};
 use crate::{
-    Extension, Genesis, OpCommitment, Schema, TransitionBundle, LIB_NAME_RGB_COMMIT,
-    LIB_NAME_RGB_LOGIC,
+    ContractFeatures, DocumentRevision, Extension, Genesis, IssuerAttestation, KeyValueRecord,
+    OpCommitment, Schema, TransitionBundle, LIB_NAME_RGB_COMMIT, LIB_NAME_RGB_LOGIC,
 };
 
 /// Strict types id for the library providing data types for RGB consensus.
 pub const LIB_ID_RGB_COMMIT: &str =
-    "stl:ZMTVCU25-QDo98xR-wI91wcu-ydb7kui-QfZbF$n-0KDS2ow#tuna-safari-design";
+    "stl:74bzOS2V-zweTwLx-bI9eK7X-2HLi1J9-UUL3qnq-Sg31mKE#kansas-people-albino";
 /// Strict types id for the library providing data types for RGB consensus.
 pub const LIB_ID_RGB_LOGIC: &str =
-    "stl:bioTBozT-NqelHGE-SPbnpMA-XBNSbXZ-6X0dANE-WHVirL8#explain-marvin-bless";
+    "stl:AyypWK2e-O7dARt7-lpLI4oU-aCSZgBd-!dffZ4s-yZYQx4Y#palace-switch-husband";
 
+#[allow(clippy::result_large_err)]
 fn _rgb_commit_stl() -> Result<TypeLib, CompileError> {
     LibBuilder::new(libname!(LIB_NAME_RGB_COMMIT), tiny_bset! {
         std_stl().to_dependency(),
@@ -57,9 +60,14 @@ fn _rgb_commit_stl() -> Result<TypeLib, CompileError> {
     .transpile::<TransitionBundle>()
     .transpile::<Extension>()
     .transpile::<OpCommitment>()
+    .transpile::<DocumentRevision>()
+    .transpile::<KeyValueRecord>()
+    .transpile::<IssuerAttestation>()
+    .transpile::<ContractFeatures>()
     .compile()
 }
 
+#[allow(clippy::result_large_err)]
 fn _rgb_logic_stl() -> Result<TypeLib, CompileError> {
     LibBuilder::new(libname!(LIB_NAME_RGB_LOGIC), tiny_bset! {
         std_stl().to_dependency(),
@@ -87,6 +95,52 @@ pub fn rgb_logic_stl() -> TypeLib {
     _rgb_logic_stl().expect("invalid strict type RGB consensus logic library")
 }
 
+/// Returns id of the strict type library defining the consensus layout of the
+/// linked RGB Core.
+///
+/// This is the id of the RGB consensus logic library, which commits to all of
+/// its dependencies, including RGB consensus commitments library; thus any
+/// change to the consensus data types changes the id. The id is computed once,
+/// on the first call.
+pub fn consensus_lib_id() -> TypeLibId {
+    static LIB_ID: OnceLock<TypeLibId> = OnceLock::new();
+    *LIB_ID.get_or_init(|| rgb_logic_stl().id())
+}
+
+#[doc(hidden)]
+pub const fn const_str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Asserts at compile time that the linked RGB Core has the expected
+/// consensus layout, given as the string representation of the
+/// [`consensus_lib_id`] (which is also provided as [`LIB_ID_RGB_LOGIC`]).
+///
+/// ```
+/// // Downstream crates put here the string literal of the id they are built for
+/// rgbcore::assert_consensus_lib_id!(rgbcore::stl::LIB_ID_RGB_LOGIC);
+/// ```
+#[macro_export]
+macro_rules! assert_consensus_lib_id {
+    ($lib_id:expr) => {
+        const _: () = assert!(
+            $crate::stl::const_str_eq($crate::stl::LIB_ID_RGB_LOGIC, $lib_id),
+            "linked RGB Core has a different consensus layout"
+        );
+    };
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -102,4 +156,14 @@ mod test {
         let lib = rgb_logic_stl();
         assert_eq!(lib.id().to_string(), LIB_ID_RGB_LOGIC);
     }
+
+    assert_consensus_lib_id!(LIB_ID_RGB_LOGIC);
+
+    #[test]
+    fn consensus_lib_id() {
+        assert_eq!(super::consensus_lib_id().to_string(), LIB_ID_RGB_LOGIC);
+        assert!(const_str_eq(LIB_ID_RGB_COMMIT, LIB_ID_RGB_COMMIT));
+        assert!(!const_str_eq(LIB_ID_RGB_COMMIT, LIB_ID_RGB_LOGIC));
+        assert!(!const_str_eq("stl:", ""));
+    }
 }
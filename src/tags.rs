@@ -0,0 +1,116 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of the tags used by the library for the domain separation of the
+//! commitment ids and signed messages.
+//!
+//! Downstream crates defining their own tagged hashes may check them against
+//! the registry to avoid collisions with the consensus commitments.
+
+use commit_verify::CommitmentId;
+
+use crate::validation::CONSIGNMENT_SIG_TAG;
+use crate::{
+    BundleId, ConcealedAttach, ConcealedData, DiscloseHash, MetadataDigest, OpId, SchemaId,
+    ENDORSEMENT_TAG, ISSUER_ATTESTATION_TAG,
+};
+
+/// Tag used by the library, together with the name of the commitment id or
+/// message type it is used for.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Display)]
+#[display("{tag} ({name})")]
+pub struct BuiltinTag {
+    pub name: &'static str,
+    pub tag: &'static str,
+}
+
+impl BuiltinTag {
+    const fn id<Id: CommitmentId>(name: &'static str) -> Self {
+        BuiltinTag { name, tag: Id::TAG }
+    }
+
+    const fn message(name: &'static str, tag: &'static str) -> Self { BuiltinTag { name, tag } }
+
+    /// Returns built-in tag with the given value, if any.
+    pub fn lookup(tag: &str) -> Option<Self> {
+        BUILTIN_TAGS.iter().find(|builtin| builtin.tag == tag).copied()
+    }
+
+    /// Detects whether the tag is used by the library.
+    pub fn is_builtin(tag: &str) -> bool { Self::lookup(tag).is_some() }
+}
+
+/// All tags used by the library.
+pub const BUILTIN_TAGS: [BuiltinTag; 10] = [
+    BuiltinTag::id::<SchemaId>("SchemaId"),
+    BuiltinTag::id::<OpId>("OpId"),
+    BuiltinTag::id::<BundleId>("BundleId"),
+    BuiltinTag::id::<DiscloseHash>("DiscloseHash"),
+    BuiltinTag::id::<MetadataDigest>("MetadataDigest"),
+    BuiltinTag::id::<ConcealedData>("ConcealedData"),
+    BuiltinTag::id::<ConcealedAttach>("ConcealedAttach"),
+    BuiltinTag::message("Endorsement", ENDORSEMENT_TAG),
+    BuiltinTag::message("IssuerAttestation", ISSUER_ATTESTATION_TAG),
+    BuiltinTag::message("SignedConsignment", CONSIGNMENT_SIG_TAG),
+];
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn no_collisions() {
+        let tags = BUILTIN_TAGS
+            .iter()
+            .map(|builtin| builtin.tag)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(tags.len(), BUILTIN_TAGS.len());
+        assert!(tags.iter().all(|tag| tag.starts_with("urn:lnp-bp:rgb:")));
+    }
+
+    #[test]
+    fn lookup() {
+        assert_eq!(BuiltinTag::lookup(OpId::TAG).map(|builtin| builtin.name), Some("OpId"));
+        assert!(BuiltinTag::is_builtin(CONSIGNMENT_SIG_TAG));
+        assert!(!BuiltinTag::is_builtin("urn:lnp-bp:rgb:operation"));
+    }
+
+    #[test]
+    fn unchanged() {
+        // Changing any of the tags changes the consensus ids and breaks
+        // signatures made by the previous versions
+        assert_eq!(BUILTIN_TAGS.map(|builtin| builtin.tag), [
+            "urn:lnp-bp:rgb:schema#2024-02-03",
+            "urn:lnp-bp:rgb:operation#2024-02-03",
+            "urn:lnp-bp:rgb:bundle#2024-02-03",
+            "urn:lnp-bp:rgb:disclose#2024-02-16",
+            "urn:lnp-bp:rgb:genesis-metadata#2026-10-16",
+            "urn:lnp-bp:rgb:state-data#2024-02-12",
+            "urn:lnp-bp:rgb:state-attach#2024-02-12",
+            "urn:lnp-bp:rgb:endorsement#2026-10-16",
+            "urn:lnp-bp:rgb:issuer-attestation#2026-10-16",
+            "urn:lnp-bp:rgb:consignment-sig#2026-10-16",
+        ]);
+    }
+}
@@ -0,0 +1,307 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between the anchored bundles kept by the party constructing the
+//! witness transaction, and the bundles sent to the clients in the
+//! consignments.
+//!
+//! The constructing party keeps the anchor with the full multi-protocol
+//! commitment block and all the transitions of the bundle ([`AnchoredBundle`]).
+//! Each client receives only the merkle path to the contract and the
+//! transitions revealed to it ([`ClientBundle`]); the client bundles can be
+//! merged back, for instance to assemble a consignment from the data received
+//! from multiple parties.
+
+use amplify::confinement::Confined;
+use bp::dbc::anchor::MergeError;
+use commit_verify::mpc;
+
+use super::EAnchor;
+use crate::{BundleId, ContractId, OpId, Operation, TransitionBundle};
+
+/// Errors converting between anchored and client bundles.
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum AnchoredBundleError {
+    /// anchor doesn't commit to bundle {0} under contract {1}.
+    NotCommitted(BundleId, ContractId),
+
+    /// invalid multi-protocol commitment proof of the client bundle. Details:
+    /// {0}
+    #[from]
+    InvalidProof(mpc::InvalidProof),
+
+    /// client anchor doesn't match the anchor of the bundle. Details: {0}
+    #[from]
+    AnchorMismatch(MergeError),
+
+    /// client bundle {actual} doesn't match bundle {expected}.
+    BundleMismatch {
+        expected: BundleId,
+        actual: BundleId,
+    },
+
+    /// transition {0} is not a part of bundle {1}.
+    UnknownTransition(OpId, BundleId),
+
+    /// transition {0} belongs to a contract other than {1}.
+    ContractMismatch(OpId, ContractId),
+
+    /// no transitions of bundle {0} are revealed to the client.
+    NoTransitions(BundleId),
+}
+
+/// Transition bundle with the anchor containing full multi-protocol commitment
+/// block of the witness transaction.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AnchoredBundle {
+    pub anchor: EAnchor<mpc::MerkleBlock>,
+    pub bundle: TransitionBundle,
+}
+
+/// Transition bundle of a single contract with the anchor containing only the
+/// merkle path to the contract commitment, as it is provided to the clients.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ClientBundle {
+    pub anchor: EAnchor,
+    pub bundle: TransitionBundle,
+}
+
+impl AnchoredBundle {
+    /// Constructs client bundle for the `contract_id`, revealing only the
+    /// transitions with the given ids.
+    ///
+    /// Checks that the anchor commits to the bundle under the contract and
+    /// that all the revealed transitions belong to the bundle and the
+    /// contract.
+    pub fn to_client_bundle(
+        &self,
+        contract_id: ContractId,
+        reveal: impl IntoIterator<Item = OpId>,
+    ) -> Result<ClientBundle, AnchoredBundleError> {
+        let bundle_id = self.bundle.bundle_id();
+        let committed = self
+            .anchor
+            .mpc_proof
+            .to_known_message_map()
+            .get(&mpc::ProtocolId::from(contract_id))
+            .copied();
+        if committed != Some(mpc::Message::from(bundle_id)) {
+            return Err(AnchoredBundleError::NotCommitted(bundle_id, contract_id));
+        }
+        let anchor = self
+            .anchor
+            .to_merkle_proof(contract_id)
+            .map_err(|_| AnchoredBundleError::NotCommitted(bundle_id, contract_id))?;
+
+        let mut transitions = bmap! {};
+        for opid in reveal {
+            let transition = self
+                .bundle
+                .known_transitions
+                .get(&opid)
+                .ok_or(AnchoredBundleError::UnknownTransition(opid, bundle_id))?;
+            if transition.contract_id != contract_id {
+                return Err(AnchoredBundleError::ContractMismatch(opid, contract_id));
+            }
+            transitions.insert(opid, transition.clone());
+        }
+        let known_transitions = Confined::try_from(transitions)
+            .map_err(|_| AnchoredBundleError::NoTransitions(bundle_id))?;
+
+        Ok(ClientBundle {
+            anchor,
+            bundle: TransitionBundle {
+                close_method: self.bundle.close_method,
+                input_map: self.bundle.input_map.clone(),
+                known_transitions,
+            },
+        })
+    }
+
+    /// Merges client bundle of the `contract_id` back, revealing its
+    /// transitions and its part of the multi-protocol commitment.
+    ///
+    /// Checks that the client bundle is the same bundle, that its anchor
+    /// commits to the bundle under the contract and matches this anchor, and
+    /// that all its transitions belong to the contract and are spent by the
+    /// bundle inputs.
+    pub fn merge_client_bundle(
+        &mut self,
+        contract_id: ContractId,
+        client: ClientBundle,
+    ) -> Result<(), AnchoredBundleError> {
+        let bundle_id = self.bundle.bundle_id();
+        let actual = client.bundle.bundle_id();
+        if actual != bundle_id {
+            return Err(AnchoredBundleError::BundleMismatch {
+                expected: bundle_id,
+                actual,
+            });
+        }
+        client.validate_transitions(contract_id)?;
+        let anchor = client.anchor.into_merkle_block(contract_id, bundle_id)?;
+        self.anchor = self.anchor.clone().merge_reveal(anchor)?;
+        for (opid, transition) in client.bundle.known_transitions {
+            self.bundle
+                .known_transitions
+                .insert(opid, transition)
+                .expect("number of transitions is limited by the bundle inputs");
+        }
+        Ok(())
+    }
+}
+
+impl ClientBundle {
+    /// Reconstructs anchored bundle containing only the part of the
+    /// multi-protocol commitment block revealed to the client, checking that
+    /// the anchor commits to the bundle under the `contract_id`.
+    pub fn into_anchored_bundle(
+        self,
+        contract_id: ContractId,
+    ) -> Result<AnchoredBundle, AnchoredBundleError> {
+        self.validate_transitions(contract_id)?;
+        let anchor = self
+            .anchor
+            .into_merkle_block(contract_id, self.bundle.bundle_id())?;
+        Ok(AnchoredBundle {
+            anchor,
+            bundle: self.bundle,
+        })
+    }
+
+    fn validate_transitions(&self, contract_id: ContractId) -> Result<(), AnchoredBundleError> {
+        let bundle_id = self.bundle.bundle_id();
+        for (opid, transition) in &self.bundle.known_transitions {
+            if transition.id() != *opid || !self.bundle.input_map.values().any(|id| id == opid) {
+                return Err(AnchoredBundleError::UnknownTransition(*opid, bundle_id));
+            }
+            if transition.contract_id != contract_id {
+                return Err(AnchoredBundleError::ContractMismatch(*opid, contract_id));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::ByteArray;
+    use commit_verify::TryCommitVerify;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::{InputMap, Transition, Vin};
+
+    fn transition(contract_id: ContractId, nonce: u64) -> Transition {
+        let mut transition = Transition::strict_dumb();
+        transition.contract_id = contract_id;
+        transition.nonce = nonce;
+        transition
+    }
+
+    fn anchored(contract_id: ContractId, other: ContractId) -> AnchoredBundle {
+        let transitions = [transition(contract_id, 1), transition(contract_id, 2)];
+        let mut input_map = InputMap::with(Vin::from_u32(0), transitions[0].id());
+        input_map.insert(Vin::from_u32(1), transitions[1].id()).unwrap();
+        let bundle = TransitionBundle {
+            close_method: strict_dumb!(),
+            input_map,
+            known_transitions: Confined::try_from_iter(transitions.map(|t| (t.id(), t))).unwrap(),
+        };
+        let source = mpc::MultiSource {
+            messages: Confined::try_from(bmap! {
+                mpc::ProtocolId::from(contract_id) => mpc::Message::from(bundle.bundle_id()),
+                mpc::ProtocolId::from(other) => mpc::Message::from_byte_array([0xAA; 32]),
+            })
+            .unwrap(),
+            ..default!()
+        };
+        let tree = mpc::MerkleTree::try_commit(&source).unwrap();
+        AnchoredBundle {
+            anchor: EAnchor::new(mpc::MerkleBlock::from(tree), strict_dumb!()),
+            bundle,
+        }
+    }
+
+    #[test]
+    fn client_bundles() {
+        let contract_id = ContractId::from_byte_array([0x01; 32]);
+        let other = ContractId::from_byte_array([0x02; 32]);
+        let anchored = anchored(contract_id, other);
+        let bundle_id = anchored.bundle.bundle_id();
+        let opids = anchored
+            .bundle
+            .known_transitions
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+
+        let client1 = anchored.to_client_bundle(contract_id, [opids[0]]).unwrap();
+        let client2 = anchored.to_client_bundle(contract_id, [opids[1]]).unwrap();
+        assert_eq!(client1.bundle.bundle_id(), bundle_id);
+        assert_eq!(client1.bundle.known_transitions.len(), 1);
+        assert!(client1.anchor.convolve(contract_id, bundle_id).is_ok());
+
+        assert_eq!(
+            anchored.to_client_bundle(other, [opids[0]]),
+            Err(AnchoredBundleError::NotCommitted(bundle_id, other))
+        );
+        assert_eq!(
+            anchored.to_client_bundle(contract_id, []),
+            Err(AnchoredBundleError::NoTransitions(bundle_id))
+        );
+        let unknown = transition(contract_id, 3).id();
+        assert_eq!(
+            anchored.to_client_bundle(contract_id, [unknown]),
+            Err(AnchoredBundleError::UnknownTransition(unknown, bundle_id))
+        );
+
+        let mut merged = client1.clone().into_anchored_bundle(contract_id).unwrap();
+        merged.merge_client_bundle(contract_id, client2).unwrap();
+        assert_eq!(merged.bundle, anchored.bundle);
+        assert!(merged.anchor.matches(&anchored.anchor));
+
+        let mut full = anchored.clone();
+        full.merge_client_bundle(contract_id, client1.clone()).unwrap();
+        assert_eq!(full, anchored);
+
+        // Transitions of other contracts can't be merged into the bundle
+        let mut foreign = client1.clone();
+        let opid = opids[0];
+        foreign.bundle.known_transitions = Confined::with((opid, transition(other, 1)));
+        assert_eq!(
+            full.merge_client_bundle(contract_id, foreign),
+            Err(AnchoredBundleError::UnknownTransition(opid, bundle_id))
+        );
+        let mut foreign = client1.clone();
+        foreign.bundle.input_map.insert(Vin::from_u32(2), transition(other, 1).id()).unwrap();
+        assert!(matches!(
+            full.merge_client_bundle(contract_id, foreign),
+            Err(AnchoredBundleError::BundleMismatch { expected, .. }) if expected == bundle_id
+        ));
+        assert_eq!(
+            client1.clone().into_anchored_bundle(other),
+            Err(AnchoredBundleError::ContractMismatch(opids[0], other))
+        );
+    }
+}
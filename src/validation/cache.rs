@@ -0,0 +1,684 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Persistent cache of the witness data resolved and verified during the
+//! validation, allowing to skip repeated witness resolution for the contracts
+//! which are validated multiple times.
+//!
+//! For long-running services the module also provides [`CachingResolver`], a
+//! bounded in-memory cache which can wrap any witness resolver.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Mutex, MutexGuard, RwLock};
+
+use amplify::confinement::{LargeOrdMap, SmallOrdSet};
+use bp::Sats;
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+use super::{ResolveWitness, SystemClock, TimeSource, WitnessResolverError};
+use crate::vm::{WitnessOrd, XWitnessId, XWitnessTx};
+use crate::{BundleId, XOutpoint, LIB_NAME_RGB_LOGIC};
+
+/// Cache of resolved witness transactions, their positions in the blockchain
+/// and the results of anchor verification, keyed by the witness id.
+///
+/// Only witnesses mined into the blockchain have their ordering cached, since
+/// the status of the tentative witnesses changes frequently. The cache is
+/// strict-encoded for the persistence.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_LOGIC)]
+pub struct WitnessCache {
+    txes: LargeOrdMap<XWitnessId, XWitnessTx>,
+    ords: LargeOrdMap<XWitnessId, WitnessOrd>,
+    anchors: LargeOrdMap<XWitnessId, SmallOrdSet<BundleId>>,
+}
+
+impl StrictSerialize for WitnessCache {}
+impl StrictDeserialize for WitnessCache {}
+
+impl WitnessCache {
+    /// Constructs empty cache.
+    pub fn new() -> Self { Self::default() }
+
+    /// Returns cached witness transaction, if any.
+    pub fn witness_tx(&self, witness_id: XWitnessId) -> Option<&XWitnessTx> {
+        self.txes.get(&witness_id)
+    }
+
+    /// Returns cached witness ordering, if any.
+    pub fn witness_ord(&self, witness_id: XWitnessId) -> Option<WitnessOrd> {
+        self.ords.get(&witness_id).copied()
+    }
+
+    /// Detects whether anchoring of the bundle to the witness was already
+    /// verified.
+    pub fn is_anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) -> bool {
+        self.anchors
+            .get(&witness_id)
+            .is_some_and(|bundles| bundles.contains(&bundle_id))
+    }
+
+    /// Adds witness transaction to the cache. Does nothing if the cache is
+    /// full.
+    pub fn cache_tx(&mut self, witness_id: XWitnessId, tx: XWitnessTx) {
+        self.txes.insert(witness_id, tx).ok();
+    }
+
+    /// Adds witness ordering to the cache, unless the witness is not mined
+    /// or the cache is full.
+    pub fn cache_ord(&mut self, witness_id: XWitnessId, ord: WitnessOrd) {
+        if matches!(ord, WitnessOrd::Mined(_)) {
+            self.ords.insert(witness_id, ord).ok();
+        } else {
+            self.ords.remove(&witness_id).ok();
+        }
+    }
+
+    /// Records successful verification of the bundle anchoring to the
+    /// witness. Does nothing if the cache is full.
+    pub fn cache_anchor(&mut self, witness_id: XWitnessId, bundle_id: BundleId) {
+        let mut bundles = self
+            .anchors
+            .remove(&witness_id)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        bundles.push(bundle_id).ok();
+        self.anchors.insert(witness_id, bundles).ok();
+    }
+
+    /// Removes all data about a witness, for instance after a blockchain
+    /// re-org.
+    pub fn forget(&mut self, witness_id: XWitnessId) {
+        self.txes.remove(&witness_id).ok();
+        self.ords.remove(&witness_id).ok();
+        self.anchors.remove(&witness_id).ok();
+    }
+
+    /// Constructs witness resolver, which uses data from this cache and
+    /// resolves missed witnesses with the `inner` resolver, caching the
+    /// results.
+    pub fn resolver<R: ResolveWitness>(&mut self, inner: R) -> CachedWitnesses<'_, R> {
+        CachedWitnesses {
+            cache: RefCell::new(self),
+            inner,
+        }
+    }
+}
+
+/// Witness resolver backed by [`WitnessCache`].
+#[derive(Debug)]
+pub struct CachedWitnesses<'cache, R: ResolveWitness> {
+    cache: RefCell<&'cache mut WitnessCache>,
+    inner: R,
+}
+
+impl<'cache, R: ResolveWitness> ResolveWitness for CachedWitnesses<'cache, R> {
+    fn resolve_pub_witness(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<XWitnessTx, WitnessResolverError> {
+        if let Some(tx) = self.cache.borrow().witness_tx(witness_id) {
+            return Ok(tx.clone());
+        }
+        let tx = self.inner.resolve_pub_witness(witness_id)?;
+        // We cache only transactions matching the id, since otherwise the
+        // validator will report the failure anyway
+        if tx.witness_id() == witness_id {
+            self.cache.borrow_mut().cache_tx(witness_id, tx.clone());
+        }
+        Ok(tx)
+    }
+
+    fn resolve_pub_witness_ord(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<WitnessOrd, WitnessResolverError> {
+        if let Some(ord) = self.cache.borrow().witness_ord(witness_id) {
+            return Ok(ord);
+        }
+        let ord = self.inner.resolve_pub_witness_ord(witness_id)?;
+        self.cache.borrow_mut().cache_ord(witness_id, ord);
+        Ok(ord)
+    }
+
+    fn is_anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) -> bool {
+        self.cache
+            .borrow()
+            .is_anchor_verified(witness_id, bundle_id)
+    }
+
+    fn anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) {
+        self.cache.borrow_mut().cache_anchor(witness_id, bundle_id)
+    }
+
+    fn resolve_spent_value(&self, outpoint: XOutpoint) -> Option<Sats> {
+        self.inner.resolve_spent_value(outpoint)
+    }
+}
+
+/// [`WitnessCache`] which can be shared between threads, allowing concurrent
+/// validation of multiple consignments against the same cache.
+#[derive(Debug, Default)]
+pub struct SharedWitnessCache(RwLock<WitnessCache>);
+
+impl From<WitnessCache> for SharedWitnessCache {
+    fn from(cache: WitnessCache) -> Self { Self(RwLock::new(cache)) }
+}
+
+impl SharedWitnessCache {
+    /// Constructs empty cache.
+    pub fn new() -> Self { Self::default() }
+
+    /// Releases the cache, for instance for persisting it.
+    pub fn into_inner(self) -> WitnessCache {
+        self.0.into_inner().expect("witness cache lock is poisoned")
+    }
+
+    /// Constructs witness resolver, which uses data from this cache and
+    /// resolves missed witnesses with the `inner` resolver, caching the
+    /// results. Multiple resolvers may use the cache at the same time.
+    pub fn resolver<R: ResolveWitness>(&self, inner: R) -> SharedCachedWitnesses<'_, R> {
+        SharedCachedWitnesses { cache: self, inner }
+    }
+
+    fn read<T>(&self, f: impl FnOnce(&WitnessCache) -> T) -> T {
+        f(&self.0.read().expect("witness cache lock is poisoned"))
+    }
+
+    fn write(&self, f: impl FnOnce(&mut WitnessCache)) {
+        f(&mut self.0.write().expect("witness cache lock is poisoned"))
+    }
+}
+
+/// Witness resolver backed by [`SharedWitnessCache`]. The resolver is
+/// [`Sync`] whenever the `inner` resolver is.
+#[derive(Debug)]
+pub struct SharedCachedWitnesses<'cache, R: ResolveWitness> {
+    cache: &'cache SharedWitnessCache,
+    inner: R,
+}
+
+impl<'cache, R: ResolveWitness> ResolveWitness for SharedCachedWitnesses<'cache, R> {
+    fn resolve_pub_witness(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<XWitnessTx, WitnessResolverError> {
+        if let Some(tx) = self
+            .cache
+            .read(|cache| cache.witness_tx(witness_id).cloned())
+        {
+            return Ok(tx);
+        }
+        let tx = self.inner.resolve_pub_witness(witness_id)?;
+        if tx.witness_id() == witness_id {
+            self.cache
+                .write(|cache| cache.cache_tx(witness_id, tx.clone()));
+        }
+        Ok(tx)
+    }
+
+    fn resolve_pub_witness_ord(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<WitnessOrd, WitnessResolverError> {
+        if let Some(ord) = self.cache.read(|cache| cache.witness_ord(witness_id)) {
+            return Ok(ord);
+        }
+        let ord = self.inner.resolve_pub_witness_ord(witness_id)?;
+        self.cache.write(|cache| cache.cache_ord(witness_id, ord));
+        Ok(ord)
+    }
+
+    fn is_anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) -> bool {
+        self.cache
+            .read(|cache| cache.is_anchor_verified(witness_id, bundle_id))
+    }
+
+    fn anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) {
+        self.cache
+            .write(|cache| cache.cache_anchor(witness_id, bundle_id))
+    }
+
+    fn resolve_spent_value(&self, outpoint: XOutpoint) -> Option<Sats> {
+        self.inner.resolve_spent_value(outpoint)
+    }
+}
+
+/// Counters of the [`CachingResolver`] cache usage.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct CacheMetrics {
+    /// Number of requests served from the cache.
+    pub hits: u64,
+    /// Number of requests passed to the inner resolver.
+    pub misses: u64,
+    /// Number of witnesses evicted from the cache due to its size limit.
+    pub evictions: u64,
+    /// Number of cached positions of the unmined witnesses which have expired.
+    pub expirations: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+struct CacheEntry {
+    tx: Option<XWitnessTx>,
+    ord: Option<(WitnessOrd, i64)>,
+    anchors: BTreeSet<BundleId>,
+    used: u64,
+}
+
+#[derive(Debug, Default)]
+struct LruCache {
+    entries: HashMap<XWitnessId, CacheEntry>,
+    recency: BTreeMap<u64, XWitnessId>,
+    tick: u64,
+    metrics: CacheMetrics,
+}
+
+impl LruCache {
+    fn touch(&mut self, witness_id: XWitnessId) -> Option<&mut CacheEntry> {
+        let entry = self.entries.get_mut(&witness_id)?;
+        self.recency.remove(&entry.used);
+        self.tick += 1;
+        entry.used = self.tick;
+        self.recency.insert(self.tick, witness_id);
+        Some(entry)
+    }
+
+    fn entry(&mut self, witness_id: XWitnessId, capacity: usize) -> &mut CacheEntry {
+        if !self.entries.contains_key(&witness_id) {
+            while self.entries.len() >= capacity {
+                let Some((_, lru)) = self.recency.pop_first() else {
+                    break;
+                };
+                self.entries.remove(&lru);
+                self.metrics.evictions += 1;
+            }
+            self.entries.insert(witness_id, CacheEntry::default());
+        }
+        self.touch(witness_id).expect("entry is just inserted")
+    }
+}
+
+/// Witness resolver decorator keeping the results of the `inner` resolver in a
+/// bounded in-memory cache with the least-recently-used eviction policy.
+///
+/// Witness transactions and positions of the mined witnesses are kept until
+/// evicted; positions of the witnesses which are not mined (like the ones in
+/// the mempool) expire after a configurable time-to-live, measured with the
+/// [`TimeSource`] of the resolver. Resolution errors are never cached.
+///
+/// The resolver is [`Sync`] whenever the `inner` resolver and the time source
+/// are.
+#[derive(Debug)]
+pub struct CachingResolver<R: ResolveWitness, T: TimeSource = SystemClock> {
+    inner: R,
+    clock: T,
+    capacity: usize,
+    ttl: i64,
+    cache: Mutex<LruCache>,
+}
+
+impl<R: ResolveWitness> CachingResolver<R> {
+    /// Default time-to-live of the cached positions of unmined witnesses, in
+    /// seconds.
+    pub const DEFAULT_TTL: i64 = 60;
+
+    /// Constructs resolver caching up to `capacity` witnesses resolved by the
+    /// `inner` resolver.
+    pub fn new(inner: R, capacity: usize) -> Self {
+        Self {
+            inner,
+            clock: SystemClock,
+            capacity: capacity.max(1),
+            ttl: Self::DEFAULT_TTL,
+            cache: default!(),
+        }
+    }
+}
+
+impl<R: ResolveWitness, T: TimeSource> CachingResolver<R, T> {
+    /// Sets time-to-live of the cached positions of unmined witnesses, in
+    /// seconds.
+    pub fn with_ttl(mut self, ttl: i64) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets time source used to expire cached positions of unmined
+    /// witnesses.
+    pub fn with_clock<T2: TimeSource>(self, clock: T2) -> CachingResolver<R, T2> {
+        CachingResolver {
+            inner: self.inner,
+            clock,
+            capacity: self.capacity,
+            ttl: self.ttl,
+            cache: self.cache,
+        }
+    }
+
+    /// Returns the cache usage counters.
+    pub fn metrics(&self) -> CacheMetrics { self.lock().metrics }
+
+    /// Returns number of the witnesses currently present in the cache.
+    pub fn len(&self) -> usize { self.lock().entries.len() }
+
+    /// Detects whether the cache is empty.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Removes all data about a witness, for instance after a blockchain
+    /// re-org.
+    pub fn forget(&self, witness_id: XWitnessId) {
+        let mut cache = self.lock();
+        if let Some(entry) = cache.entries.remove(&witness_id) {
+            cache.recency.remove(&entry.used);
+        }
+    }
+
+    /// Removes all cached data, keeping the metrics.
+    pub fn clear(&self) {
+        let mut cache = self.lock();
+        cache.entries.clear();
+        cache.recency.clear();
+    }
+
+    /// Releases the inner resolver.
+    pub fn into_inner(self) -> R { self.inner }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache> {
+        self.cache.lock().expect("witness cache lock is poisoned")
+    }
+}
+
+impl<R: ResolveWitness, T: TimeSource> ResolveWitness for CachingResolver<R, T> {
+    fn resolve_pub_witness(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<XWitnessTx, WitnessResolverError> {
+        {
+            let mut cache = self.lock();
+            if let Some(tx) = cache.touch(witness_id).and_then(|entry| entry.tx.clone()) {
+                cache.metrics.hits += 1;
+                return Ok(tx);
+            }
+            cache.metrics.misses += 1;
+        }
+        let tx = self.inner.resolve_pub_witness(witness_id)?;
+        if tx.witness_id() == witness_id {
+            self.lock().entry(witness_id, self.capacity).tx = Some(tx.clone());
+        }
+        Ok(tx)
+    }
+
+    fn resolve_pub_witness_ord(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<WitnessOrd, WitnessResolverError> {
+        let now = self.clock.now();
+        {
+            let mut cache = self.lock();
+            let mut expired = false;
+            let ord = cache.touch(witness_id).and_then(|entry| match entry.ord {
+                Some((ord @ WitnessOrd::Mined(_), _)) => Some(ord),
+                Some((ord, at)) if now - at < self.ttl => Some(ord),
+                Some(_) => {
+                    expired = true;
+                    entry.ord = None;
+                    None
+                }
+                None => None,
+            });
+            if expired {
+                cache.metrics.expirations += 1;
+            }
+            if let Some(ord) = ord {
+                cache.metrics.hits += 1;
+                return Ok(ord);
+            }
+            cache.metrics.misses += 1;
+        }
+        let ord = self.inner.resolve_pub_witness_ord(witness_id)?;
+        self.lock().entry(witness_id, self.capacity).ord = Some((ord, now));
+        Ok(ord)
+    }
+
+    fn is_anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) -> bool {
+        let cached = self
+            .lock()
+            .entries
+            .get(&witness_id)
+            .is_some_and(|entry| entry.anchors.contains(&bundle_id));
+        cached || self.inner.is_anchor_verified(witness_id, bundle_id)
+    }
+
+    fn anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) {
+        self.lock()
+            .entry(witness_id, self.capacity)
+            .anchors
+            .insert(bundle_id);
+        self.inner.anchor_verified(witness_id, bundle_id)
+    }
+
+    fn resolve_spent_value(&self, outpoint: XOutpoint) -> Option<Sats> {
+        self.inner.resolve_spent_value(outpoint)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use std::thread;
+
+    use amplify::ByteArray;
+    use bp::Txid;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::vm::WitnessPos;
+    use crate::Layer1;
+
+    struct Counting(Cell<usize>);
+
+    impl ResolveWitness for Counting {
+        fn resolve_pub_witness(
+            &self,
+            _witness_id: XWitnessId,
+        ) -> Result<XWitnessTx, WitnessResolverError> {
+            self.0.set(self.0.get() + 1);
+            Ok(XWitnessTx::strict_dumb())
+        }
+
+        fn resolve_pub_witness_ord(
+            &self,
+            _witness_id: XWitnessId,
+        ) -> Result<WitnessOrd, WitnessResolverError> {
+            self.0.set(self.0.get() + 1);
+            Ok(WitnessOrd::Mined(WitnessPos::new(1, 1231006505).unwrap()))
+        }
+    }
+
+    #[test]
+    fn resolve_cached() {
+        let witness_id = XWitnessTx::strict_dumb().witness_id();
+        let bundle_id = BundleId::strict_dumb();
+        let inner = Counting(Cell::new(0));
+
+        let mut cache = WitnessCache::new();
+        let resolver = cache.resolver(&inner);
+        for _ in 0..3 {
+            resolver.resolve_pub_witness(witness_id).unwrap();
+            resolver.resolve_pub_witness_ord(witness_id).unwrap();
+        }
+        assert!(!resolver.is_anchor_verified(witness_id, bundle_id));
+        resolver.anchor_verified(witness_id, bundle_id);
+        assert_eq!(inner.0.get(), 2);
+
+        let cache = WitnessCache::from_strict_serialized::<{ usize::MAX }>(
+            cache.to_strict_serialized::<{ usize::MAX }>().unwrap(),
+        )
+        .unwrap();
+        assert!(cache.is_anchor_verified(witness_id, bundle_id));
+        assert!(cache.witness_ord(witness_id).is_some());
+    }
+
+    struct Shared(AtomicUsize);
+
+    impl ResolveWitness for Shared {
+        fn resolve_pub_witness(
+            &self,
+            _witness_id: XWitnessId,
+        ) -> Result<XWitnessTx, WitnessResolverError> {
+            self.0.fetch_add(1, AtomicOrdering::Relaxed);
+            Ok(XWitnessTx::strict_dumb())
+        }
+
+        fn resolve_pub_witness_ord(
+            &self,
+            _witness_id: XWitnessId,
+        ) -> Result<WitnessOrd, WitnessResolverError> {
+            Ok(WitnessOrd::Mined(WitnessPos::new(1, 1231006505).unwrap()))
+        }
+    }
+
+    #[test]
+    fn resolve_shared() {
+        fn assert_sync<T: Send + Sync>(_: &T) {}
+
+        let witness_id = XWitnessTx::strict_dumb().witness_id();
+        let bundle_id = BundleId::strict_dumb();
+        let inner = Shared(AtomicUsize::new(0));
+        let cache = SharedWitnessCache::new();
+        assert_sync(&cache);
+        let resolver = cache.resolver(&inner);
+        assert_sync(&resolver);
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    resolver.resolve_pub_witness(witness_id).unwrap();
+                    resolver.anchor_verified(witness_id, bundle_id);
+                });
+            }
+        });
+        let resolved = inner.0.load(AtomicOrdering::Relaxed);
+        assert!((1..=4).contains(&resolved));
+        resolver.resolve_pub_witness(witness_id).unwrap();
+        assert_eq!(inner.0.load(AtomicOrdering::Relaxed), resolved);
+
+        let cache = cache.into_inner();
+        assert!(cache.is_anchor_verified(witness_id, bundle_id));
+        assert!(cache.witness_tx(witness_id).is_some());
+    }
+
+    struct Clock(Cell<i64>);
+
+    impl TimeSource for Clock {
+        fn now(&self) -> i64 { self.0.get() }
+    }
+
+    fn witness(no: u8) -> XWitnessId {
+        XWitnessId::with(Layer1::Bitcoin, Txid::from_byte_array([no; 32]))
+    }
+
+    #[test]
+    fn caching_resolver() {
+        let witness_id = XWitnessTx::strict_dumb().witness_id();
+        let bundle_id = BundleId::strict_dumb();
+        let inner = Counting(Cell::new(0));
+        let resolver = CachingResolver::new(&inner, 2);
+        for _ in 0..3 {
+            resolver.resolve_pub_witness(witness_id).unwrap();
+            resolver.resolve_pub_witness_ord(witness_id).unwrap();
+        }
+        resolver.anchor_verified(witness_id, bundle_id);
+        assert!(resolver.is_anchor_verified(witness_id, bundle_id));
+        assert_eq!(inner.0.get(), 2);
+        assert_eq!(resolver.metrics(), CacheMetrics {
+            hits: 4,
+            misses: 2,
+            evictions: 0,
+            expirations: 0
+        });
+
+        // The least recently used witness is evicted first
+        resolver.resolve_pub_witness_ord(witness(1)).unwrap();
+        resolver.resolve_pub_witness_ord(witness_id).unwrap();
+        resolver.resolve_pub_witness_ord(witness(2)).unwrap();
+        assert_eq!(resolver.len(), 2);
+        assert_eq!(resolver.metrics().evictions, 1);
+        resolver.resolve_pub_witness(witness_id).unwrap();
+        assert_eq!(inner.0.get(), 4);
+        resolver.resolve_pub_witness_ord(witness(1)).unwrap();
+        assert_eq!(inner.0.get(), 5);
+
+        resolver.clear();
+        assert!(resolver.is_empty());
+        assert!(!resolver.is_anchor_verified(witness_id, bundle_id));
+    }
+
+    struct Mempool(Cell<usize>);
+
+    impl ResolveWitness for Mempool {
+        fn resolve_pub_witness(
+            &self,
+            witness_id: XWitnessId,
+        ) -> Result<XWitnessTx, WitnessResolverError> {
+            Err(WitnessResolverError::Unknown(witness_id))
+        }
+
+        fn resolve_pub_witness_ord(
+            &self,
+            _witness_id: XWitnessId,
+        ) -> Result<WitnessOrd, WitnessResolverError> {
+            self.0.set(self.0.get() + 1);
+            Ok(WitnessOrd::Tentative)
+        }
+    }
+
+    #[test]
+    fn caching_resolver_ttl() {
+        let clock = Clock(Cell::new(1000));
+        let inner = Mempool(Cell::new(0));
+        let resolver = CachingResolver::new(&inner, 16)
+            .with_ttl(10)
+            .with_clock(&clock);
+
+        assert!(resolver.resolve_pub_witness(witness(1)).is_err());
+        assert!(resolver.resolve_pub_witness(witness(1)).is_err());
+        assert_eq!(resolver.metrics().misses, 2);
+
+        resolver.resolve_pub_witness_ord(witness(1)).unwrap();
+        clock.0.set(1009);
+        resolver.resolve_pub_witness_ord(witness(1)).unwrap();
+        assert_eq!(inner.0.get(), 1);
+        clock.0.set(1010);
+        assert_eq!(resolver.resolve_pub_witness_ord(witness(1)), Ok(WitnessOrd::Tentative));
+        assert_eq!(inner.0.get(), 2);
+        assert_eq!(resolver.metrics().expirations, 1);
+
+        resolver.forget(witness(1));
+        resolver.resolve_pub_witness_ord(witness(1)).unwrap();
+        assert_eq!(inner.0.get(), 3);
+    }
+}
@@ -24,22 +24,30 @@
 //! state transitions, extensions, genesis, outputs, assignments &
 //! single-use-seal data.
 
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
 
 use aluvm::library::{Lib, LibId};
 use amplify::confinement::Confined;
 use strict_types::TypeSystem;
 
-use super::EAnchor;
+use super::{EAnchor, ForkReport, ResolveWitness, WitnessResolverError};
+#[cfg(feature = "graphviz")]
+use super::{GraphFormat, Status};
 use crate::vm::XWitnessId;
 use crate::{
-    AssignmentType, AssignmentsRef, BundleId, ContractId, Extension, ExtensionType, Genesis,
-    GlobalState, GraphSeal, Inputs, Metadata, OpFullType, OpId, OpType, Operation, Schema,
-    Transition, TransitionBundle, TransitionType, TypedAssigns, Valencies,
+    Annotations, AssignmentType, AssignmentsRef, AuxProofs, BundleId, CommitmentPreimage,
+    ContractId, Disambiguator, Extension, ExtensionType, Genesis, GlobalState, GraphSeal, Inputs,
+    Metadata, OpFullType, OpId, OpType, Operation, Opout, Schema, Transition, TransitionBundle,
+    TransitionType, TypedAssigns, Valencies,
 };
 
 pub const CONSIGNMENT_MAX_LIBS: usize = 1024;
 
+/// Deduplicated collection of AluVM libraries used by a consignment, indexed
+/// by the library id.
 pub type Scripts = Confined<BTreeMap<LibId, Lib>, 0, CONSIGNMENT_MAX_LIBS>;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, From)]
@@ -77,6 +85,14 @@ impl<'op> Operation for OpRef<'op> {
         }
     }
 
+    fn commitment_preimage(&self) -> CommitmentPreimage {
+        match self {
+            Self::Genesis(op) => op.commitment_preimage(),
+            Self::Transition(op) => op.commitment_preimage(),
+            Self::Extension(op) => op.commitment_preimage(),
+        }
+    }
+
     fn contract_id(&self) -> ContractId {
         match self {
             Self::Genesis(op) => op.contract_id(),
@@ -117,6 +133,14 @@ impl<'op> Operation for OpRef<'op> {
         }
     }
 
+    fn aux_proofs(&self) -> &AuxProofs {
+        match self {
+            Self::Genesis(op) => op.aux_proofs(),
+            Self::Transition(op) => op.aux_proofs(),
+            Self::Extension(op) => op.aux_proofs(),
+        }
+    }
+
     fn globals(&self) -> &GlobalState {
         match self {
             Self::Genesis(op) => op.globals(),
@@ -158,38 +182,137 @@ impl<'op> Operation for OpRef<'op> {
     }
 }
 
-pub struct CheckedConsignment<'consignment, C: ConsignmentApi>(&'consignment C);
+pub struct CheckedConsignment<'consignment, C: ConsignmentApi> {
+    consignment: &'consignment C,
+    /// Alternative anchors selected instead of the main bundle anchors.
+    selected: BTreeMap<BundleId, (XWitnessId, &'consignment EAnchor)>,
+    /// Witness ids of the main bundle anchors replaced with the selected
+    /// alternatives.
+    replaced: BTreeMap<XWitnessId, XWitnessId>,
+    /// Cache of [`ConsignmentApi::superseded_state`].
+    superseded: OnceCell<BTreeSet<Opout>>,
+}
 
 impl<'consignment, C: ConsignmentApi> CheckedConsignment<'consignment, C> {
-    pub fn new(consignment: &'consignment C) -> Self { Self(consignment) }
+    pub fn new(consignment: &'consignment C) -> Self {
+        Self {
+            consignment,
+            selected: empty!(),
+            replaced: empty!(),
+            superseded: OnceCell::new(),
+        }
+    }
+
+    /// Detects whether the output is of a prunable state type and is spent by
+    /// the state transitions of the consignment (see
+    /// [`ConsignmentApi::superseded_state`]).
+    pub(super) fn is_superseded(&self, opout: Opout) -> bool {
+        self.superseded
+            .get_or_init(|| self.superseded_state())
+            .contains(&opout)
+    }
+
+    /// Returns all anchors known for a bundle: the main one followed by the
+    /// alternatives.
+    pub(super) fn anchor_candidates(
+        &self,
+        bundle_id: BundleId,
+    ) -> Vec<(XWitnessId, &'consignment EAnchor)> {
+        let consignment = self.consignment;
+        consignment
+            .anchor(bundle_id)
+            .into_iter()
+            .chain(consignment.anchor_alternatives(bundle_id))
+            .collect()
+    }
+
+    /// Replaces the main anchor of the bundle with one of its alternatives.
+    pub(super) fn select_anchor(
+        &mut self,
+        bundle_id: BundleId,
+        witness_id: XWitnessId,
+        anchor: &'consignment EAnchor,
+    ) {
+        if let Some((main_id, _)) = self.consignment.anchor(bundle_id) {
+            self.replaced.insert(main_id, witness_id);
+        }
+        self.selected.insert(bundle_id, (witness_id, anchor));
+    }
 }
 
 impl<'consignment, C: ConsignmentApi> ConsignmentApi for CheckedConsignment<'consignment, C> {
-    fn schema(&self) -> &Schema { self.0.schema() }
+    fn schema(&self) -> &Schema { self.consignment.schema() }
 
-    fn types(&self) -> &TypeSystem { self.0.types() }
+    fn types(&self) -> &TypeSystem { self.consignment.types() }
 
-    fn scripts(&self) -> &Scripts { self.0.scripts() }
+    fn scripts(&self) -> &Scripts { self.consignment.scripts() }
 
-    fn operation(&self, opid: OpId) -> Option<OpRef> {
-        self.0.operation(opid).filter(|op| op.id() == opid)
+    fn operation(&self, opid: OpId) -> Option<OpRef<'_>> {
+        self.consignment
+            .operation(opid)
+            .filter(|op| op.id() == opid)
     }
 
-    fn genesis(&self) -> &Genesis { self.0.genesis() }
+    fn genesis(&self) -> &Genesis { self.consignment.genesis() }
 
-    fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter { self.0.bundle_ids() }
+    fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter {
+        self.consignment.bundle_ids()
+    }
 
     fn bundle(&self, bundle_id: BundleId) -> Option<&TransitionBundle> {
-        self.0
+        self.consignment
             .bundle(bundle_id)
             .filter(|b| b.bundle_id() == bundle_id)
     }
 
     fn anchor(&self, bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)> {
-        self.0.anchor(bundle_id)
+        match self.selected.get(&bundle_id) {
+            Some((witness_id, anchor)) => Some((*witness_id, *anchor)),
+            None => self.consignment.anchor(bundle_id),
+        }
+    }
+
+    fn anchor_alternatives<'anchor>(
+        &'anchor self,
+        bundle_id: BundleId,
+    ) -> impl Iterator<Item = (XWitnessId, &'anchor EAnchor)> + 'anchor {
+        let selected = self.anchor(bundle_id).map(|(witness_id, _)| witness_id);
+        self.anchor_candidates(bundle_id)
+            .into_iter()
+            .filter(move |(witness_id, _)| Some(*witness_id) != selected)
+    }
+
+    fn op_witness_id(&self, opid: OpId) -> Option<XWitnessId> {
+        self.consignment.op_witness_id(opid).map(|witness_id| {
+            self.replaced
+                .get(&witness_id)
+                .copied()
+                .unwrap_or(witness_id)
+        })
+    }
+
+    fn bundles_by_witness_id(&self, witness_id: XWitnessId) -> impl Iterator<Item = BundleId> {
+        let bundles = if self.selected.is_empty() {
+            self.consignment
+                .bundles_by_witness_id(witness_id)
+                .collect::<Vec<_>>()
+        } else {
+            self.bundle_ids()
+                .filter(|bundle_id| {
+                    matches!(self.anchor(*bundle_id), Some((id, _)) if id == witness_id)
+                })
+                .collect()
+        };
+        bundles.into_iter()
     }
 
-    fn op_witness_id(&self, opid: OpId) -> Option<XWitnessId> { self.0.op_witness_id(opid) }
+    fn ops_by_witness_id(&self, witness_id: XWitnessId) -> impl Iterator<Item = OpId> {
+        self.bundles_by_witness_id(witness_id)
+            .filter_map(|bundle_id| self.bundle(bundle_id))
+            .flat_map(|bundle| bundle.known_transitions.keys().copied())
+    }
+
+    fn annotations(&self) -> Option<&Annotations> { self.consignment.annotations() }
 }
 
 /// Trait defining common data access API for all storage-related RGB structures
@@ -208,11 +331,17 @@ pub trait ConsignmentApi {
 
     /// Returns reference to a collection of AluVM libraries used for the
     /// validation.
+    ///
+    /// Libraries are content-addressed by their [`LibId`], such that each of
+    /// them is stored only once, even if it is used by multiple validators or
+    /// other libraries. The validator fails the consignment if any of the
+    /// libraries referenced by the schema (directly or via other libraries) is
+    /// absent from this collection.
     fn scripts(&self) -> &Scripts;
 
     /// Retrieves reference to an operation (genesis, state transition or state
     /// extension) matching the provided id, or `None` otherwise
-    fn operation(&self, opid: OpId) -> Option<OpRef>;
+    fn operation(&self, opid: OpId) -> Option<OpRef<'_>>;
 
     /// Contract genesis.
     fn genesis(&self) -> &Genesis;
@@ -226,6 +355,500 @@ pub trait ConsignmentApi {
     /// Returns a grip given a bundle id.
     fn anchor(&self, bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)>;
 
+    /// Returns alternative anchors of the bundle to other witness transactions
+    /// (like RBF candidates of the witness returned by [`Self::anchor`]).
+    ///
+    /// The validator accepts the bundle if any of its anchors is confirmed.
+    fn anchor_alternatives<'anchor>(
+        &'anchor self,
+        _bundle_id: BundleId,
+    ) -> impl Iterator<Item = (XWitnessId, &'anchor EAnchor)> + 'anchor {
+        iter::empty()
+    }
+
     /// Returns witness id for a given operation.
     fn op_witness_id(&self, opid: OpId) -> Option<XWitnessId>;
+
+    /// Returns iterator over ids of all bundles anchored to the witness with
+    /// the given id.
+    ///
+    /// The default implementation scans all the consignment bundles;
+    /// implementations maintaining a witness index should override it.
+    fn bundles_by_witness_id(&self, witness_id: XWitnessId) -> impl Iterator<Item = BundleId> {
+        self.bundle_ids().filter(
+            move |bundle_id| matches!(self.anchor(*bundle_id), Some((id, _)) if id == witness_id),
+        )
+    }
+
+    /// Returns iterator over ids of all state transitions which are
+    /// committed to in the witness with the given id.
+    fn ops_by_witness_id(&self, witness_id: XWitnessId) -> impl Iterator<Item = OpId> {
+        self.bundles_by_witness_id(witness_id)
+            .filter_map(|bundle_id| self.bundle(bundle_id))
+            .flat_map(|bundle| bundle.known_transitions.keys().copied())
+    }
+
+    /// Returns outputs spent by more than one state transition known to the
+    /// consignment, together with the ids of all the transitions spending
+    /// them.
+    ///
+    /// Each of such outputs indicates conflicting branches of the contract
+    /// history (a double spend within the known operation graph), which
+    /// happens when consignments carrying different spends of the same state
+    /// get merged.
+    fn double_spends(&self) -> BTreeMap<Opout, BTreeSet<OpId>> {
+        let mut spends = BTreeMap::<Opout, BTreeSet<OpId>>::new();
+        for bundle in self.bundle_ids().filter_map(|bundle_id| self.bundle(bundle_id)) {
+            for (opid, transition) in &bundle.known_transitions {
+                for input in &transition.inputs {
+                    spends.entry(input.prev_out).or_default().insert(*opid);
+                }
+            }
+        }
+        spends.retain(|_, opids| opids.len() > 1);
+        spends
+    }
+
+    /// Lists outputs of the prunable state types (see [`crate::RetentionSchema`])
+    /// which are spent by the state transitions of the consignment and thus
+    /// may have their data pruned with [`crate::Assignments::prune`] when
+    /// constructing a consignment.
+    fn superseded_state(&self) -> BTreeSet<Opout> {
+        let schema = self.schema();
+        self.bundle_ids()
+            .filter_map(|bundle_id| self.bundle(bundle_id))
+            .flat_map(|bundle| bundle.known_transitions.values())
+            .flat_map(|transition| &transition.inputs)
+            .map(|input| input.prev_out)
+            .filter(|opout| schema.retention(opout.ty).is_prunable())
+            .collect()
+    }
+
+    /// Reports all state transitions spending the output `opout`, with their
+    /// witness anchors and confirmation status provided by the `resolver`,
+    /// allowing to detect conflicting histories of an allocation and to pick
+    /// the branch following the consensus ordering.
+    ///
+    /// # Errors
+    ///
+    /// If the witness resolver fails to resolve witness ordering for any of
+    /// the bundles spending the output.
+    fn branches_of<R: ResolveWitness>(
+        &self,
+        opout: Opout,
+        resolver: &R,
+    ) -> Result<ForkReport, WitnessResolverError> {
+        super::history::branches_of(self, opout, resolver)
+    }
+
+    /// Returns disambiguator of the short forms of the genesis, bundle and
+    /// state transition ids of the consignment, which is used to render the
+    /// validation status with [`super::Status::to_short_string`].
+    fn disambiguator(&self) -> Disambiguator {
+        let mut ids = Disambiguator::new();
+        ids.insert(self.genesis().id());
+        for bundle_id in self.bundle_ids() {
+            ids.insert(bundle_id);
+            if let Some(bundle) = self.bundle(bundle_id) {
+                ids.extend(bundle.known_transitions.keys().copied());
+            }
+        }
+        ids
+    }
+
+    /// Returns non-committed annotations carried by the consignment, if any.
+    ///
+    /// Annotations are not a part of any commitment id and are ignored by the
+    /// validation.
+    fn annotations(&self) -> Option<&Annotations> { None }
+
+    /// Returns a page of the bundle ids, skipping first `offset` of them and
+    /// returning at most `limit` ids.
+    fn bundle_ids_paged<'iter>(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> impl Iterator<Item = BundleId> + 'iter {
+        self.bundle_ids().skip(offset).take(limit)
+    }
+
+    /// Exports the graph of the consignment operations in a given format,
+    /// annotating operations with the bundles and witnesses they belong to.
+    ///
+    /// If the validation `status` is provided, operations and bundles are also
+    /// annotated with the codes of the validation failures they have caused.
+    #[cfg(feature = "graphviz")]
+    fn export_graph(&self, format: GraphFormat, status: Option<&Status>) -> String {
+        super::graph::export_graph(self, format, status)
+    }
+}
+
+/// Source of the transition bundles, which may load them on demand (for
+/// instance from disk or network) instead of keeping them all in memory.
+///
+/// Each consignment is a bundle source providing borrowed bundles; storage
+/// implementations may return owned bundles loaded upon request.
+///
+/// NB: the [`crate::validation::Validator`] still requires the whole
+/// consignment to be accessible via [`ConsignmentApi`], since it orders all
+/// the contract operations before their validation.
+pub trait BundleSource {
+    /// Returns iterator over all ids of the bundles known to the source.
+    fn list_bundles<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter;
+
+    /// Loads bundle with a given id.
+    fn load_bundle(&self, bundle_id: BundleId) -> Option<Cow<'_, TransitionBundle>>;
+
+    /// Returns a page of the bundle ids, skipping first `offset` of them and
+    /// returning at most `limit` ids.
+    fn list_bundles_paged<'iter>(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> impl Iterator<Item = BundleId> + 'iter {
+        self.list_bundles().skip(offset).take(limit)
+    }
+}
+
+impl<C: ConsignmentApi> BundleSource for C {
+    fn list_bundles<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter { self.bundle_ids() }
+
+    fn load_bundle(&self, bundle_id: BundleId) -> Option<Cow<'_, TransitionBundle>> {
+        self.bundle(bundle_id).map(Cow::Borrowed)
+    }
+}
+
+/// Bundle source wrapper which filters out loaded bundles not matching the
+/// requested bundle id, analogously to [`CheckedConsignment`].
+pub struct CheckedBundleSource<'source, S: BundleSource>(&'source S);
+
+impl<'source, S: BundleSource> CheckedBundleSource<'source, S> {
+    pub fn new(source: &'source S) -> Self { Self(source) }
+}
+
+impl<'source, S: BundleSource> BundleSource for CheckedBundleSource<'source, S> {
+    fn list_bundles<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter {
+        self.0.list_bundles()
+    }
+
+    fn load_bundle(&self, bundle_id: BundleId) -> Option<Cow<'_, TransitionBundle>> {
+        self.0
+            .load_bundle(bundle_id)
+            .filter(|bundle| bundle.bundle_id() == bundle_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use bp::Txid;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::validation::validator::test::{Ledger, Mined, NoState};
+    use crate::validation::{Failure, Status, Validator};
+    use crate::{Input, InputMap, Layer1, Vin};
+
+    #[test]
+    fn witness_lookups() {
+        let witness = |no: u8| XWitnessId::with(Layer1::Bitcoin, Txid::from([no; 32]));
+        let bundle = |nonces: &[u64]| {
+            let mut bundle = TransitionBundle::strict_dumb();
+            bundle.known_transitions = Confined::try_from_iter(nonces.iter().map(|nonce| {
+                let mut transition = Transition::strict_dumb();
+                transition.nonce = *nonce;
+                (transition.id(), transition)
+            }))
+            .unwrap();
+            let first = *bundle.known_transitions.keys().next().unwrap();
+            bundle.input_map = InputMap::with(Vin::from_u32(0), first);
+            bundle
+        };
+        let bundles =
+            [(witness(1), bundle(&[1, 2])), (witness(1), bundle(&[3])), (witness(2), bundle(&[4]))];
+        let ledger = Ledger::with_bundles(Schema::strict_dumb(), bundles.clone());
+        assert_eq!(ledger.bundles.len(), 3);
+        let bundle_ids = |id| {
+            bundles
+                .iter()
+                .filter(|(witness_id, _)| *witness_id == id)
+                .map(|(_, bundle)| bundle.bundle_id())
+                .collect::<BTreeSet<_>>()
+        };
+        let op_ids = |id| {
+            bundles
+                .iter()
+                .filter(|(witness_id, _)| *witness_id == id)
+                .flat_map(|(_, bundle)| bundle.known_transitions.keys().copied())
+                .collect::<BTreeSet<_>>()
+        };
+
+        for id in [witness(1), witness(2)] {
+            assert_eq!(ledger.bundles_by_witness_id(id).collect::<BTreeSet<_>>(), bundle_ids(id));
+            assert_eq!(ledger.ops_by_witness_id(id).collect::<BTreeSet<_>>(), op_ids(id));
+            for opid in ledger.ops_by_witness_id(id) {
+                assert_eq!(ledger.op_witness_id(opid), Some(id));
+            }
+        }
+        assert_eq!(ledger.bundles_by_witness_id(witness(1)).count(), 2);
+        assert_eq!(ledger.ops_by_witness_id(witness(1)).count(), 3);
+        assert_eq!(ledger.ops_by_witness_id(witness(2)).count(), 1);
+        assert_eq!(ledger.bundles_by_witness_id(witness(3)).count(), 0);
+        assert_eq!(ledger.ops_by_witness_id(witness(3)).count(), 0);
+
+        let checked = CheckedConsignment::new(&ledger);
+        assert_eq!(
+            checked
+                .ops_by_witness_id(witness(1))
+                .collect::<BTreeSet<_>>(),
+            op_ids(witness(1))
+        );
+    }
+
+    #[test]
+    fn double_spends() {
+        let witness = |no: u8| XWitnessId::with(Layer1::Bitcoin, Txid::from([no; 32]));
+        let opout = Opout::new(OpId::strict_dumb(), AssignmentType::with(1), 0);
+        let bundle = |nonce: u64, opout: Opout| {
+            let mut transition = Transition::strict_dumb();
+            transition.nonce = nonce;
+            transition.inputs.push(Input::with(opout)).unwrap();
+            let opid = transition.id();
+            let mut bundle = TransitionBundle::strict_dumb();
+            bundle.known_transitions = Confined::from_checked(bmap! { opid => transition });
+            bundle.input_map = InputMap::with(Vin::from_u32(0), opid);
+            (opid, bundle)
+        };
+        let (op1, bundle1) = bundle(1, opout);
+        let (op2, bundle2) = bundle(2, opout);
+        let (_, bundle3) = bundle(3, Opout { no: 1, ..opout });
+        let ledger = Ledger::with_bundles(Schema::strict_dumb(), [
+            (witness(1), bundle1),
+            (witness(2), bundle2),
+            (witness(3), bundle3),
+        ]);
+
+        assert_eq!(ledger.double_spends(), bmap! { opout => bset! { op1, op2 } });
+
+        let status = ledger.validate(&Mined);
+        let (op1, op2) = (op1.min(op2), op1.max(op2));
+        assert!(status.failures.contains(&Failure::DoubleSpend { opout, op1, op2 }));
+        assert_eq!(
+            status
+                .failures
+                .iter()
+                .filter(|failure| failure.code() == "DoubleSpend")
+                .count(),
+            1
+        );
+    }
+
+    /// Decorator listing the bundles of the wrapped consignment in a random
+    /// order (defined by the seed) and answering lookups of the unknown
+    /// operations and bundles with junk data.
+    struct Shuffled<'c, C: ConsignmentApi> {
+        consignment: &'c C,
+        seed: u64,
+        junk_op: Transition,
+        junk_bundle: TransitionBundle,
+    }
+
+    impl<'c, C: ConsignmentApi> Shuffled<'c, C> {
+        fn new(consignment: &'c C, seed: u64) -> Self {
+            let mut junk_op = Transition::strict_dumb();
+            junk_op.nonce = seed;
+            let mut junk_bundle = TransitionBundle::strict_dumb();
+            junk_bundle.known_transitions =
+                Confined::from_checked(bmap! { junk_op.id() => junk_op.clone() });
+            junk_bundle.input_map = InputMap::with(Vin::from_u32(0), junk_op.id());
+            Self {
+                consignment,
+                seed,
+                junk_op,
+                junk_bundle,
+            }
+        }
+    }
+
+    impl<C: ConsignmentApi> ConsignmentApi for Shuffled<'_, C> {
+        fn schema(&self) -> &Schema { self.consignment.schema() }
+        fn types(&self) -> &TypeSystem { self.consignment.types() }
+        fn scripts(&self) -> &Scripts { self.consignment.scripts() }
+        fn operation(&self, opid: OpId) -> Option<OpRef<'_>> {
+            self.consignment
+                .operation(opid)
+                .or(Some(OpRef::Transition(&self.junk_op)))
+        }
+        fn genesis(&self) -> &Genesis { self.consignment.genesis() }
+        fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter {
+            // Fisher-Yates shuffle driven by a xorshift generator
+            let mut ids = self.consignment.bundle_ids().collect::<Vec<_>>();
+            let mut state = self.seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+            for i in (1..ids.len()).rev() {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                ids.swap(i, (state % (i as u64 + 1)) as usize);
+            }
+            ids.into_iter()
+        }
+        fn bundle(&self, bundle_id: BundleId) -> Option<&TransitionBundle> {
+            self.consignment
+                .bundle(bundle_id)
+                .or(Some(&self.junk_bundle))
+        }
+        fn anchor(&self, bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)> {
+            self.consignment.anchor(bundle_id)
+        }
+        fn op_witness_id(&self, opid: OpId) -> Option<XWitnessId> {
+            self.consignment.op_witness_id(opid)
+        }
+    }
+
+    #[test]
+    fn order_independent() {
+        let witness = |no: u8| XWitnessId::with(Layer1::Bitcoin, Txid::from([no; 32]));
+        let bundle = |nonce: u64, opout: Opout| {
+            let mut transition = Transition::strict_dumb();
+            transition.nonce = nonce;
+            transition.inputs.push(Input::with(opout)).unwrap();
+            let opid = transition.id();
+            let mut bundle = TransitionBundle::strict_dumb();
+            bundle.known_transitions = Confined::from_checked(bmap! { opid => transition });
+            bundle.input_map = InputMap::with(Vin::from_u32(0), opid);
+            bundle
+        };
+        let opout = Opout::new(OpId::strict_dumb(), AssignmentType::with(1), 0);
+        let ledger = Ledger::with_bundles(
+            Schema::strict_dumb(),
+            (1u8..=6).map(|no| {
+                let spent = Opout { no: no as u16 / 2, ..opout };
+                (witness(no), bundle(no as u64, spent))
+            }),
+        );
+        let report = |status: Status| {
+            let mut failures = status
+                .failures
+                .iter()
+                .map(|f| format!("{f:?}"))
+                .collect::<Vec<_>>();
+            let mut warnings = status
+                .warnings
+                .iter()
+                .map(|w| format!("{w:?}"))
+                .collect::<Vec<_>>();
+            failures.sort();
+            warnings.sort();
+            (failures, warnings, status.validity())
+        };
+
+        let expected = report(ledger.validate(&Mined));
+        assert!(!expected.0.is_empty());
+        let mut orders = BTreeSet::new();
+        for seed in 0..16 {
+            let shuffled = Shuffled::new(&ledger, seed);
+            orders.insert(shuffled.bundle_ids().collect::<Vec<_>>());
+            let status =
+                Validator::<NoState, _, _>::validate(&shuffled, &Mined, ledger.genesis.testnet, ());
+            assert_eq!(report(status), expected, "seed {seed}");
+        }
+        assert!(orders.len() > 1);
+    }
+
+    struct Stash(BTreeMap<BundleId, TransitionBundle>);
+
+    impl BundleSource for Stash {
+        fn list_bundles<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter {
+            self.0.keys().copied().collect::<Vec<_>>().into_iter()
+        }
+
+        fn load_bundle(&self, bundle_id: BundleId) -> Option<Cow<'_, TransitionBundle>> {
+            self.0.get(&bundle_id).cloned().map(Cow::Owned)
+        }
+    }
+
+    struct Rbf {
+        schema: Schema,
+        types: TypeSystem,
+        scripts: Scripts,
+        genesis: Genesis,
+        bundle: TransitionBundle,
+        anchors: Vec<(XWitnessId, EAnchor)>,
+    }
+
+    impl ConsignmentApi for Rbf {
+        fn schema(&self) -> &Schema { &self.schema }
+        fn types(&self) -> &TypeSystem { &self.types }
+        fn scripts(&self) -> &Scripts { &self.scripts }
+        fn operation(&self, _opid: OpId) -> Option<OpRef<'_>> { None }
+        fn genesis(&self) -> &Genesis { &self.genesis }
+        fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter {
+            [self.bundle.bundle_id()].into_iter()
+        }
+        fn bundle(&self, _bundle_id: BundleId) -> Option<&TransitionBundle> { Some(&self.bundle) }
+        fn anchor(&self, _bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)> {
+            self.anchors.first().map(|(id, anchor)| (*id, anchor))
+        }
+        fn anchor_alternatives<'anchor>(
+            &'anchor self,
+            _bundle_id: BundleId,
+        ) -> impl Iterator<Item = (XWitnessId, &'anchor EAnchor)> + 'anchor {
+            self.anchors
+                .iter()
+                .skip(1)
+                .map(|(id, anchor)| (*id, anchor))
+        }
+        fn op_witness_id(&self, _opid: OpId) -> Option<XWitnessId> {
+            self.anchors.first().map(|(id, _)| *id)
+        }
+    }
+
+    #[test]
+    fn anchor_selection() {
+        let main_id = XWitnessId::with(Layer1::Bitcoin, Txid::from([1u8; 32]));
+        let alt_id = XWitnessId::with(Layer1::Bitcoin, Txid::from([2u8; 32]));
+        let rbf = Rbf {
+            schema: Schema::strict_dumb(),
+            types: TypeSystem::default(),
+            scripts: Scripts::default(),
+            genesis: Genesis::strict_dumb(),
+            bundle: TransitionBundle::strict_dumb(),
+            anchors: vec![(main_id, EAnchor::strict_dumb()), (alt_id, EAnchor::strict_dumb())],
+        };
+        let bundle_id = rbf.bundle.bundle_id();
+        let opid = OpId::strict_dumb();
+
+        let mut checked = CheckedConsignment::new(&rbf);
+        assert_eq!(checked.anchor_candidates(bundle_id).len(), 2);
+        assert_eq!(checked.anchor(bundle_id).map(|(id, _)| id), Some(main_id));
+
+        checked.select_anchor(bundle_id, alt_id, &rbf.anchors[1].1);
+        assert_eq!(checked.anchor(bundle_id).map(|(id, _)| id), Some(alt_id));
+        assert_eq!(checked.op_witness_id(opid), Some(alt_id));
+        assert_eq!(checked.bundles_by_witness_id(alt_id).collect::<Vec<_>>(), vec![bundle_id]);
+        assert_eq!(checked.bundles_by_witness_id(main_id).count(), 0);
+        assert_eq!(
+            checked
+                .anchor_alternatives(bundle_id)
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>(),
+            vec![main_id]
+        );
+    }
+
+    #[test]
+    fn checked_bundle_source() {
+        let bundle = TransitionBundle::strict_dumb();
+        let bundle_id = bundle.bundle_id();
+        let wrong_id = BundleId::from([0xAB; 32]);
+        let stash = Stash(bmap! { bundle_id => bundle.clone(), wrong_id => bundle.clone() });
+
+        let checked = CheckedBundleSource::new(&stash);
+        assert_eq!(checked.list_bundles().count(), 2);
+        assert_eq!(stash.list_bundles_paged(1, 5).count(), 1);
+        assert_eq!(checked.load_bundle(bundle_id).as_deref(), Some(&bundle));
+        assert!(stash.load_bundle(wrong_id).is_some());
+        assert!(checked.load_bundle(wrong_id).is_none());
+    }
 }
@@ -0,0 +1,282 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export of the consignment operation graph for visual debugging.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use super::{ConsignmentApi, OpRef, Status};
+use crate::vm::XWitnessId;
+use crate::{BundleId, Disambiguator, OpId, OpType, Operation};
+
+/// Format of the exported operation graph.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Display)]
+pub enum GraphFormat {
+    /// Graphviz DOT language.
+    #[default]
+    #[display("dot")]
+    Dot,
+    /// GraphML XML format.
+    #[display("graphml")]
+    GraphMl,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+struct Node {
+    /// Operation type, or `None` if the operation is absent from the
+    /// consignment.
+    op_type: Option<OpType>,
+    bundle_id: Option<BundleId>,
+    witness_id: Option<XWitnessId>,
+    failures: BTreeSet<&'static str>,
+}
+
+impl Node {
+    fn kind(&self) -> &'static str {
+        match self.op_type {
+            Some(OpType::Genesis) => "genesis",
+            Some(OpType::StateTransition) => "transition",
+            Some(OpType::StateExtension) => "extension",
+            None => "absent",
+        }
+    }
+}
+
+/// Operation graph of a consignment: operations with the bundles and witnesses
+/// they belong to, and edges from each operation to the operations spending
+/// its outputs or redeeming its valencies.
+struct OpGraph {
+    nodes: BTreeMap<OpId, Node>,
+    edges: BTreeSet<(OpId, OpId, String)>,
+    bundle_failures: BTreeMap<BundleId, BTreeSet<&'static str>>,
+}
+
+impl OpGraph {
+    fn with<C: ConsignmentApi + ?Sized>(consignment: &C, status: Option<&Status>) -> Self {
+        let mut graph = OpGraph {
+            nodes: empty!(),
+            edges: empty!(),
+            bundle_failures: empty!(),
+        };
+
+        let mut queue = vec![];
+        let genesis = consignment.genesis();
+        graph.add(OpRef::Genesis(genesis), None, None, &mut queue);
+        for bundle_id in consignment.bundle_ids() {
+            let Some(bundle) = consignment.bundle(bundle_id) else {
+                continue;
+            };
+            let witness_id = consignment.anchor(bundle_id).map(|(id, _)| id);
+            for transition in bundle.known_transitions.values() {
+                graph.add(OpRef::Transition(transition), Some(bundle_id), witness_id, &mut queue);
+            }
+        }
+        while let Some(opid) = queue.pop() {
+            if graph.nodes.contains_key(&opid) {
+                continue;
+            }
+            match consignment.operation(opid) {
+                Some(op) => graph.add(op, None, None, &mut queue),
+                None => {
+                    graph.nodes.insert(opid, Node::default());
+                }
+            }
+        }
+
+        for failure in status.into_iter().flat_map(|status| &status.failures) {
+            if let Some(node) = failure.opid().and_then(|opid| graph.nodes.get_mut(&opid)) {
+                node.failures.insert(failure.code());
+            } else if let Some(bundle_id) = failure.bundle_id() {
+                graph
+                    .bundle_failures
+                    .entry(bundle_id)
+                    .or_default()
+                    .insert(failure.code());
+            }
+        }
+        graph
+    }
+
+    fn add(
+        &mut self,
+        op: OpRef,
+        bundle_id: Option<BundleId>,
+        witness_id: Option<XWitnessId>,
+        queue: &mut Vec<OpId>,
+    ) {
+        let opid = op.id();
+        for input in &op.inputs() {
+            let prev_out = input.prev_out;
+            self.edges
+                .insert((prev_out.op, opid, format!("{}/{}", prev_out.ty, prev_out.no)));
+            queue.push(prev_out.op);
+        }
+        if let OpRef::Extension(extension) = op {
+            for (valency, prev_id) in extension.redeemed() {
+                self.edges
+                    .insert((*prev_id, opid, format!("valency {valency}")));
+                queue.push(*prev_id);
+            }
+        }
+        self.nodes.insert(opid, Node {
+            op_type: Some(op.op_type()),
+            bundle_id,
+            witness_id,
+            failures: empty!(),
+        });
+    }
+
+    /// Disambiguator of the short forms of all operation and bundle ids in the
+    /// graph, used in the labels.
+    fn disambiguator(&self) -> Disambiguator {
+        let mut ids = self.nodes.keys().copied().collect::<Disambiguator>();
+        ids.extend(self.nodes.values().filter_map(|node| node.bundle_id));
+        ids
+    }
+
+    fn to_dot(&self) -> String {
+        let ids = self.disambiguator();
+        let mut dot = String::from("digraph consignment {\n  rankdir=LR;\n  node [shape=box];\n");
+        let mut bundles = BTreeMap::<BundleId, Vec<OpId>>::new();
+        for (opid, node) in &self.nodes {
+            if let Some(bundle_id) = node.bundle_id {
+                bundles.entry(bundle_id).or_default().push(*opid);
+            }
+        }
+        for (bundle_id, opids) in bundles {
+            let witness = self.nodes[&opids[0]]
+                .witness_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "unanchored".to_owned());
+            let failures = self.bundle_failures.get(&bundle_id);
+            let color = if failures.is_some() { "red" } else { "black" };
+            let mut label = format!("bundle {}\\nwitness {witness}", ids.short_id(bundle_id));
+            for code in failures.into_iter().flatten() {
+                write!(label, "\\n{code}").ok();
+            }
+            writeln!(dot, "  subgraph \"cluster_{bundle_id}\" {{").ok();
+            writeln!(dot, "    label=\"{label}\";\n    color={color};").ok();
+            for opid in opids {
+                writeln!(dot, "    \"{opid}\";").ok();
+            }
+            dot.push_str("  }\n");
+        }
+        for (opid, node) in &self.nodes {
+            let mut label = format!("{}\\n{}", node.kind(), ids.short_id(*opid));
+            for code in &node.failures {
+                write!(label, "\\n{code}").ok();
+            }
+            let color = match (node.op_type, node.failures.is_empty()) {
+                (None, _) | (_, false) => "red",
+                _ => "black",
+            };
+            writeln!(dot, "  \"{opid}\" [label=\"{label}\", color={color}];").ok();
+        }
+        for (from, to, label) in &self.edges {
+            writeln!(dot, "  \"{from}\" -> \"{to}\" [label=\"{label}\"];").ok();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn to_graphml(&self) -> String {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="kind" for="node" attr.name="kind" attr.type="string"/>
+  <key id="bundle" for="node" attr.name="bundle" attr.type="string"/>
+  <key id="witness" for="node" attr.name="witness" attr.type="string"/>
+  <key id="failures" for="node" attr.name="failures" attr.type="string"/>
+  <key id="bundle_failures" for="node" attr.name="bundle_failures" attr.type="string"/>
+  <key id="label" for="edge" attr.name="label" attr.type="string"/>
+  <graph id="consignment" edgedefault="directed">
+"#,
+        );
+        let join = |codes: &BTreeSet<&str>| codes.iter().copied().collect::<Vec<_>>().join(" ");
+        for (opid, node) in &self.nodes {
+            writeln!(xml, "    <node id=\"{opid}\">").ok();
+            writeln!(xml, "      <data key=\"kind\">{}</data>", node.kind()).ok();
+            if let Some(bundle_id) = node.bundle_id {
+                writeln!(xml, "      <data key=\"bundle\">{bundle_id}</data>").ok();
+                if let Some(failures) = self.bundle_failures.get(&bundle_id) {
+                    writeln!(xml, "      <data key=\"bundle_failures\">{}</data>", join(failures))
+                        .ok();
+                }
+            }
+            if let Some(witness_id) = node.witness_id {
+                writeln!(xml, "      <data key=\"witness\">{witness_id}</data>").ok();
+            }
+            if !node.failures.is_empty() {
+                writeln!(xml, "      <data key=\"failures\">{}</data>", join(&node.failures)).ok();
+            }
+            xml.push_str("    </node>\n");
+        }
+        for (from, to, label) in &self.edges {
+            writeln!(xml, "    <edge source=\"{from}\" target=\"{to}\">").ok();
+            writeln!(xml, "      <data key=\"label\">{label}</data>").ok();
+            xml.push_str("    </edge>\n");
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+pub(super) fn export_graph<C: ConsignmentApi + ?Sized>(
+    consignment: &C,
+    format: GraphFormat,
+    status: Option<&Status>,
+) -> String {
+    let graph = OpGraph::with(consignment, status);
+    match format {
+        GraphFormat::Dot => graph.to_dot(),
+        GraphFormat::GraphMl => graph.to_graphml(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::validation::validator::test::Consignment;
+    use crate::validation::{Failure, ScriptError, Scripts};
+    use crate::Schema;
+
+    #[test]
+    fn genesis_graph() {
+        let consignment = Consignment::issue(Schema::strict_dumb(), Scripts::default());
+        let opid = consignment.genesis.id();
+        let status = Status::with_failure(Failure::ScriptFailure(opid, ScriptError::Code(1)));
+
+        let dot = consignment.export_graph(GraphFormat::Dot, Some(&status));
+        assert!(dot.starts_with("digraph consignment {"));
+        let short = opid.short_id();
+        assert!(dot.contains(&format!("\"{opid}\" [label=\"genesis\\n{short}\\nScriptFailure\"")));
+        assert!(!dot.contains("->"));
+
+        let xml = consignment.export_graph(GraphFormat::GraphMl, None);
+        assert!(xml.contains(&format!("<node id=\"{opid}\">")));
+        assert!(xml.contains("<data key=\"kind\">genesis</data>"));
+        assert!(!xml.contains("<data key=\"failures\">"));
+    }
+}
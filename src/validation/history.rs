@@ -0,0 +1,336 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Historical queries over the contract operation graph.
+
+use std::collections::BTreeSet;
+
+use super::{ConsignmentApi, ResolveWitness, WitnessResolverError};
+use crate::vm::{OpOrd, WitnessOrd, XWitnessId};
+use crate::{BundleId, Layer1, OpId, Operation, Opout};
+
+/// State transition spending a contract output, which is one of the branches
+/// of the contract history at that output.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ForkBranch {
+    /// Id of the spending state transition.
+    pub opid: OpId,
+    /// Id of the bundle containing the transition.
+    pub bundle_id: BundleId,
+    /// Witness transaction the bundle is anchored to.
+    pub witness_id: XWitnessId,
+    /// Consensus ordering of the transition, including the confirmation
+    /// status of its witness.
+    pub op_ord: OpOrd,
+}
+
+impl ForkBranch {
+    /// Returns the confirmation status of the branch witness.
+    pub fn witness_ord(&self) -> WitnessOrd {
+        match self.op_ord {
+            OpOrd::Transition { witness, .. } | OpOrd::Extension { witness, .. } => witness,
+            OpOrd::Genesis => unreachable!("genesis can't spend outputs"),
+        }
+    }
+}
+
+/// Report on the competing spends of a contract output, produced by
+/// [`ConsignmentApi::branches_of`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ForkReport {
+    /// The output which is spent.
+    pub opout: Opout,
+    /// All anchored state transitions spending the output, sorted according
+    /// to the consensus ordering.
+    pub branches: Vec<ForkBranch>,
+}
+
+impl ForkReport {
+    /// Detects whether the output has conflicting histories, i.e. is spent by
+    /// more than one state transition.
+    pub fn is_forked(&self) -> bool { self.branches.len() > 1 }
+
+    /// Returns the branch which takes precedence according to the consensus
+    /// ordering, ignoring the branches with archived witnesses. Returns `None`
+    /// if the output is not spent by any valid witness.
+    pub fn canonical(&self) -> Option<&ForkBranch> {
+        self.branches
+            .iter()
+            .find(|branch| branch.witness_ord().is_valid())
+    }
+}
+
+pub(super) fn branches_of<C: ConsignmentApi + ?Sized, R: ResolveWitness>(
+    consignment: &C,
+    opout: Opout,
+    resolver: &R,
+) -> Result<ForkReport, WitnessResolverError> {
+    let mut branches = vec![];
+    for bundle_id in consignment.bundle_ids() {
+        let (Some(bundle), Some((witness_id, _))) =
+            (consignment.bundle(bundle_id), consignment.anchor(bundle_id))
+        else {
+            continue;
+        };
+        for (opid, transition) in &bundle.known_transitions {
+            if !transition
+                .inputs
+                .iter()
+                .any(|input| input.prev_out == opout)
+            {
+                continue;
+            }
+            let witness = resolver.resolve_pub_witness_ord(witness_id)?;
+            branches.push(ForkBranch {
+                opid: *opid,
+                bundle_id,
+                witness_id,
+                op_ord: OpOrd::Transition {
+                    witness,
+                    ty: transition.transition_type,
+                    nonce: transition.nonce,
+                    opid: *opid,
+                },
+            });
+        }
+    }
+    branches.sort_by_key(|branch| branch.op_ord);
+    Ok(ForkReport { opout, branches })
+}
+
+/// Reconstructs the set of the contract assignments which were existing (i.e.
+/// were created and not yet spent) as of a given height of the `layer1`
+/// blockchain.
+///
+/// Operations are accounted for only if their witness transaction was mined
+/// on `layer1` at or below the `height`; genesis assignments are always
+/// accounted for. The state and seals of the returned assignments can be
+/// retrieved from the consignment operations.
+///
+/// NB: assignments created by state extensions are accounted for only as
+/// spent inputs, since extensions are not anchored to witness transactions.
+///
+/// # Errors
+///
+/// If the witness resolver fails to resolve witness ordering for any of the
+/// consignment bundles.
+pub fn assignments_at_height<C: ConsignmentApi, R: ResolveWitness>(
+    consignment: &C,
+    resolver: &R,
+    layer1: Layer1,
+    height: u32,
+) -> Result<BTreeSet<Opout>, WitnessResolverError> {
+    let mut created = BTreeSet::new();
+    let mut spent = BTreeSet::new();
+
+    let genesis = consignment.genesis();
+    add_assignments(genesis, genesis.id(), &mut created);
+
+    for bundle_id in consignment.bundle_ids() {
+        let (Some(bundle), Some((witness_id, _))) =
+            (consignment.bundle(bundle_id), consignment.anchor(bundle_id))
+        else {
+            continue;
+        };
+        if witness_id.layer1() != layer1 {
+            continue;
+        }
+        match resolver.resolve_pub_witness_ord(witness_id)? {
+            WitnessOrd::Mined(pos) if pos.height().get() <= height => {}
+            _ => continue,
+        }
+        for (opid, transition) in &bundle.known_transitions {
+            add_assignments(transition, *opid, &mut created);
+            spent.extend(transition.inputs.iter().map(|input| input.prev_out));
+        }
+    }
+
+    Ok(created.difference(&spent).copied().collect())
+}
+
+fn add_assignments(op: &impl Operation, opid: OpId, set: &mut BTreeSet<Opout>) {
+    for (ty, assigns) in op.assignments().flat().iter() {
+        set.extend((0..assigns.len_u16()).map(|no| Opout::new(opid, *ty, no)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bp::dbc::Method;
+    use bp::Txid;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::validation::validator::test::{bundle, Ledger, Ordered};
+    use crate::vm::WitnessPos;
+    use crate::{
+        Assign, AssignmentType, Genesis, GenesisSeal, GraphSeal, Input, Schema, Transition,
+        TypedAssigns, VoidState, XChain,
+    };
+
+    fn witness(no: u8) -> XWitnessId { XWitnessId::with(Layer1::Bitcoin, Txid::from([no; 32])) }
+
+    fn mined(height: u32) -> WitnessOrd {
+        WitnessOrd::Mined(WitnessPos::new(height, 1231006505 + height as i64).unwrap())
+    }
+
+    fn spend(opout: Opout, nonce: u64) -> Transition {
+        let mut transition = Transition::strict_dumb();
+        transition.inputs.push(Input::with(opout)).unwrap();
+        transition.nonce = nonce;
+        transition
+    }
+
+    #[test]
+    fn forks() {
+        let opout = Opout::new(OpId::strict_dumb(), AssignmentType::with(1), 0);
+        let early = spend(opout, 1);
+        let late = spend(opout, 2);
+        let other = spend(Opout { no: 1, ..opout }, 3);
+        let consignment = Ledger::with_bundles(Schema::strict_dumb(), [
+            (witness(2), bundle([late.clone()])),
+            (witness(1), bundle([early.clone(), other])),
+        ]);
+        let resolver = |first, second| {
+            let ords = bmap! { witness(1) => first, witness(2) => second };
+            Ordered(move |id| ords.get(&id).copied())
+        };
+
+        let report = consignment
+            .branches_of(opout, &resolver(mined(200), mined(100)))
+            .unwrap();
+        assert!(report.is_forked());
+        assert_eq!(report.opout, opout);
+        assert_eq!(
+            report
+                .branches
+                .iter()
+                .map(|branch| (branch.opid, branch.witness_id))
+                .collect::<Vec<_>>(),
+            vec![(late.id(), witness(2)), (early.id(), witness(1))]
+        );
+        assert_eq!(report.canonical().unwrap().opid, late.id());
+        assert_eq!(report.canonical().unwrap().witness_ord(), mined(100));
+
+        // Re-org archives the witness of the canonical branch
+        let report = consignment
+            .branches_of(opout, &resolver(WitnessOrd::Tentative, WitnessOrd::Archived))
+            .unwrap();
+        assert_eq!(report.canonical().unwrap().opid, early.id());
+
+        let report = consignment
+            .branches_of(Opout { no: 2, ..opout }, &resolver(mined(1), mined(2)))
+            .unwrap();
+        assert!(!report.is_forked());
+        assert_eq!(report.canonical(), None);
+    }
+
+    #[test]
+    fn height_queries() {
+        let ty = AssignmentType::with(4000);
+        let rights = |no: u32| {
+            let seal = GraphSeal::with_blinding(Method::TapretFirst, Txid::from([1u8; 32]), no, 0);
+            TypedAssigns::Declarative(small_vec![Assign::revealed(
+                XChain::Bitcoin(seal),
+                VoidState::default()
+            )])
+        };
+        let mut genesis = Genesis::strict_dumb();
+        let seal = |no| {
+            XChain::Bitcoin(GenesisSeal::with_blinding(
+                Method::TapretFirst,
+                Txid::from([0u8; 32]),
+                no,
+                0,
+            ))
+        };
+        genesis
+            .assignments
+            .insert(
+                ty,
+                TypedAssigns::Declarative(small_vec![
+                    Assign::revealed(seal(0), VoidState::default()),
+                    Assign::revealed(seal(1), VoidState::default()),
+                ]),
+            )
+            .unwrap();
+        let issued = [Opout::new(genesis.id(), ty, 0), Opout::new(genesis.id(), ty, 1)];
+        let transfer = |from: Opout, nonce: u64| {
+            let mut transition = spend(from, nonce);
+            transition
+                .assignments
+                .insert(ty, rights(nonce as u32))
+                .unwrap();
+            let output = Opout::new(transition.id(), ty, 0);
+            (transition, output)
+        };
+        // First issued right is transferred twice on Bitcoin; the second one is
+        // spent by a tentative witness and then by a witness on Liquid
+        let (first, first_out) = transfer(issued[0], 1);
+        let (second, second_out) = transfer(first_out, 2);
+        let (tentative, _) = transfer(issued[1], 3);
+        let (liquid, liquid_out) = transfer(issued[1], 4);
+        let liquid_id = XWitnessId::with(Layer1::Liquid, Txid::from([4u8; 32]));
+        let mut consignment = Ledger::with_bundles(Schema::strict_dumb(), [
+            (witness(1), bundle([first])),
+            (witness(2), bundle([second])),
+            (witness(3), bundle([tentative])),
+            (liquid_id, bundle([liquid])),
+        ]);
+        consignment.genesis = genesis;
+        let ords = bmap! {
+            witness(1) => mined(100),
+            witness(2) => mined(200),
+            witness(3) => WitnessOrd::Tentative,
+            liquid_id => mined(150),
+        };
+        let resolver = Ordered(|id| ords.get(&id).copied());
+        let at = |layer1, height| {
+            assignments_at_height(&consignment, &resolver, layer1, height)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(at(Layer1::Bitcoin, 99), issued.to_vec());
+        assert_eq!(at(Layer1::Bitcoin, 100), {
+            let mut set = vec![issued[1], first_out];
+            set.sort();
+            set
+        });
+        for height in [200, u32::MAX] {
+            let mut set = vec![issued[1], second_out];
+            set.sort();
+            assert_eq!(at(Layer1::Bitcoin, height), set);
+        }
+        assert_eq!(at(Layer1::Liquid, 149), issued.to_vec());
+        let mut set = vec![issued[0], liquid_out];
+        set.sort();
+        assert_eq!(at(Layer1::Liquid, 150), set);
+
+        let resolver = Ordered(|id| (id == witness(1)).then_some(mined(100)));
+        assert!(matches!(
+            assignments_at_height(&consignment, &resolver, Layer1::Bitcoin, 100),
+            Err(WitnessResolverError::Unknown(_))
+        ));
+    }
+}
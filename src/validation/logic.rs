@@ -23,25 +23,53 @@
 use std::cell::RefCell;
 use std::collections::BTreeSet;
 use std::rc::Rc;
+use std::time::Instant;
 
 use aluvm::data::Number;
-use aluvm::isa::Instr;
+use aluvm::isa::{Instr, InstructionSet};
+use aluvm::library::LibSite;
 use aluvm::reg::{Reg32, RegA};
 use aluvm::Vm;
-use amplify::confinement::Confined;
+use amplify::confinement::{Confined, U16 as U16MAX};
 use amplify::Wrapper;
+use bp::XOnlyPk;
+use strict_encoding::StrictDeserialize;
 use strict_types::TypeSystem;
 
-use crate::schema::{AssignmentsSchema, GlobalSchema, ValencySchema};
-use crate::validation::{CheckedConsignment, ConsignmentApi};
-use crate::vm::{ContractStateAccess, ContractStateEvolve, OpInfo, OrdOpRef, RgbIsa, VmContext};
+use crate::schema::{AssignmentsSchema, FeeCollector, GlobalSchema, StateRef, ValencySchema};
+use crate::validation::{CheckedConsignment, ConsignmentApi, Scripts, ScriptStats};
+use crate::vm::{
+    ContractStateAccess, ContractStateEvolve, Metered, MeteredContext, OpInfo, OrdOpRef, RgbIsa,
+    VmContext,
+};
 use crate::{
-    validation, Assign, AssignmentType, Assignments, AssignmentsRef, ConcealedState,
-    ConfidentialState, ExposedSeal, ExposedState, Extension, GlobalState, GlobalStateSchema,
-    GlobalValues, GraphSeal, Inputs, MetaSchema, Metadata, OpId, Operation, Opout,
-    OwnedStateSchema, RevealedState, Schema, StateType, Transition, TypedAssigns, Valencies,
+    validation, Assign, AssignmentType, Assignments, AssignmentsRef, AuxProofs, ConcealedState,
+    ConfidentialState, DocumentRevision, ExposedSeal, ExposedState, Extension, Genesis,
+    GlobalState, GlobalStateKind, GlobalStateSchema, GlobalValues, GraphSeal, Inputs,
+    KeyValueRecord, MetaSchema, Metadata, OpFullType, OpId, Operation, Opout, OwnedStateSchema,
+    RevealedState, Schema, StateType, Transition, TypedAssigns, Valencies,
 };
 
+/// Runs the script from the `entry` point, returning the error code set by the
+/// script in `a8[0]` if the script fails.
+fn exec_script<I: InstructionSet>(
+    entry: LibSite,
+    scripts: &Scripts,
+    ty: Option<u16>,
+    context: &I::Context<'_>,
+) -> Result<(), validation::ScriptError> {
+    let mut vm = Vm::<I>::new();
+    if let Some(ty) = ty {
+        vm.registers.set_n(RegA::A16, Reg32::Reg0, ty);
+    }
+    if vm.exec(entry, |id| scripts.get(&id), context) {
+        Ok(())
+    } else {
+        let error_code: Option<Number> = vm.registers.get_n(RegA::A8, Reg32::Reg0).into();
+        Err(error_code.map(u8::from).into())
+    }
+}
+
 impl Schema {
     pub fn validate_state<
         'validator,
@@ -52,6 +80,23 @@ impl Schema {
         consignment: &'validator CheckedConsignment<'_, C>,
         op: OrdOpRef,
         contract_state: Rc<RefCell<S>>,
+    ) -> validation::Status {
+        self.validate_state_with(consignment, op, contract_state, false)
+    }
+
+    /// Validates the operation state, reporting resources used by the
+    /// validation script as [`validation::Info::ScriptProfile`] if
+    /// `profile_scripts` is set.
+    pub(super) fn validate_state_with<
+        'validator,
+        C: ConsignmentApi,
+        S: ContractStateAccess + ContractStateEvolve,
+    >(
+        &'validator self,
+        consignment: &'validator CheckedConsignment<'_, C>,
+        op: OrdOpRef,
+        contract_state: Rc<RefCell<S>>,
+        profile_scripts: bool,
     ) -> validation::Status {
         let opid = op.id();
         let mut status = validation::Status::new();
@@ -122,6 +167,9 @@ impl Schema {
                     Some(transition_schema) => transition_schema,
                 };
 
+                status += self.validate_endorsements(opid, op, consignment.genesis());
+                status += self.validate_fees(opid, op, consignment.genesis());
+
                 (
                     &transition_schema.metadata,
                     &transition_schema.globals,
@@ -168,8 +216,12 @@ impl Schema {
         // Validate type system
         status += self.validate_type_system();
         status += self.validate_metadata(opid, op.metadata(), metadata_schema, consignment.types());
+        status +=
+            self.validate_aux_proofs(opid, op.full_type(), op.aux_proofs(), consignment.types());
         status +=
             self.validate_global_state(opid, op.globals(), global_schema, consignment.types());
+        status += self.validate_documents(opid, op.globals(), &*contract_state.borrow());
+        status += self.validate_key_values(opid, op.globals(), &*contract_state.borrow());
         let prev_state = if let OrdOpRef::Transition(transition, ..) = op {
             let prev_state = extract_prev_state(consignment, opid, &transition.inputs, &mut status);
             status += self.validate_prev_state(opid, &prev_state, owned_schema);
@@ -186,10 +238,10 @@ impl Schema {
         }
         status += match op.assignments() {
             AssignmentsRef::Genesis(assignments) => {
-                self.validate_owned_state(opid, assignments, assign_schema, consignment.types())
+                self.validate_owned_state(opid, assignments, assign_schema, consignment)
             }
             AssignmentsRef::Graph(assignments) => {
-                self.validate_owned_state(opid, assignments, assign_schema, consignment.types())
+                self.validate_owned_state(opid, assignments, assign_schema, consignment)
             }
         };
 
@@ -197,7 +249,7 @@ impl Schema {
 
         let genesis = consignment.genesis();
         let op_info = OpInfo::with(opid, &op, &prev_state, &redeemed);
-        let context = VmContext {
+        let mut context = VmContext {
             contract_id: genesis.contract_id(),
             asset_tags: &genesis.asset_tags,
             op_info,
@@ -209,17 +261,26 @@ impl Schema {
         // scripts are not required to validate the structure of the state
         if let Some(validator) = validator {
             let scripts = consignment.scripts();
-            let mut vm = Vm::<Instr<RgbIsa<S>>>::new();
-            if let Some(ty) = ty {
-                vm.registers.set_n(RegA::A16, Reg32::Reg0, ty);
-            }
-            if !vm.exec(validator, |id| scripts.get(&id), &context) {
-                let error_code: Option<Number> = vm.registers.get_n(RegA::A8, Reg32::Reg0).into();
-                status.add_failure(validation::Failure::ScriptFailure(
-                    opid,
-                    error_code.map(u8::from),
-                    None,
-                ));
+            let result = if profile_scripts {
+                let start = Instant::now();
+                let metered = MeteredContext::<Instr<RgbIsa<S>>>::new(context);
+                let result =
+                    exec_script::<Metered<Instr<RgbIsa<S>>>>(validator, scripts, ty, &metered);
+                let micros = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX);
+                let meter = metered.meter();
+                status.add_info(validation::Info::ScriptProfile(opid, ScriptStats {
+                    instructions: meter.instructions,
+                    complexity: meter.complexity,
+                    memory: meter.memory,
+                    micros,
+                }));
+                context = metered.into_inner();
+                result
+            } else {
+                exec_script::<Instr<RgbIsa<S>>>(validator, scripts, ty, &context)
+            };
+            if let Err(error) = result {
+                status.add_failure(validation::Failure::ScriptFailure(opid, error));
                 // We return here since all other validations will have no valid state to access
                 return status;
             }
@@ -233,6 +294,119 @@ impl Schema {
         status
     }
 
+    fn validate_endorsements(
+        &self,
+        opid: OpId,
+        op: OrdOpRef,
+        genesis: &Genesis,
+    ) -> validation::Status {
+        let mut status = validation::Status::new();
+
+        let OrdOpRef::Transition(transition, ..) = op else {
+            return status;
+        };
+        let Some(endorsement) = self.ext.endorsement() else {
+            return status;
+        };
+        if !endorsement
+            .transitions
+            .contains(&transition.transition_type)
+        {
+            return status;
+        }
+
+        if transition.endorsements.is_empty() {
+            status.add_failure(validation::Failure::EndorsementMissing(opid));
+            return status;
+        }
+
+        let mut endorsers = vec![];
+        for key in genesis
+            .globals
+            .get(&endorsement.endorsers)
+            .into_iter()
+            .flat_map(|values| values.iter())
+        {
+            match XOnlyPk::from_bytes(key.as_slice()) {
+                Ok(key) => endorsers.push(key),
+                Err(_) => {
+                    status.add_failure(validation::Failure::EndorserKeyInvalid(
+                        endorsement.endorsers,
+                    ));
+                }
+            }
+        }
+        if let Err(err) = transition.endorsements.verify(opid, &endorsers) {
+            status.add_failure(validation::Failure::EndorsementInvalid(opid, err));
+        }
+
+        status
+    }
+
+    pub(super) fn validate_fees(
+        &self,
+        opid: OpId,
+        op: OrdOpRef,
+        genesis: &Genesis,
+    ) -> validation::Status {
+        let mut status = validation::Status::new();
+
+        let OrdOpRef::Transition(transition, ..) = op else {
+            return status;
+        };
+        let Some(fees) = self.ext.fees() else {
+            return status;
+        };
+        if !fees.transitions.contains(&transition.transition_type) {
+            return status;
+        }
+
+        let mut collectors = BTreeSet::new();
+        for data in genesis
+            .globals
+            .get(&fees.collector)
+            .into_iter()
+            .flat_map(|values| values.iter())
+        {
+            match FeeCollector::from_strict_serialized::<U16MAX>(data.as_inner().clone()) {
+                Ok(collector) => {
+                    collectors.insert(collector);
+                }
+                Err(_) => {
+                    status.add_failure(validation::Failure::FeeCollectorInvalid(fees.collector));
+                }
+            }
+        }
+
+        let paid = transition
+            .assignments
+            .get(&fees.assignment)
+            .map(TypedAssigns::as_fungible)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|assign| match assign {
+                Assign::Revealed { seal, state, .. }
+                    if collectors.iter().any(|collector| collector.matches(seal)) =>
+                {
+                    Some(state)
+                }
+                Assign::ConfidentialSeal { seal, state, .. }
+                    if collectors
+                        .iter()
+                        .any(|collector| collector.matches_concealed(seal)) =>
+                {
+                    Some(state)
+                }
+                _ => None,
+            })
+            .fold(0u64, |sum, state| sum.saturating_add(state.value.as_u64()));
+        if paid < fees.min_amount {
+            status.add_failure(validation::Failure::FeeUnpaid(opid, fees.min_amount, paid));
+        }
+
+        status
+    }
+
     fn validate_type_system(&self) -> validation::Status {
         validation::Status::new()
         // TODO: Validate type system
@@ -285,6 +459,63 @@ impl Schema {
         status
     }
 
+    fn validate_aux_proofs(
+        &self,
+        opid: OpId,
+        op_type: OpFullType,
+        aux_proofs: &AuxProofs,
+        types: &TypeSystem,
+    ) -> validation::Status {
+        let mut status = validation::Status::new();
+
+        let aux_schema = self.ext.aux_proofs();
+        let op_schema = aux_schema.and_then(|schema| schema.op_proofs(op_type));
+        for type_id in aux_proofs.keys() {
+            if !op_schema.is_some_and(|op_schema| op_schema.contains_key(type_id)) {
+                status.add_failure(validation::Failure::SchemaUnknownAuxProofType(opid, *type_id));
+            }
+        }
+        let (Some(aux_schema), Some(op_schema)) = (aux_schema, op_schema) else {
+            return status;
+        };
+
+        for (type_id, occ) in op_schema {
+            let proofs = aux_proofs.get(type_id);
+            let count = proofs.map(|proofs| proofs.len() as u16).unwrap_or_default();
+            if let Err(err) = occ.check(count) {
+                status.add_failure(validation::Failure::SchemaAuxProofOccurrences(
+                    opid, *type_id, err,
+                ));
+            }
+
+            let details = aux_schema.types.get(type_id).expect(
+                "if this proof type were absent, the schema would not be able to pass the \
+                 internal validation and we would not reach this point",
+            );
+            for proof in proofs.into_iter().flat_map(|proofs| proofs.iter()) {
+                if proof.len() > details.max_len as usize {
+                    status.add_failure(validation::Failure::AuxProofOversized(
+                        opid,
+                        *type_id,
+                        proof.len(),
+                        details.max_len,
+                    ));
+                } else if types
+                    .strict_deserialize_type(details.sem_id, proof.as_ref())
+                    .is_err()
+                {
+                    status.add_failure(validation::Failure::SchemaInvalidAuxProof(
+                        opid,
+                        *type_id,
+                        details.sem_id,
+                    ));
+                }
+            }
+        }
+
+        status
+    }
+
     fn validate_global_state(
         &self,
         opid: OpId,
@@ -299,8 +530,9 @@ impl Schema {
             .collect::<BTreeSet<_>>()
             .difference(&global_schema.keys().collect())
             .for_each(|field_id| {
-                status.add_failure(validation::Failure::SchemaUnknownGlobalStateType(
-                    opid, **field_id,
+                status.add_failure(validation::Failure::SchemaUnknownStateType(
+                    opid,
+                    StateRef::Global(**field_id),
                 ));
             });
 
@@ -315,7 +547,7 @@ impl Schema {
             let GlobalStateSchema {
                 sem_id,
                 max_items,
-                reserved: _,
+                kind: _,
             } = self.global_types.get(type_id).expect(
                 "if the field were absent, the schema would not be able to pass the internal \
                  validation and we would not reach this point",
@@ -350,6 +582,135 @@ impl Schema {
         status
     }
 
+    /// Validates integrity of the revision chains of the notarized documents
+    /// (see [`GlobalStateKind::Document`]) against the documents already
+    /// notarized in the contract.
+    fn validate_documents<S: ContractStateAccess>(
+        &self,
+        opid: OpId,
+        global: &GlobalState,
+        contract_state: &S,
+    ) -> validation::Status {
+        use std::borrow::Borrow;
+
+        let mut status = validation::Status::new();
+
+        for (type_id, values) in global.iter() {
+            if !matches!(
+                self.global_types.get(type_id),
+                Some(GlobalStateSchema {
+                    kind: GlobalStateKind::Document,
+                    ..
+                })
+            ) {
+                continue;
+            }
+
+            let mut known = BTreeSet::new();
+            let mut revised = BTreeSet::new();
+            if let Ok(history) = contract_state.global(*type_id) {
+                for data in history {
+                    if let Ok(revision) = DocumentRevision::from_data(data.borrow()) {
+                        known.insert(revision.digest);
+                        revised.extend(revision.prev);
+                    }
+                }
+            }
+
+            for data in values.iter() {
+                let Ok(revision) = DocumentRevision::from_data(data) else {
+                    status
+                        .add_failure(validation::Failure::DocumentRevisionInvalid(opid, *type_id));
+                    continue;
+                };
+                if let Some(prev) = revision.prev {
+                    if !known.contains(&prev) {
+                        status.add_failure(validation::Failure::DocumentRevisionUnknown(
+                            opid, *type_id, prev,
+                        ));
+                    } else if !revised.insert(prev) {
+                        status.add_failure(validation::Failure::DocumentRevisionForked(
+                            opid, *type_id, prev,
+                        ));
+                    }
+                }
+                known.insert(revision.digest);
+            }
+        }
+
+        status
+    }
+
+    fn validate_key_values<S: ContractStateAccess>(
+        &self,
+        opid: OpId,
+        global: &GlobalState,
+        contract_state: &S,
+    ) -> validation::Status {
+        use std::borrow::Borrow;
+
+        let mut status = validation::Status::new();
+
+        for (type_id, values) in global.iter() {
+            let Some(domain) = self.key_domain(*type_id) else {
+                continue;
+            };
+
+            // The history goes from the most recent records, so only the first record of each
+            // key defines whether the key is present in the contract state
+            let mut known = BTreeSet::new();
+            let mut recorded = BTreeSet::new();
+            if let Ok(history) = contract_state.global(*type_id) {
+                for data in history {
+                    let Ok(record) = KeyValueRecord::from_data(data.borrow()) else {
+                        continue;
+                    };
+                    if recorded.insert(record.key.clone()) && !record.is_delete() {
+                        known.insert(record.key);
+                    }
+                }
+            }
+
+            let mut updated = BTreeSet::new();
+            for data in values.iter() {
+                let Ok(record) = KeyValueRecord::from_data(data) else {
+                    status.add_failure(validation::Failure::KeyValueRecordInvalid(opid, *type_id));
+                    continue;
+                };
+                let key = record.key.to_string();
+                if !domain.contains_key(&key) {
+                    status.add_failure(validation::Failure::KeyValueKeyInvalid(
+                        opid,
+                        *type_id,
+                        key.clone(),
+                    ));
+                }
+                if !updated.insert(record.key.clone()) {
+                    status.add_failure(validation::Failure::KeyValueKeyDuplicate(
+                        opid,
+                        *type_id,
+                        key.clone(),
+                    ));
+                }
+                match record.value {
+                    Some(value) if value.len() > domain.max_value_len as usize => {
+                        status.add_failure(validation::Failure::KeyValueValueOversized(
+                            opid, *type_id, key,
+                        ));
+                    }
+                    None if !known.contains(&record.key) => {
+                        status.add_failure(validation::Failure::KeyValueKeyUnknown(
+                            opid, *type_id, key,
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        status
+    }
+
     fn validate_prev_state<Seal: ExposedSeal>(
         &self,
         id: OpId,
@@ -363,9 +724,9 @@ impl Schema {
             .collect::<BTreeSet<_>>()
             .difference(&assign_schema.keys().collect())
             .for_each(|owned_type_id| {
-                status.add_failure(validation::Failure::SchemaUnknownAssignmentType(
+                status.add_failure(validation::Failure::SchemaUnknownStateType(
                     id,
-                    **owned_type_id,
+                    StateRef::Owned(**owned_type_id),
                 ));
             });
 
@@ -399,32 +760,33 @@ impl Schema {
         valencies
             .difference(valency_schema)
             .for_each(|public_type_id| {
-                status.add_failure(validation::Failure::SchemaUnknownValencyType(
+                status.add_failure(validation::Failure::SchemaUnknownStateType(
                     id,
-                    *public_type_id,
+                    StateRef::Valency(*public_type_id),
                 ));
             });
 
         status
     }
 
-    fn validate_owned_state<Seal: ExposedSeal>(
+    fn validate_owned_state<Seal: ExposedSeal, C: ConsignmentApi>(
         &self,
         id: OpId,
         owned_state: &Assignments<Seal>,
         assign_schema: &AssignmentsSchema,
-        types: &TypeSystem,
+        consignment: &CheckedConsignment<'_, C>,
     ) -> validation::Status {
         let mut status = validation::Status::new();
+        let types = consignment.types();
 
         owned_state
             .keys()
             .collect::<BTreeSet<_>>()
             .difference(&assign_schema.keys().collect())
             .for_each(|assignment_type_id| {
-                status.add_failure(validation::Failure::SchemaUnknownAssignmentType(
+                status.add_failure(validation::Failure::SchemaUnknownStateType(
                     id,
-                    **assignment_type_id,
+                    StateRef::Owned(**assignment_type_id),
                 ));
             });
 
@@ -446,21 +808,43 @@ impl Schema {
                  validation and we would not reach this point",
             );
 
-            match owned_state.get(state_id) {
-                None => {}
+            let assign_statuses: Vec<_> = match owned_state.get(state_id) {
+                None => vec![],
                 Some(TypedAssigns::Declarative(set)) => set
                     .iter()
-                    .for_each(|data| status += assignment.validate(id, *state_id, data, types)),
+                    .map(|data| assignment.validate(id, *state_id, data, types))
+                    .collect(),
                 Some(TypedAssigns::Fungible(set)) => set
                     .iter()
-                    .for_each(|data| status += assignment.validate(id, *state_id, data, types)),
+                    .map(|data| assignment.validate(id, *state_id, data, types))
+                    .collect(),
                 Some(TypedAssigns::Structured(set)) => set
                     .iter()
-                    .for_each(|data| status += assignment.validate(id, *state_id, data, types)),
+                    .map(|data| assignment.validate(id, *state_id, data, types))
+                    .collect(),
                 Some(TypedAssigns::Attachment(set)) => set
                     .iter()
-                    .for_each(|data| status += assignment.validate(id, *state_id, data, types)),
+                    .map(|data| assignment.validate(id, *state_id, data, types))
+                    .collect(),
             };
+
+            // Concealed data of a prunable state type is expected once the state
+            // is spent, so it is reported as pruned instead of being uncheckable
+            let prunable = self.retention(*state_id).is_prunable();
+            let mut pruned = 0;
+            for (no, mut assign_status) in assign_statuses.into_iter().enumerate() {
+                if prunable && consignment.is_superseded(Opout::new(id, *state_id, no as u16)) {
+                    let len = assign_status.warnings.len();
+                    assign_status.warnings.retain(|warning| {
+                        !matches!(warning, validation::Warning::UncheckableConfidentialState(..))
+                    });
+                    pruned += len - assign_status.warnings.len();
+                }
+                status += assign_status;
+            }
+            if pruned > 0 {
+                status.add_info(validation::Info::StatePruned(id, *state_id, pruned));
+            }
         }
 
         status
@@ -477,9 +861,9 @@ impl Schema {
         valencies
             .difference(valency_schema)
             .for_each(|public_type_id| {
-                status.add_failure(validation::Failure::SchemaUnknownValencyType(
+                status.add_failure(validation::Failure::SchemaUnknownStateType(
                     id,
-                    *public_type_id,
+                    StateRef::Valency(*public_type_id),
                 ));
             });
 
@@ -580,8 +964,10 @@ impl OwnedStateSchema {
         type_system: &TypeSystem,
     ) -> validation::Status {
         let mut status = validation::Status::new();
+        status.stats.assignments += 1;
         match data {
             Assign::Confidential { state, .. } | Assign::ConfidentialState { state, .. } => {
+                status.stats.assignments_concealed += 1;
                 match (self, state.state_commitment()) {
                     (OwnedStateSchema::Declarative, ConcealedState::Void) => {}
                     (OwnedStateSchema::Fungible(_), ConcealedState::Fungible(value)) => {
@@ -590,7 +976,7 @@ impl OwnedStateSchema {
                             status.add_failure(validation::Failure::BulletproofsInvalid(
                                 opid,
                                 state_type,
-                                err.to_string(),
+                                err,
                             ));
                         }
                     }
@@ -641,10 +1027,7 @@ impl OwnedStateSchema {
                     }
                     (OwnedStateSchema::Fungible(_), RevealedState::Fungible(_)) => {}
                     (OwnedStateSchema::Structured(sem_id), RevealedState::Structured(data)) => {
-                        if type_system
-                            .strict_deserialize_type(*sem_id, data.value.as_ref())
-                            .is_err()
-                        {
+                        if data.interpret(type_system, *sem_id).is_err() {
                             status.add_failure(validation::Failure::SchemaInvalidOwnedValue(
                                 opid, state_type, *sem_id,
                             ));
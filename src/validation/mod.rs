@@ -26,8 +26,47 @@ mod validator;
 mod consignment;
 mod status;
 mod commitments;
+mod simulate;
+mod cache;
+mod history;
+mod swap;
+mod time;
+mod snapshot;
+mod seals;
+mod anchored;
+mod signed;
+mod scripted;
+#[cfg(feature = "graphviz")]
+mod graph;
 
+pub use anchored::{AnchoredBundle, AnchoredBundleError, ClientBundle};
+pub use cache::{
+    CacheMetrics, CachedWitnesses, CachingResolver, SharedCachedWitnesses, SharedWitnessCache,
+    WitnessCache,
+};
 pub use commitments::{DbcError, DbcProof, EAnchor};
-pub use consignment::{CheckedConsignment, ConsignmentApi, OpRef, Scripts, CONSIGNMENT_MAX_LIBS};
-pub use status::{Failure, Info, Status, Validity, Warning};
-pub use validator::{ResolveWitness, Validator, WitnessResolverError};
+#[cfg(feature = "graphviz")]
+pub use graph::GraphFormat;
+pub use consignment::{
+    BundleSource, CheckedBundleSource, CheckedConsignment, ConsignmentApi, OpRef, Scripts,
+    CONSIGNMENT_MAX_LIBS,
+};
+pub use history::{assignments_at_height, ForkBranch, ForkReport};
+pub use scripted::{ChainEvent, ChainScenario, ScriptedResolver};
+pub use seals::verify_seal_closure;
+pub use signed::{ConsignmentSigError, SignedConsignment, CONSIGNMENT_SIG_TAG};
+pub use simulate::simulate_transition;
+pub use snapshot::{
+    Allocation, BranchAnchor, ContractStateSnapshot, SnapshotError, SnapshotVersion,
+};
+pub use status::{
+    Failure, FailureGroup, FailureTemplate, Info, NamedFailure, OpUsage, ScriptError, ScriptStats,
+    SealReport, SealStatus, StateUsage, Status, StatusLabels, UsageStats, ValidationStats,
+    Validity, ValidityReport, Warning,
+};
+pub use swap::SwapBundle;
+pub use time::{FixedClock, SystemClock, TimeSource, MAX_TIME_DRIFT};
+pub use validator::{
+    ResolveWitness, ValidationPolicy, Validator, WitnessResolverError, MAX_FEE_RATE, MIN_FEE_RATE,
+    MAX_UNCONFIRMED_ANCESTRY,
+};
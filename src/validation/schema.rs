@@ -20,11 +20,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
+
+use aluvm::library::LibSite;
 use strict_types::TypeSystem;
 
-use crate::{validation, OpFullType, OpSchema, OwnedStateSchema, Schema, TransitionType};
+use crate::validation::Scripts;
+use crate::{
+    validation, AssignmentType, GlobalStateKind, GlobalStateType, Occurrences, OpFullType,
+    OpSchema, OwnedStateSchema, Schema, StateRef, TransitionType, ValencyType,
+};
 
 impl Schema {
+    /// Verifies internal consistency of the schema, not requiring any
+    /// consignment data.
+    ///
+    /// Failures are reported for references to undeclared types, invalid
+    /// occurrences and semantic types absent from the provided type system.
+    /// State types which are never used or can't ever be produced are
+    /// reported as warnings.
     pub fn verify(&self, types: &TypeSystem) -> validation::Status {
         let mut status = validation::Status::new();
 
@@ -39,6 +53,19 @@ impl Schema {
         if self.transitions.contains_key(&TransitionType::BLANK) {
             status.add_failure(validation::Failure::SchemaBlankTransitionRedefined);
         }
+        for type_id in self.transitions.keys() {
+            if type_id.is_reserved() && !type_id.is_blank() {
+                status.add_failure(validation::Failure::SchemaTransitionTypeReserved(*type_id));
+            }
+        }
+        for type_id in self.global_types.keys().filter(|ty| ty.is_reserved()) {
+            let state = StateRef::Global(*type_id);
+            status.add_failure(validation::Failure::SchemaStateTypeReserved(state));
+        }
+        for type_id in self.owned_types.keys().filter(|ty| ty.is_reserved()) {
+            let state = StateRef::Owned(*type_id);
+            status.add_failure(validation::Failure::SchemaStateTypeReserved(state));
+        }
 
         for (type_id, sem_id) in &self.meta_types {
             if !types.contains_key(sem_id) {
@@ -55,6 +82,23 @@ impl Schema {
             }
         }
 
+        for (type_id, schema) in &self.global_types {
+            if schema.kind == GlobalStateKind::KeyValue && self.key_domain(*type_id).is_none() {
+                status.add_failure(validation::Failure::SchemaKeyDomainAbsent(*type_id));
+            }
+        }
+        for type_id in self
+            .ext
+            .key_domains()
+            .into_iter()
+            .flat_map(|domains| domains.keys())
+        {
+            if !matches!(self.global_types.get(type_id), Some(schema) if schema.kind == GlobalStateKind::KeyValue)
+            {
+                status.add_failure(validation::Failure::SchemaKeyDomainUnused(*type_id));
+            }
+        }
+
         for (type_id, schema) in &self.owned_types {
             if let OwnedStateSchema::Structured(sem_id) = schema {
                 if !types.contains_key(sem_id) {
@@ -65,9 +109,277 @@ impl Schema {
             }
         }
 
+        if let Some(endorsement) = self.ext.endorsement() {
+            if !self.global_types.contains_key(&endorsement.endorsers) {
+                status.add_failure(validation::Failure::SchemaEndorsersUnknown(
+                    endorsement.endorsers,
+                ));
+            }
+            for type_id in &endorsement.transitions {
+                if !self.transitions.contains_key(type_id) {
+                    status.add_failure(validation::Failure::SchemaEndorsedTransitionUnknown(
+                        *type_id,
+                    ));
+                }
+            }
+        }
+
+        if let Some(fees) = self.ext.fees() {
+            if !matches!(self.owned_types.get(&fees.assignment), Some(OwnedStateSchema::Fungible(_)))
+            {
+                status.add_failure(validation::Failure::SchemaFeeStateInvalid(fees.assignment));
+            }
+            if !self.global_types.contains_key(&fees.collector) {
+                status.add_failure(validation::Failure::SchemaFeeCollectorUnknown(fees.collector));
+            }
+            for type_id in &fees.transitions {
+                if !self.transitions.contains_key(type_id) {
+                    status.add_failure(validation::Failure::SchemaFeeTransitionUnknown(*type_id));
+                }
+            }
+        }
+
+        if let Some(supply) = self.ext.chain_supply() {
+            if !matches!(
+                self.owned_types.get(&supply.assignment),
+                Some(OwnedStateSchema::Fungible(_))
+            ) {
+                status.add_failure(validation::Failure::SchemaChainSupplyStateInvalid(
+                    supply.assignment,
+                ));
+            }
+            if !self.global_types.contains_key(&supply.caps) {
+                status.add_failure(validation::Failure::SchemaChainSupplyCapsUnknown(supply.caps));
+            }
+            for type_id in supply.bridges.keys().chain(supply.bridges.values()) {
+                if !self.transitions.contains_key(type_id) {
+                    status
+                        .add_failure(validation::Failure::SchemaBridgeTransitionUnknown(*type_id));
+                }
+            }
+        }
+
+        if let Some(features) = self.ext.features() {
+            if !matches!(
+                self.owned_types.get(&features.assignment),
+                Some(OwnedStateSchema::Fungible(_))
+            ) {
+                status.add_failure(validation::Failure::SchemaFeatureStateInvalid(
+                    features.assignment,
+                ));
+            }
+            if !self.global_types.contains_key(&features.flags) {
+                status.add_failure(validation::Failure::SchemaFeatureFlagsUnknown(features.flags));
+            }
+            for type_id in features.burns.iter().chain(&features.inflation) {
+                if !self.transitions.contains_key(type_id) {
+                    status
+                        .add_failure(validation::Failure::SchemaFeatureTransitionUnknown(*type_id));
+                }
+            }
+        }
+
+        if let Some(aux_proofs) = self.ext.aux_proofs() {
+            for (type_id, details) in &aux_proofs.types {
+                if !types.contains_key(&details.sem_id) {
+                    status.add_failure(validation::Failure::SchemaAuxProofSemIdUnknown(
+                        *type_id,
+                        details.sem_id,
+                    ));
+                }
+            }
+            for type_id in aux_proofs.transitions.keys() {
+                if !self.transitions.contains_key(type_id) {
+                    status.add_failure(validation::Failure::SchemaAuxProofTransitionUnknown(
+                        *type_id,
+                    ));
+                }
+            }
+            for type_id in aux_proofs.extensions.keys() {
+                if !self.extensions.contains_key(type_id) {
+                    status
+                        .add_failure(validation::Failure::SchemaAuxProofExtensionUnknown(*type_id));
+                }
+            }
+            aux_proofs
+                .genesis
+                .keys()
+                .chain(aux_proofs.transitions.values().flat_map(|proofs| proofs.keys()))
+                .chain(aux_proofs.extensions.values().flat_map(|proofs| proofs.keys()))
+                .filter(|type_id| !aux_proofs.types.contains_key(*type_id))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .for_each(|type_id| {
+                    status.add_failure(validation::Failure::SchemaAuxProofTypeUnknown(*type_id));
+                });
+        }
+
+        for type_id in self
+            .ext
+            .retention()
+            .into_iter()
+            .flat_map(|retention| &retention.prunable)
+        {
+            if !matches!(
+                self.owned_types.get(type_id),
+                Some(OwnedStateSchema::Structured(_) | OwnedStateSchema::Attachment(_))
+            ) {
+                status.add_failure(validation::Failure::SchemaRetentionStateInvalid(*type_id));
+            }
+        }
+
+        if let Some(issuer) = self.ext.issuer_proof() {
+            if !self
+                .ext
+                .aux_proofs()
+                .is_some_and(|aux_proofs| aux_proofs.genesis.contains_key(&issuer.proof_type))
+            {
+                status.add_failure(validation::Failure::SchemaIssuerProofUnknown(
+                    issuer.proof_type,
+                ));
+            }
+        }
+
+        status += self.verify_reachability();
+
         status
     }
 
+    /// Verifies that all AluVM libraries referenced by the schema validators,
+    /// including their transitive dependencies, are present in `scripts`,
+    /// that each of the libraries is stored under its own content-derived id
+    /// and that the validator entry points lie within the library code.
+    pub fn verify_scripts(&self, scripts: &Scripts) -> validation::Status {
+        let mut status = validation::Status::new();
+
+        for (id, lib) in scripts {
+            let actual = lib.id();
+            if actual != *id {
+                status.add_failure(validation::Failure::ScriptLibIdMismatch(*id, actual));
+            }
+        }
+
+        let mut queue = self.libs().collect::<Vec<_>>();
+        let mut checked = BTreeSet::new();
+        while let Some(id) = queue.pop() {
+            if !checked.insert(id) {
+                continue;
+            }
+            match scripts.get(&id) {
+                Some(lib) => queue.extend(lib.libs.iter().copied()),
+                None => {
+                    status.add_failure(validation::Failure::ScriptLibAbsent(id));
+                }
+            }
+        }
+
+        let validators = self
+            .genesis
+            .validator
+            .map(|site| (OpFullType::Genesis, site))
+            .into_iter()
+            .chain(self.transitions.iter().filter_map(|(type_id, schema)| {
+                schema
+                    .validator
+                    .map(|site| (OpFullType::StateTransition(*type_id), site))
+            }))
+            .chain(self.extensions.iter().filter_map(|(type_id, schema)| {
+                schema
+                    .validator
+                    .map(|site| (OpFullType::StateExtension(*type_id), site))
+            }));
+        for (op_type, site) in validators {
+            let LibSite { lib, pos } = site;
+            if scripts
+                .get(&lib)
+                .is_some_and(|script| pos as usize >= script.code_segment().len())
+            {
+                status.add_failure(validation::Failure::SchemaScriptEntryOutOfRange(
+                    op_type, site,
+                ));
+            }
+        }
+
+        status
+    }
+
+    fn verify_reachability(&self) -> validation::Status {
+        let mut status = validation::Status::new();
+
+        let ops = self.operations();
+
+        let mut globals = BTreeSet::<&GlobalStateType>::new();
+        let mut assigned = BTreeSet::<&AssignmentType>::new();
+        let mut spent = BTreeSet::<&AssignmentType>::new();
+        let mut defined = BTreeSet::<&ValencyType>::new();
+        let mut redeemed = BTreeSet::<&ValencyType>::new();
+        for (_, schema) in &ops {
+            globals.extend(schema.globals().keys());
+            assigned.extend(schema.assignments().keys());
+            spent.extend(schema.inputs().into_iter().flat_map(|inputs| inputs.keys()));
+            defined.extend(schema.valencies());
+            redeemed.extend(schema.redeems().into_iter().flatten());
+        }
+
+        for (op_type, schema) in &ops {
+            for type_id in schema.inputs().into_iter().flat_map(|inputs| inputs.keys()) {
+                if self.owned_types.contains_key(type_id) && !assigned.contains(type_id) {
+                    status.add_warning(validation::Warning::SchemaOpUnreachable(
+                        *op_type,
+                        StateRef::Owned(*type_id),
+                    ));
+                }
+            }
+            for type_id in schema.redeems().into_iter().flatten() {
+                if self.valency_types.contains(type_id) && !defined.contains(type_id) {
+                    status.add_warning(validation::Warning::SchemaOpUnreachable(
+                        *op_type,
+                        StateRef::Valency(*type_id),
+                    ));
+                }
+            }
+        }
+
+        for type_id in self.global_types.keys() {
+            if !globals.contains(type_id) {
+                status.add_warning(validation::Warning::SchemaUnusedStateType(StateRef::Global(
+                    *type_id,
+                )));
+            }
+        }
+        for type_id in self.owned_types.keys() {
+            if !assigned.contains(type_id) && !spent.contains(type_id) {
+                status.add_warning(validation::Warning::SchemaUnusedStateType(StateRef::Owned(
+                    *type_id,
+                )));
+            }
+        }
+        for type_id in &self.valency_types {
+            if !defined.contains(type_id) && !redeemed.contains(type_id) {
+                status.add_warning(validation::Warning::SchemaUnusedStateType(StateRef::Valency(
+                    *type_id,
+                )));
+            }
+        }
+
+        status
+    }
+
+    fn operations(&self) -> Vec<(OpFullType, &dyn OpSchema)> {
+        let mut ops = vec![(OpFullType::Genesis, &self.genesis as &dyn OpSchema)];
+        ops.extend(
+            self.transitions
+                .iter()
+                .map(|(type_id, schema)| (OpFullType::StateTransition(*type_id), schema as _)),
+        );
+        ops.extend(
+            self.extensions
+                .iter()
+                .map(|(type_id, schema)| (OpFullType::StateExtension(*type_id), schema as _)),
+        );
+        ops
+    }
+
     fn verify_operation(&self, op_type: OpFullType, schema: &impl OpSchema) -> validation::Status {
         let mut status = validation::Status::new();
 
@@ -82,23 +394,48 @@ impl Schema {
         if matches!(schema.redeems(), Some(inputs) if inputs.is_empty()) {
             status.add_failure(validation::Failure::SchemaOpEmptyInputs(op_type));
         }
-        for type_id in schema.globals().keys() {
+        for (type_id, occ) in schema.globals() {
             if !self.global_types.contains_key(type_id) {
-                status
-                    .add_failure(validation::Failure::SchemaOpGlobalTypeUnknown(op_type, *type_id));
+                status.add_failure(validation::Failure::SchemaOpStateTypeUnknown(
+                    op_type,
+                    StateRef::Global(*type_id),
+                ));
+            }
+            if !is_valid_occurrences(occ) {
+                status.add_failure(validation::Failure::SchemaOpOccurrencesInvalid(
+                    op_type,
+                    StateRef::Global(*type_id),
+                ));
             }
         }
-        for type_id in schema.assignments().keys() {
+        let inputs = schema.inputs().into_iter().flatten();
+        for (type_id, occ) in inputs.chain(schema.assignments()) {
             if !self.owned_types.contains_key(type_id) {
-                status.add_failure(validation::Failure::SchemaOpAssignmentTypeUnknown(
-                    op_type, *type_id,
+                status.add_failure(validation::Failure::SchemaOpStateTypeUnknown(
+                    op_type,
+                    StateRef::Owned(*type_id),
+                ));
+            }
+            if !is_valid_occurrences(occ) {
+                status.add_failure(validation::Failure::SchemaOpOccurrencesInvalid(
+                    op_type,
+                    StateRef::Owned(*type_id),
+                ));
+            }
+        }
+        for type_id in schema.redeems().into_iter().flatten() {
+            if !self.valency_types.contains(type_id) {
+                status.add_failure(validation::Failure::SchemaOpStateTypeUnknown(
+                    op_type,
+                    StateRef::Valency(*type_id),
                 ));
             }
         }
         for type_id in schema.valencies() {
             if !self.valency_types.contains(type_id) {
-                status.add_failure(validation::Failure::SchemaOpValencyTypeUnknown(
-                    op_type, *type_id,
+                status.add_failure(validation::Failure::SchemaOpStateTypeUnknown(
+                    op_type,
+                    StateRef::Valency(*type_id),
                 ));
             }
         }
@@ -106,3 +443,390 @@ impl Schema {
         status
     }
 }
+
+fn is_valid_occurrences(occ: &Occurrences) -> bool {
+    occ.max_value() > 0 && occ.min_value() <= occ.max_value()
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::RangeInclusive;
+
+    use aluvm::aluasm;
+    use aluvm::isa::Instr;
+    use aluvm::library::Lib;
+    use amplify::confinement::Confined;
+    use strict_encoding::StrictDumb;
+    use strict_types::SemId;
+
+    use super::*;
+    use crate::validation::{Failure, Warning};
+    use crate::{
+        AuxProofDetails, AuxProofSchema, AuxProofType, ChainSupplySchema, ExtensionSchema,
+        ExtensionType, FeatureSchema, FeeSchema, GlobalStateSchema, IssuerProofSchema,
+        RetentionClass, RetentionSchema, TransitionSchema,
+    };
+
+    const GLOBAL: GlobalStateType = GlobalStateType::with(2000);
+    const OWNED: AssignmentType = AssignmentType::with(4000);
+    const VALENCY: ValencyType = ValencyType::with(1);
+    const UNDECLARED: u16 = 5000;
+
+    fn schema() -> Schema {
+        let mut schema = Schema::strict_dumb();
+        schema.global_types =
+            tiny_bmap! { GLOBAL => GlobalStateSchema::once(SemId::strict_dumb()) };
+        schema.owned_types =
+            tiny_bmap! { OWNED => OwnedStateSchema::Structured(SemId::strict_dumb()) };
+        schema.valency_types = tiny_bset! { VALENCY };
+        schema.genesis.globals = tiny_bmap! { GLOBAL => Occurrences::Once };
+        schema.genesis.assignments = tiny_bmap! { OWNED => Occurrences::OnceOrMore };
+        schema.genesis.valencies = tiny_bset! { VALENCY };
+        schema.transitions = tiny_bmap! {
+            TransitionType::with(1) => TransitionSchema {
+                inputs: tiny_bmap! { OWNED => Occurrences::OnceOrMore },
+                assignments: tiny_bmap! { OWNED => Occurrences::NoneOrMore },
+                ..default!()
+            }
+        };
+        schema.extensions = tiny_bmap! {
+            ExtensionType::with(1) => ExtensionSchema {
+                redeems: tiny_bset! { VALENCY },
+                ..default!()
+            }
+        };
+        schema
+    }
+
+    #[test]
+    fn occurrences() {
+        for occ in [
+            Occurrences::Once,
+            Occurrences::NoneOrMore,
+            Occurrences::NoneOrUpTo(1),
+            Occurrences::Exactly(2),
+            Occurrences::Range(0..=0xFFFF),
+        ] {
+            assert!(is_valid_occurrences(&occ), "{occ:?}");
+        }
+        for occ in [
+            Occurrences::NoneOrUpTo(0),
+            Occurrences::Exactly(0),
+            Occurrences::Range(0..=0),
+            Occurrences::Range(RangeInclusive::new(3, 2)),
+        ] {
+            assert!(!is_valid_occurrences(&occ), "{occ:?}");
+        }
+
+        let mut schema = schema();
+        schema.genesis.globals = tiny_bmap! { GLOBAL => Occurrences::Exactly(0) };
+        let transition = schema
+            .transitions
+            .get_mut(&TransitionType::with(1))
+            .unwrap();
+        transition.inputs = tiny_bmap! { OWNED => Occurrences::Range(RangeInclusive::new(3, 2)) };
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaOpOccurrencesInvalid(
+            OpFullType::Genesis,
+            StateRef::Global(GLOBAL)
+        )));
+        assert!(failures.contains(&Failure::SchemaOpOccurrencesInvalid(
+            OpFullType::StateTransition(TransitionType::with(1)),
+            StateRef::Owned(OWNED)
+        )));
+    }
+
+    #[test]
+    fn undeclared_types() {
+        let status = schema().verify(&TypeSystem::new());
+        assert!(!status.failures.iter().any(|failure| matches!(
+            failure,
+            Failure::SchemaOpStateTypeUnknown(..) | Failure::SchemaOpOccurrencesInvalid(..)
+        )));
+        assert_eq!(status.warnings, vec![]);
+
+        let mut schema = schema();
+        let transition = schema
+            .transitions
+            .get_mut(&TransitionType::with(1))
+            .unwrap();
+        transition
+            .inputs
+            .insert(AssignmentType::with(UNDECLARED), Occurrences::Once)
+            .unwrap();
+        transition
+            .globals
+            .insert(GlobalStateType::with(UNDECLARED), Occurrences::Once)
+            .unwrap();
+        let extension = schema.extensions.get_mut(&ExtensionType::with(1)).unwrap();
+        extension.redeems = tiny_bset! { ValencyType::with(UNDECLARED) };
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        let transition = OpFullType::StateTransition(TransitionType::with(1));
+        let extension = OpFullType::StateExtension(ExtensionType::with(1));
+        for (op_type, state) in [
+            (transition, StateRef::Owned(AssignmentType::with(UNDECLARED))),
+            (transition, StateRef::Global(GlobalStateType::with(UNDECLARED))),
+            (extension, StateRef::Valency(ValencyType::with(UNDECLARED))),
+        ] {
+            assert!(failures.contains(&Failure::SchemaOpStateTypeUnknown(op_type, state)));
+        }
+    }
+
+    #[test]
+    fn reachability() {
+        let mut schema = schema();
+        schema.genesis.assignments = none!();
+        schema.genesis.valencies = none!();
+        schema.genesis.globals = none!();
+        let unused = AssignmentType::with(4001);
+        schema
+            .owned_types
+            .insert(unused, OwnedStateSchema::Declarative)
+            .unwrap();
+
+        let status = schema.verify(&TypeSystem::new());
+        // Owned state is assigned by the transition itself, but no operation
+        // may create it for the first time
+        assert!(!status
+            .warnings
+            .contains(&Warning::SchemaUnusedStateType(StateRef::Owned(OWNED))));
+        assert_eq!(status.warnings, vec![
+            Warning::SchemaOpUnreachable(
+                OpFullType::StateExtension(ExtensionType::with(1)),
+                StateRef::Valency(VALENCY)
+            ),
+            Warning::SchemaUnusedStateType(StateRef::Global(GLOBAL)),
+            Warning::SchemaUnusedStateType(StateRef::Owned(unused)),
+        ]);
+
+        schema.transitions = none!();
+        schema.extensions = none!();
+        let status = schema.verify(&TypeSystem::new());
+        assert_eq!(status.warnings, vec![
+            Warning::SchemaUnusedStateType(StateRef::Global(GLOBAL)),
+            Warning::SchemaUnusedStateType(StateRef::Owned(OWNED)),
+            Warning::SchemaUnusedStateType(StateRef::Owned(unused)),
+            Warning::SchemaUnusedStateType(StateRef::Valency(VALENCY)),
+        ]);
+    }
+
+    #[test]
+    fn fees() {
+        let mut schema = schema();
+        schema.ext.insert(FeeSchema {
+            assignment: OWNED,
+            collector: GLOBAL,
+            min_amount: 1000,
+            transitions: tiny_bset![TransitionType::with(1)],
+        });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaFeeStateInvalid(OWNED)));
+        assert!(!failures
+            .iter()
+            .any(|f| matches!(f, Failure::SchemaFeeCollectorUnknown(_))));
+
+        schema.owned_types = tiny_bmap! { OWNED => OwnedStateSchema::Fungible(default!()) };
+        schema.ext.insert(FeeSchema {
+            assignment: OWNED,
+            collector: GlobalStateType::with(UNDECLARED),
+            min_amount: 1000,
+            transitions: tiny_bset![TransitionType::with(UNDECLARED)],
+        });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(!failures.contains(&Failure::SchemaFeeStateInvalid(OWNED)));
+        assert!(failures.contains(&Failure::SchemaFeeCollectorUnknown(GlobalStateType::with(
+            UNDECLARED
+        ))));
+        assert!(failures.contains(&Failure::SchemaFeeTransitionUnknown(TransitionType::with(
+            UNDECLARED
+        ))));
+    }
+
+    #[test]
+    fn chain_supply() {
+        let mut schema = schema();
+        schema.ext.insert(ChainSupplySchema {
+            assignment: OWNED,
+            caps: GlobalStateType::with(UNDECLARED),
+            bridges: tiny_bmap! {
+                TransitionType::with(1) => TransitionType::with(UNDECLARED),
+            },
+        });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaChainSupplyStateInvalid(OWNED)));
+        assert!(failures.contains(&Failure::SchemaChainSupplyCapsUnknown(GlobalStateType::with(
+            UNDECLARED
+        ))));
+        assert!(failures.contains(&Failure::SchemaBridgeTransitionUnknown(
+            TransitionType::with(UNDECLARED)
+        )));
+        assert!(!failures.contains(&Failure::SchemaBridgeTransitionUnknown(
+            TransitionType::with(1)
+        )));
+
+        schema.owned_types = tiny_bmap! { OWNED => OwnedStateSchema::Fungible(default!()) };
+        schema.ext.insert(ChainSupplySchema {
+            assignment: OWNED,
+            caps: GLOBAL,
+            bridges: tiny_bmap! { TransitionType::with(1) => TransitionType::with(1) },
+        });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(!failures.iter().any(|f| f.code().contains("ChainSupply")));
+    }
+
+    #[test]
+    fn features() {
+        let mut schema = schema();
+        schema.ext.insert(FeatureSchema {
+            flags: GlobalStateType::with(UNDECLARED),
+            assignment: OWNED,
+            burns: tiny_bset! { TransitionType::with(1) },
+            inflation: tiny_bset! { TransitionType::with(UNDECLARED) },
+        });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaFeatureStateInvalid(OWNED)));
+        assert!(failures.contains(&Failure::SchemaFeatureFlagsUnknown(GlobalStateType::with(
+            UNDECLARED
+        ))));
+        assert!(failures.contains(&Failure::SchemaFeatureTransitionUnknown(
+            TransitionType::with(UNDECLARED)
+        )));
+        assert!(!failures.contains(&Failure::SchemaFeatureTransitionUnknown(
+            TransitionType::with(1)
+        )));
+
+        schema.owned_types = tiny_bmap! { OWNED => OwnedStateSchema::Fungible(default!()) };
+        schema.ext.insert(FeatureSchema {
+            flags: GLOBAL,
+            assignment: OWNED,
+            burns: tiny_bset! { TransitionType::with(1) },
+            inflation: none!(),
+        });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(!failures.iter().any(|f| f.code().contains("Feature")));
+    }
+
+    #[test]
+    fn retention() {
+        let mut schema = schema();
+        schema.ext.insert(RetentionSchema {
+            prunable: tiny_bset! { OWNED, AssignmentType::with(UNDECLARED) },
+        });
+        assert_eq!(schema.retention(OWNED), RetentionClass::Prunable);
+        assert_eq!(schema.retention(AssignmentType::with(1)), RetentionClass::Permanent);
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaRetentionStateInvalid(AssignmentType::with(
+            UNDECLARED
+        ))));
+        assert!(!failures.contains(&Failure::SchemaRetentionStateInvalid(OWNED)));
+
+        schema.owned_types = tiny_bmap! { OWNED => OwnedStateSchema::Fungible(default!()) };
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaRetentionStateInvalid(OWNED)));
+    }
+
+    #[test]
+    fn reserved_types() {
+        let mut schema = schema();
+        let reserved = TransitionType::with(0xFF00);
+        schema.transitions.insert(reserved, default!()).unwrap();
+        schema
+            .transitions
+            .insert(TransitionType::experimental(0), default!())
+            .unwrap();
+        schema
+            .global_types
+            .insert(GlobalStateType::with(0xFFFE), GlobalStateSchema::once(SemId::strict_dumb()))
+            .unwrap();
+        schema
+            .owned_types
+            .insert(AssignmentType::with(0xFF10), OwnedStateSchema::Declarative)
+            .unwrap();
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaTransitionTypeReserved(reserved)));
+        assert!(failures.contains(&Failure::SchemaStateTypeReserved(StateRef::Global(
+            GlobalStateType::with(0xFFFE)
+        ))));
+        assert!(failures.contains(&Failure::SchemaStateTypeReserved(StateRef::Owned(
+            AssignmentType::with(0xFF10)
+        ))));
+        assert_eq!(failures.iter().filter(|f| f.code().ends_with("Reserved")).count(), 3);
+
+        schema.transitions.remove(&reserved).unwrap();
+        schema
+            .transitions
+            .insert(TransitionType::BLANK, default!())
+            .unwrap();
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaBlankTransitionRedefined));
+        assert!(!failures.iter().any(|f| matches!(f, Failure::SchemaTransitionTypeReserved(_))));
+    }
+
+    #[test]
+    fn aux_proofs() {
+        let mut schema = schema();
+        let proof = AuxProofType::with(1);
+        let undeclared = AuxProofType::with(UNDECLARED);
+        schema.ext.insert(AuxProofSchema {
+            types: tiny_bmap! { proof => AuxProofDetails {
+                sem_id: SemId::strict_dumb(),
+                max_len: 64,
+            } },
+            genesis: tiny_bmap! { proof => Occurrences::NoneOrOnce },
+            transitions: tiny_bmap! {
+                TransitionType::with(1) => tiny_bmap! { undeclared => Occurrences::Once },
+                TransitionType::with(UNDECLARED) => none!(),
+            },
+            extensions: none!(),
+        });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaAuxProofSemIdUnknown(
+            proof,
+            SemId::strict_dumb()
+        )));
+        assert!(failures.contains(&Failure::SchemaAuxProofTypeUnknown(undeclared)));
+        assert!(failures.contains(&Failure::SchemaAuxProofTransitionUnknown(TransitionType::with(
+            UNDECLARED
+        ))));
+        assert!(!failures.contains(&Failure::SchemaAuxProofTypeUnknown(proof)));
+        assert!(!failures.iter().any(|f| f.code() == "SchemaIssuerProofUnknown"));
+
+        schema.ext.insert(IssuerProofSchema { proof_type: undeclared });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(failures.contains(&Failure::SchemaIssuerProofUnknown(undeclared)));
+
+        schema.ext.insert(IssuerProofSchema { proof_type: proof });
+        let failures = schema.verify(&TypeSystem::new()).failures;
+        assert!(!failures.contains(&Failure::SchemaIssuerProofUnknown(proof)));
+    }
+
+    #[test]
+    fn scripts() {
+        let code = aluasm! {
+            ret     ;
+        };
+        let lib = Lib::assemble::<Instr>(&code).unwrap();
+        let lib_id = lib.id();
+        let scripts = Confined::from_checked(bmap! { lib_id => lib });
+
+        let mut schema = schema();
+        schema.genesis.validator = Some(LibSite::with(0, lib_id));
+        assert_eq!(schema.verify_scripts(&scripts).failures, vec![]);
+
+        let site = LibSite::with(1, lib_id);
+        schema
+            .transitions
+            .get_mut(&TransitionType::with(1))
+            .unwrap()
+            .validator = Some(site);
+        assert_eq!(schema.verify_scripts(&scripts).failures, vec![
+            Failure::SchemaScriptEntryOutOfRange(
+                OpFullType::StateTransition(TransitionType::with(1)),
+                site
+            )
+        ]);
+        assert_eq!(schema.verify_scripts(&Scripts::default()).failures, vec![
+            Failure::ScriptLibAbsent(lib_id)
+        ]);
+    }
+}
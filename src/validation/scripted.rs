@@ -0,0 +1,241 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Witness resolver following a scripted scenario of the blockchain events,
+//! like transaction broadcasting, mining, re-orgs and mempool evictions. It
+//! allows to test the validation under the chain turbulence deterministically,
+//! without access to a real blockchain.
+
+use std::collections::BTreeMap;
+
+use super::{ResolveWitness, WitnessResolverError};
+use crate::vm::{WitnessOrd, WitnessPos, XWitnessId, XWitnessTx};
+use crate::Layer1;
+
+/// Event changing the status of witness transactions in a [`ChainScenario`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ChainEvent {
+    /// Witness enters the mempool with the given ordering, which must not be
+    /// [`WitnessOrd::Mined`].
+    Broadcast(XWitnessId, WitnessOrd),
+
+    /// Witness is mined at the given position.
+    Mine(XWitnessId, WitnessPos),
+
+    /// Witness is evicted from the mempool and becomes unknown to the
+    /// blockchain.
+    Evict(XWitnessId),
+
+    /// Witness is replaced (for instance, by an RBF transaction) and must be
+    /// excluded from the state processing.
+    Archive(XWitnessId),
+
+    /// Blocks of the layer 1 starting from the given height are re-organized.
+    /// Witnesses mined in these blocks are returned into the mempool as
+    /// tentative ones.
+    Reorg(Layer1, u32),
+}
+
+/// Sequence of steps, each of which is a set of [`ChainEvent`]s happening at
+/// the same moment.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ChainScenario {
+    steps: Vec<Vec<ChainEvent>>,
+}
+
+impl ChainScenario {
+    /// Constructs empty scenario.
+    pub fn new() -> Self { Self::default() }
+
+    /// Adds a step with the given events to the end of the scenario.
+    pub fn step(mut self, events: impl IntoIterator<Item = ChainEvent>) -> Self {
+        self.steps.push(events.into_iter().collect());
+        self
+    }
+
+    /// Number of steps in the scenario.
+    pub fn len(&self) -> usize { self.steps.len() }
+
+    /// Detects whether the scenario has no steps.
+    pub fn is_empty(&self) -> bool { self.steps.is_empty() }
+}
+
+/// Witness resolver returning the status of the witness transactions according
+/// to the [`ChainScenario`] steps applied so far.
+///
+/// Witnesses are resolved only after they are broadcast and until they are
+/// evicted from the mempool; before the first step is applied all witnesses
+/// are unknown.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ScriptedResolver {
+    txes: BTreeMap<XWitnessId, XWitnessTx>,
+    ords: BTreeMap<XWitnessId, WitnessOrd>,
+    scenario: ChainScenario,
+    step: usize,
+}
+
+impl ScriptedResolver {
+    /// Constructs resolver for the given witness transactions following the
+    /// scenario.
+    pub fn new(txes: impl IntoIterator<Item = XWitnessTx>, scenario: ChainScenario) -> Self {
+        ScriptedResolver {
+            txes: txes.into_iter().map(|tx| (tx.witness_id(), tx)).collect(),
+            ords: none!(),
+            scenario,
+            step: 0,
+        }
+    }
+
+    /// Number of the scenario steps applied so far.
+    pub fn step(&self) -> usize { self.step }
+
+    /// Detects whether all the scenario steps were applied.
+    pub fn is_complete(&self) -> bool { self.step >= self.scenario.len() }
+
+    /// Returns current status of the witness, or `None` if the witness is not
+    /// known to the blockchain.
+    pub fn witness_ord(&self, witness_id: XWitnessId) -> Option<WitnessOrd> {
+        self.ords.get(&witness_id).copied()
+    }
+
+    /// Applies next step of the scenario. Returns `false` if the scenario was
+    /// already complete.
+    pub fn advance(&mut self) -> bool {
+        let Some(events) = self.scenario.steps.get(self.step) else {
+            return false;
+        };
+        for event in events {
+            match *event {
+                ChainEvent::Broadcast(witness_id, ord) => {
+                    debug_assert!(!matches!(ord, WitnessOrd::Mined(_)));
+                    self.ords.insert(witness_id, ord);
+                }
+                ChainEvent::Mine(witness_id, pos) => {
+                    self.ords.insert(witness_id, WitnessOrd::Mined(pos));
+                }
+                ChainEvent::Evict(witness_id) => {
+                    self.ords.remove(&witness_id);
+                }
+                ChainEvent::Archive(witness_id) => {
+                    self.ords.insert(witness_id, WitnessOrd::Archived);
+                }
+                ChainEvent::Reorg(layer1, height) => {
+                    for (witness_id, ord) in &mut self.ords {
+                        if witness_id.layer1() != layer1 {
+                            continue;
+                        }
+                        if matches!(ord, WitnessOrd::Mined(pos) if pos.height().get() >= height) {
+                            *ord = WitnessOrd::Tentative;
+                        }
+                    }
+                }
+            }
+        }
+        self.step += 1;
+        true
+    }
+
+    /// Applies scenario steps until the given number of them are applied or
+    /// the scenario is complete.
+    pub fn advance_to(&mut self, step: usize) {
+        while self.step < step && self.advance() {}
+    }
+}
+
+impl ResolveWitness for ScriptedResolver {
+    fn resolve_pub_witness(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<XWitnessTx, WitnessResolverError> {
+        if !self.ords.contains_key(&witness_id) {
+            return Err(WitnessResolverError::Unknown(witness_id));
+        }
+        self.txes
+            .get(&witness_id)
+            .cloned()
+            .ok_or(WitnessResolverError::Unknown(witness_id))
+    }
+
+    fn resolve_pub_witness_ord(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<WitnessOrd, WitnessResolverError> {
+        self.witness_ord(witness_id)
+            .ok_or(WitnessResolverError::Unknown(witness_id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bp::Tx;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::XChain;
+
+    #[test]
+    fn scenario() {
+        let tx = XChain::Bitcoin(Tx::strict_dumb());
+        let id = tx.witness_id();
+        let pos = |height| WitnessPos::new(height, 1_700_000_000 + height as i64).unwrap();
+
+        let scenario = ChainScenario::new()
+            .step([ChainEvent::Broadcast(id, WitnessOrd::Tentative)])
+            .step([])
+            .step([ChainEvent::Mine(id, pos(100))])
+            .step([ChainEvent::Reorg(Layer1::Liquid, 90)])
+            .step([ChainEvent::Reorg(Layer1::Bitcoin, 100)])
+            .step([ChainEvent::Evict(id)])
+            .step([ChainEvent::Broadcast(id, WitnessOrd::TentativeChained(1))])
+            .step([ChainEvent::Archive(id)]);
+        let mut resolver = ScriptedResolver::new([tx.clone()], scenario);
+        assert_eq!(resolver.resolve_pub_witness(id), Err(WitnessResolverError::Unknown(id)));
+        assert_eq!(resolver.resolve_pub_witness_ord(id), Err(WitnessResolverError::Unknown(id)));
+
+        assert!(resolver.advance());
+        assert_eq!(resolver.resolve_pub_witness(id), Ok(tx));
+        assert_eq!(resolver.resolve_pub_witness_ord(id), Ok(WitnessOrd::Tentative));
+
+        // Delayed confirmation
+        resolver.advance_to(2);
+        assert_eq!(resolver.witness_ord(id), Some(WitnessOrd::Tentative));
+        resolver.advance_to(3);
+        assert_eq!(resolver.witness_ord(id), Some(WitnessOrd::Mined(pos(100))));
+
+        // Re-org of other layer 1 doesn't affect the witness
+        resolver.advance_to(4);
+        assert_eq!(resolver.witness_ord(id), Some(WitnessOrd::Mined(pos(100))));
+        resolver.advance_to(5);
+        assert_eq!(resolver.witness_ord(id), Some(WitnessOrd::Tentative));
+
+        resolver.advance_to(6);
+        assert_eq!(resolver.resolve_pub_witness(id), Err(WitnessResolverError::Unknown(id)));
+        resolver.advance_to(7);
+        assert_eq!(resolver.witness_ord(id), Some(WitnessOrd::TentativeChained(1)));
+
+        resolver.advance_to(usize::MAX);
+        assert_eq!(resolver.step(), 8);
+        assert!(resolver.is_complete());
+        assert!(!resolver.advance());
+        assert_eq!(resolver.witness_ord(id), Some(WitnessOrd::Archived));
+    }
+}
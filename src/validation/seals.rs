@@ -0,0 +1,172 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of the single-use-seal closing by a witness transaction, which
+//! is the core security check of the client-side validation.
+//!
+//! The [`Validator`](super::Validator) runs the check for each bundle once its
+//! witness transaction is resolved; here it is exposed separately, so it can
+//! be run in isolation, without a consignment.
+
+use bp::dbc::{self, Anchor};
+use bp::seals::txout::{CloseMethod, TxoSeal, Witness};
+use commit_verify::mpc;
+use single_use_seals::SealWitness;
+
+use super::{DbcProof, EAnchor, Failure, Status};
+use crate::vm::XWitnessTx;
+use crate::{BundleId, ContractId, XChain, XOutputSeal};
+
+/// Verifies that the `seals` are closed by the witness transaction over the
+/// commitment to the bundle under the contract, using the anchor proofs.
+///
+/// The seals must use the `close_method` of the bundle. Failures are reported
+/// with the same [`Failure`] variants as by the validator.
+pub fn verify_seal_closure(
+    contract_id: ContractId,
+    seals: impl AsRef<[XOutputSeal]>,
+    witness_tx: &XWitnessTx,
+    anchor: &EAnchor,
+    bundle_id: BundleId,
+    close_method: CloseMethod,
+) -> Status {
+    let mut status = Status::new();
+    let seals = seals.as_ref();
+    for seal in seals.iter().filter(|seal| seal.method() != close_method) {
+        status.add_failure(Failure::SealInvalidMethod(bundle_id, *seal));
+    }
+    match (close_method, anchor.clone()) {
+        (
+            CloseMethod::TapretFirst,
+            EAnchor {
+                mpc_proof,
+                dbc_proof: DbcProof::Tapret(tapret),
+                ..
+            },
+        ) => {
+            let witness = witness_tx.clone().map(|tx| Witness::with(tx, tapret));
+            status += verify_seal_closing(contract_id, seals, bundle_id, witness, mpc_proof);
+        }
+        (
+            CloseMethod::OpretFirst,
+            EAnchor {
+                mpc_proof,
+                dbc_proof: DbcProof::Opret(opret),
+                ..
+            },
+        ) => {
+            let witness = witness_tx.clone().map(|tx| Witness::with(tx, opret));
+            status += verify_seal_closing(contract_id, seals, bundle_id, witness, mpc_proof);
+        }
+        (_, _) => {
+            status.add_failure(Failure::AnchorMethodMismatch(bundle_id));
+        }
+    }
+    status
+}
+
+/// Single-use-seal closing validation.
+///
+/// Checks that the set of seals is closed over the message, which is
+/// multi-protocol commitment, by utilizing witness, consisting of
+/// transaction with deterministic bitcoin commitments (defined by
+/// generic type `Dbc`) and extra-transaction data, which are taken from
+/// anchor's DBC proof.
+///
+/// Additionally, checks that the provided message contains commitment to
+/// the bundle under the current contract.
+fn verify_seal_closing<'seal, Seal: 'seal, Dbc: dbc::Proof>(
+    contract_id: ContractId,
+    seals: impl IntoIterator<Item = &'seal Seal>,
+    bundle_id: BundleId,
+    witness: XChain<Witness<Dbc>>,
+    mpc_proof: mpc::MerkleProof,
+) -> Status
+where
+    XChain<Witness<Dbc>>: SealWitness<Seal, Message = mpc::Commitment>,
+{
+    let mut status = Status::new();
+    let message = mpc::Message::from(bundle_id);
+    let witness_id = witness.witness_id();
+    let anchor = Anchor::new(mpc_proof, witness.as_reduced_unsafe().proof.clone());
+    // [VALIDATION]: Checking anchor MPC commitment
+    match anchor.convolve(contract_id, message) {
+        Err(err) => {
+            // The operation is not committed to bitcoin transaction graph!
+            // Ultimate failure. But continuing to detect the rest (after reporting it).
+            status.add_failure(Failure::MpcInvalid(bundle_id, witness_id, err));
+        }
+        Ok(commitment) => {
+            // [VALIDATION]: CHECKING SINGLE-USE-SEALS
+            if let Err(err) = witness.verify_many_seals(seals, &commitment) {
+                status.add_failure(Failure::SealsInvalid(bundle_id, witness_id, err.to_string()));
+            }
+        }
+    }
+    status
+}
+
+#[cfg(test)]
+mod test {
+    use bp::Outpoint;
+    use bp::seals::txout::ExplicitSeal;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+
+    #[test]
+    fn seal_closure() {
+        let contract_id = ContractId::strict_dumb();
+        let bundle_id = BundleId::strict_dumb();
+        let witness_tx = XWitnessTx::strict_dumb();
+        let anchor = EAnchor::strict_dumb();
+        let outpoint = Outpoint::new(*witness_tx.witness_id().as_reduced_unsafe(), 0);
+        let seal = XChain::Bitcoin(ExplicitSeal::new(CloseMethod::OpretFirst, outpoint));
+
+        let status = verify_seal_closure(
+            contract_id,
+            [seal],
+            &witness_tx,
+            &anchor,
+            bundle_id,
+            CloseMethod::OpretFirst,
+        );
+        assert_eq!(status.failures, vec![Failure::AnchorMethodMismatch(bundle_id)]);
+
+        let status = verify_seal_closure(
+            contract_id,
+            [seal],
+            &witness_tx,
+            &anchor,
+            bundle_id,
+            CloseMethod::TapretFirst,
+        );
+        assert_eq!(status.failures[0], Failure::SealInvalidMethod(bundle_id, seal));
+        assert!(
+            status.failures[1..].iter().all(|failure| matches!(
+                failure,
+                Failure::MpcInvalid(..) | Failure::SealsInvalid(..)
+            ))
+        );
+        assert_eq!(status.failures.len(), 2);
+    }
+}
@@ -0,0 +1,177 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Authenticity of the consignments: the sender signs the commitment id of a
+//! consignment, such that the receiver can prove who has sent it, for instance
+//! when disputing a malicious or faulty consignment.
+//!
+//! The signature is not a part of the contract data and doesn't affect the
+//! validity of the consignment.
+
+use amplify::{ByteArray, Bytes64};
+use bp::secp256k1::{schnorr, Keypair, Message, SECP256K1};
+use bp::XOnlyPk;
+use commit_verify::{CommitId, CommitmentId, DigestExt, Sha256};
+
+/// Tag used for the domain separation of the signed consignment messages.
+pub const CONSIGNMENT_SIG_TAG: &str = "urn:lnp-bp:rgb:consignment-sig#2026-10-16";
+
+/// Errors verifying [`SignedConsignment`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum ConsignmentSigError {
+    /// consignment signature by {0} is invalid.
+    InvalidSignature(XOnlyPk),
+
+    /// consignment is signed by {0} instead of the expected sender.
+    UnexpectedSigner(XOnlyPk),
+}
+
+/// Consignment signed by its sender with a BIP-340 signature over the
+/// consignment commitment id.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct SignedConsignment<C> {
+    consignment: C,
+    signer: XOnlyPk,
+    sig: Bytes64,
+}
+
+impl<C: CommitId> SignedConsignment<C>
+where C::CommitmentId: ByteArray<32>
+{
+    /// Computes message signed by the sender of a consignment with the given
+    /// commitment id.
+    pub fn message(id: C::CommitmentId) -> Message {
+        let mut hasher = Sha256::from_tag(CONSIGNMENT_SIG_TAG);
+        hasher.input_with_len::<{ u8::MAX as usize }>(C::CommitmentId::TAG.as_bytes());
+        hasher.input_raw(&id.to_byte_array());
+        Message::from_digest(hasher.finish())
+    }
+
+    /// Signs the consignment with the sender key.
+    pub fn sign(consignment: C, keypair: &Keypair) -> Self {
+        let sig = SECP256K1.sign_schnorr(&Self::message(consignment.commit_id()), keypair);
+        SignedConsignment {
+            consignment,
+            signer: XOnlyPk::from(keypair.x_only_public_key().0),
+            sig: Bytes64::from_byte_array(*sig.as_ref()),
+        }
+    }
+
+    /// Constructs signed consignment from the signature received together with
+    /// the consignment. The signature is not verified (see [`Self::verify`]).
+    pub fn with(consignment: C, signer: XOnlyPk, sig: Bytes64) -> Self {
+        SignedConsignment {
+            consignment,
+            signer,
+            sig,
+        }
+    }
+
+    /// Returns the signed consignment.
+    pub fn consignment(&self) -> &C { &self.consignment }
+
+    /// Returns key of the sender which has signed the consignment.
+    pub fn signer(&self) -> XOnlyPk { self.signer }
+
+    /// Returns the signature.
+    pub fn signature(&self) -> Bytes64 { self.sig }
+
+    /// Verifies that the signature is a valid signature of the consignment
+    /// by the key of its signer.
+    pub fn verify(&self) -> Result<(), ConsignmentSigError> {
+        let msg = Self::message(self.consignment.commit_id());
+        schnorr::Signature::from_slice(self.sig.as_slice())
+            .and_then(|sig| SECP256K1.verify_schnorr(&sig, &msg, &self.signer))
+            .map_err(|_| ConsignmentSigError::InvalidSignature(self.signer))
+    }
+
+    /// Verifies that the consignment is validly signed by the `sender`.
+    pub fn verify_sender(&self, sender: XOnlyPk) -> Result<(), ConsignmentSigError> {
+        if self.signer != sender {
+            return Err(ConsignmentSigError::UnexpectedSigner(self.signer));
+        }
+        self.verify()
+    }
+
+    /// Verifies the signature, returning the consignment if it is valid.
+    pub fn into_verified(self) -> Result<C, ConsignmentSigError> {
+        self.verify()?;
+        Ok(self.consignment)
+    }
+
+    /// Splits the signed consignment into the consignment, signer key and
+    /// signature, without verifying the signature.
+    pub fn into_parts(self) -> (C, XOnlyPk, Bytes64) { (self.consignment, self.signer, self.sig) }
+}
+
+#[cfg(test)]
+mod test {
+    use bp::secp256k1::rand::thread_rng;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::{Schema, TransitionBundle};
+
+    #[test]
+    fn sign_verify() {
+        let keypair = Keypair::new(SECP256K1, &mut thread_rng());
+        let other = XOnlyPk::from(
+            Keypair::new(SECP256K1, &mut thread_rng())
+                .x_only_public_key()
+                .0,
+        );
+        let sender = XOnlyPk::from(keypair.x_only_public_key().0);
+
+        let signed = SignedConsignment::sign(Schema::strict_dumb(), &keypair);
+        assert_eq!(signed.signer(), sender);
+        assert_eq!(signed.verify(), Ok(()));
+        assert_eq!(signed.verify_sender(sender), Ok(()));
+        assert_eq!(signed.verify_sender(other), Err(ConsignmentSigError::UnexpectedSigner(sender)));
+
+        // Signature must not be valid for other signer or other consignment
+        let (mut schema, _, sig) = signed.clone().into_parts();
+        let forged = SignedConsignment::with(schema.clone(), other, sig);
+        assert_eq!(forged.verify(), Err(ConsignmentSigError::InvalidSignature(other)));
+        schema.timestamp += 1;
+        let forged = SignedConsignment::with(schema, sender, sig);
+        assert_eq!(forged.into_verified(), Err(ConsignmentSigError::InvalidSignature(sender)));
+        assert_eq!(signed.into_verified(), Ok(Schema::strict_dumb()));
+
+        // Messages are domain-separated by the type of the commitment id
+        let id = [0xAD; 32];
+        assert_ne!(
+            SignedConsignment::<Schema>::message(id.into()),
+            SignedConsignment::<TransitionBundle>::message(id.into())
+        );
+    }
+}
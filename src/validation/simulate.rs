@@ -0,0 +1,364 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simulation of state transitions against a contract state snapshot, allowing
+//! to preview the outcome of an operation before it gets anchored.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use strict_encoding::StrictDumb;
+use strict_types::TypeSystem;
+
+use super::{
+    CheckedConsignment, ConsignmentApi, EAnchor, Failure, OpRef, Scripts, Status, Validity,
+};
+use crate::vm::{ContractStateAccess, ContractStateEvolve, OrdOpRef, WitnessOrd, XWitnessId};
+use crate::{BundleId, Genesis, OpId, Operation, Schema, Transition, TransitionBundle};
+
+/// Consignment extending the contract history with a proposed, not yet
+/// anchored state transition.
+struct Simulated<'consignment, C: ConsignmentApi> {
+    consignment: &'consignment C,
+    transition: &'consignment Transition,
+}
+
+impl<'consignment, C: ConsignmentApi> ConsignmentApi for Simulated<'consignment, C> {
+    fn schema(&self) -> &Schema { self.consignment.schema() }
+
+    fn types(&self) -> &TypeSystem { self.consignment.types() }
+
+    fn scripts(&self) -> &Scripts { self.consignment.scripts() }
+
+    fn operation(&self, opid: OpId) -> Option<OpRef<'_>> {
+        if self.transition.id() == opid {
+            return Some(OpRef::Transition(self.transition));
+        }
+        self.consignment.operation(opid)
+    }
+
+    fn genesis(&self) -> &Genesis { self.consignment.genesis() }
+
+    fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter {
+        self.consignment.bundle_ids()
+    }
+
+    fn bundle(&self, bundle_id: BundleId) -> Option<&TransitionBundle> {
+        self.consignment.bundle(bundle_id)
+    }
+
+    fn anchor(&self, bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)> {
+        self.consignment.anchor(bundle_id)
+    }
+
+    fn op_witness_id(&self, opid: OpId) -> Option<XWitnessId> {
+        self.consignment.op_witness_id(opid)
+    }
+}
+
+/// Applies a proposed state transition to the contract `state` snapshot
+/// without requiring it to be anchored, returning the would-be new contract
+/// state together with the validation status.
+///
+/// The `consignment` must provide the contract history containing all the
+/// operations spent by the transition. The transition is validated against the
+/// schema, including execution of the validation script, as a tentative
+/// operation with a placeholder witness id.
+///
+/// The schema is verified before the transition gets validated; if the schema
+/// is invalid the state is returned unchanged. If the validation fails, the
+/// returned state must be discarded.
+pub fn simulate_transition<C: ConsignmentApi, S: ContractStateAccess + ContractStateEvolve>(
+    consignment: &C,
+    state: S,
+    transition: &Transition,
+) -> (S, Status) {
+    let simulated = Simulated {
+        consignment,
+        transition,
+    };
+    let checked = CheckedConsignment::new(&simulated);
+    let schema = consignment.schema();
+    let opid = transition.id();
+    let mut status = Status::new();
+
+    let contract_id = consignment.genesis().contract_id();
+    if transition.contract_id != contract_id {
+        status.add_failure(Failure::ContractMismatch(opid, transition.contract_id));
+    }
+
+    // The state validation relies on the schema consistency, so we must not
+    // proceed with the schema which doesn't pass the verification.
+    status += schema.verify(consignment.types());
+    status += schema.verify_scripts(consignment.scripts());
+    if status.validity() == Validity::Invalid {
+        return (state, status);
+    }
+
+    let op = OrdOpRef::Transition(transition, XWitnessId::strict_dumb(), WitnessOrd::Tentative);
+    let state = Rc::new(RefCell::new(state));
+    status += schema.validate_state(&checked, op, state.clone());
+
+    // Validation evolves the state only when a validation script is executed,
+    // so for the operations without scripts we have to do it here.
+    let has_script = schema
+        .transitions
+        .get(&transition.transition_type)
+        .and_then(|schema| schema.validator)
+        .is_some();
+    if !has_script
+        && status.validity() != Validity::Invalid
+        && state.borrow_mut().evolve_state(op).is_err()
+    {
+        status.add_failure(Failure::ContractStateFilled(opid));
+    }
+
+    let state = Rc::try_unwrap(state)
+        .unwrap_or_else(|_| panic!("contract state is still borrowed after the validation"))
+        .into_inner();
+    (state, status)
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::num::u24;
+    use bp::dbc::Method;
+    use bp::Txid;
+
+    use super::*;
+    use crate::validation::validator::test::{global_state_types, Consignment, MemState, NoState};
+    use crate::{
+        Assign, AssignmentType, DataState, DocumentRevision, GenesisSeal, GlobalStateKind,
+        GlobalStateSchema, GlobalStateType, Input, KeyCharset, KeyDomain, KeyDomains,
+        KeyValueRecord, Occurrences, OpFullType, Opout, OwnedStateSchema, StateRef,
+        TransitionSchema, TransitionType, TypedAssigns, VoidState, XChain,
+    };
+
+    #[test]
+    fn unverified_schema() {
+        let ty = GlobalStateType::with(1);
+        let transition_ty = TransitionType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema
+            .transitions
+            .insert(transition_ty, TransitionSchema {
+                globals: tiny_bmap! { ty => Occurrences::Once },
+                ..default!()
+            })
+            .unwrap();
+        let consignment = Consignment::issue(schema, Scripts::default());
+
+        let mut transition = Transition::strict_dumb();
+        transition.transition_type = transition_ty;
+        transition.contract_id = consignment.genesis().contract_id();
+
+        let (_, status) = simulate_transition(&consignment, NoState, &transition);
+        assert!(status.failures.contains(&Failure::SchemaOpStateTypeUnknown(
+            OpFullType::StateTransition(transition_ty),
+            StateRef::Global(ty)
+        )));
+        assert_eq!(status.validity(), Validity::Invalid);
+    }
+
+    /// Constructs consignment of a contract of a schema without validation
+    /// scripts, which issues `issued` value of the global state type `1`, and
+    /// a state transition updating it with `update`.
+    fn script_less_contract(
+        global_schema: GlobalStateSchema,
+        key_domains: Option<KeyDomains>,
+        issued: DataState,
+        update: DataState,
+    ) -> (Consignment, Transition) {
+        let ty = GlobalStateType::with(1);
+        let owned_ty = AssignmentType::with(1);
+        let transition_ty = TransitionType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema.global_types.insert(ty, global_schema).unwrap();
+        if let Some(key_domains) = key_domains {
+            schema.ext.insert(key_domains);
+        }
+        schema
+            .owned_types
+            .insert(owned_ty, OwnedStateSchema::Declarative)
+            .unwrap();
+        schema
+            .genesis
+            .globals
+            .insert(ty, Occurrences::Once)
+            .unwrap();
+        schema
+            .genesis
+            .assignments
+            .insert(owned_ty, Occurrences::Once)
+            .unwrap();
+        schema
+            .transitions
+            .insert(transition_ty, TransitionSchema {
+                globals: tiny_bmap! { ty => Occurrences::Once },
+                inputs: tiny_bmap! { owned_ty => Occurrences::Once },
+                ..default!()
+            })
+            .unwrap();
+
+        let mut consignment = Consignment::issue(schema, Scripts::default());
+        consignment.types = global_state_types().as_types().clone();
+        consignment.genesis.globals.add_state(ty, issued).unwrap();
+        let seal = GenesisSeal::with_blinding(Method::OpretFirst, Txid::from([1u8; 32]), 0, 0);
+        consignment
+            .genesis
+            .assignments
+            .insert(
+                owned_ty,
+                TypedAssigns::Declarative(small_vec![Assign::revealed(
+                    XChain::Bitcoin(seal),
+                    VoidState::default()
+                )]),
+            )
+            .unwrap();
+
+        let mut transition = Transition::strict_dumb();
+        transition.transition_type = transition_ty;
+        transition.contract_id = consignment.genesis().contract_id();
+        transition
+            .inputs
+            .push(Input::with(Opout::new(consignment.genesis().id(), owned_ty, 0)))
+            .unwrap();
+        transition.globals.add_state(ty, update).unwrap();
+        (consignment, transition)
+    }
+
+    /// Simulates the transition of [`script_less_contract`] against the
+    /// contract state after the genesis.
+    fn simulate_script_less(
+        consignment: &Consignment,
+        transition: &Transition,
+    ) -> (MemState, Status) {
+        let mut state = MemState::default();
+        state
+            .evolve_state(OrdOpRef::Genesis(consignment.genesis()))
+            .unwrap();
+        simulate_transition(consignment, state, transition)
+    }
+
+    #[test]
+    fn script_less_transition() {
+        let ty = GlobalStateType::with(1);
+        let sem_id = *global_state_types()
+            .resolve("RGBCommit.DocumentRevision")
+            .unwrap();
+        let global_schema = GlobalStateSchema {
+            kind: GlobalStateKind::Document,
+            sem_id,
+            max_items: u24::MAX,
+        };
+        let first = DocumentRevision::new([1u8; 32]);
+        let next = first.revise([2u8; 32]);
+        let (consignment, transition) = script_less_contract(
+            global_schema,
+            None,
+            first.to_data().unwrap(),
+            next.to_data().unwrap(),
+        );
+
+        let (state, status) = simulate_script_less(&consignment, &transition);
+        assert_eq!(status.failures, vec![]);
+        let mut history = state.global(ty).unwrap();
+        assert_eq!(history.size(), u24::with(2));
+    }
+
+    #[test]
+    fn script_less_key_values() {
+        let ty = GlobalStateType::with(1);
+        let sem_id = *global_state_types()
+            .resolve("RGBCommit.KeyValueRecord")
+            .unwrap();
+        let domains = KeyDomains::from(tiny_bmap! { ty => KeyDomain {
+            charset: KeyCharset::DomainName,
+            max_key_len: 16,
+            max_value_len: 64,
+        }});
+        let set = KeyValueRecord::set(tiny_s!("example.rgb"), small_blob![1, 2, 3]);
+        let simulate = |record: KeyValueRecord| {
+            let (consignment, transition) = script_less_contract(
+                GlobalStateSchema::key_value(sem_id),
+                Some(domains.clone()),
+                set.to_data().unwrap(),
+                record.to_data().unwrap(),
+            );
+            let (_, status) = simulate_script_less(&consignment, &transition);
+            (status, transition.id())
+        };
+
+        // The keys set by the operations without validation scripts must be known to the
+        // subsequent operations
+        let (status, _) = simulate(KeyValueRecord::delete(tiny_s!("example.rgb")));
+        assert_eq!(status.failures, vec![]);
+
+        let (status, opid) = simulate(KeyValueRecord::delete(tiny_s!("unknown.rgb")));
+        assert_eq!(status.failures, vec![Failure::KeyValueKeyUnknown(opid, ty, s!("unknown.rgb"))]);
+    }
+
+    #[test]
+    fn script_less_key_value_deletion() {
+        let ty = GlobalStateType::with(1);
+        let sem_id = *global_state_types()
+            .resolve("RGBCommit.KeyValueRecord")
+            .unwrap();
+        let domains = KeyDomains::from(tiny_bmap! { ty => KeyDomain {
+            charset: KeyCharset::DomainName,
+            max_key_len: 16,
+            max_value_len: 64,
+        }});
+        let set = KeyValueRecord::set(tiny_s!("example.rgb"), small_blob![1, 2, 3]);
+        let delete = KeyValueRecord::delete(tiny_s!("example.rgb"));
+        let (consignment, deletion) = script_less_contract(
+            GlobalStateSchema::key_value(sem_id),
+            Some(domains),
+            set.to_data().unwrap(),
+            delete.to_data().unwrap(),
+        );
+        let (state, status) = simulate_script_less(&consignment, &deletion);
+        assert_eq!(status.failures, vec![]);
+
+        let follow = |state: MemState, nonce: u64, record: &KeyValueRecord| {
+            let mut transition = deletion.clone();
+            transition.nonce = nonce;
+            transition.globals = none!();
+            transition
+                .globals
+                .add_state(ty, record.to_data().unwrap())
+                .unwrap();
+            let (state, status) = simulate_transition(&consignment, state, &transition);
+            (state, status, transition.id())
+        };
+
+        // Deleted key is not present anymore and can't be deleted once again
+        let (state, status, opid) = follow(state, 1, &delete);
+        assert_eq!(status.failures, vec![Failure::KeyValueKeyUnknown(opid, ty, s!("example.rgb"))]);
+
+        // Deleted key can be set again, after which it can be deleted
+        let (state, status, _) = follow(state, 2, &set);
+        assert_eq!(status.failures, vec![]);
+        let (_, status, _) = follow(state, 3, &delete);
+        assert_eq!(status.failures, vec![]);
+    }
+}
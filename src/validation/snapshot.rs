@@ -0,0 +1,561 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Snapshot of the contract state folded from its history, used by wallets to
+//! persist the state between the sessions without re-validating the
+//! consignment.
+
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use amplify::confinement::{self, LargeOrdMap, MediumOrdMap, TinyOrdMap};
+use amplify::num::u24;
+use strict_encoding::{DeserializeError, SerializeError, StrictDeserialize, StrictSerialize};
+
+use super::ConsignmentApi;
+use crate::vm::{
+    ContractStateAccess, ContractStateEvolve, GlobalContractState, GlobalOrd, GlobalStateIter,
+    OpOrd, OrdOpRef, UnknownGlobalStateType, WitnessOrd, XWitnessId,
+};
+use crate::{
+    AssignmentType, AttachState, ContractId, DataState, FungibleState, GlobalStateType, Layer1,
+    OpId, Operation, Opout, RevealedState, XOutpoint, XOutputSeal, LIB_NAME_RGB_LOGIC,
+};
+
+/// Version of the [`ContractStateSnapshot`] encoding.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Display)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_LOGIC, tags = repr, into_u8, try_from_u8)]
+#[display(lowercase)]
+#[repr(u8)]
+pub enum SnapshotVersion {
+    /// Initial version.
+    #[default]
+    V0 = 0,
+}
+
+/// Owned state assigned to a seal which is not closed yet.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_LOGIC)]
+pub struct Allocation {
+    pub seal: XOutputSeal,
+    pub state: RevealedState,
+}
+
+/// The most recent witness transaction of a contract on one of the layer 1
+/// chains.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_LOGIC)]
+pub struct BranchAnchor {
+    pub witness_id: XWitnessId,
+    pub witness_ord: WitnessOrd,
+}
+
+/// Errors detected by [`ContractStateSnapshot::verify_against`].
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum SnapshotError {
+    /// snapshot of contract {0} is verified against consignment of a
+    /// different contract {1}.
+    ContractMismatch(ContractId, ContractId),
+
+    /// allocation {0} is not defined by the consignment operations.
+    AllocationUnknown(Opout),
+
+    /// seal or state of allocation {0} doesn't match the consignment.
+    AllocationMismatch(Opout),
+
+    /// allocation {0} is spent by the consignment operation {1}.
+    AllocationSpent(Opout, OpId),
+
+    /// global state of type {0} at index {2} of operation {1} is not defined
+    /// by the consignment.
+    GlobalUnknown(GlobalStateType, OpId, u16),
+
+    /// witness {0} is not known to the consignment.
+    WitnessUnknown(XWitnessId),
+}
+
+/// Contract state folded from the contract history: unspent allocations of
+/// the owned state, global state and the last witness on each of the layer 1
+/// chains.
+///
+/// The snapshot is evolved with the contract operations in their consensus
+/// order via [`ContractStateEvolve`] and provides the state to the validation
+/// scripts via [`ContractStateAccess`]. Only the assignments with both seal
+/// and state revealed are accounted for as allocations.
+///
+/// The snapshot is strict-encoded for the persistence.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_LOGIC)]
+pub struct ContractStateSnapshot {
+    version: SnapshotVersion,
+    contract_id: ContractId,
+    allocations: LargeOrdMap<Opout, Allocation>,
+    global: TinyOrdMap<GlobalStateType, MediumOrdMap<GlobalOrd, DataState>>,
+    anchors: TinyOrdMap<Layer1, BranchAnchor>,
+}
+
+impl StrictSerialize for ContractStateSnapshot {}
+impl StrictDeserialize for ContractStateSnapshot {}
+
+impl ContractStateSnapshot {
+    /// Constructs empty snapshot of a contract state.
+    pub fn new(contract_id: ContractId) -> Self {
+        Self {
+            version: default!(),
+            contract_id,
+            allocations: empty!(),
+            global: empty!(),
+            anchors: empty!(),
+        }
+    }
+
+    /// Loads snapshot from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DeserializeError> {
+        Self::strict_deserialize_from_file::<{ usize::MAX }>(path)
+    }
+
+    /// Saves snapshot to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SerializeError> {
+        self.strict_serialize_to_file::<{ usize::MAX }>(path)
+    }
+
+    /// Returns version of the snapshot encoding.
+    pub fn version(&self) -> SnapshotVersion { self.version }
+
+    /// Returns id of the contract.
+    pub fn contract_id(&self) -> ContractId { self.contract_id }
+
+    /// Iterates over all unspent allocations.
+    pub fn allocations(&self) -> impl Iterator<Item = (Opout, &Allocation)> {
+        self.allocations
+            .iter()
+            .map(|(opout, allocation)| (*opout, allocation))
+    }
+
+    /// Returns the most recent witness on a layer 1 chain, if any.
+    pub fn anchor(&self, layer1: Layer1) -> Option<BranchAnchor> {
+        self.anchors.get(&layer1).copied()
+    }
+
+    fn state_at(
+        &self,
+        outpoint: XOutpoint,
+        ty: AssignmentType,
+    ) -> impl DoubleEndedIterator<Item = &RevealedState> {
+        self.allocations
+            .iter()
+            .filter(move |(opout, allocation)| {
+                opout.ty == ty && allocation.seal.to_outpoint() == outpoint
+            })
+            .map(|(_, allocation)| &allocation.state)
+    }
+
+    /// Checks that the snapshot is consistent with the consignment: all
+    /// allocations and global state are defined by the consignment operations,
+    /// the allocations are not spent by the consignment state transitions and
+    /// the witnesses are known to the consignment.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first detected inconsistency.
+    pub fn verify_against<C: ConsignmentApi>(&self, consignment: &C) -> Result<(), SnapshotError> {
+        let genesis = consignment.genesis();
+        let contract_id = genesis.contract_id();
+        if contract_id != self.contract_id {
+            return Err(SnapshotError::ContractMismatch(self.contract_id, contract_id));
+        }
+
+        let mut spent = bmap! {};
+        let mut witnesses = BTreeSet::new();
+        for bundle_id in consignment.bundle_ids() {
+            if let Some((witness_id, _)) = consignment.anchor(bundle_id) {
+                witnesses.insert(witness_id);
+            }
+            let Some(bundle) = consignment.bundle(bundle_id) else {
+                continue;
+            };
+            for (opid, transition) in &bundle.known_transitions {
+                spent.extend(transition.inputs.iter().map(|input| (input.prev_out, *opid)));
+            }
+        }
+
+        for (opout, allocation) in &self.allocations {
+            if let Some(opid) = spent.get(opout) {
+                return Err(SnapshotError::AllocationSpent(*opout, *opid));
+            }
+            let op = consignment
+                .operation(opout.op)
+                .ok_or(SnapshotError::AllocationUnknown(*opout))?;
+            let assigns = op
+                .assignments_by_type(opout.ty)
+                .ok_or(SnapshotError::AllocationUnknown(*opout))?;
+            let witness_id = consignment.op_witness_id(opout.op);
+            let matches = assigns
+                .iter_revealed()
+                .find(|(no, ..)| *no == opout.no)
+                .is_some_and(|(_, seal, state)| {
+                    let seal = match witness_id {
+                        Some(witness_id) => seal.try_to_output_seal(witness_id).ok(),
+                        None => seal.to_output_seal(),
+                    };
+                    seal == Some(allocation.seal) && RevealedState::from(state) == allocation.state
+                });
+            if !matches {
+                return Err(SnapshotError::AllocationMismatch(*opout));
+            }
+        }
+
+        for (ty, history) in &self.global {
+            for (ord, data) in history {
+                let opid = match ord.op_ord {
+                    OpOrd::Genesis => genesis.id(),
+                    OpOrd::Transition { opid, .. } | OpOrd::Extension { opid, .. } => opid,
+                };
+                let known = consignment.operation(opid).is_some_and(|op| {
+                    op.globals()
+                        .get(ty)
+                        .and_then(|values| values.get(ord.idx as usize))
+                        == Some(data)
+                });
+                if !known {
+                    return Err(SnapshotError::GlobalUnknown(*ty, opid, ord.idx));
+                }
+            }
+        }
+
+        for anchor in self.anchors.values() {
+            if !witnesses.contains(&anchor.witness_id) {
+                return Err(SnapshotError::WitnessUnknown(anchor.witness_id));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct SnapshotGlobals<'snapshot> {
+    items: Vec<(GlobalOrd, &'snapshot DataState)>,
+    pos: usize,
+}
+
+impl<'snapshot> GlobalStateIter for SnapshotGlobals<'snapshot> {
+    type Data = &'snapshot DataState;
+
+    fn size(&mut self) -> u24 { u24::try_from(self.items.len() as u32).expect("medium map") }
+
+    fn prev(&mut self) -> Option<(GlobalOrd, Self::Data)> {
+        self.pos = self.pos.checked_sub(1)?;
+        self.items.get(self.pos).copied()
+    }
+
+    fn last(&mut self) -> Option<(GlobalOrd, Self::Data)> { self.items.get(self.pos).copied() }
+
+    fn reset(&mut self, depth: u24) {
+        self.pos = self
+            .items
+            .len()
+            .saturating_sub(depth.to_u32() as usize + 1);
+    }
+}
+
+impl ContractStateAccess for ContractStateSnapshot {
+    fn global(
+        &self,
+        ty: GlobalStateType,
+    ) -> Result<GlobalContractState<impl GlobalStateIter>, UnknownGlobalStateType> {
+        let items = self
+            .global
+            .get(&ty)
+            .map(|history| {
+                history
+                    .iter()
+                    .map(|(ord, data)| (*ord, data))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let pos = items.len();
+        Ok(GlobalContractState::new(SnapshotGlobals { items, pos }))
+    }
+
+    fn rights(&self, outpoint: XOutpoint, ty: AssignmentType) -> u32 {
+        self.state_at(outpoint, ty)
+            .filter(|state| state.is_void())
+            .count() as u32
+    }
+
+    fn fungible(
+        &self,
+        outpoint: XOutpoint,
+        ty: AssignmentType,
+    ) -> impl DoubleEndedIterator<Item = FungibleState> {
+        self.state_at(outpoint, ty)
+            .filter_map(|state| state.as_fungible().map(|value| value.value))
+    }
+
+    fn data(
+        &self,
+        outpoint: XOutpoint,
+        ty: AssignmentType,
+    ) -> impl DoubleEndedIterator<Item = impl Borrow<DataState>> {
+        self.state_at(outpoint, ty)
+            .filter_map(|state| state.as_structured().map(|data| &data.value))
+    }
+
+    fn attach(
+        &self,
+        outpoint: XOutpoint,
+        ty: AssignmentType,
+    ) -> impl DoubleEndedIterator<Item = impl Borrow<AttachState>> {
+        self.state_at(outpoint, ty)
+            .filter_map(|state| state.as_attachment().map(|attach| &attach.file))
+    }
+}
+
+impl ContractStateEvolve for ContractStateSnapshot {
+    type Context<'ctx> = ContractId;
+
+    fn init(contract_id: Self::Context<'_>) -> Self { Self::new(contract_id) }
+
+    fn evolve_state(&mut self, op: OrdOpRef) -> Result<(), confinement::Error> {
+        let opid = op.id();
+        for input in &op.inputs() {
+            self.allocations.remove(&input.prev_out)?;
+        }
+
+        let witness_id = op.witness_id();
+        for (ty, assigns) in op.assignments().flat().iter() {
+            for (no, seal, state) in assigns.iter_revealed() {
+                // Assignments to seals which can't be resolved into a
+                // transaction output can't be spent and are not allocations
+                let seal = match witness_id {
+                    Some(witness_id) => seal.try_to_output_seal(witness_id).ok(),
+                    None => seal.to_output_seal(),
+                };
+                let Some(seal) = seal else {
+                    continue;
+                };
+                let allocation = Allocation {
+                    seal,
+                    state: state.into(),
+                };
+                self.allocations
+                    .insert(Opout::new(opid, *ty, no), allocation)?;
+            }
+        }
+
+        let op_ord = op.op_ord();
+        for (ty, values) in op.globals().iter() {
+            let mut history = self.global.remove(ty)?.unwrap_or_default();
+            for (idx, data) in values.iter().enumerate() {
+                let ord = GlobalOrd {
+                    op_ord,
+                    idx: idx as u16,
+                };
+                history.insert(ord, data.clone())?;
+            }
+            self.global.insert(*ty, history)?;
+        }
+
+        if let (
+            Some(witness_id),
+            OpOrd::Transition { witness, .. } | OpOrd::Extension { witness, .. },
+        ) = (witness_id, op_ord)
+        {
+            let layer1 = witness_id.layer1();
+            if self
+                .anchors
+                .get(&layer1)
+                .map_or(true, |anchor| anchor.witness_ord <= witness)
+            {
+                self.anchors.insert(layer1, BranchAnchor {
+                    witness_id,
+                    witness_ord: witness,
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bp::dbc::Method;
+    use bp::Txid;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::validation::validator::test::Consignment;
+    use crate::validation::Scripts;
+    use crate::{
+        Assign, GenesisSeal, RevealedData, RevealedValue, Schema, TypedAssigns, VoidState, XChain,
+    };
+
+    fn value(amount: u64) -> RevealedValue {
+        RevealedValue::with_blinding(amount, StrictDumb::strict_dumb(), StrictDumb::strict_dumb())
+    }
+
+    fn consignment() -> Consignment {
+        let mut consignment = Consignment::issue(Schema::strict_dumb(), Scripts::default());
+        let genesis = &mut consignment.genesis;
+        let seal = |vout| {
+            XChain::Bitcoin(GenesisSeal::with_blinding(
+                Method::OpretFirst,
+                Txid::from([1u8; 32]),
+                vout,
+                0,
+            ))
+        };
+        genesis
+            .globals
+            .add_state(GlobalStateType::with(1), DataState::from(small_blob![1, 2, 3]))
+            .unwrap();
+        genesis
+            .assignments
+            .insert(
+                AssignmentType::with(1),
+                TypedAssigns::Declarative(small_vec![
+                    Assign::revealed(seal(0), VoidState::default()),
+                    Assign::revealed(seal(0), VoidState::default())
+                ]),
+            )
+            .unwrap();
+        genesis
+            .assignments
+            .insert(
+                AssignmentType::with(2),
+                TypedAssigns::Fungible(small_vec![Assign::revealed(
+                    seal(1),
+                    value(100)
+                )]),
+            )
+            .unwrap();
+        genesis
+            .assignments
+            .insert(
+                AssignmentType::with(3),
+                TypedAssigns::Structured(small_vec![Assign::revealed(
+                    seal(1),
+                    RevealedData::with_salt(small_blob![4, 5], 0)
+                )]),
+            )
+            .unwrap();
+        consignment
+    }
+
+    #[test]
+    fn fold_genesis() {
+        let consignment = consignment();
+        let genesis = &consignment.genesis;
+        let mut snapshot = ContractStateSnapshot::init(genesis.contract_id());
+        snapshot.evolve_state(OrdOpRef::Genesis(genesis)).unwrap();
+        assert_eq!(snapshot.allocations().count(), 4);
+        assert_eq!(snapshot.anchor(Layer1::Bitcoin), None);
+
+        let outpoint = |vout| {
+            XChain::Bitcoin(GenesisSeal::with_blinding(
+                Method::OpretFirst,
+                Txid::from([1u8; 32]),
+                vout,
+                0,
+            ))
+            .to_outpoint()
+        };
+        assert_eq!(snapshot.rights(outpoint(0), AssignmentType::with(1)), 2);
+        assert_eq!(snapshot.rights(outpoint(1), AssignmentType::with(1)), 0);
+        assert_eq!(
+            snapshot
+                .fungible(outpoint(1), AssignmentType::with(2))
+                .collect::<Vec<_>>(),
+            vec![FungibleState::from(100u64)]
+        );
+        assert_eq!(
+            snapshot
+                .data(outpoint(1), AssignmentType::with(3))
+                .map(|data| data.borrow().clone())
+                .collect::<Vec<_>>(),
+            vec![DataState::from(small_blob![4, 5])]
+        );
+        let mut global = snapshot.global(GlobalStateType::with(1)).unwrap();
+        assert_eq!(global.size(), u24::ONE);
+        assert_eq!(
+            global.nth(u24::ZERO).unwrap().borrow(),
+            &DataState::from(small_blob![1, 2, 3])
+        );
+
+        let data = snapshot.to_strict_serialized::<{ usize::MAX }>().unwrap();
+        assert_eq!(ContractStateSnapshot::from_strict_serialized(data).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn verify() {
+        let consignment = consignment();
+        let genesis = &consignment.genesis;
+        let opid = genesis.id();
+        let mut snapshot = ContractStateSnapshot::init(genesis.contract_id());
+        snapshot.evolve_state(OrdOpRef::Genesis(genesis)).unwrap();
+        assert_eq!(snapshot.verify_against(&consignment), Ok(()));
+
+        let other = ContractStateSnapshot::new(ContractId::strict_dumb());
+        assert_eq!(
+            other.verify_against(&consignment),
+            Err(SnapshotError::ContractMismatch(ContractId::strict_dumb(), genesis.contract_id()))
+        );
+
+        let mut tampered = snapshot.clone();
+        let opout = Opout::new(opid, AssignmentType::with(2), 0);
+        let mut allocation = tampered.allocations.get(&opout).unwrap().clone();
+        allocation.state = value(1000).into();
+        tampered.allocations.insert(opout, allocation).unwrap();
+        assert_eq!(
+            tampered.verify_against(&consignment),
+            Err(SnapshotError::AllocationMismatch(opout))
+        );
+
+        let mut tampered = snapshot.clone();
+        let unknown = Opout::new(opid, AssignmentType::with(4), 0);
+        let allocation = tampered.allocations.values().next().unwrap().clone();
+        tampered.allocations.insert(unknown, allocation).unwrap();
+        assert_eq!(
+            tampered.verify_against(&consignment),
+            Err(SnapshotError::AllocationUnknown(unknown))
+        );
+
+        let mut tampered = snapshot;
+        let ty = GlobalStateType::with(1);
+        let mut history = tampered.global.remove(&ty).unwrap().unwrap();
+        history
+            .insert(GlobalOrd::genesis(1), DataState::from(small_blob![1]))
+            .unwrap();
+        tampered.global.insert(ty, history).unwrap();
+        assert_eq!(
+            tampered.verify_against(&consignment),
+            Err(SnapshotError::GlobalUnknown(ty, opid, 1))
+        );
+    }
+}
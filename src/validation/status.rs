@@ -21,21 +21,31 @@
 // limitations under the License.
 
 use core::ops::AddAssign;
+use std::collections::BTreeMap;
 use std::fmt::{self, Display, Formatter};
 
+use aluvm::library::{LibId, LibSite};
 use amplify::num::u24;
+use bp::XOnlyPk;
 use commit_verify::mpc::InvalidProof;
+use strict_encoding::{StrictDeserialize, StrictSerialize};
 use strict_types::SemId;
 
-use crate::schema::{self, SchemaId};
+use crate::schema::{self, Schema, SchemaId};
 use crate::validation::WitnessResolverError;
 use crate::vm::XWitnessId;
 use crate::{
-    BundleId, ContractId, Layer1, OccurrencesMismatch, OpFullType, OpId, Opout, StateType, Vin,
-    XGraphSeal, XOutputSeal,
+    BundleId, ContractId, Disambiguator, DocumentDigest, EndorsementError,
+    IssuerAttestationError, Layer1, OccurrencesMismatch, OpFullType, OpId, OpType, Operation,
+    Opout, RangeProofError, ShortId, StateType, Vin, XGraphSeal, XOutputSeal, LIB_NAME_RGB_LOGIC,
 };
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
 #[repr(u8)]
 pub enum Validity {
     #[display("is valid")]
@@ -58,6 +68,400 @@ pub struct Status {
     pub failures: Vec<Failure>,
     pub warnings: Vec<Warning>,
     pub info: Vec<Info>,
+    /// Labels attributing status entries to their origin, like a contract or a
+    /// validation phase.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub labels: StatusLabels,
+    /// Counters of the data inspected by the validation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stats: ValidationStats,
+    /// State usage statistics, present only if requested by the validation
+    /// policy.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub usage: Option<UsageStats>,
+    /// Supply of the state tracked per layer 1 (see
+    /// [`crate::ChainSupplySchema`]), as accounted from the validated history.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub supply: BTreeMap<Layer1, u64>,
+    /// Status of the single-use seals closed by the validated bundles.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seals: Vec<SealReport>,
+}
+
+/// Status of a single-use seal closed by a state transition bundle.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(doc_comments)]
+pub enum SealStatus {
+    /// seal is closed by a mined witness transaction.
+    Closed,
+    /// seal is closed by a witness transaction which is not mined yet.
+    PendingWitness,
+    /// witness transaction closing the seal is unknown or archived.
+    WitnessUnknown,
+    /// witness transaction doesn't close the seal over the bundle commitment.
+    WitnessInvalid,
+}
+
+impl SealStatus {
+    /// Detects whether the seal closing is final.
+    pub fn is_closed(self) -> bool { self == SealStatus::Closed }
+}
+
+/// Status of a single-use seal closed by a validated bundle, together with
+/// the bundle and its witness.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct SealReport {
+    pub seal: XOutputSeal,
+    pub bundle_id: BundleId,
+    pub witness_id: XWitnessId,
+    pub status: SealStatus,
+}
+
+/// Counters of the data inspected by the validation, used to derive
+/// [`ValidityReport`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct ValidationStats {
+    /// Number of witness transactions requested from the resolver.
+    pub witnesses: usize,
+    /// Number of witness transactions the resolver failed to provide.
+    pub witnesses_unresolved: usize,
+    /// Number of resolved witness transactions which are not mined.
+    pub witnesses_unconfirmed: usize,
+    /// Number of validated owned state assignments.
+    pub assignments: usize,
+    /// Number of validated owned state assignments with concealed state.
+    pub assignments_concealed: usize,
+}
+
+impl AddAssign for ValidationStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.witnesses += rhs.witnesses;
+        self.witnesses_unresolved += rhs.witnesses_unresolved;
+        self.witnesses_unconfirmed += rhs.witnesses_unconfirmed;
+        self.assignments += rhs.assignments;
+        self.assignments_concealed += rhs.assignments_concealed;
+    }
+}
+
+/// Breakdown of the validation result, produced by [`Status::report`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct ValidityReport {
+    /// Consensus validity, the same as returned by [`Status::validity`].
+    pub consensus: Validity,
+    /// Counters of the data inspected by the validation.
+    pub stats: ValidationStats,
+}
+
+impl ValidityReport {
+    /// Detects whether all witness transactions were resolved and mined.
+    pub fn is_confirmed(&self) -> bool {
+        self.stats.witnesses_unresolved == 0 && self.stats.witnesses_unconfirmed == 0
+    }
+
+    /// Share of the validated owned state assignments which have revealed
+    /// state, in range `0.0..=1.0`. Returns `1.0` if there were no assignments.
+    pub fn concealed_coverage(&self) -> f64 {
+        let revealed = self
+            .stats
+            .assignments
+            .saturating_sub(self.stats.assignments_concealed);
+        ratio(revealed, self.stats.assignments)
+    }
+
+    /// Share of the witness transactions provided by the resolver, in range
+    /// `0.0..=1.0`. Returns `1.0` if no witnesses were requested.
+    pub fn resolver_completeness(&self) -> f64 {
+        let resolved = self
+            .stats
+            .witnesses
+            .saturating_sub(self.stats.witnesses_unresolved);
+        ratio(resolved, self.stats.witnesses)
+    }
+}
+
+fn ratio(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 1.0;
+    }
+    part as f64 / total as f64
+}
+
+/// Number of occurrences of a single global state, assignment or valency type
+/// across the validated operations of some type.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct StateUsage {
+    /// Number of operations containing the state type.
+    pub operations: usize,
+    /// Minimal number of occurrences within an operation containing the state
+    /// type.
+    pub min: u16,
+    /// Maximal number of occurrences within a single operation.
+    pub max: u16,
+    /// Total number of occurrences across all operations.
+    pub total: usize,
+}
+
+impl StateUsage {
+    fn record(&mut self, count: u16) {
+        self.min = if self.operations == 0 { count } else { self.min.min(count) };
+        self.max = self.max.max(count);
+        self.operations += 1;
+        self.total += count as usize;
+    }
+}
+
+impl AddAssign for StateUsage {
+    fn add_assign(&mut self, rhs: Self) {
+        if rhs.operations == 0 {
+            return;
+        }
+        self.min = if self.operations == 0 { rhs.min } else { self.min.min(rhs.min) };
+        self.max = self.max.max(rhs.max);
+        self.operations += rhs.operations;
+        self.total += rhs.total;
+    }
+}
+
+/// State usage by the validated operations of a single type.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct OpUsage {
+    pub op_type: OpFullType,
+    /// Number of validated operations of this type.
+    pub operations: usize,
+    pub globals: BTreeMap<schema::GlobalStateType, StateUsage>,
+    pub assignments: BTreeMap<schema::AssignmentType, StateUsage>,
+    pub valencies: BTreeMap<schema::ValencyType, StateUsage>,
+}
+
+impl OpUsage {
+    fn new(op_type: OpFullType) -> Self {
+        OpUsage {
+            op_type,
+            operations: 0,
+            globals: empty!(),
+            assignments: empty!(),
+            valencies: empty!(),
+        }
+    }
+
+    /// Returns the range of occurrences of a global state type actually used by
+    /// the operations, which is the tightest [`schema::Occurrences`] bound the
+    /// schema may define for them.
+    pub fn global_occurrences(&self, ty: schema::GlobalStateType) -> (u16, u16) {
+        self.occurrences(self.globals.get(&ty))
+    }
+
+    /// Returns the range of occurrences of an assignment type actually used by
+    /// the operations.
+    pub fn assignment_occurrences(&self, ty: schema::AssignmentType) -> (u16, u16) {
+        self.occurrences(self.assignments.get(&ty))
+    }
+
+    fn occurrences(&self, usage: Option<&StateUsage>) -> (u16, u16) {
+        match usage {
+            None => (0, 0),
+            Some(usage) if usage.operations < self.operations => (0, usage.max),
+            Some(usage) => (usage.min, usage.max),
+        }
+    }
+}
+
+/// Per-type statistics of the state used by the validated operations, which
+/// allows schema authors to tighten the occurrence bounds of their schemata.
+///
+/// Collected only if [`ValidationPolicy::collect_usage`] is set.
+///
+/// [`ValidationPolicy::collect_usage`]: super::ValidationPolicy::collect_usage
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct UsageStats(Vec<OpUsage>);
+
+impl UsageStats {
+    /// Accounts state used by the operation.
+    pub fn record(&mut self, op: &impl Operation) {
+        let usage = self.entry(op.full_type());
+        usage.operations += 1;
+        for (ty, values) in op.globals().iter() {
+            usage
+                .globals
+                .entry(*ty)
+                .or_default()
+                .record(values.len() as u16);
+        }
+        let assignments = op.assignments();
+        for ty in assignments.types() {
+            let count = assignments.get(ty).map(|a| a.len_u16()).unwrap_or_default();
+            usage.assignments.entry(ty).or_default().record(count);
+        }
+        for ty in op.valencies() {
+            usage.valencies.entry(ty).or_default().record(1);
+        }
+    }
+
+    /// Returns usage by the operations of a given type, if any of them were
+    /// validated.
+    pub fn get(&self, op_type: OpFullType) -> Option<&OpUsage> {
+        self.0.iter().find(|usage| usage.op_type == op_type)
+    }
+
+    /// Iterates over usage by each of the validated operation types.
+    pub fn iter(&self) -> impl Iterator<Item = &OpUsage> { self.0.iter() }
+
+    fn entry(&mut self, op_type: OpFullType) -> &mut OpUsage {
+        let pos = match self.0.binary_search_by_key(&op_type, |usage| usage.op_type) {
+            Ok(pos) => pos,
+            Err(pos) => {
+                self.0.insert(pos, OpUsage::new(op_type));
+                pos
+            }
+        };
+        &mut self.0[pos]
+    }
+}
+
+impl AddAssign for UsageStats {
+    fn add_assign(&mut self, rhs: Self) {
+        for rhs in rhs.0 {
+            let usage = self.entry(rhs.op_type);
+            usage.operations += rhs.operations;
+            for (ty, state) in rhs.globals {
+                *usage.globals.entry(ty).or_default() += state;
+            }
+            for (ty, state) in rhs.assignments {
+                *usage.assignments.entry(ty).or_default() += state;
+            }
+            for (ty, state) in rhs.valencies {
+                *usage.valencies.entry(ty).or_default() += state;
+            }
+        }
+    }
+}
+
+/// Resources used by the execution of a validation script of a single
+/// operation.
+///
+/// Reported in [`Info::ScriptProfile`] only if
+/// [`ValidationPolicy::profile_scripts`] is set.
+///
+/// [`ValidationPolicy::profile_scripts`]: super::ValidationPolicy::profile_scripts
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default, Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(
+    "{instructions} instructions of total complexity {complexity}, using {memory} bytes of \
+     registers, in {micros} us"
+)]
+pub struct ScriptStats {
+    /// Number of the executed instructions.
+    pub instructions: u64,
+    /// Total complexity of the executed instructions, which is compared
+    /// against the complexity limit of the virtual machine.
+    pub complexity: u64,
+    /// Total size of the registers written by the script, in bytes.
+    pub memory: usize,
+    /// Execution time measured with the monotonic clock, in microseconds.
+    pub micros: u64,
+}
+
+/// Labels of the [`Status`] entries, indexed by the position of the entry in
+/// the corresponding list. Entries without labels are absent.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct StatusLabels {
+    pub failures: BTreeMap<usize, String>,
+    pub warnings: BTreeMap<usize, String>,
+    pub info: BTreeMap<usize, String>,
+}
+
+fn merge_labels(
+    labels: &mut BTreeMap<usize, String>,
+    offset: usize,
+    len: usize,
+    rhs: BTreeMap<usize, String>,
+    label: Option<&str>,
+) {
+    match label {
+        None => labels.extend(rhs.into_iter().map(|(idx, l)| (idx + offset, l))),
+        Some(label) => labels.extend((0..len).map(|idx| {
+            let l = match rhs.get(&idx) {
+                Some(l) => format!("{label}/{l}"),
+                None => label.to_owned(),
+            };
+            (idx + offset, l)
+        })),
+    }
+}
+
+/// Appends `rhs` entries, reusing the `rhs` buffer if the entries can't fit
+/// into the already allocated one.
+fn append<T>(items: &mut Vec<T>, rhs: Vec<T>) {
+    if items.is_empty() && items.capacity() < rhs.len() {
+        *items = rhs;
+    } else {
+        items.extend(rhs);
+    }
+}
+
+/// Retains only the entries for which `f` returns `true`, keeping their labels.
+/// The closure receives the already retained entries together with their
+/// labels.
+fn retain_labeled<T>(
+    items: &mut Vec<T>,
+    labels: &mut BTreeMap<usize, String>,
+    mut f: impl FnMut(&[T], &BTreeMap<usize, String>, &T, Option<&String>) -> bool,
+) {
+    let old_items = std::mem::take(items);
+    let old_labels = std::mem::take(labels);
+    for (idx, item) in old_items.into_iter().enumerate() {
+        let label = old_labels.get(&idx);
+        if f(items, labels, &item, label) {
+            if let Some(label) = label {
+                labels.insert(items.len(), label.clone());
+            }
+            items.push(item);
+        }
+    }
 }
 
 impl Display for Status {
@@ -68,22 +472,25 @@ impl Display for Status {
 
         if !self.failures.is_empty() {
             f.write_str("Validation failures:\n")?;
-            for fail in &self.failures {
-                writeln!(f, "- {fail}")?;
+            for (label, fail) in self.labeled_failures() {
+                write_label(f, label)?;
+                writeln!(f, "{fail}")?;
             }
         }
 
         if !self.warnings.is_empty() {
             f.write_str("Validation warnings:\n")?;
-            for warn in &self.warnings {
-                writeln!(f, "- {warn}")?;
+            for (label, warn) in self.labeled_warnings() {
+                write_label(f, label)?;
+                writeln!(f, "{warn}")?;
             }
         }
 
         if !self.info.is_empty() {
             f.write_str("Validation info:\n")?;
-            for info in &self.info {
-                writeln!(f, "- {info}")?;
+            for (label, info) in self.labeled_info() {
+                write_label(f, label)?;
+                writeln!(f, "{info}")?;
             }
         }
 
@@ -91,20 +498,56 @@ impl Display for Status {
     }
 }
 
-impl AddAssign for Status {
-    fn add_assign(&mut self, rhs: Self) {
-        self.failures.extend(rhs.failures);
-        self.warnings.extend(rhs.warnings);
-        self.info.extend(rhs.info);
+/// Replaces all the 64-character hex strings in the text, which are the ids of
+/// operations, bundles and witnesses, with their short forms, disambiguated
+/// against the `ids` and all the other hex ids found in the text.
+fn shorten_ids(text: &str, ids: &Disambiguator) -> String {
+    let is_hex = |c: &u8| matches!(c, b'0'..=b'9' | b'a'..=b'f');
+    let bytes = text.as_bytes();
+    let mut found = vec![];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let len = bytes[pos..].iter().take_while(|c| is_hex(c)).count();
+        if len == ShortId::MAX_LEN {
+            let mut id = [0u8; 32];
+            for (byte, hex) in id.iter_mut().zip(bytes[pos..pos + len].chunks(2)) {
+                let hex = std::str::from_utf8(hex).expect("ascii hex digits");
+                *byte = u8::from_str_radix(hex, 16).expect("ascii hex digits");
+            }
+            found.push((pos, id));
+        }
+        pos += len.max(1);
+    }
+
+    let mut ids = ids.clone();
+    ids.extend(found.iter().map(|(_, id)| *id));
+    let mut short = String::with_capacity(text.len());
+    let mut last = 0;
+    for (pos, id) in found {
+        short.push_str(&text[last..pos]);
+        short.push_str(&ids.short_id(id).to_string());
+        last = pos + ShortId::MAX_LEN;
+    }
+    short.push_str(&text[last..]);
+    short
+}
+
+fn write_label(f: &mut Formatter<'_>, label: Option<&str>) -> fmt::Result {
+    match label {
+        Some(label) => write!(f, "- [{label}] "),
+        None => f.write_str("- "),
     }
 }
 
+impl AddAssign for Status {
+    fn add_assign(&mut self, rhs: Self) { self.merge(rhs, None) }
+}
+
 impl Status {
     pub fn from_error(v: Failure) -> Self {
         Status {
             failures: vec![v],
-            warnings: vec![],
-            info: vec![],
+            ..Self::default()
         }
     }
 }
@@ -128,6 +571,18 @@ impl Status {
         }
     }
 
+    /// Renders the status like its [`Display`] implementation does, replacing
+    /// the ids with their short forms disambiguated against the `ids`, which
+    /// are usually the ids of the validated consignment (see
+    /// [`super::ConsignmentApi::disambiguator`]).
+    pub fn to_short_string(&self, ids: &Disambiguator) -> String {
+        shorten_ids(&self.to_string(), ids)
+    }
+
+    /// Reserves capacity for at least `additional` more failures, avoiding
+    /// repeated reallocations when a large number of failures is expected.
+    pub fn reserve(&mut self, additional: usize) { self.failures.reserve(additional) }
+
     pub fn add_failure(&mut self, failure: impl Into<Failure>) -> &Self {
         self.failures.push(failure.into());
         self
@@ -143,6 +598,158 @@ impl Status {
         self
     }
 
+    /// Merges `rhs` status into this one, labelling all of its entries with
+    /// `label`. If an entry already has a label, the new label is prepended to
+    /// it as a namespace, separated with `/`.
+    pub fn merge_labeled(&mut self, rhs: Self, label: impl AsRef<str>) {
+        self.merge(rhs, Some(label.as_ref()))
+    }
+
+    fn merge(&mut self, rhs: Self, label: Option<&str>) {
+        let labels = &mut self.labels;
+        merge_labels(
+            &mut labels.failures,
+            self.failures.len(),
+            rhs.failures.len(),
+            rhs.labels.failures,
+            label,
+        );
+        merge_labels(
+            &mut labels.warnings,
+            self.warnings.len(),
+            rhs.warnings.len(),
+            rhs.labels.warnings,
+            label,
+        );
+        merge_labels(&mut labels.info, self.info.len(), rhs.info.len(), rhs.labels.info, label);
+        append(&mut self.failures, rhs.failures);
+        append(&mut self.warnings, rhs.warnings);
+        append(&mut self.info, rhs.info);
+        self.stats += rhs.stats;
+        match (&mut self.usage, rhs.usage) {
+            (Some(usage), Some(rhs)) => *usage += rhs,
+            (usage @ None, rhs) => *usage = rhs,
+            (Some(_), None) => {}
+        }
+        for (layer1, supply) in rhs.supply {
+            let total = self.supply.entry(layer1).or_default();
+            *total = total.saturating_add(supply);
+        }
+        append(&mut self.seals, rhs.seals);
+    }
+
+    /// Returns status of the seal, if it is closed by one of the validated
+    /// bundles.
+    pub fn seal_status(&self, seal: XOutputSeal) -> Option<SealStatus> {
+        self.seals
+            .iter()
+            .rfind(|report| report.seal == seal)
+            .map(|report| report.status)
+    }
+
+    /// Returns reports on the seals which closing is not final, i.e. the
+    /// transfers which are blocking the history from being confirmed.
+    pub fn unclosed_seals(&self) -> impl Iterator<Item = &SealReport> {
+        self.seals
+            .iter()
+            .filter(|report| !report.status.is_closed())
+    }
+
+    /// Returns iterator over failures together with their labels.
+    pub fn labeled_failures(&self) -> impl Iterator<Item = (Option<&str>, &Failure)> {
+        let labels = &self.labels.failures;
+        self.failures
+            .iter()
+            .enumerate()
+            .map(|(idx, f)| (labels.get(&idx).map(String::as_str), f))
+    }
+
+    /// Returns iterator over warnings together with their labels.
+    pub fn labeled_warnings(&self) -> impl Iterator<Item = (Option<&str>, &Warning)> {
+        let labels = &self.labels.warnings;
+        self.warnings
+            .iter()
+            .enumerate()
+            .map(|(idx, w)| (labels.get(&idx).map(String::as_str), w))
+    }
+
+    /// Returns iterator over information entries together with their labels.
+    pub fn labeled_info(&self) -> impl Iterator<Item = (Option<&str>, &Info)> {
+        let labels = &self.labels.info;
+        self.info
+            .iter()
+            .enumerate()
+            .map(|(idx, i)| (labels.get(&idx).map(String::as_str), i))
+    }
+
+    /// Removes duplicated failures, warnings and information entries,
+    /// preserving the order of their first occurrence. Entries with different
+    /// labels are not considered duplicates.
+    pub fn dedup(&mut self) {
+        fn unique<T: PartialEq>(
+            items: &[T],
+            labels: &BTreeMap<usize, String>,
+            item: &T,
+            label: Option<&String>,
+        ) -> bool {
+            !items
+                .iter()
+                .enumerate()
+                .any(|(idx, i)| i == item && labels.get(&idx) == label)
+        }
+        retain_labeled(&mut self.failures, &mut self.labels.failures, unique);
+        retain_labeled(&mut self.warnings, &mut self.labels.warnings, unique);
+        retain_labeled(&mut self.info, &mut self.labels.info, unique);
+    }
+
+    /// Aggregates failures by their code (see [`Failure::code`]), keeping up to
+    /// `max_examples` first failures in each of the groups. The groups are
+    /// ordered by the first occurrence of a failure of the group.
+    pub fn failure_groups(&self, max_examples: usize) -> Vec<FailureGroup> {
+        let mut groups = Vec::<FailureGroup>::new();
+        for failure in &self.failures {
+            let code = failure.code();
+            let group = match groups.iter_mut().position(|group| group.code == code) {
+                Some(pos) => &mut groups[pos],
+                None => {
+                    groups.push(FailureGroup {
+                        code: code.to_owned(),
+                        count: 0,
+                        examples: vec![],
+                    });
+                    groups.last_mut().expect("just inserted")
+                }
+            };
+            group.count += 1;
+            if group.examples.len() < max_examples {
+                group.examples.push(failure.clone());
+            }
+        }
+        groups
+    }
+
+    /// Caps number of the failures of each kind to `max_per_code`, replacing
+    /// the omitted failures with a summary information entry.
+    ///
+    /// Intended to be applied to the status returned by any of the
+    /// [`super::Validator`] entry points before reporting it to the user.
+    pub fn cap_failures(&mut self, max_per_code: usize) {
+        let mut counts = BTreeMap::<&'static str, usize>::new();
+        retain_labeled(&mut self.failures, &mut self.labels.failures, |_, _, failure, _| {
+            let count = counts.entry(failure.code()).or_default();
+            *count += 1;
+            *count <= max_per_code
+        });
+        for (code, count) in counts {
+            if count > max_per_code {
+                self.info.push(Info::FailuresOmitted {
+                    code: code.to_owned(),
+                    count: count - max_per_code,
+                });
+            }
+        }
+    }
+
     pub fn validity(&self) -> Validity {
         if !self.failures.is_empty() {
             Validity::Invalid
@@ -152,6 +759,50 @@ impl Status {
             Validity::Valid
         }
     }
+
+    /// Returns detailed breakdown of the validation result.
+    pub fn report(&self) -> ValidityReport {
+        ValidityReport {
+            consensus: self.validity(),
+            stats: self.stats,
+        }
+    }
+}
+
+/// Cause of a failed evaluation of an AluVM validation script.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Display)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_LOGIC, tags = order)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(doc_comments)]
+pub enum ScriptError {
+    /// script has failed without providing an error code.
+    #[strict_type(dumb)]
+    Failed,
+
+    /// script has failed with the error code {0}.
+    Code(u8),
+}
+
+impl StrictSerialize for ScriptError {}
+impl StrictDeserialize for ScriptError {}
+
+impl ScriptError {
+    /// Error code set by the script, if any.
+    pub fn code(&self) -> Option<u8> {
+        match self {
+            ScriptError::Failed => None,
+            ScriptError::Code(code) => Some(*code),
+        }
+    }
+}
+
+impl From<Option<u8>> for ScriptError {
+    fn from(code: Option<u8>) -> Self { code.map(ScriptError::Code).unwrap_or(ScriptError::Failed) }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Display, From)]
@@ -177,6 +828,11 @@ pub enum Failure {
     },
     /// schema uses reserved type for the blank state transition.
     SchemaBlankTransitionRedefined,
+    /// schema declares {0} with a type id reserved by the consensus.
+    SchemaStateTypeReserved(schema::StateRef),
+    /// schema declares state transition {0} with a type id reserved by the
+    /// consensus.
+    SchemaTransitionTypeReserved(schema::TransitionType),
 
     /// schema global state #{0} uses semantic data type absent in type library
     /// ({1}).
@@ -188,16 +844,25 @@ pub enum Failure {
     /// ({1}).
     SchemaMetaSemIdUnknown(schema::MetaType, SemId),
 
+    /// AluVM library {0} used by the schema validation scripts is absent from
+    /// the consignment.
+    ScriptLibAbsent(LibId),
+    /// AluVM library {1} is stored in the consignment under a mismatching id
+    /// {0}.
+    ScriptLibIdMismatch(LibId, LibId),
+    /// validation script entry point {1} for {0} lies outside of the library
+    /// code segment.
+    SchemaScriptEntryOutOfRange(OpFullType, LibSite),
+
     /// schema for {0} has zero inputs.
     SchemaOpEmptyInputs(OpFullType),
     /// schema for {0} references undeclared metadata type {1}.
     SchemaOpMetaTypeUnknown(OpFullType, schema::MetaType),
-    /// schema for {0} references undeclared global state type {1}.
-    SchemaOpGlobalTypeUnknown(OpFullType, schema::GlobalStateType),
-    /// schema for {0} references undeclared owned state type {1}.
-    SchemaOpAssignmentTypeUnknown(OpFullType, schema::AssignmentType),
-    /// schema for {0} references undeclared valency type {1}.
-    SchemaOpValencyTypeUnknown(OpFullType, schema::ValencyType),
+    /// schema for {0} references undeclared {1}.
+    SchemaOpStateTypeUnknown(OpFullType, schema::StateRef),
+    /// schema for {0} defines invalid occurrences for {1}: maximum is zero or
+    /// below the minimum.
+    SchemaOpOccurrencesInvalid(OpFullType, schema::StateRef),
 
     /// operation {0} uses invalid state extension type {1}.
     SchemaUnknownExtensionType(OpId, schema::ExtensionType),
@@ -205,12 +870,8 @@ pub enum Failure {
     SchemaUnknownTransitionType(OpId, schema::TransitionType),
     /// operation {0} uses invalid metadata type {1}.
     SchemaUnknownMetaType(OpId, schema::MetaType),
-    /// operation {0} uses invalid global state type {1}.
-    SchemaUnknownGlobalStateType(OpId, schema::GlobalStateType),
-    /// operation {0} uses invalid assignment type {1}.
-    SchemaUnknownAssignmentType(OpId, schema::AssignmentType),
-    /// operation {0} uses invalid valency type {1}.
-    SchemaUnknownValencyType(OpId, schema::ValencyType),
+    /// operation {0} uses invalid {1}.
+    SchemaUnknownStateType(OpId, schema::StateRef),
 
     /// invalid number of global state entries of type {1} in operation {0} -
     /// {2}
@@ -228,11 +889,153 @@ pub enum Failure {
     /// invalid owned state value in operation {0}, state type #{1} which does
     /// not match semantic type id {2}.
     SchemaInvalidOwnedValue(OpId, schema::AssignmentType, SemId),
+    /// global state type #{1} in operation {0} must contain notarized document
+    /// revisions, but contains other data.
+    DocumentRevisionInvalid(OpId, schema::GlobalStateType),
+    /// operation {0} revises document {2} under global state type #{1}, which
+    /// was never notarized in the contract.
+    DocumentRevisionUnknown(OpId, schema::GlobalStateType, DocumentDigest),
+    /// operation {0} revises document {2} under global state type #{1}, which
+    /// was already revised before.
+    DocumentRevisionForked(OpId, schema::GlobalStateType, DocumentDigest),
+    /// global state type #{1} in operation {0} must contain key-value records,
+    /// but contains other data.
+    KeyValueRecordInvalid(OpId, schema::GlobalStateType),
+    /// operation {0} updates key {2:?} under global state type #{1}, which
+    /// doesn't belong to the key domain declared by the schema.
+    KeyValueKeyInvalid(OpId, schema::GlobalStateType, String),
+    /// operation {0} sets value under key {2:?} of global state type #{1}
+    /// exceeding the maximal value length declared by the schema.
+    KeyValueValueOversized(OpId, schema::GlobalStateType, String),
+    /// operation {0} updates key {2:?} under global state type #{1} more than
+    /// once.
+    KeyValueKeyDuplicate(OpId, schema::GlobalStateType, String),
+    /// operation {0} deletes key {2:?} under global state type #{1}, which was
+    /// never set in the contract.
+    KeyValueKeyUnknown(OpId, schema::GlobalStateType, String),
     /// invalid number of input entries of type {1} in operation {0} - {2}  
     SchemaInputOccurrences(OpId, schema::AssignmentType, OccurrencesMismatch),
     /// invalid number of assignment entries of type {1} in operation {0} - {2}
     SchemaAssignmentOccurrences(OpId, schema::AssignmentType, OccurrencesMismatch),
 
+    /// schema doesn't declare key domain for key-value global state type {0}.
+    SchemaKeyDomainAbsent(schema::GlobalStateType),
+    /// schema declares key domain for global state type {0}, which is not a
+    /// key-value global state.
+    SchemaKeyDomainUnused(schema::GlobalStateType),
+
+    /// schema requires endorsements by the keys from undeclared global state
+    /// type {0}.
+    SchemaEndorsersUnknown(schema::GlobalStateType),
+    /// schema requires endorsement of undeclared state transition type {0}.
+    SchemaEndorsedTransitionUnknown(schema::TransitionType),
+    /// genesis global state of type {0} contains invalid endorser key.
+    EndorserKeyInvalid(schema::GlobalStateType),
+    /// operation {0} must be endorsed, but has no endorsements.
+    EndorsementMissing(OpId),
+    /// operation {0} has invalid endorsement: {1}
+    EndorsementInvalid(OpId, EndorsementError),
+
+    /// schema requires fees to be paid in state of type {0}, which is not a
+    /// declared fungible owned state.
+    SchemaFeeStateInvalid(schema::AssignmentType),
+    /// schema requires fee collector to be defined in undeclared global state
+    /// type {0}.
+    SchemaFeeCollectorUnknown(schema::GlobalStateType),
+    /// schema requires fees to be paid by undeclared state transition type {0}.
+    SchemaFeeTransitionUnknown(schema::TransitionType),
+    /// genesis global state of type {0} contains invalid fee collector seal
+    /// template.
+    FeeCollectorInvalid(schema::GlobalStateType),
+    /// operation {0} must pay fee of at least {1}, but assigns only {2} to the
+    /// fee collector.
+    FeeUnpaid(OpId, u64, u64),
+
+    /// schema tracks supply of state type {0} per layer 1, but it is not a
+    /// declared fungible owned state.
+    SchemaChainSupplyStateInvalid(schema::AssignmentType),
+    /// schema requires layer 1 supply caps to be defined in undeclared global
+    /// state type {0}.
+    SchemaChainSupplyCapsUnknown(schema::GlobalStateType),
+    /// schema uses undeclared state transition type {0} as a bridge between
+    /// layers 1.
+    SchemaBridgeTransitionUnknown(schema::TransitionType),
+    /// genesis global state of type {0} contains invalid layer 1 supply cap.
+    ChainCapInvalid(schema::GlobalStateType),
+    /// operation {0} issues or moves between layers 1 state of type {1} with
+    /// concealed amount, which can't be accounted in the layer 1 supply.
+    ChainSupplyConcealed(OpId, schema::AssignmentType),
+    /// operation {0} raises supply on {1} to {2}, exceeding the cap of {3}
+    /// defined by the contract genesis.
+    ChainSupplyExceeded(OpId, Layer1, u64, u64),
+    /// operation {0} burns {2} of state on {1}, exceeding its supply of {3}
+    /// on that layer 1.
+    ChainSupplyUnderflow(OpId, Layer1, u64, u64),
+    /// operation {0} makes the supply on {1} overflow the range of the state
+    /// values.
+    ChainSupplyOverflow(OpId, Layer1),
+    /// transition {0} spends state burned on another layer 1 by {1}, which
+    /// may be spent only by a release transition of type {2}.
+    ReleaseTypeMismatch(OpId, OpId, schema::TransitionType),
+    /// release transition {0} doesn't spend any state burned on another layer
+    /// 1.
+    ReleaseWithoutBurn(OpId),
+    /// release transition {0} spends state burned by {1}, which witness is not
+    /// mined.
+    BurnUnconfirmed(OpId, OpId),
+
+    /// operation {0} makes the total amount of state type {1} it assigns, or
+    /// the contract has issued, overflow the range of the state values.
+    AmountOverflow(OpId, schema::AssignmentType),
+
+    /// schema declares contract features for state type {0}, which is not a
+    /// declared fungible owned state.
+    SchemaFeatureStateInvalid(schema::AssignmentType),
+    /// schema requires contract features to be defined in undeclared global
+    /// state type {0}.
+    SchemaFeatureFlagsUnknown(schema::GlobalStateType),
+    /// schema declares contract features for undeclared state transition type
+    /// {0}.
+    SchemaFeatureTransitionUnknown(schema::TransitionType),
+    /// genesis global state of type {0} contains invalid or unknown contract
+    /// features.
+    FeatureFlagsInvalid(schema::GlobalStateType),
+    /// operation {0} uses contract features ({1}) not enabled by the contract
+    /// genesis.
+    FeatureDisabled(OpId, schema::ContractFeatures),
+
+    /// schema auxiliary proof #{0} uses semantic data type absent in type
+    /// library ({1}).
+    SchemaAuxProofSemIdUnknown(schema::AuxProofType, SemId),
+    /// schema allows operations to carry undeclared auxiliary proof type {0}.
+    SchemaAuxProofTypeUnknown(schema::AuxProofType),
+    /// schema defines auxiliary proofs for undeclared state transition type
+    /// {0}.
+    SchemaAuxProofTransitionUnknown(schema::TransitionType),
+    /// schema defines auxiliary proofs for undeclared state extension type
+    /// {0}.
+    SchemaAuxProofExtensionUnknown(schema::ExtensionType),
+    /// operation {0} carries auxiliary proof of type {1} which is not allowed
+    /// by the schema for this operation type.
+    SchemaUnknownAuxProofType(OpId, schema::AuxProofType),
+    /// operation {0} violates occurrence bounds of auxiliary proofs of type
+    /// {1}: {2}
+    SchemaAuxProofOccurrences(OpId, schema::AuxProofType, OccurrencesMismatch),
+    /// operation {0} carries auxiliary proof of type {1} with size {2} bytes
+    /// exceeding the schema limit of {3} bytes.
+    AuxProofOversized(OpId, schema::AuxProofType, usize, u16),
+    /// operation {0} carries auxiliary proof of type {1} which doesn't match
+    /// the schema type {2}.
+    SchemaInvalidAuxProof(OpId, schema::AuxProofType, SemId),
+
+    /// schema allows pruning of state type {0}, which is not a declared
+    /// structured or attachment owned state.
+    SchemaRetentionStateInvalid(schema::AssignmentType),
+
+    /// schema expects issuer attestation in auxiliary proof type {0}, which is
+    /// not allowed in genesis.
+    SchemaIssuerProofUnknown(schema::AuxProofType),
+
     // Consignment consistency errors
     /// operation {0} is referenced within the history multiple times. RGB
     /// contracts allow only direct acyclic graphs.
@@ -249,8 +1052,25 @@ pub enum Failure {
     /// stopped since operations can't be consensus-ordered. The resolver
     /// responded with error {2}
     WitnessUnresolved(BundleId, XWitnessId, WitnessResolverError),
+    /// bundle {0} is anchored to witness {1} which has unconfirmed ancestry of
+    /// depth {2}, exceeding the depth allowed by the validation policy.
+    WitnessAncestryUnconfirmed(BundleId, XWitnessId, u8),
+    /// both legs of the swap belong to the same contract {0}.
+    SwapSameContract(ContractId),
+    /// swap leg of contract {0} doesn't contain bundles anchored to the swap
+    /// witness {1}.
+    SwapLegUnanchored(ContractId, XWitnessId),
+    /// swap witness {0} can't be resolved: {1}
+    SwapWitnessUnresolved(XWitnessId, WitnessResolverError),
     /// operation {0} is under a different contract {1}.
     ContractMismatch(OpId, ContractId),
+    /// operation {opid} is used as {expected} while its id commits to
+    /// {actual}, which indicates misuse of the operation type tags.
+    OpKindMismatch {
+        opid: OpId,
+        expected: OpType,
+        actual: OpType,
+    },
 
     // Errors checking bundle commitments
     /// transition bundle {0} references state transition {1} which is not
@@ -280,6 +1100,9 @@ pub enum Failure {
     },
     /// transition {0} references non-existing previous output {1}.
     NoPrevOut(OpId, Opout),
+    /// output {opout} is spent by two different state transitions {op1} and
+    /// {op2} known to the consignment.
+    DoubleSpend { opout: Opout, op1: OpId, op2: OpId },
     /// seal defined in the history as a part of operation output {0} is
     /// confidential and can't be validated.
     ConfidentialSeal(Opout),
@@ -288,6 +1111,18 @@ pub enum Failure {
     SealNoPubWitness(BundleId, XWitnessId, WitnessResolverError),
     /// witness layer 1 {anchor} doesn't match seal definition {seal}.
     SealWitnessLayer1Mismatch { seal: Layer1, anchor: Layer1 },
+    /// bundle {0} is anchored to witness {1} on a layer 1 which is not in the
+    /// set of layers allowed by the contract genesis.
+    AnchorLayer1Unsupported(BundleId, XWitnessId),
+    /// transition {opid} anchored on {layer1} spends output of transition
+    /// {prev_id} anchored on {prev_layer1}; contract history can't mix
+    /// different layers 1.
+    HistoryLayer1Mixed {
+        opid: OpId,
+        prev_id: OpId,
+        layer1: Layer1,
+        prev_layer1: Layer1,
+    },
     /// seal {1} is defined on {0} which is not in the set of layers allowed
     /// by the contract genesis.
     SealLayerMismatch(Layer1, XGraphSeal),
@@ -305,6 +1140,9 @@ pub enum Failure {
     /// transition bundle {0} is not properly anchored to the witness {1}.
     /// Details: {2}
     MpcInvalid(BundleId, XWitnessId, InvalidProof),
+    /// operation {0} assigns state to output {1} holding {2} sats, which is
+    /// below the schema dust limit of {3} sats.
+    OutputBelowDust(OpId, XOutputSeal, u64, u64),
 
     // State extensions errors
     /// valency {valency} redeemed by state extension {opid} references
@@ -348,10 +1186,9 @@ pub enum Failure {
         found: schema::FungibleType,
     },
     /// invalid bulletproofs in {0}:{1}: {2}
-    BulletproofsInvalid(OpId, schema::AssignmentType, String),
-    /// evaluation of AluVM script for operation {0} has failed with the code
-    /// {1:?} and message {2:?}.
-    ScriptFailure(OpId, Option<u8>, Option<String>),
+    BulletproofsInvalid(OpId, schema::AssignmentType, RangeProofError),
+    /// evaluation of AluVM script for operation {0} has failed: {1}
+    ScriptFailure(OpId, ScriptError),
     /// contract state can't fit more data (at operation id {0}).
     ContractStateFilled(OpId),
 
@@ -360,6 +1197,586 @@ pub enum Failure {
     Custom(String),
 }
 
+impl Failure {
+    /// Returns stable code of the failure, which is the same for all failures
+    /// of the same kind regardless of their details.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Failure::NetworkMismatch(..) => "NetworkMismatch",
+            Failure::SchemaMismatch { .. } => "SchemaMismatch",
+            Failure::SchemaBlankTransitionRedefined => "SchemaBlankTransitionRedefined",
+            Failure::SchemaStateTypeReserved(..) => "SchemaStateTypeReserved",
+            Failure::SchemaTransitionTypeReserved(..) => "SchemaTransitionTypeReserved",
+            Failure::SchemaGlobalSemIdUnknown(..) => "SchemaGlobalSemIdUnknown",
+            Failure::SchemaOwnedSemIdUnknown(..) => "SchemaOwnedSemIdUnknown",
+            Failure::SchemaMetaSemIdUnknown(..) => "SchemaMetaSemIdUnknown",
+            Failure::ScriptLibAbsent(..) => "ScriptLibAbsent",
+            Failure::ScriptLibIdMismatch(..) => "ScriptLibIdMismatch",
+            Failure::SchemaScriptEntryOutOfRange(..) => "SchemaScriptEntryOutOfRange",
+            Failure::SchemaOpEmptyInputs(..) => "SchemaOpEmptyInputs",
+            Failure::SchemaOpMetaTypeUnknown(..) => "SchemaOpMetaTypeUnknown",
+            Failure::SchemaOpStateTypeUnknown(..) => "SchemaOpStateTypeUnknown",
+            Failure::SchemaOpOccurrencesInvalid(..) => "SchemaOpOccurrencesInvalid",
+            Failure::SchemaUnknownExtensionType(..) => "SchemaUnknownExtensionType",
+            Failure::SchemaUnknownTransitionType(..) => "SchemaUnknownTransitionType",
+            Failure::SchemaUnknownMetaType(..) => "SchemaUnknownMetaType",
+            Failure::SchemaUnknownStateType(..) => "SchemaUnknownStateType",
+            Failure::SchemaGlobalStateOccurrences(..) => "SchemaGlobalStateOccurrences",
+            Failure::SchemaGlobalStateLimit(..) => "SchemaGlobalStateLimit",
+            Failure::SchemaNoMetadata(..) => "SchemaNoMetadata",
+            Failure::SchemaInvalidMetadata(..) => "SchemaInvalidMetadata",
+            Failure::SchemaInvalidGlobalValue(..) => "SchemaInvalidGlobalValue",
+            Failure::SchemaInvalidOwnedValue(..) => "SchemaInvalidOwnedValue",
+            Failure::DocumentRevisionInvalid(..) => "DocumentRevisionInvalid",
+            Failure::DocumentRevisionUnknown(..) => "DocumentRevisionUnknown",
+            Failure::DocumentRevisionForked(..) => "DocumentRevisionForked",
+            Failure::KeyValueRecordInvalid(..) => "KeyValueRecordInvalid",
+            Failure::KeyValueKeyInvalid(..) => "KeyValueKeyInvalid",
+            Failure::KeyValueValueOversized(..) => "KeyValueValueOversized",
+            Failure::KeyValueKeyDuplicate(..) => "KeyValueKeyDuplicate",
+            Failure::KeyValueKeyUnknown(..) => "KeyValueKeyUnknown",
+            Failure::SchemaKeyDomainAbsent(..) => "SchemaKeyDomainAbsent",
+            Failure::SchemaKeyDomainUnused(..) => "SchemaKeyDomainUnused",
+            Failure::SchemaInputOccurrences(..) => "SchemaInputOccurrences",
+            Failure::SchemaAssignmentOccurrences(..) => "SchemaAssignmentOccurrences",
+            Failure::SchemaEndorsersUnknown(..) => "SchemaEndorsersUnknown",
+            Failure::SchemaEndorsedTransitionUnknown(..) => "SchemaEndorsedTransitionUnknown",
+            Failure::EndorserKeyInvalid(..) => "EndorserKeyInvalid",
+            Failure::EndorsementMissing(..) => "EndorsementMissing",
+            Failure::EndorsementInvalid(..) => "EndorsementInvalid",
+            Failure::SchemaFeeStateInvalid(..) => "SchemaFeeStateInvalid",
+            Failure::SchemaFeeCollectorUnknown(..) => "SchemaFeeCollectorUnknown",
+            Failure::SchemaFeeTransitionUnknown(..) => "SchemaFeeTransitionUnknown",
+            Failure::FeeCollectorInvalid(..) => "FeeCollectorInvalid",
+            Failure::FeeUnpaid(..) => "FeeUnpaid",
+            Failure::SchemaChainSupplyStateInvalid(..) => "SchemaChainSupplyStateInvalid",
+            Failure::SchemaChainSupplyCapsUnknown(..) => "SchemaChainSupplyCapsUnknown",
+            Failure::SchemaBridgeTransitionUnknown(..) => "SchemaBridgeTransitionUnknown",
+            Failure::ChainCapInvalid(..) => "ChainCapInvalid",
+            Failure::ChainSupplyConcealed(..) => "ChainSupplyConcealed",
+            Failure::ChainSupplyExceeded(..) => "ChainSupplyExceeded",
+            Failure::ChainSupplyUnderflow(..) => "ChainSupplyUnderflow",
+            Failure::ChainSupplyOverflow(..) => "ChainSupplyOverflow",
+            Failure::ReleaseTypeMismatch(..) => "ReleaseTypeMismatch",
+            Failure::ReleaseWithoutBurn(..) => "ReleaseWithoutBurn",
+            Failure::BurnUnconfirmed(..) => "BurnUnconfirmed",
+            Failure::AmountOverflow(..) => "AmountOverflow",
+            Failure::SchemaFeatureStateInvalid(..) => "SchemaFeatureStateInvalid",
+            Failure::SchemaFeatureFlagsUnknown(..) => "SchemaFeatureFlagsUnknown",
+            Failure::SchemaFeatureTransitionUnknown(..) => "SchemaFeatureTransitionUnknown",
+            Failure::FeatureFlagsInvalid(..) => "FeatureFlagsInvalid",
+            Failure::FeatureDisabled(..) => "FeatureDisabled",
+            Failure::SchemaAuxProofSemIdUnknown(..) => "SchemaAuxProofSemIdUnknown",
+            Failure::SchemaAuxProofTypeUnknown(..) => "SchemaAuxProofTypeUnknown",
+            Failure::SchemaAuxProofTransitionUnknown(..) => "SchemaAuxProofTransitionUnknown",
+            Failure::SchemaAuxProofExtensionUnknown(..) => "SchemaAuxProofExtensionUnknown",
+            Failure::SchemaUnknownAuxProofType(..) => "SchemaUnknownAuxProofType",
+            Failure::SchemaAuxProofOccurrences(..) => "SchemaAuxProofOccurrences",
+            Failure::AuxProofOversized(..) => "AuxProofOversized",
+            Failure::SchemaInvalidAuxProof(..) => "SchemaInvalidAuxProof",
+            Failure::SchemaRetentionStateInvalid(..) => "SchemaRetentionStateInvalid",
+            Failure::SchemaIssuerProofUnknown(..) => "SchemaIssuerProofUnknown",
+            Failure::CyclicGraph(..) => "CyclicGraph",
+            Failure::OperationAbsent(..) => "OperationAbsent",
+            Failure::BundleAbsent(..) => "BundleAbsent",
+            Failure::AnchorAbsent(..) => "AnchorAbsent",
+            Failure::WitnessIdAbsent(..) => "WitnessIdAbsent",
+            Failure::WitnessUnresolved(..) => "WitnessUnresolved",
+            Failure::WitnessAncestryUnconfirmed(..) => "WitnessAncestryUnconfirmed",
+            Failure::SwapSameContract(..) => "SwapSameContract",
+            Failure::SwapLegUnanchored(..) => "SwapLegUnanchored",
+            Failure::SwapWitnessUnresolved(..) => "SwapWitnessUnresolved",
+            Failure::ContractMismatch(..) => "ContractMismatch",
+            Failure::OpKindMismatch { .. } => "OpKindMismatch",
+            Failure::BundleExtraTransition(..) => "BundleExtraTransition",
+            Failure::BundleInvalidInput(..) => "BundleInvalidInput",
+            Failure::BundleInvalidCommitment(..) => "BundleInvalidCommitment",
+            Failure::AssetTagNoState(..) => "AssetTagNoState",
+            Failure::FungibleStateNoTag(..) => "FungibleStateNoTag",
+            Failure::NoPrevState { .. } => "NoPrevState",
+            Failure::NoPrevOut(..) => "NoPrevOut",
+            Failure::DoubleSpend { .. } => "DoubleSpend",
+            Failure::ConfidentialSeal(..) => "ConfidentialSeal",
+            Failure::SealNoPubWitness(..) => "SealNoPubWitness",
+            Failure::SealWitnessLayer1Mismatch { .. } => "SealWitnessLayer1Mismatch",
+            Failure::AnchorLayer1Unsupported(..) => "AnchorLayer1Unsupported",
+            Failure::HistoryLayer1Mixed { .. } => "HistoryLayer1Mixed",
+            Failure::SealLayerMismatch(..) => "SealLayerMismatch",
+            Failure::SealInvalidMethod(..) => "SealInvalidMethod",
+            Failure::SealsInvalid(..) => "SealsInvalid",
+            Failure::SealsUnvalidated(..) => "SealsUnvalidated",
+            Failure::AnchorMethodMismatch(..) => "AnchorMethodMismatch",
+            Failure::MpcInvalid(..) => "MpcInvalid",
+            Failure::OutputBelowDust(..) => "OutputBelowDust",
+            Failure::ValencyNoParent { .. } => "ValencyNoParent",
+            Failure::NoPrevValency { .. } => "NoPrevValency",
+            Failure::StateTypeMismatch { .. } => "StateTypeMismatch",
+            Failure::MediaTypeMismatch { .. } => "MediaTypeMismatch",
+            Failure::FungibleTypeMismatch { .. } => "FungibleTypeMismatch",
+            Failure::BulletproofsInvalid(..) => "BulletproofsInvalid",
+            Failure::ScriptFailure(..) => "ScriptFailure",
+            Failure::ContractStateFilled(..) => "ContractStateFilled",
+            Failure::Custom(..) => "Custom",
+        }
+    }
+
+    /// Returns stable message template of the failure, allowing to localize
+    /// the failure without matching on its variants.
+    ///
+    /// The template key is the failure [`Failure::code`]; the arguments follow
+    /// the order of the failure fields. Absent optional values are represented
+    /// by empty strings.
+    pub fn template(&self) -> FailureTemplate {
+        fn opt<T: ToString>(val: &Option<T>) -> String {
+            val.as_ref().map(T::to_string).unwrap_or_default()
+        }
+        let args = match self {
+            Failure::NetworkMismatch(a) => vec![a.to_string()],
+            Failure::SchemaMismatch { expected, actual } => {
+                vec![expected.to_string(), actual.to_string()]
+            }
+            Failure::SchemaBlankTransitionRedefined => vec![],
+            Failure::SchemaStateTypeReserved(a) => vec![a.to_string()],
+            Failure::SchemaTransitionTypeReserved(a) => vec![a.to_string()],
+            Failure::SchemaGlobalSemIdUnknown(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaOwnedSemIdUnknown(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaMetaSemIdUnknown(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::ScriptLibAbsent(a) => vec![a.to_string()],
+            Failure::ScriptLibIdMismatch(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaScriptEntryOutOfRange(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaOpEmptyInputs(a) => vec![a.to_string()],
+            Failure::SchemaOpMetaTypeUnknown(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaOpStateTypeUnknown(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaOpOccurrencesInvalid(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaUnknownExtensionType(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaUnknownTransitionType(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaUnknownMetaType(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaUnknownStateType(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaGlobalStateOccurrences(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::SchemaGlobalStateLimit(a, b, c, d) => {
+                vec![a.to_string(), b.to_string(), c.to_string(), d.to_string()]
+            }
+            Failure::SchemaNoMetadata(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaInvalidMetadata(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaInvalidGlobalValue(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::SchemaInvalidOwnedValue(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::DocumentRevisionInvalid(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::DocumentRevisionUnknown(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::DocumentRevisionForked(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::KeyValueRecordInvalid(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::KeyValueKeyInvalid(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::KeyValueValueOversized(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::KeyValueKeyDuplicate(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::KeyValueKeyUnknown(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::SchemaInputOccurrences(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::SchemaAssignmentOccurrences(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::SchemaKeyDomainAbsent(a) => vec![a.to_string()],
+            Failure::SchemaKeyDomainUnused(a) => vec![a.to_string()],
+            Failure::SchemaEndorsersUnknown(a) => vec![a.to_string()],
+            Failure::SchemaEndorsedTransitionUnknown(a) => vec![a.to_string()],
+            Failure::EndorserKeyInvalid(a) => vec![a.to_string()],
+            Failure::EndorsementMissing(a) => vec![a.to_string()],
+            Failure::EndorsementInvalid(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaFeeStateInvalid(a) => vec![a.to_string()],
+            Failure::SchemaFeeCollectorUnknown(a) => vec![a.to_string()],
+            Failure::SchemaFeeTransitionUnknown(a) => vec![a.to_string()],
+            Failure::FeeCollectorInvalid(a) => vec![a.to_string()],
+            Failure::FeeUnpaid(a, b, c) => vec![a.to_string(), b.to_string(), c.to_string()],
+            Failure::SchemaChainSupplyStateInvalid(a) => vec![a.to_string()],
+            Failure::SchemaChainSupplyCapsUnknown(a) => vec![a.to_string()],
+            Failure::SchemaBridgeTransitionUnknown(a) => vec![a.to_string()],
+            Failure::ChainCapInvalid(a) => vec![a.to_string()],
+            Failure::ChainSupplyConcealed(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::ChainSupplyExceeded(a, b, c, d)
+            | Failure::ChainSupplyUnderflow(a, b, c, d) => {
+                vec![a.to_string(), b.to_string(), c.to_string(), d.to_string()]
+            }
+            Failure::ChainSupplyOverflow(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::ReleaseTypeMismatch(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::ReleaseWithoutBurn(a) => vec![a.to_string()],
+            Failure::BurnUnconfirmed(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::AmountOverflow(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaFeatureStateInvalid(a) => vec![a.to_string()],
+            Failure::SchemaFeatureFlagsUnknown(a) => vec![a.to_string()],
+            Failure::SchemaFeatureTransitionUnknown(a) => vec![a.to_string()],
+            Failure::FeatureFlagsInvalid(a) => vec![a.to_string()],
+            Failure::FeatureDisabled(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaAuxProofSemIdUnknown(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaAuxProofTypeUnknown(a) => vec![a.to_string()],
+            Failure::SchemaAuxProofTransitionUnknown(a) => vec![a.to_string()],
+            Failure::SchemaAuxProofExtensionUnknown(a) => vec![a.to_string()],
+            Failure::SchemaUnknownAuxProofType(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SchemaAuxProofOccurrences(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::AuxProofOversized(a, b, c, d) => {
+                vec![a.to_string(), b.to_string(), c.to_string(), d.to_string()]
+            }
+            Failure::SchemaInvalidAuxProof(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::SchemaRetentionStateInvalid(a) => vec![a.to_string()],
+            Failure::SchemaIssuerProofUnknown(a) => vec![a.to_string()],
+            Failure::CyclicGraph(a) => vec![a.to_string()],
+            Failure::OperationAbsent(a) => vec![a.to_string()],
+            Failure::BundleAbsent(a) => vec![a.to_string()],
+            Failure::AnchorAbsent(a) => vec![a.to_string()],
+            Failure::WitnessIdAbsent(a) => vec![a.to_string()],
+            Failure::WitnessUnresolved(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::WitnessAncestryUnconfirmed(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::SwapSameContract(a) => vec![a.to_string()],
+            Failure::SwapLegUnanchored(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SwapWitnessUnresolved(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::ContractMismatch(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::OpKindMismatch {
+                opid,
+                expected,
+                actual,
+            } => {
+                vec![opid.to_string(), expected.to_string(), actual.to_string()]
+            }
+            Failure::BundleExtraTransition(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::BundleInvalidInput(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::BundleInvalidCommitment(a, b, c, d) => {
+                vec![a.to_string(), b.to_string(), c.to_string(), d.to_string()]
+            }
+            Failure::AssetTagNoState(a) => vec![a.to_string()],
+            Failure::FungibleStateNoTag(a) => vec![a.to_string()],
+            Failure::NoPrevState {
+                opid,
+                prev_id,
+                state_type,
+            } => {
+                vec![opid.to_string(), prev_id.to_string(), state_type.to_string()]
+            }
+            Failure::NoPrevOut(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::DoubleSpend { opout, op1, op2 } => {
+                vec![opout.to_string(), op1.to_string(), op2.to_string()]
+            }
+            Failure::ConfidentialSeal(a) => vec![a.to_string()],
+            Failure::SealNoPubWitness(a, b, c) => vec![a.to_string(), b.to_string(), c.to_string()],
+            Failure::SealWitnessLayer1Mismatch { seal, anchor } => {
+                vec![seal.to_string(), anchor.to_string()]
+            }
+            Failure::AnchorLayer1Unsupported(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::HistoryLayer1Mixed {
+                opid,
+                prev_id,
+                layer1,
+                prev_layer1,
+            } => vec![
+                opid.to_string(),
+                prev_id.to_string(),
+                layer1.to_string(),
+                prev_layer1.to_string(),
+            ],
+            Failure::SealLayerMismatch(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SealInvalidMethod(a, b) => vec![a.to_string(), b.to_string()],
+            Failure::SealsInvalid(a, b, c) => vec![a.to_string(), b.to_string(), c.to_string()],
+            Failure::SealsUnvalidated(a) => vec![a.to_string()],
+            Failure::AnchorMethodMismatch(a) => vec![a.to_string()],
+            Failure::MpcInvalid(a, b, c) => vec![a.to_string(), b.to_string(), c.to_string()],
+            Failure::OutputBelowDust(a, b, c, d) => {
+                vec![a.to_string(), b.to_string(), c.to_string(), d.to_string()]
+            }
+            Failure::ValencyNoParent {
+                opid,
+                prev_id,
+                valency,
+            } => {
+                vec![opid.to_string(), prev_id.to_string(), valency.to_string()]
+            }
+            Failure::NoPrevValency {
+                opid,
+                prev_id,
+                valency,
+            } => {
+                vec![opid.to_string(), prev_id.to_string(), valency.to_string()]
+            }
+            Failure::StateTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => vec![
+                opid.to_string(),
+                state_type.to_string(),
+                expected.to_string(),
+                found.to_string(),
+            ],
+            Failure::MediaTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => vec![
+                opid.to_string(),
+                state_type.to_string(),
+                expected.to_string(),
+                found.to_string(),
+            ],
+            Failure::FungibleTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => vec![
+                opid.to_string(),
+                state_type.to_string(),
+                expected.to_string(),
+                found.to_string(),
+            ],
+            Failure::BulletproofsInvalid(a, b, c) => {
+                vec![a.to_string(), b.to_string(), c.to_string()]
+            }
+            Failure::ScriptFailure(a, b) => vec![a.to_string(), opt(&b.code())],
+            Failure::ContractStateFilled(a) => vec![a.to_string()],
+            Failure::Custom(a) => vec![a.to_string()],
+        };
+        FailureTemplate {
+            key: self.code(),
+            args,
+        }
+    }
+
+    /// Returns schema state types referenced by the failure.
+    pub fn state_refs(&self) -> Vec<schema::StateRef> {
+        use schema::StateRef;
+        match self {
+            Failure::SchemaGlobalSemIdUnknown(ty, _)
+            | Failure::SchemaGlobalStateOccurrences(_, ty, _)
+            | Failure::SchemaGlobalStateLimit(_, ty, _, _)
+            | Failure::SchemaInvalidGlobalValue(_, ty, _)
+            | Failure::DocumentRevisionInvalid(_, ty)
+            | Failure::DocumentRevisionUnknown(_, ty, _)
+            | Failure::DocumentRevisionForked(_, ty, _)
+            | Failure::KeyValueRecordInvalid(_, ty)
+            | Failure::KeyValueKeyInvalid(_, ty, _)
+            | Failure::KeyValueValueOversized(_, ty, _)
+            | Failure::KeyValueKeyDuplicate(_, ty, _)
+            | Failure::KeyValueKeyUnknown(_, ty, _)
+            | Failure::SchemaKeyDomainAbsent(ty)
+            | Failure::SchemaKeyDomainUnused(ty)
+            | Failure::SchemaEndorsersUnknown(ty)
+            | Failure::EndorserKeyInvalid(ty)
+            | Failure::SchemaFeeCollectorUnknown(ty)
+            | Failure::FeeCollectorInvalid(ty)
+            | Failure::SchemaChainSupplyCapsUnknown(ty)
+            | Failure::ChainCapInvalid(ty)
+            | Failure::SchemaFeatureFlagsUnknown(ty)
+            | Failure::FeatureFlagsInvalid(ty) => vec![StateRef::Global(*ty)],
+            Failure::SchemaOwnedSemIdUnknown(ty, _)
+            | Failure::SchemaInvalidOwnedValue(_, ty, _)
+            | Failure::SchemaInputOccurrences(_, ty, _)
+            | Failure::SchemaAssignmentOccurrences(_, ty, _)
+            | Failure::AssetTagNoState(ty)
+            | Failure::FungibleStateNoTag(ty)
+            | Failure::SchemaFeeStateInvalid(ty)
+            | Failure::SchemaChainSupplyStateInvalid(ty)
+            | Failure::SchemaRetentionStateInvalid(ty)
+            | Failure::ChainSupplyConcealed(_, ty)
+            | Failure::AmountOverflow(_, ty)
+            | Failure::SchemaFeatureStateInvalid(ty)
+            | Failure::BulletproofsInvalid(_, ty, _)
+            | Failure::NoPrevState { state_type: ty, .. }
+            | Failure::StateTypeMismatch { state_type: ty, .. }
+            | Failure::MediaTypeMismatch { state_type: ty, .. }
+            | Failure::FungibleTypeMismatch { state_type: ty, .. } => vec![StateRef::Owned(*ty)],
+            Failure::ValencyNoParent { valency, .. } | Failure::NoPrevValency { valency, .. } => {
+                vec![StateRef::Valency(*valency)]
+            }
+            Failure::SchemaOpStateTypeUnknown(_, state)
+            | Failure::SchemaOpOccurrencesInvalid(_, state)
+            | Failure::SchemaUnknownStateType(_, state)
+            | Failure::SchemaStateTypeReserved(state) => vec![*state],
+            _ => vec![],
+        }
+    }
+
+    /// Returns id of the operation which has caused the failure, if the
+    /// failure is specific to a single operation.
+    pub fn opid(&self) -> Option<OpId> {
+        match self {
+            Failure::SchemaUnknownExtensionType(opid, _)
+            | Failure::SchemaUnknownTransitionType(opid, _)
+            | Failure::SchemaUnknownMetaType(opid, _)
+            | Failure::SchemaUnknownStateType(opid, _)
+            | Failure::SchemaGlobalStateOccurrences(opid, _, _)
+            | Failure::SchemaGlobalStateLimit(opid, _, _, _)
+            | Failure::SchemaNoMetadata(opid, _)
+            | Failure::SchemaInvalidMetadata(opid, _)
+            | Failure::SchemaInvalidGlobalValue(opid, _, _)
+            | Failure::SchemaInvalidOwnedValue(opid, _, _)
+            | Failure::DocumentRevisionInvalid(opid, _)
+            | Failure::DocumentRevisionUnknown(opid, _, _)
+            | Failure::DocumentRevisionForked(opid, _, _)
+            | Failure::KeyValueRecordInvalid(opid, _)
+            | Failure::KeyValueKeyInvalid(opid, _, _)
+            | Failure::KeyValueValueOversized(opid, _, _)
+            | Failure::KeyValueKeyDuplicate(opid, _, _)
+            | Failure::KeyValueKeyUnknown(opid, _, _)
+            | Failure::SchemaInputOccurrences(opid, _, _)
+            | Failure::SchemaAssignmentOccurrences(opid, _, _)
+            | Failure::EndorsementMissing(opid)
+            | Failure::EndorsementInvalid(opid, _)
+            | Failure::FeeUnpaid(opid, _, _)
+            | Failure::ChainSupplyConcealed(opid, _)
+            | Failure::ChainSupplyExceeded(opid, _, _, _)
+            | Failure::ChainSupplyUnderflow(opid, _, _, _)
+            | Failure::ChainSupplyOverflow(opid, _)
+            | Failure::ReleaseTypeMismatch(opid, _, _)
+            | Failure::ReleaseWithoutBurn(opid)
+            | Failure::BurnUnconfirmed(opid, _)
+            | Failure::AmountOverflow(opid, _)
+            | Failure::FeatureDisabled(opid, _)
+            | Failure::SchemaUnknownAuxProofType(opid, _)
+            | Failure::SchemaAuxProofOccurrences(opid, _, _)
+            | Failure::AuxProofOversized(opid, _, _, _)
+            | Failure::SchemaInvalidAuxProof(opid, _, _)
+            | Failure::CyclicGraph(opid)
+            | Failure::OperationAbsent(opid)
+            | Failure::ContractMismatch(opid, _)
+            | Failure::OpKindMismatch { opid, .. }
+            | Failure::BundleExtraTransition(_, opid)
+            | Failure::BundleInvalidInput(_, opid, _)
+            | Failure::BundleInvalidCommitment(_, _, _, opid)
+            | Failure::NoPrevState { opid, .. }
+            | Failure::NoPrevOut(opid, _)
+            | Failure::HistoryLayer1Mixed { opid, .. }
+            | Failure::SealsUnvalidated(opid)
+            | Failure::OutputBelowDust(opid, _, _, _)
+            | Failure::ValencyNoParent { opid, .. }
+            | Failure::NoPrevValency { opid, .. }
+            | Failure::StateTypeMismatch { opid, .. }
+            | Failure::MediaTypeMismatch { opid, .. }
+            | Failure::FungibleTypeMismatch { opid, .. }
+            | Failure::BulletproofsInvalid(opid, _, _)
+            | Failure::ScriptFailure(opid, _)
+            | Failure::ContractStateFilled(opid) => Some(*opid),
+            Failure::ConfidentialSeal(opout) => Some(opout.op),
+            _ => None,
+        }
+    }
+
+    /// Returns id of the transition bundle which has caused the failure, if
+    /// the failure is specific to a bundle.
+    pub fn bundle_id(&self) -> Option<BundleId> {
+        match self {
+            Failure::BundleAbsent(bundle_id)
+            | Failure::AnchorAbsent(bundle_id)
+            | Failure::WitnessIdAbsent(bundle_id)
+            | Failure::WitnessUnresolved(bundle_id, _, _)
+            | Failure::WitnessAncestryUnconfirmed(bundle_id, _, _)
+            | Failure::BundleExtraTransition(bundle_id, _)
+            | Failure::BundleInvalidInput(bundle_id, _, _)
+            | Failure::BundleInvalidCommitment(bundle_id, _, _, _)
+            | Failure::SealNoPubWitness(bundle_id, _, _)
+            | Failure::AnchorLayer1Unsupported(bundle_id, _)
+            | Failure::SealInvalidMethod(bundle_id, _)
+            | Failure::SealsInvalid(bundle_id, _, _)
+            | Failure::AnchorMethodMismatch(bundle_id)
+            | Failure::MpcInvalid(bundle_id, _, _) => Some(*bundle_id),
+            _ => None,
+        }
+    }
+
+    /// Returns displayable form of the failure, which mentions the names the
+    /// schema gives to the state types referenced by the failure.
+    pub fn named<'a>(&'a self, schema: &'a Schema) -> NamedFailure<'a> {
+        NamedFailure {
+            failure: self,
+            schema,
+        }
+    }
+}
+
+/// Localization template of a failure, produced by [`Failure::template`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct FailureTemplate {
+    /// Stable key of the message template.
+    pub key: &'static str,
+    /// Ordered arguments to substitute into the message template.
+    pub args: Vec<String>,
+}
+
+/// Failure displayed together with the schema names of the state types it
+/// references. Produced by [`Failure::named`].
+#[derive(Copy, Clone, Debug)]
+pub struct NamedFailure<'a> {
+    failure: &'a Failure,
+    schema: &'a Schema,
+}
+
+impl Display for NamedFailure<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.failure, f)?;
+        let names = self
+            .failure
+            .state_refs()
+            .into_iter()
+            .filter_map(|state| {
+                let name = self.schema.state_name(state)?;
+                Some(format!("{state} is '{name}'"))
+            })
+            .collect::<Vec<_>>();
+        if !names.is_empty() {
+            write!(f, " ({})", names.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Group of failures of the same kind, produced by [`Status::failure_groups`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct FailureGroup {
+    /// Code of the failures in the group (see [`Failure::code`]).
+    pub code: String,
+    /// Total number of failures in the group.
+    pub count: usize,
+    /// First failures in the group.
+    pub examples: Vec<Failure>,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Display, From)]
 #[cfg_attr(
     feature = "serde",
@@ -372,6 +1789,32 @@ pub enum Warning {
     /// thus was not validated.
     UncheckableConfidentialState(OpId, schema::AssignmentType),
 
+    /// schema declares {0} which is not used by any of its operations.
+    SchemaUnusedStateType(schema::StateRef),
+    /// schema for {0} requires {1} which is never produced by any other
+    /// operation, making it unreachable.
+    SchemaOpUnreachable(OpFullType, schema::StateRef),
+
+    /// bundle {0} is anchored to witness {1} mined at {2}, which is ahead of
+    /// the current time {3}.
+    WitnessTimestampFuture(BundleId, XWitnessId, i64, i64),
+    /// contract genesis timestamp {0} is ahead of the current time {1}.
+    GenesisTimestampFuture(i64, i64),
+
+    /// operation {0} assigns state to output {1} holding {2} sats, which is
+    /// below the schema dust limit of {3} sats.
+    OutputBelowDust(OpId, XOutputSeal, u64, u64),
+    /// witness {0} pays fee of {1} sats for {2} vbytes, which is outside of
+    /// the fee rate range expected by the validation policy.
+    AbnormalFeeRate(XWitnessId, u64, u32),
+
+    /// issuer of contract {0} can't be verified: {1}
+    IssuerUnattested(ContractId, IssuerAttestationError),
+
+    /// operation {0} discloses amount of state type {1} assigned to a
+    /// concealed seal, while the contract requires confidential amounts.
+    AmountDisclosed(OpId, schema::AssignmentType),
+
     /// Custom warning by external services on top of RGB Core.
     #[display(inner)]
     Custom(String),
@@ -385,7 +1828,278 @@ pub enum Warning {
 )]
 #[display(doc_comments)]
 pub enum Info {
+    /// {count} more failures of kind {code} were omitted from the report.
+    FailuresOmitted { code: String, count: usize },
+
+    /// alternative anchor of bundle {0} to witness {1} is superseded by
+    /// another anchor and was ignored.
+    AnchorAlternativeStale(BundleId, XWitnessId),
+
+    /// consignment carries {0} non-committed annotations, which were ignored
+    /// by the validation.
+    AnnotationsIgnored(usize),
+
+    /// validation script of operation {0} executed {1}.
+    ScriptProfile(OpId, ScriptStats),
+
+    /// operation {0} has data of {2} assignments of prunable state type {1}
+    /// pruned from the consignment.
+    StatePruned(OpId, schema::AssignmentType, usize),
+
+    /// contract {0} is issued by the holder of identity key {1}.
+    IssuerAttested(ContractId, XOnlyPk),
+
     /// Custom info by external services on top of RGB Core.
     #[display(inner)]
     Custom(String),
 }
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+
+    #[test]
+    fn aggregation() {
+        let opid = OpId::strict_dumb();
+        let mut status = Status::from_iter([
+            Failure::OperationAbsent(opid),
+            Failure::SealsUnvalidated(opid),
+            Failure::OperationAbsent(opid),
+            Failure::CyclicGraph(opid),
+            Failure::SealsUnvalidated(opid),
+        ]);
+        status.add_failure(Failure::Custom(s!("first")));
+        status.add_failure(Failure::Custom(s!("second")));
+        status.add_failure(Failure::Custom(s!("third")));
+
+        let groups = status.failure_groups(2);
+        assert_eq!(groups.len(), 4);
+        assert_eq!(groups[0].code, "OperationAbsent");
+        assert_eq!(groups[0].count, 2);
+        assert_eq!(groups[3].code, "Custom");
+        assert_eq!(groups[3].count, 3);
+        assert_eq!(groups[3].examples, vec![
+            Failure::Custom(s!("first")),
+            Failure::Custom(s!("second"))
+        ]);
+
+        let mut capped = status.clone();
+        capped.cap_failures(2);
+        assert_eq!(capped.failures.len(), 7);
+        assert_eq!(capped.info, vec![Info::FailuresOmitted {
+            code: s!("Custom"),
+            count: 1
+        }]);
+
+        status.dedup();
+        assert_eq!(status.failures.len(), 6);
+        assert_eq!(status.failures[2], Failure::CyclicGraph(opid));
+    }
+
+    #[test]
+    fn merge_large() {
+        let opid = OpId::strict_dumb();
+        let count = 100_000;
+
+        let mut status = Status::new();
+        status.reserve(count);
+        let capacity = status.failures.capacity();
+        let mut rhs = Status::new();
+        for _ in 0..count {
+            rhs.add_failure(Failure::SealsUnvalidated(opid));
+        }
+        rhs.add_warning(Warning::Custom(s!("warning")));
+        status += rhs.clone();
+        assert_eq!(status.failures.capacity(), capacity);
+
+        status.merge_labeled(rhs.clone(), "second");
+        assert_eq!(status.failures.len(), 2 * count);
+        assert_eq!(status.labels.failures.len(), count);
+        assert_eq!(status.labels.failures.get(&count).map(String::as_str), Some("second"));
+        assert_eq!(status.labels.warnings.get(&1).map(String::as_str), Some("second"));
+
+        let mut empty = Status::new();
+        empty += rhs;
+        assert_eq!(empty.failures.len(), count);
+        assert_eq!(empty.warnings.len(), 1);
+    }
+
+    #[test]
+    fn usage() {
+        let ty1 = schema::GlobalStateType::with(1);
+        let ty2 = schema::GlobalStateType::with(2);
+        let mut genesis = crate::Genesis::strict_dumb();
+        genesis.globals.add_state(ty1, crate::DataState::strict_dumb()).unwrap();
+        genesis.globals.add_state(ty1, crate::DataState::strict_dumb()).unwrap();
+        let mut stats = UsageStats::default();
+        stats.record(&genesis);
+
+        let mut genesis = crate::Genesis::strict_dumb();
+        genesis.globals.add_state(ty1, crate::DataState::strict_dumb()).unwrap();
+        genesis.globals.add_state(ty2, crate::DataState::strict_dumb()).unwrap();
+        let mut rhs = UsageStats::default();
+        rhs.record(&genesis);
+
+        let mut status = Status {
+            usage: Some(stats),
+            ..Status::default()
+        };
+        status += Status {
+            usage: Some(rhs),
+            ..Status::default()
+        };
+        status += Status::default();
+
+        let usage = status.usage.as_ref().unwrap();
+        assert!(usage.get(OpFullType::StateTransition(1.into())).is_none());
+        let usage = usage.get(OpFullType::Genesis).unwrap();
+        assert_eq!(usage.operations, 2);
+        assert_eq!(usage.globals[&ty1], StateUsage {
+            operations: 2,
+            min: 1,
+            max: 2,
+            total: 3
+        });
+        assert_eq!(usage.global_occurrences(ty1), (1, 2));
+        assert_eq!(usage.global_occurrences(ty2), (0, 1));
+        assert_eq!(usage.global_occurrences(schema::GlobalStateType::with(3)), (0, 0));
+    }
+
+    #[test]
+    fn labels() {
+        let opid = OpId::strict_dumb();
+        let mut inner = Status::with_failure(Failure::CyclicGraph(opid));
+        inner.merge_labeled(Status::with_failure(Failure::OperationAbsent(opid)), "genesis");
+
+        let mut status = Status::with_failure(Failure::CyclicGraph(opid));
+        status.add_warning(Warning::Custom(s!("warning")));
+        status.merge_labeled(inner, "contract");
+        status += Status::with_failure(Failure::CyclicGraph(opid));
+
+        assert_eq!(status.labeled_failures().collect::<Vec<_>>(), vec![
+            (None, &Failure::CyclicGraph(opid)),
+            (Some("contract"), &Failure::CyclicGraph(opid)),
+            (Some("contract/genesis"), &Failure::OperationAbsent(opid)),
+            (None, &Failure::CyclicGraph(opid)),
+        ]);
+        assert_eq!(status.labeled_warnings().next(), Some((None, &Warning::Custom(s!("warning")))));
+
+        status.dedup();
+        assert_eq!(status.labeled_failures().collect::<Vec<_>>(), vec![
+            (None, &Failure::CyclicGraph(opid)),
+            (Some("contract"), &Failure::CyclicGraph(opid)),
+            (Some("contract/genesis"), &Failure::OperationAbsent(opid)),
+        ]);
+        assert!(status
+            .to_string()
+            .contains("\n- [contract/genesis] operation "));
+    }
+
+    #[test]
+    fn named() {
+        let mut schema = Schema::strict_dumb();
+        let failure = Failure::AssetTagNoState(schema::AssignmentType::with(4000));
+        assert_eq!(failure.named(&schema).to_string(), failure.to_string());
+
+        let mut names = schema::SchemaTypeNames::default();
+        names
+            .assignments
+            .insert(schema::AssignmentType::with(4000), fname!("assetOwner"))
+            .unwrap();
+        schema.ext.insert(names);
+        assert_eq!(
+            failure.named(&schema).to_string(),
+            format!("{failure} (owned state type 0x0FA0 is 'assetOwner')")
+        );
+    }
+
+    #[test]
+    fn short_ids() {
+        let op1 = OpId::from([0x11u8; 32]);
+        let mut op2 = [0x11u8; 32];
+        op2[5] = 0x12;
+        let op2 = OpId::from(op2);
+        let bundle_id = BundleId::from([0xAAu8; 32]);
+        let status = Status::from_iter([
+            Failure::CyclicGraph(op1),
+            Failure::OperationAbsent(op2),
+            Failure::BundleAbsent(bundle_id),
+        ]);
+
+        // The second operation id is not known to the disambiguator, but is
+        // found in the rendered status
+        let ids = Disambiguator::from_iter([op1]);
+        let short = status.to_short_string(&ids);
+        for id in [op1.to_string(), op2.to_string(), bundle_id.to_string()] {
+            assert!(!short.contains(&id));
+        }
+        assert!(short.contains(&op1.short_id().with_len(12).to_string()));
+        assert!(short.contains(&op2.short_id().with_len(12).to_string()));
+        assert!(short.contains(&bundle_id.short_id().to_string()));
+        assert_eq!(short.lines().count(), status.to_string().lines().count());
+    }
+
+    #[test]
+    fn script_error() {
+        assert_eq!(ScriptError::from(None), ScriptError::Failed);
+        assert_eq!(ScriptError::from(Some(7)).code(), Some(7));
+        assert_eq!(ScriptError::Code(7).to_string(), "script has failed with the error code 7.");
+
+        let data = ScriptError::Code(7).to_strict_serialized::<2>().unwrap();
+        assert_eq!(data.as_slice(), &[1, 7]);
+        assert_eq!(ScriptError::from_strict_serialized::<2>(data).unwrap(), ScriptError::Code(7));
+    }
+
+    #[test]
+    fn template() {
+        let opid = OpId::strict_dumb();
+        let template = Failure::FeeUnpaid(opid, 1000, 10).template();
+        assert_eq!(template.key, "FeeUnpaid");
+        assert_eq!(template.args, vec![opid.to_string(), s!("1000"), s!("10")]);
+
+        let template = Failure::ScriptFailure(opid, ScriptError::Code(4)).template();
+        assert_eq!(template.key, Failure::ScriptFailure(opid, ScriptError::Failed).code());
+        assert_eq!(template.args, vec![opid.to_string(), s!("4")]);
+
+        assert!(Failure::SchemaBlankTransitionRedefined
+            .template()
+            .args
+            .is_empty());
+    }
+
+    #[test]
+    fn report() {
+        let report = Status::new().report();
+        assert_eq!(report.consensus, Validity::Valid);
+        assert!(report.is_confirmed());
+        assert_eq!(report.concealed_coverage(), 1.0);
+        assert_eq!(report.resolver_completeness(), 1.0);
+
+        let mut status = Status::new();
+        status.stats.assignments = 4;
+        status.stats.assignments_concealed = 1;
+        let mut other = Status::with_failure(Failure::CyclicGraph(OpId::strict_dumb()));
+        other.stats.witnesses = 2;
+        other.stats.witnesses_unresolved = 1;
+        status += other;
+
+        let report = status.report();
+        assert_eq!(report.consensus, Validity::Invalid);
+        assert!(!report.is_confirmed());
+        assert_eq!(report.concealed_coverage(), 0.75);
+        assert_eq!(report.resolver_completeness(), 0.5);
+
+        // Counters merged from inconsistent statuses must not underflow
+        let mut status = Status::new();
+        status.stats.assignments_concealed = 1;
+        status.stats.witnesses_unresolved = 1;
+        let report = status.report();
+        assert_eq!(report.concealed_coverage(), 1.0);
+        assert_eq!(report.resolver_completeness(), 1.0);
+        status.stats.assignments = 1;
+        status.stats.assignments_concealed = 2;
+        assert_eq!(status.report().concealed_coverage(), 0.0);
+    }
+}
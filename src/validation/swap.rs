@@ -0,0 +1,243 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Atomic swaps of the state of two contracts, anchored in the same witness
+//! transaction.
+
+use super::{verify_seal_closure, ConsignmentApi, Failure, ResolveWitness, Status, Validator};
+use crate::vm::{ContractStateAccess, ContractStateEvolve, XWitnessId, XWitnessTx};
+use crate::{BundleId, Operation, XOutputSeal};
+
+/// Container for the consignments of two contracts which state is swapped by
+/// the transitions anchored in the same witness transaction.
+///
+/// The swap is valid only as a whole: each of the consignments must be valid
+/// and must contain a bundle committed to by the swap witness transaction.
+#[derive(Clone, Debug)]
+pub struct SwapBundle<A: ConsignmentApi, B: ConsignmentApi> {
+    /// Witness transaction anchoring both legs of the swap.
+    pub witness_id: XWitnessId,
+    /// Consignment of the first contract.
+    pub left: A,
+    /// Consignment of the second contract.
+    pub right: B,
+}
+
+impl<A: ConsignmentApi, B: ConsignmentApi> SwapBundle<A, B> {
+    pub fn new(witness_id: XWitnessId, left: A, right: B) -> Self {
+        SwapBundle {
+            witness_id,
+            left,
+            right,
+        }
+    }
+
+    /// Returns ids of the bundles from the first consignment anchored to the
+    /// swap witness transaction.
+    pub fn left_bundles(&self) -> Vec<BundleId> {
+        self.left.bundles_by_witness_id(self.witness_id).collect()
+    }
+
+    /// Returns ids of the bundles from the second consignment anchored to the
+    /// swap witness transaction.
+    pub fn right_bundles(&self) -> Vec<BundleId> {
+        self.right.bundles_by_witness_id(self.witness_id).collect()
+    }
+
+    /// Verifies that the swap legs belong to different contracts and that the
+    /// resolved swap witness transaction commits to the bundles of both legs.
+    /// Doesn't validate the consignments themselves (see [`Self::validate`]).
+    pub fn verify_legs(&self, resolver: &impl ResolveWitness) -> Status {
+        let mut status = Status::new();
+        let left_id = self.left.genesis().contract_id();
+        let right_id = self.right.genesis().contract_id();
+        if left_id == right_id {
+            status.add_failure(Failure::SwapSameContract(left_id));
+        }
+        if self.left_bundles().is_empty() {
+            status.add_failure(Failure::SwapLegUnanchored(left_id, self.witness_id));
+        }
+        if self.right_bundles().is_empty() {
+            status.add_failure(Failure::SwapLegUnanchored(right_id, self.witness_id));
+        }
+        match resolver.resolve_pub_witness(self.witness_id) {
+            Ok(witness) => {
+                status += self.verify_leg(&self.left, &witness);
+                status += self.verify_leg(&self.right, &witness);
+            }
+            Err(err) => {
+                status.add_failure(Failure::SwapWitnessUnresolved(self.witness_id, err));
+            }
+        }
+        status
+    }
+
+    /// Verifies the MPC commitments of the leg bundles anchored to the swap
+    /// witness. Since a witness transaction contains a single MPC commitment,
+    /// this proves that both legs are anchored in the same MPC tree. The seals
+    /// closed by the bundles are verified by the consignment validation.
+    fn verify_leg(&self, leg: &impl ConsignmentApi, witness: &XWitnessTx) -> Status {
+        let mut status = Status::new();
+        let contract_id = leg.genesis().contract_id();
+        for bundle_id in leg.bundles_by_witness_id(self.witness_id) {
+            let Some(bundle) = leg.bundle(bundle_id) else {
+                status.add_failure(Failure::BundleAbsent(bundle_id));
+                continue;
+            };
+            let Some((_, anchor)) = leg.anchor(bundle_id) else {
+                status.add_failure(Failure::AnchorAbsent(bundle_id));
+                continue;
+            };
+            let seals: [XOutputSeal; 0] = [];
+            status += verify_seal_closure(
+                contract_id,
+                seals,
+                witness,
+                anchor,
+                bundle_id,
+                bundle.close_method,
+            );
+        }
+        status
+    }
+
+    /// Validates both legs of the swap together with both consignments. The
+    /// failures of each of the consignments are labeled with its contract id.
+    pub fn validate<SA, SB, R>(
+        &self,
+        resolver: &R,
+        testnet: bool,
+        left_context: SA::Context<'_>,
+        right_context: SB::Context<'_>,
+    ) -> Status
+    where
+        SA: ContractStateAccess + ContractStateEvolve,
+        SB: ContractStateAccess + ContractStateEvolve,
+        R: ResolveWitness,
+    {
+        let mut status = self.verify_legs(resolver);
+        status.merge_labeled(
+            Validator::<SA, _, _>::validate(&self.left, resolver, testnet, left_context),
+            self.left.genesis().contract_id().to_string(),
+        );
+        status.merge_labeled(
+            Validator::<SB, _, _>::validate(&self.right, resolver, testnet, right_context),
+            self.right.genesis().contract_id().to_string(),
+        );
+        status
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+    use bp::dbc::opret::OpretFirst;
+    use bp::dbc::Method;
+    use bp::opcodes::OP_RETURN;
+    use bp::{ScriptPubkey, Tx, TxOut, Txid};
+    use commit_verify::{mpc, CommitId, EmbedCommitVerify, TryCommitVerify};
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::validation::validator::test::{bundle, Ledger, Mined, Witness};
+    use crate::validation::{DbcProof, EAnchor, WitnessResolverError};
+    use crate::{ContractId, Layer1, Schema, Transition, XChain};
+
+    /// Witness transaction committing to the bundle under each of the
+    /// contracts, together with the anchors of the bundle for each of them.
+    fn commit(contracts: &[ContractId], bundle_id: BundleId) -> (XWitnessTx, Vec<EAnchor>) {
+        let source = mpc::MultiSource {
+            messages: Confined::try_from_iter(contracts.iter().map(|contract_id| {
+                (mpc::ProtocolId::from(*contract_id), mpc::Message::from(bundle_id))
+            }))
+            .unwrap(),
+            ..default!()
+        };
+        let tree = mpc::MerkleTree::try_commit(&source).unwrap();
+        let mut tx = Tx::strict_dumb();
+        let script = ScriptPubkey::from_unsafe(vec![OP_RETURN]);
+        tx.outputs = Confined::from_checked(vec![TxOut::new(script, 0u64)]);
+        let opret = EmbedCommitVerify::<_, OpretFirst>::embed_commit(&mut tx, &tree.commit_id())
+            .unwrap();
+        let block = mpc::MerkleBlock::from(tree);
+        let anchors = contracts
+            .iter()
+            .map(|contract_id| {
+                let mpc_proof = block
+                    .to_merkle_proof(mpc::ProtocolId::from(*contract_id))
+                    .unwrap();
+                EAnchor::new(mpc_proof, DbcProof::Opret(opret))
+            })
+            .collect();
+        (XChain::Bitcoin(tx), anchors)
+    }
+
+    #[test]
+    fn legs() {
+        let mut bundle = bundle([Transition::strict_dumb()]);
+        bundle.close_method = Method::OpretFirst;
+        let bundle_id = bundle.bundle_id();
+        let leg = |timestamp, witness_id, anchor: &EAnchor| {
+            let mut ledger =
+                Ledger::with_bundles(Schema::strict_dumb(), [(witness_id, bundle.clone())]);
+            ledger.genesis.timestamp = timestamp;
+            ledger.anchor = anchor.clone();
+            ledger
+        };
+        let contract_id = |timestamp| {
+            let ledger = leg(timestamp, XWitnessId::strict_dumb(), &EAnchor::strict_dumb());
+            ledger.genesis.contract_id()
+        };
+        let contracts = [contract_id(1), contract_id(2)];
+        let (witness, anchors) = commit(&contracts, bundle_id);
+        let witness_id = witness.witness_id();
+        let resolver = Witness(witness, None);
+        let left = || leg(1, witness_id, &anchors[0]);
+
+        let swap = SwapBundle::new(witness_id, left(), leg(2, witness_id, &anchors[1]));
+        assert_eq!(swap.left_bundles(), vec![bundle_id]);
+        assert_eq!(swap.verify_legs(&resolver).failures, vec![]);
+        assert_eq!(swap.verify_legs(&Mined).failures, vec![Failure::SwapWitnessUnresolved(
+            witness_id,
+            WitnessResolverError::Unknown(witness_id)
+        )]);
+
+        // Right leg is committed by another witness
+        let (_, other) = commit(&contracts[1..], bundle_id);
+        let swap = SwapBundle::new(witness_id, left(), leg(2, witness_id, &other[0]));
+        let failures = swap.verify_legs(&resolver).failures;
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(failures[0], Failure::SealsInvalid(id, ..) if id == bundle_id));
+
+        let other_id = XWitnessId::with(Layer1::Liquid, Txid::from([0xAB; 32]));
+        let swap = SwapBundle::new(witness_id, left(), leg(2, other_id, &anchors[1]));
+        assert_eq!(swap.verify_legs(&resolver).failures, vec![Failure::SwapLegUnanchored(
+            contracts[1],
+            witness_id
+        )]);
+
+        let swap = SwapBundle::new(witness_id, left(), left());
+        assert_eq!(swap.verify_legs(&resolver).failures, vec![Failure::SwapSameContract(
+            contracts[0]
+        )]);
+    }
+}
@@ -0,0 +1,57 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::Utc;
+
+/// Maximal number of seconds a timestamp used in the validation may be ahead
+/// of the current time provided by [`TimeSource`].
+pub const MAX_TIME_DRIFT: i64 = 2 * 60 * 60;
+
+/// Source of the current time for the time-dependent validation rules.
+///
+/// The validator samples the time once at the start of the validation, so a
+/// replay of the validation with the same time source gives the same result.
+pub trait TimeSource {
+    /// Returns current time as a UNIX timestamp, in seconds.
+    fn now(&self) -> i64;
+}
+
+impl<T: TimeSource + ?Sized> TimeSource for &T {
+    fn now(&self) -> i64 { T::now(*self) }
+}
+
+/// Time source using the system clock of the host.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct SystemClock;
+
+impl TimeSource for SystemClock {
+    fn now(&self) -> i64 { Utc::now().timestamp() }
+}
+
+/// Time source always returning the same timestamp, for deterministic
+/// validation replays and tests.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FixedClock(pub i64);
+
+impl TimeSource for FixedClock {
+    fn now(&self) -> i64 { self.0 }
+}
@@ -20,24 +20,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
 use std::rc::Rc;
 
-use bp::dbc::Anchor;
-use bp::seals::txout::{CloseMethod, TxoSeal, Witness};
-use bp::{dbc, Outpoint};
-use commit_verify::mpc;
-use single_use_seals::SealWitness;
+use amplify::confinement::U16 as U16MAX;
+use amplify::Wrapper;
+use bp::seals::txout::{CloseMethod, TxoSeal};
+use bp::{Outpoint, Sats, Weight};
+use strict_encoding::StrictDeserialize;
+use strict_types::TypeSystem;
 
-use super::status::Failure;
-use super::{CheckedConsignment, ConsignmentApi, DbcProof, EAnchor, OpRef, Status, Validity};
+use super::status::{Failure, Info, SealReport, SealStatus, UsageStats, Warning};
+use super::{
+    verify_seal_closure, CheckedConsignment, ConsignmentApi, EAnchor, OpRef, Scripts, Status,
+    SystemClock, TimeSource, Validity, MAX_TIME_DRIFT,
+};
 use crate::vm::{
     ContractStateAccess, ContractStateEvolve, OrdOpRef, WitnessOrd, XWitnessId, XWitnessTx,
 };
 use crate::{
-    validation, AltLayer1, BundleId, ContractId, Layer1, OpId, OpType, Operation, Opout, Schema,
-    SchemaId, TransitionBundle, XChain, XOutpoint, XOutputSeal,
+    validation, AltLayer1, Assign, AssignmentType, BundleId, ContractFeatures, ContractId, Genesis,
+    IssuerAttestation, Layer1, Layer1Cap, OpId, OpType, Operation, Opout, OwnedStateSchema, Schema,
+    SchemaId, TransitionBundle, TransitionType, XChain, XOutpoint, XOutputSeal,
 };
 
 #[derive(Clone, PartialEq, Eq, Debug, Display, Error, From)]
@@ -59,6 +65,119 @@ pub enum WitnessResolverError {
     Other(XWitnessId, String),
 }
 
+/// Maximal depth of the unconfirmed ancestry of a witness transaction accepted
+/// by the default [`ValidationPolicy`]. Matches the default limit of the
+/// Bitcoin Core mempool, which does not accept transactions having more than
+/// 24 unconfirmed ancestors.
+pub const MAX_UNCONFIRMED_ANCESTRY: u8 = 24;
+
+/// Minimal witness fee rate, in satoshis per vbyte, not reported as abnormal
+/// by the default [`ValidationPolicy`]. Matches the default minimal relay fee
+/// rate of the Bitcoin Core nodes.
+pub const MIN_FEE_RATE: u64 = 1;
+
+/// Maximal witness fee rate, in satoshis per vbyte, not reported as abnormal
+/// by the default [`ValidationPolicy`].
+pub const MAX_FEE_RATE: u64 = 1000;
+
+/// Policy applied by the [`Validator`] on top of the consensus rules, defining
+/// which witness transactions are acceptable and which additional data are
+/// collected by the validation.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase", default)
+)]
+pub struct ValidationPolicy {
+    /// Maximal acceptable depth of the unconfirmed ancestry of a witness
+    /// transaction (see [`WitnessOrd::TentativeChained`]). Zero allows only
+    /// tentative witnesses spending mined outputs.
+    pub max_unconfirmed_ancestry: u8,
+    /// Whether to collect statistics of the state used by the validated
+    /// operations into [`Status::usage`].
+    pub collect_usage: bool,
+    /// Whether outputs below the schema dust limit (see
+    /// [`crate::DustLimitSchema`]) are reported as failures instead of
+    /// warnings.
+    pub strict_dust: bool,
+    /// Whether to report resources used by each execution of a validation
+    /// script as [`Info::ScriptProfile`].
+    pub profile_scripts: bool,
+    /// Whether to verify the issuer attestation of the contract genesis (see
+    /// [`crate::IssuerAttestation`]), reporting the outcome as
+    /// [`Info::IssuerAttested`] or [`Warning::IssuerUnattested`].
+    pub verify_issuer: bool,
+    /// Witness fee rate, in satoshis per vbyte, below which the fee rate is
+    /// reported as abnormal.
+    pub min_fee_rate: u64,
+    /// Witness fee rate, in satoshis per vbyte, above which the fee rate is
+    /// reported as abnormal.
+    pub max_fee_rate: u64,
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        ValidationPolicy {
+            max_unconfirmed_ancestry: MAX_UNCONFIRMED_ANCESTRY,
+            collect_usage: false,
+            strict_dust: false,
+            profile_scripts: false,
+            verify_issuer: false,
+            min_fee_rate: MIN_FEE_RATE,
+            max_fee_rate: MAX_FEE_RATE,
+        }
+    }
+}
+
+impl ValidationPolicy {
+    /// Policy accepting only mined witnesses and tentative witnesses which
+    /// spend mined outputs.
+    pub fn confirmed_ancestry() -> Self {
+        ValidationPolicy {
+            max_unconfirmed_ancestry: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Enables collection of the state usage statistics.
+    pub fn with_usage_stats(mut self) -> Self {
+        self.collect_usage = true;
+        self
+    }
+
+    /// Makes outputs below the schema dust limit fail the validation.
+    pub fn with_strict_dust(mut self) -> Self {
+        self.strict_dust = true;
+        self
+    }
+
+    /// Enables profiling of the validation scripts.
+    pub fn with_script_profiling(mut self) -> Self {
+        self.profile_scripts = true;
+        self
+    }
+
+    /// Enables verification of the issuer attestation.
+    pub fn with_issuer_verification(mut self) -> Self {
+        self.verify_issuer = true;
+        self
+    }
+
+    /// Checks whether a witness paying `fee` for its `vbytes` size has a fee
+    /// rate within the expected range.
+    pub fn accepts_fee(&self, fee: u64, vbytes: u32) -> bool {
+        let vbytes = vbytes as u64;
+        fee >= self.min_fee_rate.saturating_mul(vbytes)
+            && fee <= self.max_fee_rate.saturating_mul(vbytes)
+    }
+
+    /// Checks whether the witness with a given ordering is acceptable.
+    pub fn accepts(&self, witness_ord: WitnessOrd) -> bool {
+        witness_ord.unconfirmed_ancestry() <= self.max_unconfirmed_ancestry
+    }
+}
+
 pub trait ResolveWitness {
     // TODO: Return with SPV proof data
     fn resolve_pub_witness(
@@ -70,6 +189,23 @@ pub trait ResolveWitness {
         &self,
         witness_id: XWitnessId,
     ) -> Result<WitnessOrd, WitnessResolverError>;
+
+    /// Detects whether anchoring of the bundle to the witness was already
+    /// verified before (for instance, by a previous validation using a
+    /// persistent [`super::WitnessCache`]), such that the validator may skip
+    /// verification of the seal closing and the anchor commitment. The checks
+    /// of the bundle input map and the policy-dependent checks of the witness
+    /// are performed anyway.
+    fn is_anchor_verified(&self, _witness_id: XWitnessId, _bundle_id: BundleId) -> bool { false }
+
+    /// Notifies resolver that anchoring of the bundle to the witness was
+    /// successfully verified.
+    fn anchor_verified(&self, _witness_id: XWitnessId, _bundle_id: BundleId) {}
+
+    /// Resolves value of a transaction output spent by a witness transaction,
+    /// which is required to compute the witness fee rate. Resolvers not
+    /// providing the values disable the fee rate checks.
+    fn resolve_spent_value(&self, _outpoint: XOutpoint) -> Option<Sats> { None }
 }
 
 impl<T: ResolveWitness> ResolveWitness for &T {
@@ -86,6 +222,18 @@ impl<T: ResolveWitness> ResolveWitness for &T {
     ) -> Result<WitnessOrd, WitnessResolverError> {
         ResolveWitness::resolve_pub_witness_ord(*self, witness_id)
     }
+
+    fn is_anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) -> bool {
+        ResolveWitness::is_anchor_verified(*self, witness_id, bundle_id)
+    }
+
+    fn anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) {
+        ResolveWitness::anchor_verified(*self, witness_id, bundle_id)
+    }
+
+    fn resolve_spent_value(&self, outpoint: XOutpoint) -> Option<Sats> {
+        ResolveWitness::resolve_spent_value(*self, outpoint)
+    }
 }
 
 struct CheckedWitnessResolver<R: ResolveWitness> {
@@ -117,7 +265,29 @@ impl<R: ResolveWitness> ResolveWitness for CheckedWitnessResolver<R> {
         &self,
         witness_id: XWitnessId,
     ) -> Result<WitnessOrd, WitnessResolverError> {
-        self.inner.resolve_pub_witness_ord(witness_id)
+        self.inner
+            .resolve_pub_witness_ord(witness_id)
+            .map(|ord| match ord {
+                // Witness with zero-depth unconfirmed ancestry is just a tentative one, and it
+                // must be ordered as such
+                WitnessOrd::TentativeChained(0) => WitnessOrd::Tentative,
+                ord => ord,
+            })
+    }
+
+    #[inline]
+    fn is_anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) -> bool {
+        self.inner.is_anchor_verified(witness_id, bundle_id)
+    }
+
+    #[inline]
+    fn anchor_verified(&self, witness_id: XWitnessId, bundle_id: BundleId) {
+        self.inner.anchor_verified(witness_id, bundle_id)
+    }
+
+    #[inline]
+    fn resolve_spent_value(&self, outpoint: XOutpoint) -> Option<Sats> {
+        self.inner.resolve_spent_value(outpoint)
     }
 }
 
@@ -135,9 +305,14 @@ pub struct Validator<
     schema_id: SchemaId,
     contract_id: ContractId,
     layers1: BTreeSet<Layer1>,
+    supply_caps: RefCell<BTreeMap<Layer1, u64>>,
+    issued: RefCell<BTreeMap<AssignmentType, u128>>,
+    features: Cell<ContractFeatures>,
 
     contract_state: Rc<RefCell<S>>,
     validated_op_seals: RefCell<BTreeSet<OpId>>,
+    policy: ValidationPolicy,
+    now: i64,
 
     resolver: CheckedWitnessResolver<&'resolver R>,
 }
@@ -150,10 +325,19 @@ impl<
     R: ResolveWitness,
 > Validator<'consignment, 'resolver, S, C, R>
 {
-    fn init(consignment: &'consignment C, resolver: &'resolver R, context: S::Context<'_>) -> Self {
+    fn init(
+        consignment: &'consignment C,
+        resolver: &'resolver R,
+        context: S::Context<'_>,
+        policy: ValidationPolicy,
+        now: i64,
+    ) -> Self {
         // We use validation status object to store all detected failures and
         // warnings
-        let status = Status::default();
+        let status = Status {
+            usage: policy.collect_usage.then(UsageStats::default),
+            ..Status::default()
+        };
         let consignment = CheckedConsignment::new(consignment);
 
         // Frequently used computation-heavy data
@@ -173,7 +357,12 @@ impl<
             schema_id,
             contract_id,
             layers1,
+            supply_caps: none!(),
+            issued: none!(),
+            features: none!(),
             validated_op_seals,
+            policy,
+            now,
             resolver: CheckedWitnessResolver::from(resolver),
             contract_state: Rc::new(RefCell::new(S::init(context))),
         }
@@ -194,7 +383,35 @@ impl<
         testnet: bool,
         context: S::Context<'_>,
     ) -> Status {
-        let mut validator = Self::init(consignment, resolver, context);
+        Self::validate_with_policy(consignment, resolver, testnet, context, default!())
+    }
+
+    /// Runs [`Self::validate`] applying the provided [`ValidationPolicy`],
+    /// which defines the acceptable unconfirmed ancestry of the witness
+    /// transactions, their fee rates and dust outputs, and whether the state
+    /// usage statistics, script profiles and the issuer attestation are
+    /// reported in the returned status.
+    pub fn validate_with_policy(
+        consignment: &'consignment C,
+        resolver: &'resolver R,
+        testnet: bool,
+        context: S::Context<'_>,
+        policy: ValidationPolicy,
+    ) -> Status {
+        Self::validate_with_clock(consignment, resolver, testnet, context, policy, SystemClock)
+    }
+
+    /// Runs [`Self::validate_with_policy`] taking the current time for the
+    /// time-dependent validation rules from the provided [`TimeSource`].
+    pub fn validate_with_clock(
+        consignment: &'consignment C,
+        resolver: &'resolver R,
+        testnet: bool,
+        context: S::Context<'_>,
+        policy: ValidationPolicy,
+        clock: impl TimeSource,
+    ) -> Status {
+        let mut validator = Self::init(consignment, resolver, context, policy, clock.now());
         // If the network mismatches there is no point in validating the contract since
         // all witness transactions will be missed.
         if testnet != validator.consignment.genesis().testnet {
@@ -220,14 +437,52 @@ impl<
         }
 
         validator.validate_logic();
+
+        if validator.policy.verify_issuer {
+            validator.validate_issuer(consignment.schema());
+        }
+
+        // Annotations are not committed to, so we just report their presence
+        if let Some(count) = consignment
+            .annotations()
+            .map(|annotations| annotations.len())
+            .filter(|count| *count > 0)
+        {
+            validator
+                .status
+                .borrow_mut()
+                .add_info(Info::AnnotationsIgnored(count));
+        }
+
         // Done. Returning status report with all possible failures, issues, warnings
         // and notifications about transactions we were unable to obtain.
         validator.status.into_inner()
     }
 
+    /// Verifies the issuer attestation in the genesis, if the schema defines
+    /// one. The attestation doesn't affect the validity of the contract.
+    fn validate_issuer(&self, schema: &Schema) {
+        let Some(issuer_proof) = schema.ext.issuer_proof() else {
+            return;
+        };
+        let genesis = self.consignment.genesis();
+        let mut status = self.status.borrow_mut();
+        match IssuerAttestation::verify_genesis(genesis, issuer_proof.proof_type) {
+            Ok(issuer) => status.add_info(Info::IssuerAttested(self.contract_id, issuer)),
+            Err(err) => status.add_warning(Warning::IssuerUnattested(self.contract_id, err)),
+        };
+    }
+
     // *** PART I: Schema validation
     fn validate_schema(&mut self, schema: &Schema) {
         *self.status.borrow_mut() += schema.verify(self.consignment.types());
+        self.validate_scripts(schema);
+    }
+
+    /// Checks that all AluVM libraries used by the schema validators are
+    /// present in the consignment (see [`Schema::verify_scripts`]).
+    fn validate_scripts(&self, schema: &Schema) {
+        *self.status.borrow_mut() += schema.verify_scripts(self.consignment.scripts());
     }
 
     // *** PART II: Validating business logic
@@ -249,12 +504,25 @@ impl<
             return;
         }
 
-        // [VALIDATION]: Validate genesis
-        *self.status.borrow_mut() += schema.validate_state(
+        // [VALIDATION]: Validate genesis. Timestamps ahead of the current time are reported as
+        //               warnings, since they depend on the clock of the validating host
+        let timestamp = self.consignment.genesis().timestamp;
+        if timestamp > self.now.saturating_add(MAX_TIME_DRIFT) {
+            self.status
+                .borrow_mut()
+                .add_warning(Warning::GenesisTimestampFuture(timestamp, self.now));
+        }
+        *self.status.borrow_mut() += schema.validate_state_with(
             &self.consignment,
             OrdOpRef::Genesis(self.consignment.genesis()),
             self.contract_state.clone(),
+            self.policy.profile_scripts,
         );
+        self.record_usage(self.consignment.genesis());
+        self.load_supply_caps();
+        self.load_features();
+        self.validate_chain_supply(OrdOpRef::Genesis(self.consignment.genesis()));
+        self.validate_amounts(OrdOpRef::Genesis(self.consignment.genesis()));
 
         // [VALIDATION]: Iterating over all consignment operations, ordering them according to the
         //               consensus ordering rules.
@@ -279,6 +547,31 @@ impl<
                         return;
                     }
                 };
+            match witness_ord {
+                WitnessOrd::Mined(pos)
+                    if pos.timestamp() > self.now.saturating_add(MAX_TIME_DRIFT) =>
+                {
+                    self.status
+                        .borrow_mut()
+                        .add_warning(Warning::WitnessTimestampFuture(
+                            bundle_id,
+                            witness_id,
+                            pos.timestamp(),
+                            self.now,
+                        ));
+                }
+                WitnessOrd::Mined(_) => {}
+                _ => self.status.borrow_mut().stats.witnesses_unconfirmed += 1,
+            }
+            if !self.policy.accepts(witness_ord) {
+                self.status
+                    .borrow_mut()
+                    .add_failure(Failure::WitnessAncestryUnconfirmed(
+                        bundle_id,
+                        witness_id,
+                        witness_ord.unconfirmed_ancestry(),
+                    ));
+            }
             for op in bundle.known_transitions.values() {
                 ops.insert(OrdOpRef::Transition(op, witness_id, witness_ord));
                 for input in &op.inputs {
@@ -314,6 +607,12 @@ impl<
         }
     }
 
+    fn record_usage(&self, operation: &impl Operation) {
+        if let Some(usage) = &mut self.status.borrow_mut().usage {
+            usage.record(operation);
+        }
+    }
+
     fn validate_operation(&self, operation: OrdOpRef<'consignment>) {
         let schema = self.consignment.schema();
         let opid = operation.id();
@@ -323,6 +622,17 @@ impl<
                 .borrow_mut()
                 .add_failure(Failure::ContractMismatch(opid, operation.contract_id()));
         }
+        // Operation ids are domain-separated by the operation kind, so only the
+        // genesis may have an id equal to the contract id
+        if opid == self.contract_id && operation.op_type() != OpType::Genesis {
+            self.status
+                .borrow_mut()
+                .add_failure(Failure::OpKindMismatch {
+                    opid,
+                    expected: operation.op_type(),
+                    actual: OpType::Genesis,
+                });
+        }
 
         if !self.validated_op_seals.borrow().contains(&opid)
             && operation.op_type() == OpType::StateTransition
@@ -332,8 +642,16 @@ impl<
                 .add_failure(Failure::SealsUnvalidated(opid));
         }
         // [VALIDATION]: Verify operation against the schema and scripts
-        *self.status.borrow_mut() +=
-            schema.validate_state(&self.consignment, operation, self.contract_state.clone());
+        *self.status.borrow_mut() += schema.validate_state_with(
+            &self.consignment,
+            operation,
+            self.contract_state.clone(),
+            self.policy.profile_scripts,
+        );
+        self.record_usage(&operation);
+        self.validate_chain_supply(operation);
+        self.validate_amounts(operation);
+        self.validate_features(operation);
 
         match operation {
             OrdOpRef::Genesis(_) => {
@@ -376,8 +694,261 @@ impl<
         }
     }
 
+    /// Reads supply caps for each of the layers 1 from the contract genesis,
+    /// if the schema tracks the supply per layer 1.
+    fn load_supply_caps(&self) {
+        let Some(supply) = self.consignment.schema().ext.chain_supply() else {
+            return;
+        };
+        let mut caps = self.supply_caps.borrow_mut();
+        for data in self
+            .consignment
+            .genesis()
+            .globals
+            .get(&supply.caps)
+            .into_iter()
+            .flat_map(|values| values.iter())
+        {
+            match Layer1Cap::from_strict_serialized::<U16MAX>(data.as_inner().clone()) {
+                Ok(cap)
+                    if self.layers1.contains(&cap.layer1) && !caps.contains_key(&cap.layer1) =>
+                {
+                    caps.insert(cap.layer1, cap.max_supply);
+                }
+                _ => {
+                    self.status
+                        .borrow_mut()
+                        .add_failure(Failure::ChainCapInvalid(supply.caps));
+                }
+            }
+        }
+    }
+
+    /// Accounts the state issued by genesis and moved between layers 1 by the
+    /// burn transitions into the per-layer 1 supply, checking it against the
+    /// caps defined by the contract genesis.
+    fn validate_chain_supply(&self, operation: OrdOpRef) {
+        let Some(supply) = self.consignment.schema().ext.chain_supply() else {
+            return;
+        };
+        // State assigned on the layer 1 of the burn witness is not moved
+        let origin = match operation {
+            OrdOpRef::Genesis(_) => None,
+            OrdOpRef::Transition(transition, witness_id, _)
+                if supply.bridges.contains_key(&transition.transition_type) =>
+            {
+                Some(witness_id.layer1())
+            }
+            _ => return,
+        };
+        let Some(assigns) = operation.assignments().get(supply.assignment) else {
+            return;
+        };
+
+        let opid = operation.id();
+        let mut status = self.status.borrow_mut();
+        let mut changed = BTreeSet::new();
+        for assign in assigns.as_fungible() {
+            let layer1 = assign.to_confidential_seal().layer1();
+            if Some(layer1) == origin {
+                continue;
+            }
+            let Some(state) = assign.as_revealed_state() else {
+                status.add_failure(Failure::ChainSupplyConcealed(opid, supply.assignment));
+                continue;
+            };
+            let amount = state.value.as_u64();
+            if let Some(origin) = origin {
+                let supply = status.supply.get(&origin).copied().unwrap_or_default();
+                let Some(rest) = supply.checked_sub(amount) else {
+                    status.add_failure(Failure::ChainSupplyUnderflow(opid, origin, amount, supply));
+                    continue;
+                };
+                status.supply.insert(origin, rest);
+            }
+            let supply = status.supply.get(&layer1).copied().unwrap_or_default();
+            let Some(total) = supply.checked_add(amount) else {
+                status.add_failure(Failure::ChainSupplyOverflow(opid, layer1));
+                continue;
+            };
+            status.supply.insert(layer1, total);
+            changed.insert(layer1);
+        }
+
+        let caps = self.supply_caps.borrow();
+        for layer1 in changed {
+            let total = status.supply[&layer1];
+            if let Some(cap) = caps.get(&layer1).copied().filter(|cap| total > *cap) {
+                status.add_failure(Failure::ChainSupplyExceeded(opid, layer1, total, cap));
+            }
+        }
+    }
+
+    /// Sums revealed amounts of the fungible state assigned by the operation,
+    /// accounting the amounts issued by genesis and state extensions over the
+    /// whole contract history. Neither the amount assigned by a single
+    /// operation nor the total issued amount may overflow the range of the
+    /// state values.
+    fn validate_amounts(&self, operation: OrdOpRef) {
+        let issuance = !matches!(operation, OrdOpRef::Transition(..));
+        let assignments = operation.assignments();
+        let mut issued = self.issued.borrow_mut();
+        for (ty, state_schema) in &self.consignment.schema().owned_types {
+            let OwnedStateSchema::Fungible(fungible_type) = state_schema else {
+                continue;
+            };
+            let Some(assigns) = assignments.get(*ty) else {
+                continue;
+            };
+            let base = if issuance { issued.get(ty).copied().unwrap_or_default() } else { 0 };
+            let total = assigns
+                .as_fungible()
+                .iter()
+                .filter_map(Assign::as_revealed_state)
+                .try_fold(base, |sum, state| {
+                    sum.checked_add(state.value.as_u64() as u128)
+                })
+                .filter(|total| *total <= fungible_type.max_value());
+            let Some(total) = total else {
+                self.status
+                    .borrow_mut()
+                    .add_failure(Failure::AmountOverflow(operation.id(), *ty));
+                continue;
+            };
+            if issuance {
+                issued.insert(*ty, total);
+            }
+        }
+    }
+
+    /// Reads contract features enabled by the contract genesis, if the schema
+    /// declares them. Contracts enabling features unknown to this version of
+    /// the library are invalid, since they can't be enforced.
+    fn load_features(&self) {
+        let Some(schema) = self.consignment.schema().ext.features() else {
+            return;
+        };
+        let Some(values) = self.consignment.genesis().globals.get(&schema.flags) else {
+            return;
+        };
+        let features = match values.as_slice() {
+            [data] => ContractFeatures::from_strict_serialized::<U16MAX>(data.as_inner().clone())
+                .ok()
+                .filter(|features| features.is_known()),
+            _ => None,
+        };
+        match features {
+            Some(features) => self.features.set(features),
+            None => {
+                self.status
+                    .borrow_mut()
+                    .add_failure(Failure::FeatureFlagsInvalid(schema.flags));
+            }
+        }
+    }
+
+    /// Checks that the operation uses only the contract features enabled by
+    /// the contract genesis (see [`crate::FeatureSchema`]).
+    fn validate_features(&self, operation: OrdOpRef) {
+        let Some(schema) = self.consignment.schema().ext.features() else {
+            return;
+        };
+        let enabled = self.features.get();
+        let assigns = operation.assignments().get(schema.assignment);
+        let required = match operation {
+            OrdOpRef::Genesis(_) => ContractFeatures::NONE,
+            OrdOpRef::Transition(transition, ..) => {
+                let ty = transition.transition_type;
+                let mut required = ContractFeatures::NONE;
+                if schema.burns.contains(&ty) {
+                    required = required.union(ContractFeatures::BURN);
+                }
+                if schema.inflation.contains(&ty) {
+                    required = required.union(ContractFeatures::INFLATION);
+                }
+                required
+            }
+            OrdOpRef::Extension(..) if assigns.as_ref().is_some_and(|a| !a.is_empty()) => {
+                ContractFeatures::INFLATION
+            }
+            OrdOpRef::Extension(..) => ContractFeatures::NONE,
+        };
+
+        let opid = operation.id();
+        let mut status = self.status.borrow_mut();
+        let missing = required.difference(enabled);
+        if missing != ContractFeatures::NONE {
+            status.add_failure(Failure::FeatureDisabled(opid, missing));
+        }
+        // Issued amounts are always allowed to be revealed. The amounts of the
+        // concealed seals are not committed to, so their disclosure can't be
+        // a consensus rule.
+        if enabled.contains(ContractFeatures::CONFIDENTIAL_AMOUNTS)
+            && matches!(operation, OrdOpRef::Transition(..))
+            && assigns
+                .iter()
+                .flat_map(|assigns| assigns.as_fungible())
+                .any(|assign| matches!(assign, Assign::ConfidentialSeal { .. }))
+        {
+            status.add_warning(Warning::AmountDisclosed(opid, schema.assignment));
+        }
+    }
+
+    /// Returns type of the release transition paired with the operation, if
+    /// the operation is a burn transition (see [`crate::ChainSupplySchema`]).
+    fn paired_release(&self, op: OpRef) -> Option<TransitionType> {
+        let OpRef::Transition(transition) = op else {
+            return None;
+        };
+        let supply = self.consignment.schema().ext.chain_supply()?;
+        supply.bridges.get(&transition.transition_type).copied()
+    }
+
+    /// Detects whether transitions of the type release the state burned on
+    /// another layer 1.
+    fn is_release(&self, transition_type: TransitionType) -> bool {
+        self.consignment
+            .schema()
+            .ext
+            .chain_supply()
+            .is_some_and(|supply| supply.bridges.values().any(|ty| *ty == transition_type))
+    }
+
     // *** PART III: Validating single-use-seals
+    /// Selects the anchor of each bundle having alternative anchors (like RBF
+    /// candidates): the first anchor with a mined witness is used; if none of
+    /// them is mined, the main anchor is kept. Other alternatives are reported
+    /// as stale.
+    fn select_anchors(&mut self) {
+        let bundle_ids = self.consignment.bundle_ids().collect::<Vec<_>>();
+        for bundle_id in bundle_ids {
+            let candidates = self.consignment.anchor_candidates(bundle_id);
+            if candidates.len() < 2 {
+                continue;
+            }
+            let (witness_id, anchor) = candidates
+                .iter()
+                .find(|(witness_id, _)| {
+                    matches!(
+                        self.resolver.resolve_pub_witness_ord(*witness_id),
+                        Ok(WitnessOrd::Mined(_))
+                    )
+                })
+                .copied()
+                .unwrap_or(candidates[0]);
+            self.consignment.select_anchor(bundle_id, witness_id, anchor);
+            for (stale_id, _) in candidates.into_iter().filter(|(id, _)| *id != witness_id) {
+                self.status
+                    .borrow_mut()
+                    .add_info(Info::AnchorAlternativeStale(bundle_id, stale_id));
+            }
+        }
+    }
+
     fn validate_commitments(&mut self) {
+        self.select_anchors();
+
+        let mut fee_checked = BTreeSet::new();
         for bundle_id in self.consignment.bundle_ids() {
             let Some(bundle) = self.consignment.bundle(bundle_id) else {
                 self.status
@@ -392,62 +963,117 @@ impl<
                 continue;
             };
 
+            // [VALIDATION]: Anchors must use one of layers 1 the contract genesis commits to,
+            //               preventing replays of the contract history on other chains
+            if !self.layers1.contains(&witness_id.layer1()) {
+                self.status
+                    .borrow_mut()
+                    .add_failure(Failure::AnchorLayer1Unsupported(bundle_id, witness_id));
+            }
+
             // [VALIDATION]: We validate that the seals were properly defined on BP-type layers
             let (seals, input_map) = self.validate_seal_definitions(witness_id.layer1(), bundle);
 
-            // [VALIDATION]: We validate that the seals were properly closed on BP-type layers
-            let Some(witness_tx) = self.validate_seal_commitments(
-                &seals,
-                bundle_id,
-                witness_id,
-                bundle.close_method,
-                anchor,
-            ) else {
-                continue;
+            // Anchors verified by the previous validations are not verified once again; the
+            // rest of the witness checks depend on the validation policy and are always performed
+            let witness_tx = if self.resolver.is_anchor_verified(witness_id, bundle_id) {
+                let status = self.witness_seal_status(witness_id);
+                self.report_seals(&seals, bundle_id, witness_id, |_| status);
+                // Resolvers keep the witnesses of the verified anchors, so the transaction may be
+                // absent only if the resolver was provided with the verification results alone
+                let Ok(witness_tx) = self.resolver.resolve_pub_witness(witness_id) else {
+                    continue;
+                };
+                witness_tx
+            } else {
+                let failures = self.status.borrow().failures.len();
+
+                // [VALIDATION]: We validate that the seals were properly closed on BP-type layers
+                let Some(witness_tx) = self.validate_seal_commitments(
+                    &seals,
+                    bundle_id,
+                    witness_id,
+                    bundle.close_method,
+                    anchor,
+                ) else {
+                    self.report_seals(&seals, bundle_id, witness_id, |_| {
+                        SealStatus::WitnessUnknown
+                    });
+                    continue;
+                };
+                let closing_failed = self.status.borrow().failures[failures..]
+                    .iter()
+                    .any(|failure| !matches!(failure, Failure::SealInvalidMethod(..)));
+                let status = self.witness_seal_status(witness_id);
+                self.report_seals(&seals, bundle_id, witness_id, |seal| {
+                    if closing_failed || seal.method() != bundle.close_method {
+                        SealStatus::WitnessInvalid
+                    } else {
+                        status
+                    }
+                });
+
+                if self.status.borrow().failures.len() == failures {
+                    self.resolver.anchor_verified(witness_id, bundle_id);
+                }
+                witness_tx
             };
 
+            // [VALIDATION]: We validate that the witness pays a reasonable fee
+            if fee_checked.insert(witness_id) {
+                self.validate_fee_rate(witness_id, &witness_tx);
+            }
+
             // [VALIDATION]: We validate bundle commitments to the input map
-            self.validate_bundle_commitments(bundle_id, bundle, witness_tx, input_map);
+            *self.status.borrow_mut() +=
+                bundle.verify_input_map_with_id(bundle_id, &witness_tx, &input_map);
+
+            // [VALIDATION]: We validate that the state is not assigned to dust outputs
+            self.validate_dust(bundle, witness_id, &witness_tx);
         }
-    }
 
-    /// Validates that the transition bundle is internally consistent: inputs of
-    /// its state transitions correspond to the way how they are committed
-    /// in the input map of the bundle; and these inputs are real inputs of
-    /// the transaction.
-    fn validate_bundle_commitments(
-        &self,
-        bundle_id: BundleId,
-        bundle: &TransitionBundle,
-        pub_witness: XWitnessTx,
-        input_map: BTreeMap<OpId, BTreeSet<XOutpoint>>,
-    ) {
-        let witness_id = pub_witness.witness_id();
-        for (vin, opid) in &bundle.input_map {
-            let Some(outpoints) = input_map.get(opid) else {
-                self.status
-                    .borrow_mut()
-                    .add_failure(Failure::BundleExtraTransition(bundle_id, *opid));
-                continue;
-            };
-            let layer1 = pub_witness.layer1();
-            let pub_witness = pub_witness.as_reduced_unsafe();
-            let Some(input) = pub_witness.inputs.get(vin.to_usize()) else {
-                self.status
-                    .borrow_mut()
-                    .add_failure(Failure::BundleInvalidInput(bundle_id, *opid, witness_id));
-                continue;
-            };
-            if !outpoints.contains(&XChain::with(layer1, input.prev_output)) {
+        // [VALIDATION]: Each seal can be closed only once, so two different transitions
+        //               spending the same output can't be both valid
+        for (opout, spenders) in self.consignment.double_spends() {
+            let mut spenders = spenders.into_iter();
+            let op1 = spenders.next().expect("double spend has multiple spenders");
+            for op2 in spenders {
                 self.status
                     .borrow_mut()
-                    .add_failure(Failure::BundleInvalidCommitment(
-                        bundle_id, *vin, witness_id, *opid,
-                    ));
+                    .add_failure(Failure::DoubleSpend { opout, op1, op2 });
+            }
+        }
+    }
+
+    /// Detects status of the seals closed by a witness transaction which was
+    /// resolved and verified.
+    fn witness_seal_status(&self, witness_id: XWitnessId) -> SealStatus {
+        match self.resolver.resolve_pub_witness_ord(witness_id) {
+            Ok(WitnessOrd::Mined(_)) => SealStatus::Closed,
+            Ok(WitnessOrd::Tentative | WitnessOrd::TentativeChained(_)) => {
+                SealStatus::PendingWitness
             }
+            Ok(WitnessOrd::Archived) | Err(_) => SealStatus::WitnessUnknown,
         }
     }
 
+    /// Records status of the seals closed by the bundle witness.
+    fn report_seals(
+        &self,
+        seals: &[XOutputSeal],
+        bundle_id: BundleId,
+        witness_id: XWitnessId,
+        status: impl Fn(&XOutputSeal) -> SealStatus,
+    ) {
+        let reports = seals.iter().map(|seal| SealReport {
+            seal: *seal,
+            bundle_id,
+            witness_id,
+            status: status(seal),
+        });
+        self.status.borrow_mut().seals.extend(reports);
+    }
+
     /// Bitcoin- and liquid-specific commitment validation using deterministic
     /// bitcoin commitments with opret and tapret schema.
     fn validate_seal_commitments(
@@ -462,6 +1088,7 @@ impl<
         // transition inputs.
         // Here the method can do SPV proof instead of querying the indexer. The SPV
         // proofs can be part of the consignments, but do not require .
+        self.status.borrow_mut().stats.witnesses += 1;
         match self.resolver.resolve_pub_witness(witness_id) {
             Err(err) => {
                 // We wre unable to retrieve corresponding transaction, so can't check.
@@ -473,48 +1100,20 @@ impl<
                 // the anchor, i.e. consignment is invalid. We are proceeding with further
                 // validation in order to detect the rest of problems (and reporting the
                 // failure!)
-                self.status
-                    .borrow_mut()
-                    .add_failure(Failure::SealNoPubWitness(bundle_id, witness_id, err));
+                let mut status = self.status.borrow_mut();
+                status.stats.witnesses_unresolved += 1;
+                status.add_failure(Failure::SealNoPubWitness(bundle_id, witness_id, err));
                 None
             }
             Ok(pub_witness) => {
-                let seals = seals.as_ref();
-                for seal in seals.iter().filter(|seal| seal.method() != close_method) {
-                    self.status
-                        .borrow_mut()
-                        .add_failure(Failure::SealInvalidMethod(bundle_id, *seal));
-                }
-                match (close_method, anchor.clone()) {
-                    (
-                        CloseMethod::TapretFirst,
-                        EAnchor {
-                            mpc_proof,
-                            dbc_proof: DbcProof::Tapret(tapret),
-                            ..
-                        },
-                    ) => {
-                        let witness = pub_witness.clone().map(|tx| Witness::with(tx, tapret));
-                        self.validate_seal_closing(seals, bundle_id, witness, mpc_proof)
-                    }
-                    (
-                        CloseMethod::OpretFirst,
-                        EAnchor {
-                            mpc_proof,
-                            dbc_proof: DbcProof::Opret(opret),
-                            ..
-                        },
-                    ) => {
-                        let witness = pub_witness.clone().map(|tx| Witness::with(tx, opret));
-                        self.validate_seal_closing(seals, bundle_id, witness, mpc_proof)
-                    }
-                    (_, _) => {
-                        self.status
-                            .borrow_mut()
-                            .add_failure(Failure::AnchorMethodMismatch(bundle_id));
-                    }
-                }
-
+                *self.status.borrow_mut() += verify_seal_closure(
+                    self.contract_id,
+                    seals,
+                    &pub_witness,
+                    anchor,
+                    bundle_id,
+                    close_method,
+                );
                 Some(pub_witness)
             }
         }
@@ -542,6 +1141,7 @@ impl<
 
             // Checking that witness transaction closes seals defined by transition previous
             // outputs.
+            let mut released = false;
             for input in &transition.inputs {
                 let Opout { op, ty, no } = input.prev_out;
 
@@ -601,6 +1201,32 @@ impl<
                             .add_failure(Failure::OperationAbsent(op));
                         continue;
                     };
+                    // State burned on the other layer 1 is spent only by the paired release
+                    if witness_id.layer1() != layer1 {
+                        match self.paired_release(prev_op) {
+                            Some(release) if release == transition.transition_type => {
+                                released = true;
+                                self.validate_burn_confirmed(opid, op, witness_id);
+                            }
+                            Some(release) => {
+                                self.status
+                                    .borrow_mut()
+                                    .add_failure(Failure::ReleaseTypeMismatch(opid, op, release));
+                                continue;
+                            }
+                            None => {
+                                self.status
+                                    .borrow_mut()
+                                    .add_failure(Failure::HistoryLayer1Mixed {
+                                        opid,
+                                        prev_id: op,
+                                        layer1,
+                                        prev_layer1: witness_id.layer1(),
+                                    });
+                                continue;
+                            }
+                        }
+                    }
 
                     match seal.try_to_output_seal(witness_id) {
                         Ok(seal) => seal,
@@ -625,54 +1251,1692 @@ impl<
                     .or_default()
                     .insert(seal.map(|seal| Outpoint::new(seal.txid, seal.vout)).into());
             }
+
+            if !released && self.is_release(transition.transition_type) {
+                self.status
+                    .borrow_mut()
+                    .add_failure(Failure::ReleaseWithoutBurn(opid));
+            }
         }
         (seals, input_map)
     }
 
-    /// Single-use-seal closing validation.
-    ///
-    /// Checks that the set of seals is closed over the message, which is
-    /// multi-protocol commitment, by utilizing witness, consisting of
-    /// transaction with deterministic bitcoin commitments (defined by
-    /// generic type `Dbc`) and extra-transaction data, which are taken from
-    /// anchor's DBC proof.
-    ///
-    /// Additionally, checks that the provided message contains commitment to
-    /// the bundle under the current contract.
-    fn validate_seal_closing<'seal, Seal: 'seal, Dbc: dbc::Proof>(
-        &self,
-        seals: impl IntoIterator<Item = &'seal Seal>,
-        bundle_id: BundleId,
-        witness: XChain<Witness<Dbc>>,
-        mpc_proof: mpc::MerkleProof,
-    ) where
-        XChain<Witness<Dbc>>: SealWitness<Seal, Message = mpc::Commitment>,
-    {
-        let message = mpc::Message::from(bundle_id);
-        let witness_id = witness.witness_id();
-        let anchor = Anchor::new(mpc_proof, witness.as_reduced_unsafe().proof.clone());
-        // [VALIDATION]: Checking anchor MPC commitment
-        match anchor.convolve(self.contract_id, message) {
-            Err(err) => {
-                // The operation is not committed to bitcoin transaction graph!
-                // Ultimate failure. But continuing to detect the rest (after reporting it).
+    /// Checks that the witness of the burn transition, which state is released
+    /// by the transition `opid` on another layer 1, is mined. Witnesses which
+    /// can't be resolved are reported when the operations are ordered.
+    fn validate_burn_confirmed(&self, opid: OpId, burn: OpId, witness_id: XWitnessId) {
+        match self.resolver.resolve_pub_witness_ord(witness_id) {
+            Ok(WitnessOrd::Mined(_)) | Err(_) => {}
+            Ok(_) => {
                 self.status
                     .borrow_mut()
-                    .add_failure(Failure::MpcInvalid(bundle_id, witness_id, err));
-            }
-            Ok(commitment) => {
-                // [VALIDATION]: CHECKING SINGLE-USE-SEALS
-                witness
-                    .verify_many_seals(seals, &commitment)
-                    .map_err(|err| {
-                        self.status.borrow_mut().add_failure(Failure::SealsInvalid(
-                            bundle_id,
-                            witness_id,
-                            err.to_string(),
-                        ));
-                    })
-                    .ok();
+                    .add_failure(Failure::BurnUnconfirmed(opid, burn));
             }
         }
     }
+
+    /// Checks the values of the witness transaction outputs hosting the state
+    /// assigned by the bundle transitions against the schema dust limit.
+    fn validate_dust(
+        &self,
+        bundle: &TransitionBundle,
+        witness_id: XWitnessId,
+        witness_tx: &XWitnessTx,
+    ) {
+        let Some(dust) = self.consignment.schema().ext.dust_limit() else {
+            return;
+        };
+        let tx = witness_tx.as_reduced_unsafe();
+        let txid = tx.txid();
+        for (opid, transition) in &bundle.known_transitions {
+            for assigns in transition.assignments.values() {
+                for no in 0..assigns.len_u16() {
+                    let Ok(Some(seal)) = assigns.revealed_seal_at(no) else {
+                        continue;
+                    };
+                    let Ok(seal) = seal.try_to_output_seal(witness_id) else {
+                        continue;
+                    };
+                    let seal_ref = seal.as_reduced_unsafe();
+                    if seal_ref.txid != txid {
+                        continue;
+                    }
+                    let Some(output) = tx.outputs.get(seal_ref.vout.into_usize()) else {
+                        continue;
+                    };
+                    let value = output.value.sats();
+                    if value >= dust.min_value {
+                        continue;
+                    }
+                    let mut status = self.status.borrow_mut();
+                    if self.policy.strict_dust {
+                        status.add_failure(Failure::OutputBelowDust(
+                            *opid,
+                            seal,
+                            value,
+                            dust.min_value,
+                        ));
+                    } else {
+                        status.add_warning(Warning::OutputBelowDust(
+                            *opid,
+                            seal,
+                            value,
+                            dust.min_value,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the fee rate of the witness transaction against the policy,
+    /// if the resolver provides values of all the outputs it spends.
+    fn validate_fee_rate(&self, witness_id: XWitnessId, witness_tx: &XWitnessTx) {
+        let tx = witness_tx.as_reduced_unsafe();
+        let Some(spent) = tx
+            .inputs()
+            .map(|input| {
+                let outpoint = XChain::<Outpoint>::with(witness_id.layer1(), input.prev_output);
+                self.resolver.resolve_spent_value(outpoint.into())
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+        let spent = spent.into_iter().sum::<Sats>();
+        let Some(fee) = spent.checked_sub(tx.outputs().map(|output| output.value).sum::<Sats>())
+        else {
+            return;
+        };
+        let fee = fee.sats();
+        let vbytes = tx.vbytes().into_u32();
+        if !self.policy.accepts_fee(fee, vbytes) {
+            self.status
+                .borrow_mut()
+                .add_warning(Warning::AbnormalFeeRate(witness_id, fee, vbytes));
+        }
+    }
+}
+
+/// Consignment containing nothing but a proposed contract genesis, used for the
+/// dry-run issuance validation.
+struct IssuanceDraft<'draft> {
+    schema: &'draft Schema,
+    types: &'draft TypeSystem,
+    scripts: &'draft Scripts,
+    genesis: &'draft Genesis,
+}
+
+impl<'draft> ConsignmentApi for IssuanceDraft<'draft> {
+    fn schema(&self) -> &Schema { self.schema }
+
+    fn types(&self) -> &TypeSystem { self.types }
+
+    fn scripts(&self) -> &Scripts { self.scripts }
+
+    fn operation(&self, opid: OpId) -> Option<OpRef<'_>> {
+        Some(OpRef::Genesis(self.genesis)).filter(|_| self.genesis.id() == opid)
+    }
+
+    fn genesis(&self) -> &Genesis { self.genesis }
+
+    fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter { iter::empty() }
+
+    fn bundle(&self, _bundle_id: BundleId) -> Option<&TransitionBundle> { None }
+
+    fn anchor(&self, _bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)> { None }
+
+    fn op_witness_id(&self, _opid: OpId) -> Option<XWitnessId> { None }
+}
+
+/// Witness resolver for the consignments which must not reference any
+/// witnesses.
+struct NoWitnesses;
+
+impl ResolveWitness for NoWitnesses {
+    fn resolve_pub_witness(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<XWitnessTx, WitnessResolverError> {
+        Err(WitnessResolverError::Unknown(witness_id))
+    }
+
+    fn resolve_pub_witness_ord(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<WitnessOrd, WitnessResolverError> {
+        Err(WitnessResolverError::Unknown(witness_id))
+    }
+}
+
+impl Schema {
+    /// Performs dry-run validation of a proposed contract issuance.
+    ///
+    /// Runs the same validation path which is used for the contract genesis by
+    /// [`Validator::validate`], including the schema verification and the
+    /// execution of the genesis validation script, but without requiring any
+    /// anchors or witness transactions. This allows issuers to check issuance
+    /// parameters before committing to them on-chain.
+    ///
+    /// Returns the id of the prospective contract together with the validation
+    /// status.
+    pub fn dry_run_issuance<S: ContractStateAccess + ContractStateEvolve>(
+        &self,
+        genesis: &Genesis,
+        types: &TypeSystem,
+        scripts: &Scripts,
+        context: S::Context<'_>,
+    ) -> (ContractId, Status) {
+        let draft = IssuanceDraft {
+            schema: self,
+            types,
+            scripts,
+            genesis,
+        };
+        let status = Validator::<S, _, _>::validate(&draft, &NoWitnesses, genesis.testnet, context);
+        (genesis.contract_id(), status)
+    }
+}
+
+impl TransitionBundle {
+    /// Verifies that the bundle is internally consistent with the witness
+    /// transaction: inputs of its state transitions correspond to the way how
+    /// they are committed in the input map of the bundle; and these inputs are
+    /// real inputs of the witness transaction.
+    ///
+    /// The `prevouts` map lists outpoints of the seals closed by each of the
+    /// bundle state transitions. The returned status contains the same
+    /// failures which are reported by the [`Validator`] for the bundle.
+    pub fn verify_input_map(
+        &self,
+        witness: &XWitnessTx,
+        prevouts: &BTreeMap<OpId, BTreeSet<XOutpoint>>,
+    ) -> Status {
+        self.verify_input_map_with_id(self.bundle_id(), witness, prevouts)
+    }
+
+    fn verify_input_map_with_id(
+        &self,
+        bundle_id: BundleId,
+        witness: &XWitnessTx,
+        prevouts: &BTreeMap<OpId, BTreeSet<XOutpoint>>,
+    ) -> Status {
+        let mut status = Status::new();
+        let witness_id = witness.witness_id();
+        let layer1 = witness.layer1();
+        let tx = witness.as_reduced_unsafe();
+        for (vin, opid) in &self.input_map {
+            let Some(outpoints) = prevouts.get(opid) else {
+                status.add_failure(Failure::BundleExtraTransition(bundle_id, *opid));
+                continue;
+            };
+            let Some(input) = tx.inputs.get(vin.to_usize()) else {
+                status.add_failure(Failure::BundleInvalidInput(bundle_id, *opid, witness_id));
+                continue;
+            };
+            if !outpoints.contains(&XChain::with(layer1, input.prev_output)) {
+                status.add_failure(Failure::BundleInvalidCommitment(
+                    bundle_id, *vin, witness_id, *opid,
+                ));
+            }
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use std::borrow::Borrow;
+    use std::iter;
+
+    use aluvm::aluasm;
+    use aluvm::isa::{ControlFlowOp, Instr, InstructionSet};
+    use aluvm::library::{Lib, LibId, LibSite};
+    use aluvm::reg::{CoreRegs, Reg16, Reg32, RegA, RegS};
+    use amplify::confinement::{self, Confined, SmallBlob};
+    use amplify::num::u24;
+    use amplify::{Bytes32, Bytes64};
+    use bp::dbc::Method;
+    use bp::secp256k1::rand::thread_rng;
+    use bp::secp256k1::{Keypair, SECP256K1};
+    use bp::seals::txout::ExplicitSeal;
+    use bp::{ScriptPubkey, Tx, TxIn, TxOut, Txid, XOnlyPk};
+    use strict_encoding::{StrictDumb, StrictSerialize};
+    use strict_types::stl::std_stl;
+    use strict_types::{LibBuilder, SymbolicSys, SystemBuilder, TypeSystem};
+
+    use super::*;
+    use crate::validation::{
+        ChainEvent, ChainScenario, FixedClock, ScriptError, ScriptedResolver, Scripts,
+        WitnessCache,
+    };
+    use crate::vm::{
+        ContractOp, GlobalContractState, GlobalOrd, GlobalStateIter, OpInfo,
+        UnknownGlobalStateType, VmContext, WitnessPos,
+    };
+    use crate::{
+        AltLayer1, AltLayer1Set, AssetTag, AssetTags, Assign, AssignFungible, AssignmentType,
+        Assignments, AttachState, AuxProof, AuxProofDetails, AuxProofSchema, AuxProofType,
+        ChainSupplySchema, DataState, DocumentRevision, DustLimitSchema, FeatureSchema,
+        FeeCollector, FeeSchema, FungibleState, FungibleType, Genesis, GenesisSeal,
+        GlobalStateSchema, GlobalStateType, GraphSeal, Input, InputMap, IssuerAttestationError,
+        IssuerProofSchema, KeyValueRecord, Occurrences, OwnedStateSchema, RetentionSchema,
+        RevealedData, RevealedValue, SchemaExtensionType, Transition, TransitionSchema,
+        TypedAssigns, Valencies, Vin, VoidState, LIB_NAME_RGB_COMMIT,
+    };
+
+    /// Resolver treating all witnesses as mined.
+    pub(crate) struct Mined;
+
+    impl ResolveWitness for Mined {
+        fn resolve_pub_witness(
+            &self,
+            witness_id: XWitnessId,
+        ) -> Result<XWitnessTx, WitnessResolverError> {
+            Err(WitnessResolverError::Unknown(witness_id))
+        }
+
+        fn resolve_pub_witness_ord(
+            &self,
+            _witness_id: XWitnessId,
+        ) -> Result<WitnessOrd, WitnessResolverError> {
+            Ok(WitnessOrd::Mined(WitnessPos::new(1, 1_700_000_000).unwrap()))
+        }
+    }
+
+    /// Resolver reporting ordering of the witnesses with a function, which
+    /// returns `None` for the witnesses unknown to the resolver.
+    pub(crate) struct Ordered<F: Fn(XWitnessId) -> Option<WitnessOrd>>(pub(crate) F);
+
+    impl<F: Fn(XWitnessId) -> Option<WitnessOrd>> ResolveWitness for Ordered<F> {
+        fn resolve_pub_witness(
+            &self,
+            witness_id: XWitnessId,
+        ) -> Result<XWitnessTx, WitnessResolverError> {
+            Err(WitnessResolverError::Unknown(witness_id))
+        }
+
+        fn resolve_pub_witness_ord(
+            &self,
+            witness_id: XWitnessId,
+        ) -> Result<WitnessOrd, WitnessResolverError> {
+            (self.0)(witness_id).ok_or(WitnessResolverError::Unknown(witness_id))
+        }
+    }
+
+    /// Resolver knowing a single mined witness transaction and, optionally,
+    /// the value of all the outputs it spends.
+    pub(crate) struct Witness(pub(crate) XWitnessTx, pub(crate) Option<Sats>);
+
+    impl ResolveWitness for Witness {
+        fn resolve_pub_witness(
+            &self,
+            witness_id: XWitnessId,
+        ) -> Result<XWitnessTx, WitnessResolverError> {
+            if self.0.witness_id() == witness_id {
+                Ok(self.0.clone())
+            } else {
+                Err(WitnessResolverError::Unknown(witness_id))
+            }
+        }
+
+        fn resolve_pub_witness_ord(
+            &self,
+            witness_id: XWitnessId,
+        ) -> Result<WitnessOrd, WitnessResolverError> {
+            Mined.resolve_pub_witness_ord(witness_id)
+        }
+
+        fn resolve_spent_value(&self, _outpoint: XOutpoint) -> Option<Sats> { self.1 }
+    }
+
+    #[derive(Debug)]
+    pub(crate) struct NoState;
+
+    struct NoGlobals;
+
+    impl GlobalStateIter for NoGlobals {
+        type Data = DataState;
+        fn size(&mut self) -> u24 { u24::ZERO }
+        fn prev(&mut self) -> Option<(GlobalOrd, Self::Data)> { None }
+        fn last(&mut self) -> Option<(GlobalOrd, Self::Data)> { None }
+        fn reset(&mut self, _depth: u24) {}
+    }
+
+    impl ContractStateAccess for NoState {
+        fn global(
+            &self,
+            ty: GlobalStateType,
+        ) -> Result<GlobalContractState<impl GlobalStateIter>, UnknownGlobalStateType> {
+            Err::<GlobalContractState<NoGlobals>, _>(UnknownGlobalStateType(ty))
+        }
+        fn rights(&self, _outpoint: XOutpoint, _ty: AssignmentType) -> u32 { 0 }
+        fn fungible(
+            &self,
+            _outpoint: XOutpoint,
+            _ty: AssignmentType,
+        ) -> impl DoubleEndedIterator<Item = FungibleState> {
+            iter::empty()
+        }
+        fn data(
+            &self,
+            _outpoint: XOutpoint,
+            _ty: AssignmentType,
+        ) -> impl DoubleEndedIterator<Item = impl Borrow<DataState>> {
+            iter::empty::<DataState>()
+        }
+        fn attach(
+            &self,
+            _outpoint: XOutpoint,
+            _ty: AssignmentType,
+        ) -> impl DoubleEndedIterator<Item = impl Borrow<AttachState>> {
+            iter::empty::<AttachState>()
+        }
+    }
+
+    impl ContractStateEvolve for NoState {
+        type Context<'ctx> = ();
+        fn init(_context: Self::Context<'_>) -> Self { NoState }
+        fn evolve_state(&mut self, _op: OrdOpRef) -> Result<(), confinement::Error> { Ok(()) }
+    }
+
+    /// Contract state keeping the history of the global state in memory.
+    #[derive(Debug, Default)]
+    pub(crate) struct MemState(BTreeMap<GlobalStateType, Vec<(GlobalOrd, DataState)>>);
+
+    struct MemGlobals {
+        items: Vec<(GlobalOrd, DataState)>,
+        pos: usize,
+    }
+
+    impl GlobalStateIter for MemGlobals {
+        type Data = DataState;
+        fn size(&mut self) -> u24 { u24::try_from(self.items.len() as u32).unwrap() }
+        fn prev(&mut self) -> Option<(GlobalOrd, Self::Data)> {
+            self.pos = self.pos.checked_sub(1)?;
+            self.items.get(self.pos).cloned()
+        }
+        fn last(&mut self) -> Option<(GlobalOrd, Self::Data)> { self.items.get(self.pos).cloned() }
+        fn reset(&mut self, depth: u24) {
+            self.pos = self.items.len().saturating_sub(depth.to_u32() as usize + 1);
+        }
+    }
+
+    impl ContractStateAccess for MemState {
+        fn global(
+            &self,
+            ty: GlobalStateType,
+        ) -> Result<GlobalContractState<impl GlobalStateIter>, UnknownGlobalStateType> {
+            let items = self.0.get(&ty).cloned().unwrap_or_default();
+            let pos = items.len();
+            Ok(GlobalContractState::new(MemGlobals { items, pos }))
+        }
+        fn rights(&self, _outpoint: XOutpoint, _ty: AssignmentType) -> u32 { 0 }
+        fn fungible(
+            &self,
+            _outpoint: XOutpoint,
+            _ty: AssignmentType,
+        ) -> impl DoubleEndedIterator<Item = FungibleState> {
+            iter::empty()
+        }
+        fn data(
+            &self,
+            _outpoint: XOutpoint,
+            _ty: AssignmentType,
+        ) -> impl DoubleEndedIterator<Item = impl Borrow<DataState>> {
+            iter::empty::<DataState>()
+        }
+        fn attach(
+            &self,
+            _outpoint: XOutpoint,
+            _ty: AssignmentType,
+        ) -> impl DoubleEndedIterator<Item = impl Borrow<AttachState>> {
+            iter::empty::<AttachState>()
+        }
+    }
+
+    impl ContractStateEvolve for MemState {
+        type Context<'ctx> = ();
+        fn init(_context: Self::Context<'_>) -> Self { MemState::default() }
+        fn evolve_state(&mut self, op: OrdOpRef) -> Result<(), confinement::Error> {
+            for (ty, values) in op.globals().iter() {
+                let history = self.0.entry(*ty).or_default();
+                for (idx, data) in values.iter().enumerate() {
+                    let ord = GlobalOrd {
+                        op_ord: op.op_ord(),
+                        idx: idx as u16,
+                    };
+                    history.push((ord, data.clone()));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Type system containing the consensus types used by the global state
+    /// kinds with the structure known to the validation.
+    pub(crate) fn global_state_types() -> SymbolicSys {
+        let std = std_stl();
+        let lib = LibBuilder::new(libname!(LIB_NAME_RGB_COMMIT), [std.to_dependency()])
+            .transpile::<DocumentRevision>()
+            .transpile::<KeyValueRecord>()
+            .transpile::<Layer1Cap>()
+            .transpile::<ContractFeatures>()
+            .compile()
+            .unwrap();
+        SystemBuilder::new()
+            .import(lib)
+            .unwrap()
+            .import(std)
+            .unwrap()
+            .finalize()
+            .unwrap()
+    }
+
+    pub(crate) struct Consignment {
+        schema: Schema,
+        pub(crate) types: TypeSystem,
+        scripts: Scripts,
+        pub(crate) genesis: Genesis,
+    }
+
+    impl Consignment {
+        pub(crate) fn issue(schema: Schema, scripts: Scripts) -> Self {
+            let mut genesis = Genesis::strict_dumb();
+            genesis.schema_id = schema.schema_id();
+            Consignment {
+                schema,
+                types: TypeSystem::default(),
+                scripts,
+                genesis,
+            }
+        }
+    }
+
+    impl ConsignmentApi for Consignment {
+        fn schema(&self) -> &Schema { &self.schema }
+        fn types(&self) -> &TypeSystem { &self.types }
+        fn scripts(&self) -> &Scripts { &self.scripts }
+        fn operation(&self, opid: OpId) -> Option<OpRef<'_>> {
+            if opid == self.genesis.id() {
+                return Some(OpRef::Genesis(&self.genesis));
+            }
+            None
+        }
+        fn genesis(&self) -> &Genesis { &self.genesis }
+        fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter { iter::empty() }
+        fn bundle(&self, _bundle_id: BundleId) -> Option<&TransitionBundle> { None }
+        fn anchor(&self, _bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)> { None }
+        fn op_witness_id(&self, _opid: OpId) -> Option<XWitnessId> { None }
+    }
+
+    /// Consignment with the transition bundles, all of them sharing the same
+    /// anchor.
+    pub(crate) struct Ledger {
+        pub(crate) schema: Schema,
+        pub(crate) types: TypeSystem,
+        pub(crate) scripts: Scripts,
+        pub(crate) genesis: Genesis,
+        pub(crate) anchor: EAnchor,
+        pub(crate) bundles: BTreeMap<BundleId, (XWitnessId, TransitionBundle)>,
+    }
+
+    impl Ledger {
+        pub(crate) fn with_bundles(
+            schema: Schema,
+            bundles: impl IntoIterator<Item = (XWitnessId, TransitionBundle)>,
+        ) -> Self {
+            let Consignment {
+                schema,
+                types,
+                scripts,
+                genesis,
+            } = Consignment::issue(schema, Scripts::default());
+            Ledger {
+                schema,
+                types,
+                scripts,
+                genesis,
+                anchor: EAnchor::strict_dumb(),
+                bundles: bundles
+                    .into_iter()
+                    .map(|(witness_id, bundle)| (bundle.bundle_id(), (witness_id, bundle)))
+                    .collect(),
+            }
+        }
+
+        pub(crate) fn validate(&self, resolver: &impl ResolveWitness) -> Status {
+            Validator::<NoState, _, _>::validate(self, resolver, self.genesis.testnet, ())
+        }
+    }
+
+    impl ConsignmentApi for Ledger {
+        fn schema(&self) -> &Schema { &self.schema }
+        fn types(&self) -> &TypeSystem { &self.types }
+        fn scripts(&self) -> &Scripts { &self.scripts }
+        fn operation(&self, opid: OpId) -> Option<OpRef<'_>> {
+            if opid == self.genesis.id() {
+                return Some(OpRef::Genesis(&self.genesis));
+            }
+            self.bundles
+                .values()
+                .find_map(|(_, bundle)| bundle.known_transitions.get(&opid))
+                .map(OpRef::Transition)
+        }
+        fn genesis(&self) -> &Genesis { &self.genesis }
+        fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter {
+            self.bundles.keys().copied().collect::<Vec<_>>().into_iter()
+        }
+        fn bundle(&self, bundle_id: BundleId) -> Option<&TransitionBundle> {
+            self.bundles.get(&bundle_id).map(|(_, bundle)| bundle)
+        }
+        fn anchor(&self, bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)> {
+            self.bundles
+                .get(&bundle_id)
+                .map(|(witness_id, _)| (*witness_id, &self.anchor))
+        }
+        fn op_witness_id(&self, opid: OpId) -> Option<XWitnessId> {
+            self.bundles
+                .values()
+                .find(|(_, bundle)| bundle.known_transitions.contains_key(&opid))
+                .map(|(witness_id, _)| *witness_id)
+        }
+    }
+
+    /// Bundle of the transitions, each of them spending its own input of the
+    /// witness transaction.
+    pub(crate) fn bundle(transitions: impl IntoIterator<Item = Transition>) -> TransitionBundle {
+        let transitions = transitions
+            .into_iter()
+            .map(|transition| (transition.id(), transition))
+            .collect::<BTreeMap<_, _>>();
+        let input_map = transitions
+            .keys()
+            .enumerate()
+            .map(|(no, opid)| (Vin::from_u32(no as u32), *opid))
+            .collect::<BTreeMap<_, _>>();
+        TransitionBundle {
+            input_map: InputMap::from(Confined::try_from(input_map).unwrap()),
+            known_transitions: Confined::try_from(transitions).unwrap(),
+            ..TransitionBundle::strict_dumb()
+        }
+    }
+
+    #[test]
+    fn script_libs() {
+        let dep = Lib::assemble::<Instr>(&aluasm! { ret ; }).unwrap();
+        let dep_id = dep.id();
+        let code = [
+            Instr::ControlFlow(ControlFlowOp::Call(LibSite::with(0, dep_id))),
+            Instr::ControlFlow(ControlFlowOp::Ret),
+        ];
+        let lib = Lib::assemble::<Instr>(&code).unwrap();
+        let lib_id = lib.id();
+        assert!(lib.libs.iter().any(|id| *id == dep_id));
+        let other = Lib::assemble::<Instr>(&aluasm! { fail ; }).unwrap();
+        let other_id = other.id();
+
+        let mut schema = Schema::strict_dumb();
+        schema.genesis.validator = Some(LibSite::with(0, lib_id));
+        let validate = |scripts: BTreeMap<LibId, Lib>| {
+            let consignment = Consignment::issue(schema.clone(), Confined::from_checked(scripts));
+            let validator =
+                Validator::<NoState, _, _>::init(&consignment, &Mined, (), default!(), 0);
+            validator.validate_scripts(&schema);
+            validator.status.into_inner().failures
+        };
+
+        assert_eq!(validate(bmap! { lib_id => lib.clone(), dep_id => dep.clone() }), vec![]);
+        // Library used by the schema is absent
+        assert_eq!(validate(bmap! { dep_id => dep }), vec![Failure::ScriptLibAbsent(lib_id)]);
+        // Library used by another library is absent
+        assert_eq!(validate(bmap! { lib_id => lib.clone() }), vec![Failure::ScriptLibAbsent(
+            dep_id
+        )]);
+        // Library is stored under the id of another library
+        assert_eq!(validate(bmap! { lib_id => lib, dep_id => other }), vec![
+            Failure::ScriptLibIdMismatch(dep_id, other_id)
+        ]);
+    }
+
+    #[test]
+    fn script_profiling() {
+        let code = aluasm! {
+            put     a8[1],5     ;
+            put     a16[2],9    ;
+            put     a16[2],1    ;
+            ret                 ;
+        };
+        let lib = Lib::assemble::<Instr>(&code).unwrap();
+        let lib_id = lib.id();
+        let mut schema = Schema::strict_dumb();
+        schema.genesis.validator = Some(LibSite::with(0, lib_id));
+        let consignment =
+            Consignment::issue(schema, Confined::from_checked(bmap! { lib_id => lib }));
+        let validate = |policy| {
+            Validator::<NoState, _, _>::validate_with_policy(
+                &consignment,
+                &Mined,
+                false,
+                (),
+                policy,
+            )
+        };
+
+        let status = validate(ValidationPolicy::default());
+        assert_eq!(status.failures, vec![]);
+        assert!(status.info.is_empty());
+
+        let status = validate(ValidationPolicy::default().with_script_profiling());
+        assert_eq!(status.failures, vec![]);
+        let [Info::ScriptProfile(opid, stats)] = status.info.as_slice() else {
+            panic!("no script profile in {:?}", status.info);
+        };
+        assert_eq!(*opid, consignment.genesis.id());
+        assert_eq!(stats.instructions, 4);
+        assert!(stats.complexity > 0);
+        // Registers a8[1] and a16[2] written
+        assert_eq!(stats.memory, 3);
+    }
+
+    #[test]
+    fn dry_run_issuance() {
+        let code = aluasm! {
+            put     a8[0],7     ;
+            fail                ;
+        };
+        let lib = Lib::assemble::<Instr>(&code).unwrap();
+        let lib_id = lib.id();
+        let scripts = Confined::from_checked(bmap! { lib_id => lib });
+        let types = TypeSystem::default();
+
+        let schema = Schema::strict_dumb();
+        let mut genesis = Genesis::strict_dumb();
+        genesis.schema_id = schema.schema_id();
+        let (contract_id, status) =
+            schema.dry_run_issuance::<NoState>(&genesis, &types, &scripts, ());
+        assert_eq!(contract_id, genesis.contract_id());
+        assert_eq!(status.failures, vec![]);
+        assert_eq!(status.validity(), Validity::Valid);
+
+        // Genesis script is executed and its error code is reported
+        let mut failing = schema.clone();
+        failing.genesis.validator = Some(LibSite::with(0, lib_id));
+        genesis.schema_id = failing.schema_id();
+        let (contract_id, status) =
+            failing.dry_run_issuance::<NoState>(&genesis, &types, &scripts, ());
+        assert_eq!(contract_id, genesis.contract_id());
+        assert!(status
+            .failures
+            .contains(&Failure::ScriptFailure(genesis.id(), ScriptError::Code(7))));
+        let (_, status) =
+            failing.dry_run_issuance::<NoState>(&genesis, &types, &Scripts::default(), ());
+        assert!(status.failures.contains(&Failure::ScriptLibAbsent(lib_id)));
+
+        // Genesis must commit to the schema
+        let (_, status) = schema.dry_run_issuance::<NoState>(&genesis, &types, &scripts, ());
+        assert!(status.failures.contains(&Failure::SchemaMismatch {
+            expected: failing.schema_id(),
+            actual: schema.schema_id()
+        }));
+        assert_eq!(status.validity(), Validity::Invalid);
+    }
+
+    #[test]
+    fn unconfirmed_ancestry() {
+        let policy = ValidationPolicy::default();
+        assert!(policy.accepts(WitnessOrd::Tentative));
+        assert!(policy.accepts(WitnessOrd::TentativeChained(MAX_UNCONFIRMED_ANCESTRY)));
+        assert!(!policy.accepts(WitnessOrd::TentativeChained(MAX_UNCONFIRMED_ANCESTRY + 1)));
+        let policy = ValidationPolicy::confirmed_ancestry();
+        assert!(policy.accepts(WitnessOrd::Tentative));
+        assert!(!policy.accepts(WitnessOrd::TentativeChained(1)));
+
+        let witness_id = XWitnessId::with(Layer1::Bitcoin, Txid::from([1u8; 32]));
+        for (ord, checked) in [
+            (WitnessOrd::TentativeChained(0), WitnessOrd::Tentative),
+            (WitnessOrd::TentativeChained(1), WitnessOrd::TentativeChained(1)),
+            (WitnessOrd::Tentative, WitnessOrd::Tentative),
+            (WitnessOrd::Archived, WitnessOrd::Archived),
+        ] {
+            let resolver = CheckedWitnessResolver::from(Ordered(|_| Some(ord)));
+            assert_eq!(resolver.resolve_pub_witness_ord(witness_id).unwrap(), checked);
+        }
+    }
+
+    #[test]
+    fn future_timestamps() {
+        let consignment = Consignment::issue(Schema::strict_dumb(), Scripts::default());
+        let timestamp = consignment.genesis.timestamp;
+        let validate = |now| {
+            let testnet = consignment.genesis.testnet;
+            Validator::<NoState, _, _>::validate_with_clock(
+                &consignment,
+                &Mined,
+                testnet,
+                (),
+                default!(),
+                FixedClock(now),
+            )
+        };
+
+        let status = validate(timestamp - MAX_TIME_DRIFT);
+        assert_eq!(status.warnings, vec![]);
+
+        // Genesis created ahead of the clock of the validating host is not a consensus failure
+        let now = timestamp - MAX_TIME_DRIFT - 1;
+        let status = validate(now);
+        assert_eq!(status.failures, vec![]);
+        assert_eq!(status.warnings, vec![Warning::GenesisTimestampFuture(timestamp, now)]);
+
+        // Clocks far in the future must not overflow the drift computation
+        let status = validate(i64::MAX);
+        assert_eq!(status.warnings, vec![]);
+    }
+
+    #[test]
+    fn input_map() {
+        let bundle = TransitionBundle::strict_dumb();
+        let bundle_id = bundle.bundle_id();
+        let opid = OpId::strict_dumb();
+        let witness = XChain::Bitcoin(Tx::strict_dumb());
+        let witness_id = witness.witness_id();
+
+        let status = bundle.verify_input_map(&witness, &bmap! {});
+        assert_eq!(status.failures, vec![Failure::BundleExtraTransition(bundle_id, opid)]);
+
+        let prevouts =
+            bmap! { opid => bset! { XOutpoint::from(XChain::Bitcoin(Outpoint::strict_dumb())) } };
+        let status = bundle.verify_input_map(&witness, &prevouts);
+        assert_eq!(status.failures, vec![Failure::BundleInvalidInput(bundle_id, opid, witness_id)]);
+    }
+
+    #[test]
+    fn aux_proofs() {
+        let types = global_state_types();
+        let sem_id = *types.resolve("RGBCommit.DocumentRevision").unwrap();
+        let types = types.as_types().clone();
+        let ty = AuxProofType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema.ext.insert(AuxProofSchema {
+            types: tiny_bmap! { ty => AuxProofDetails { sem_id, max_len: 64 } },
+            genesis: tiny_bmap! { ty => Occurrences::Once },
+            transitions: none!(),
+            extensions: none!(),
+        });
+        let revision = DocumentRevision::new([1u8; 32]).to_data().unwrap();
+        let validate = |proofs: &[&[u8]], other: Option<AuxProofType>| {
+            let mut genesis = Genesis::strict_dumb();
+            genesis.schema_id = schema.schema_id();
+            for proof in proofs {
+                let proof = AuxProof::from(SmallBlob::from_checked(proof.to_vec()));
+                genesis.aux_proofs.add_proof(other.unwrap_or(ty), proof).unwrap();
+            }
+            let (_, status) =
+                schema.dry_run_issuance::<NoState>(&genesis, &types, &Scripts::default(), ());
+            (genesis.id(), status.failures)
+        };
+
+        assert_eq!(validate(&[&revision], None).1, vec![]);
+        let (opid, failures) = validate(&[], None);
+        assert!(matches!(failures[..], [Failure::SchemaAuxProofOccurrences(id, t, _)]
+            if id == opid && t == ty));
+        let (opid, failures) = validate(&[&revision], Some(AuxProofType::with(2)));
+        assert!(failures.contains(&Failure::SchemaUnknownAuxProofType(
+            opid,
+            AuxProofType::with(2)
+        )));
+        let (opid, failures) = validate(&[&[0xFF]], None);
+        assert_eq!(failures, vec![Failure::SchemaInvalidAuxProof(opid, ty, sem_id)]);
+        let (opid, failures) = validate(&[&[0u8; 65]], None);
+        assert_eq!(failures, vec![Failure::AuxProofOversized(opid, ty, 65, 64)]);
+    }
+
+    #[test]
+    fn chain_supply() {
+        let types = global_state_types();
+        let sem_id = *types.resolve("RGBCommit.Layer1Cap").unwrap();
+        let types = types.as_types().clone();
+        let caps = GlobalStateType::with(1);
+        let asset = AssignmentType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema.global_types = tiny_bmap! {
+            caps => GlobalStateSchema { kind: default!(), sem_id, max_items: u24::with(2) }
+        };
+        schema.owned_types =
+            tiny_bmap! { asset => OwnedStateSchema::Fungible(FungibleType::Unsigned64Bit) };
+        schema.genesis.globals = tiny_bmap! { caps => Occurrences::OnceOrMore };
+        schema.genesis.assignments = tiny_bmap! { asset => Occurrences::OnceOrMore };
+        schema.ext.insert(ChainSupplySchema {
+            assignment: asset,
+            caps,
+            bridges: none!(),
+        });
+
+        let tag = AssetTag::from([1u8; 32]);
+        let validate = |issued: &[(Layer1, u64)], caps_data: &[Layer1Cap]| {
+            let mut genesis = Genesis::strict_dumb();
+            genesis.schema_id = schema.schema_id();
+            genesis.alt_layers1 = AltLayer1Set::from(tiny_bset! { AltLayer1::Liquid });
+            genesis.asset_tags = AssetTags::from(tiny_bmap! { asset => tag });
+            for cap in caps_data {
+                let data = cap.to_strict_serialized::<U16MAX>().unwrap();
+                genesis.globals.add_state(caps, DataState::from(data)).unwrap();
+            }
+            let assigns = issued.iter().enumerate().map(|(vout, (layer1, amount))| {
+                let seal = GenesisSeal::with_blinding(
+                    Method::TapretFirst,
+                    Txid::from([1u8; 32]),
+                    vout as u32,
+                    0,
+                );
+                let seal = match layer1 {
+                    Layer1::Bitcoin => XChain::Bitcoin(seal),
+                    Layer1::Liquid => XChain::Liquid(seal),
+                };
+                Assign::revealed(seal, RevealedValue::new_random_blinding(*amount, tag))
+            });
+            genesis
+                .assignments
+                .insert(asset, TypedAssigns::Fungible(Confined::from_iter_checked(assigns)))
+                .unwrap();
+            let (_, status) =
+                schema.dry_run_issuance::<NoState>(&genesis, &types, &Scripts::default(), ());
+            (genesis.id(), status)
+        };
+        let caps_data = [
+            Layer1Cap { layer1: Layer1::Bitcoin, max_supply: 1000 },
+            Layer1Cap { layer1: Layer1::Liquid, max_supply: 100 },
+        ];
+
+        let issued = [(Layer1::Bitcoin, 800), (Layer1::Liquid, 60), (Layer1::Liquid, 40)];
+        let (_, status) = validate(&issued, &caps_data);
+        assert_eq!(status.failures, vec![]);
+        assert_eq!(status.supply, bmap! { Layer1::Bitcoin => 800, Layer1::Liquid => 100 });
+
+        let (opid, status) = validate(&[(Layer1::Bitcoin, 800), (Layer1::Liquid, 150)], &caps_data);
+        assert_eq!(status.failures, vec![Failure::ChainSupplyExceeded(
+            opid,
+            Layer1::Liquid,
+            150,
+            100
+        )]);
+
+        // Layers 1 without caps are not limited
+        let (_, status) = validate(&[(Layer1::Liquid, 150)], &caps_data[..1]);
+        assert_eq!(status.failures, vec![]);
+
+        let (opid, status) = validate(&[(Layer1::Liquid, u64::MAX), (Layer1::Liquid, 1)], &[]);
+        assert!(status
+            .failures
+            .contains(&Failure::ChainSupplyOverflow(opid, Layer1::Liquid)));
+
+        let (_, status) = validate(&[(Layer1::Bitcoin, 1)], &[caps_data[0], caps_data[0]]);
+        assert_eq!(status.failures, vec![Failure::ChainCapInvalid(caps)]);
+    }
+
+    #[test]
+    fn amount_overflow() {
+        let asset = AssignmentType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema.owned_types =
+            tiny_bmap! { asset => OwnedStateSchema::Fungible(FungibleType::Unsigned64Bit) };
+        schema.genesis.assignments = tiny_bmap! { asset => Occurrences::OnceOrMore };
+
+        let tag = AssetTag::from([1u8; 32]);
+        let validate = |amounts: &[u64]| {
+            let mut genesis = Genesis::strict_dumb();
+            genesis.schema_id = schema.schema_id();
+            genesis.asset_tags = AssetTags::from(tiny_bmap! { asset => tag });
+            let assigns = amounts.iter().enumerate().map(|(vout, amount)| {
+                let seal = GenesisSeal::with_blinding(
+                    Method::TapretFirst,
+                    Txid::from([1u8; 32]),
+                    vout as u32,
+                    0,
+                );
+                Assign::revealed(
+                    XChain::Bitcoin(seal),
+                    RevealedValue::new_random_blinding(*amount, tag),
+                )
+            });
+            genesis
+                .assignments
+                .insert(asset, TypedAssigns::Fungible(Confined::from_iter_checked(assigns)))
+                .unwrap();
+            let (_, status) = schema.dry_run_issuance::<NoState>(
+                &genesis,
+                &TypeSystem::default(),
+                &Scripts::default(),
+                (),
+            );
+            (genesis.id(), status)
+        };
+
+        let (_, status) = validate(&[u64::MAX - 1, 1]);
+        assert_eq!(status.failures, vec![]);
+
+        // Each of the issued amounts fits into u64, but not their sum
+        let (opid, status) = validate(&[u64::MAX, 1]);
+        assert_eq!(status.failures, vec![Failure::AmountOverflow(opid, asset)]);
+        let (opid, status) = validate(&[u64::MAX; 3]);
+        assert_eq!(status.failures, vec![Failure::AmountOverflow(opid, asset)]);
+    }
+
+    #[test]
+    fn retention() {
+        let types = global_state_types();
+        let sem_id = *types.resolve("RGBCommit.DocumentRevision").unwrap();
+        let types = types.as_types().clone();
+        let ty = AssignmentType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema.owned_types = tiny_bmap! { ty => OwnedStateSchema::Structured(sem_id) };
+        schema.genesis.assignments = tiny_bmap! { ty => Occurrences::OnceOrMore };
+
+        let data = DocumentRevision::new([1u8; 32]).to_data().unwrap();
+        let mut genesis = Genesis::strict_dumb();
+        let assigns = (0..3u32).map(|vout| {
+            let seal =
+                GenesisSeal::with_blinding(Method::TapretFirst, Txid::from([1u8; 32]), vout, 0);
+            Assign::revealed(XChain::Bitcoin(seal), RevealedData::with_salt(data.clone(), 0))
+        });
+        genesis
+            .assignments
+            .insert(ty, TypedAssigns::Structured(Confined::from_iter_checked(assigns)))
+            .unwrap();
+        let opid = genesis.id();
+        let superseded = bset! { Opout::new(opid, ty, 0), Opout::new(opid, ty, 2) };
+        // Validates the genesis together with transitions spending its outputs
+        let validate = |schema: &Schema, genesis: &Genesis, spent: &[u16]| {
+            let mut genesis = genesis.clone();
+            genesis.schema_id = schema.schema_id();
+            let id = genesis.id();
+            let transitions = spent.iter().map(|no| {
+                let mut transition = Transition::strict_dumb();
+                transition.inputs.push(Input::with(Opout::new(id, ty, *no))).unwrap();
+                transition
+            });
+            let witness_id = XWitnessId::with(Layer1::Bitcoin, Txid::from([2u8; 32]));
+            let bundle = bundle(transitions);
+            let mut cache = WitnessCache::new();
+            cache.cache_anchor(witness_id, bundle.bundle_id());
+            let mut ledger = Ledger::with_bundles(schema.clone(), [(witness_id, bundle)]);
+            ledger.types = types.clone();
+            ledger.genesis = genesis;
+            let mut status = ledger.validate(&cache.resolver(&Mined));
+            status.warnings.retain(|warning| {
+                matches!(warning, Warning::UncheckableConfidentialState(op, _) if *op == id)
+            });
+            status.info.retain(|info| matches!(info, Info::StatePruned(op, ..) if *op == id));
+            (id, status)
+        };
+
+        // Permanent state data can't be pruned
+        let mut pruned = genesis.clone();
+        assert_eq!(pruned.assignments.prune(opid, &schema, &superseded), 0);
+        assert_eq!(pruned.assignments, genesis.assignments);
+
+        schema.ext.insert(RetentionSchema { prunable: tiny_bset! { ty } });
+        assert_eq!(pruned.assignments.prune(opid, &schema, &superseded), 2);
+        assert_eq!(pruned.id(), opid);
+        let (id, status) = validate(&schema, &pruned, &[0, 2]);
+        assert_eq!(status.warnings, vec![]);
+        assert_eq!(status.info, vec![Info::StatePruned(id, ty, 2)]);
+
+        // Concealed data of the unspent state is still uncheckable
+        let (id, status) = validate(&schema, &pruned, &[0]);
+        assert_eq!(status.warnings, vec![Warning::UncheckableConfidentialState(id, ty)]);
+        assert_eq!(status.info, vec![Info::StatePruned(id, ty, 1)]);
+
+        schema.ext.remove(SchemaExtensionType::Retention);
+        let (id, status) = validate(&schema, &pruned, &[0, 2]);
+        assert_eq!(status.warnings, vec![Warning::UncheckableConfidentialState(id, ty); 2]);
+        assert_eq!(status.info, vec![]);
+    }
+
+    #[test]
+    fn issuer_attestation() {
+        /// Layout of the [`IssuerAttestation`] data using only the standard
+        /// types.
+        #[derive(Clone, Debug, Default)]
+        #[derive(StrictType, StrictEncode, StrictDecode)]
+        #[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+        struct AttestationLayout {
+            issuer: Bytes32,
+            sig: Bytes64,
+        }
+
+        let std = std_stl();
+        let lib = LibBuilder::new(libname!(LIB_NAME_RGB_COMMIT), [std.to_dependency()])
+            .transpile::<AttestationLayout>()
+            .compile()
+            .unwrap();
+        let sys = SystemBuilder::new()
+            .import(lib)
+            .unwrap()
+            .import(std)
+            .unwrap()
+            .finalize()
+            .unwrap();
+        let sem_id = *sys.resolve("RGBCommit.AttestationLayout").unwrap();
+        let types = sys.as_types().clone();
+
+        let ty = AuxProofType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema.ext.insert(AuxProofSchema {
+            types: tiny_bmap! { ty => AuxProofDetails { sem_id, max_len: 96 } },
+            genesis: tiny_bmap! { ty => Occurrences::NoneOrOnce },
+            transitions: none!(),
+            extensions: none!(),
+        });
+        schema.ext.insert(IssuerProofSchema { proof_type: ty });
+        let mut genesis = Genesis::strict_dumb();
+        genesis.schema_id = schema.schema_id();
+        let validate = |genesis: &Genesis, policy: ValidationPolicy| {
+            let draft = IssuanceDraft {
+                schema: &schema,
+                types: &types,
+                scripts: &Scripts::default(),
+                genesis,
+            };
+            let status = Validator::<NoState, _, _>::validate_with_policy(
+                &draft,
+                &NoWitnesses,
+                genesis.testnet,
+                (),
+                policy,
+            );
+            assert_eq!(status.failures, vec![]);
+            (status.warnings, status.info)
+        };
+        let policy = ValidationPolicy::default().with_issuer_verification();
+
+        let contract_id = genesis.contract_id();
+        assert_eq!(
+            validate(&genesis, policy),
+            (vec![Warning::IssuerUnattested(contract_id, IssuerAttestationError::Absent)], vec![])
+        );
+
+        let keypair = Keypair::new(SECP256K1, &mut thread_rng());
+        let issuer = XOnlyPk::from(keypair.x_only_public_key().0);
+        IssuerAttestation::sign(&genesis, ty, &keypair)
+            .embed(&mut genesis, ty)
+            .unwrap();
+        let contract_id = genesis.contract_id();
+        assert_eq!(
+            validate(&genesis, policy),
+            (vec![], vec![Info::IssuerAttested(contract_id, issuer)])
+        );
+        assert_eq!(validate(&genesis, default!()), (vec![], vec![]));
+
+        genesis.timestamp += 1;
+        let contract_id = genesis.contract_id();
+        assert_eq!(
+            validate(&genesis, policy),
+            (
+                vec![Warning::IssuerUnattested(
+                    contract_id,
+                    IssuerAttestationError::InvalidSignature(issuer)
+                )],
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn nxc_iterates_history() {
+        let ty = GlobalStateType::with(1);
+        let mut genesis = Genesis::strict_dumb();
+        for byte in 1..=3u8 {
+            let data = DataState::from(SmallBlob::from_checked(vec![byte]));
+            genesis.globals.add_state(ty, data).unwrap();
+        }
+        let op = OrdOpRef::Genesis(&genesis);
+        let mut state = MemState::default();
+        state.evolve_state(op).unwrap();
+
+        let asset_tags = AssetTags::default();
+        let prev_state = Assignments::default();
+        let redeemed = Valencies::default();
+        let context = VmContext {
+            contract_id: genesis.contract_id(),
+            asset_tags: &asset_tags,
+            op_info: OpInfo::with(genesis.id(), &op, &prev_state, &redeemed),
+            contract_state: Rc::new(RefCell::new(state)),
+        };
+
+        let nxc = ContractOp::<MemState>::NxC(ty, Reg16::Reg0, RegS::from(0));
+        let mut regs = CoreRegs::new();
+        nxc.exec(&mut regs, LibSite::default(), &context);
+        assert!(!regs.status());
+
+        regs = CoreRegs::new();
+        regs.set_n(RegA::A32, Reg32::Reg0, Some(0u32));
+        for byte in [3u8, 2, 1] {
+            nxc.exec(&mut regs, LibSite::default(), &context);
+            assert!(regs.status());
+            assert_eq!(regs.get_s(RegS::from(0)).map(AsRef::as_ref), Some(&[byte][..]));
+        }
+        nxc.exec(&mut regs, LibSite::default(), &context);
+        assert!(regs.status());
+        assert_eq!(regs.get_s(RegS::from(0)), None);
+        assert_eq!(*regs.get_n(RegA::A32, Reg32::Reg0), Some(3u32.into()));
+    }
+
+    #[test]
+    fn fee_collectors() {
+        let asset = AssignmentType::with(1);
+        let collector = GlobalStateType::with(1);
+        let tag = AssetTag::from([1u8; 32]);
+        let seal = |vout| {
+            XChain::Bitcoin(GraphSeal::with_blinded_vout(Method::TapretFirst, vout, vout as u64))
+        };
+        let outpoint = Outpoint::new(Txid::from([1u8; 32]), 0);
+        let on_outpoint = XChain::Bitcoin(GraphSeal::with_blinding(
+            Method::OpretFirst,
+            outpoint.txid,
+            outpoint.vout,
+            7,
+        ));
+
+        let mut transition = Transition::strict_dumb();
+        transition
+            .assignments
+            .insert(
+                asset,
+                TypedAssigns::Fungible(small_vec![
+                    Assign::revealed(on_outpoint, RevealedValue::new_random_blinding(4, tag)),
+                    Assign::ConfidentialSeal {
+                        seal: seal(1).to_secret_seal(),
+                        state: RevealedValue::new_random_blinding(5, tag),
+                        lock: none!(),
+                    },
+                    Assign::revealed(seal(2), RevealedValue::new_random_blinding(100, tag))
+                ]),
+            )
+            .unwrap();
+        let opid = transition.id();
+        let witness_id = XWitnessId::strict_dumb();
+        let op = OrdOpRef::Transition(&transition, witness_id, WitnessOrd::Tentative);
+
+        let validate = |min_amount: u64, collectors: &[&[u8]]| {
+            let mut schema = Schema::strict_dumb();
+            schema.ext.insert(FeeSchema {
+                assignment: asset,
+                collector,
+                min_amount,
+                transitions: tiny_bset! { transition.transition_type },
+            });
+            let mut genesis = Genesis::strict_dumb();
+            for data in collectors {
+                let data = DataState::from(SmallBlob::from_checked(data.to_vec()));
+                genesis.globals.add_state(collector, data).unwrap();
+            }
+            schema.validate_fees(opid, op, &genesis).failures
+        };
+        let by_outpoint = FeeCollector::Outpoint(XChain::Bitcoin(outpoint).into())
+            .to_strict_serialized::<U16MAX>()
+            .unwrap();
+        let by_seal = FeeCollector::Concealed(seal(1).to_secret_seal())
+            .to_strict_serialized::<U16MAX>()
+            .unwrap();
+
+        assert_eq!(validate(9, &[&by_outpoint, &by_seal]), vec![]);
+        assert_eq!(validate(10, &[&by_outpoint, &by_seal]), vec![Failure::FeeUnpaid(opid, 10, 9)]);
+        assert_eq!(validate(5, &[&by_seal]), vec![]);
+        assert_eq!(validate(5, &[&by_outpoint]), vec![Failure::FeeUnpaid(opid, 5, 4)]);
+        assert_eq!(validate(1, &[&[0xFF]]), vec![
+            Failure::FeeCollectorInvalid(collector),
+            Failure::FeeUnpaid(opid, 1, 0)
+        ]);
+    }
+
+    #[test]
+    fn dust_outputs() {
+        let mut tx = Tx::strict_dumb();
+        tx.outputs = Confined::from_checked(vec![
+            TxOut::new(ScriptPubkey::default(), 100u64),
+            TxOut::new(ScriptPubkey::default(), 1000u64),
+        ]);
+        let witness = XChain::Bitcoin(tx);
+        let witness_id = witness.witness_id();
+
+        let seal = |vout| {
+            XChain::Bitcoin(GraphSeal::with_blinded_vout(Method::TapretFirst, vout, 0))
+        };
+        let mut transition = Transition::strict_dumb();
+        transition
+            .assignments
+            .insert(
+                AssignmentType::with(1),
+                TypedAssigns::Declarative(small_vec![
+                    Assign::revealed(seal(0), VoidState::default()),
+                    Assign::revealed(seal(1), VoidState::default())
+                ]),
+            )
+            .unwrap();
+        let opid = transition.id();
+
+        let mut schema = Schema::strict_dumb();
+        schema.ext.insert(DustLimitSchema { min_value: 546 });
+        let ledger = Ledger::with_bundles(schema, [(witness_id, bundle([transition]))]);
+        let dust = XChain::Bitcoin(ExplicitSeal::new(
+            Method::TapretFirst,
+            Outpoint::new(*witness_id.as_reduced_unsafe(), 0),
+        ));
+
+        let resolver = Witness(witness, None);
+        let status = ledger.validate(&resolver);
+        assert_eq!(status.warnings, vec![Warning::OutputBelowDust(opid, dust, 100, 546)]);
+        assert!(!status.failures.iter().any(|f| f.code() == "OutputBelowDust"));
+
+        let status = Validator::<NoState, _, _>::validate_with_policy(
+            &ledger,
+            &resolver,
+            ledger.genesis.testnet,
+            (),
+            ValidationPolicy::default().with_strict_dust(),
+        );
+        assert!(status.warnings.is_empty());
+        assert!(status
+            .failures
+            .contains(&Failure::OutputBelowDust(opid, dust, 100, 546)));
+    }
+
+    #[test]
+    fn dust_outputs_cached() {
+        let mut tx = Tx::strict_dumb();
+        tx.outputs = Confined::from_checked(vec![TxOut::new(ScriptPubkey::default(), 100u64)]);
+        let witness = XChain::Bitcoin(tx);
+        let witness_id = witness.witness_id();
+
+        let seal = XChain::Bitcoin(GraphSeal::with_blinded_vout(Method::TapretFirst, 0, 0));
+        let mut transition = Transition::strict_dumb();
+        transition
+            .assignments
+            .insert(
+                AssignmentType::with(1),
+                TypedAssigns::Declarative(small_vec![Assign::revealed(seal, VoidState::default())]),
+            )
+            .unwrap();
+        let opid = transition.id();
+        let bundle = bundle([transition]);
+        let bundle_id = bundle.bundle_id();
+
+        let mut schema = Schema::strict_dumb();
+        schema.ext.insert(DustLimitSchema { min_value: 546 });
+        let ledger = Ledger::with_bundles(schema, [(witness_id, bundle)]);
+        let dust = XChain::Bitcoin(ExplicitSeal::new(
+            Method::TapretFirst,
+            Outpoint::new(*witness_id.as_reduced_unsafe(), 0),
+        ));
+
+        let mut cache = WitnessCache::new();
+        cache.cache_anchor(witness_id, bundle_id);
+        for _ in 0..2 {
+            let status = Validator::<NoState, _, _>::validate_with_policy(
+                &ledger,
+                &cache.resolver(Witness(witness.clone(), None)),
+                ledger.genesis.testnet,
+                (),
+                ValidationPolicy::default().with_strict_dust(),
+            );
+            assert!(status
+                .failures
+                .contains(&Failure::OutputBelowDust(opid, dust, 100, 546)));
+        }
+    }
+
+    #[test]
+    fn fee_rate() {
+        let mut tx = Tx::strict_dumb();
+        tx.inputs = Confined::from_checked(vec![TxIn::strict_dumb()]);
+        tx.outputs = Confined::from_checked(vec![TxOut::new(ScriptPubkey::default(), 1000u64)]);
+        let vbytes = tx.vbytes().into_u32();
+        let witness = XChain::Bitcoin(tx);
+        let witness_id = witness.witness_id();
+
+        let bundle = bundle([Transition::strict_dumb()]);
+        let ledger = Ledger::with_bundles(Schema::strict_dumb(), [(witness_id, bundle)]);
+        let validate = |spent: Option<u64>| {
+            ledger
+                .validate(&Witness(witness.clone(), spent.map(Sats::from)))
+                .warnings
+        };
+
+        assert!(validate(None).is_empty());
+        assert!(validate(Some(1000 + 2 * vbytes as u64)).is_empty());
+        assert!(validate(Some(900)).is_empty());
+        let fee = MAX_FEE_RATE * vbytes as u64 + 1;
+        assert_eq!(validate(Some(1000 + fee)), vec![Warning::AbnormalFeeRate(
+            witness_id, fee, vbytes
+        )]);
+        assert_eq!(validate(Some(1000)), vec![Warning::AbnormalFeeRate(witness_id, 0, vbytes)]);
+    }
+
+    #[test]
+    fn fee_rate_cached() {
+        let mut tx = Tx::strict_dumb();
+        tx.inputs = Confined::from_checked(vec![TxIn::strict_dumb()]);
+        tx.outputs = Confined::from_checked(vec![TxOut::new(ScriptPubkey::default(), 1000u64)]);
+        let witness = XChain::Bitcoin(tx);
+        let witness_id = witness.witness_id();
+
+        let bundle = bundle([Transition::strict_dumb()]);
+        let bundle_id = bundle.bundle_id();
+        let ledger = Ledger::with_bundles(Schema::strict_dumb(), [(witness_id, bundle)]);
+        let resolver = || Witness(witness.clone(), Some(Sats::from(1000u64)));
+
+        let uncached = ledger.validate(&resolver()).warnings;
+        assert_eq!(uncached.len(), 1);
+        let mut cache = WitnessCache::new();
+        cache.cache_anchor(witness_id, bundle_id);
+        for _ in 0..2 {
+            assert_eq!(ledger.validate(&cache.resolver(resolver())).warnings, uncached);
+        }
+    }
+
+    #[test]
+    fn chain_turbulence() {
+        let witness = XChain::Bitcoin(Tx::strict_dumb());
+        let witness_id = witness.witness_id();
+        let bundle = bundle([Transition::strict_dumb()]);
+        let ledger = Ledger::with_bundles(Schema::strict_dumb(), [(witness_id, bundle)]);
+
+        let pos = WitnessPos::new(100, 1_700_000_000).unwrap();
+        let scenario = ChainScenario::new()
+            .step([ChainEvent::Broadcast(witness_id, WitnessOrd::Tentative)])
+            .step([ChainEvent::Mine(witness_id, pos)])
+            .step([ChainEvent::Reorg(Layer1::Bitcoin, 99)])
+            .step([ChainEvent::Evict(witness_id)]);
+        let mut resolver = ScriptedResolver::new([witness], scenario);
+        let mut validate = || {
+            let status = ledger.validate(&resolver);
+            resolver.advance();
+            status.stats.witnesses_unresolved
+        };
+
+        // Witness is known to the resolver only while it is in the mempool or
+        // mined, including after it is re-organized back into the mempool
+        assert_eq!(validate(), 1);
+        assert_eq!(validate(), 0);
+        assert_eq!(validate(), 0);
+        assert_eq!(validate(), 0);
+        assert_eq!(validate(), 1);
+    }
+
+    #[test]
+    fn seal_statuses() {
+        let witness = XChain::Bitcoin(Tx::strict_dumb());
+        let witness_id = witness.witness_id();
+        let ty = AssignmentType::with(1);
+        let txid = Txid::from([1u8; 32]);
+        let schema = Schema::strict_dumb();
+        let mut genesis = Genesis::strict_dumb();
+        genesis.schema_id = schema.schema_id();
+        genesis
+            .assignments
+            .insert(
+                ty,
+                TypedAssigns::Declarative(small_vec![Assign::revealed(
+                    XChain::Bitcoin(GenesisSeal::with_blinding(Method::OpretFirst, txid, 0, 0)),
+                    VoidState::default(),
+                )]),
+            )
+            .unwrap();
+        let mut transition = Transition::strict_dumb();
+        transition
+            .inputs
+            .push(Input::with(Opout::new(genesis.id(), ty, 0)))
+            .unwrap();
+        let mut bundle = bundle([transition]);
+        bundle.close_method = Method::OpretFirst;
+        let bundle_id = bundle.bundle_id();
+        let mut ledger = Ledger::with_bundles(schema, [(witness_id, bundle)]);
+        ledger.genesis = genesis;
+        let seal = XChain::Bitcoin(ExplicitSeal::new(Method::OpretFirst, Outpoint::new(txid, 0)));
+
+        let status = ledger.validate(&ScriptedResolver::default());
+        assert_eq!(status.seals, vec![SealReport {
+            seal,
+            bundle_id,
+            witness_id,
+            status: SealStatus::WitnessUnknown,
+        }]);
+
+        // Anchor doesn't commit to the bundle
+        let status = ledger.validate(&Witness(witness.clone(), None));
+        assert_eq!(status.seal_status(seal), Some(SealStatus::WitnessInvalid));
+        assert_eq!(status.unclosed_seals().count(), 1);
+
+        // Closing of the seals with already verified anchor depends only on
+        // the witness mining
+        let pos = WitnessPos::new(100, 1_700_000_000).unwrap();
+        let scenario = ChainScenario::new()
+            .step([ChainEvent::Broadcast(witness_id, WitnessOrd::Tentative)])
+            .step([ChainEvent::Mine(witness_id, pos)]);
+        let mut resolver = ScriptedResolver::new([witness], scenario);
+        let mut cache = WitnessCache::new();
+        cache.cache_anchor(witness_id, bundle_id);
+        resolver.advance();
+        let status = ledger.validate(&cache.resolver(&resolver));
+        assert_eq!(status.seal_status(seal), Some(SealStatus::PendingWitness));
+        resolver.advance();
+        let status = ledger.validate(&cache.resolver(&resolver));
+        assert_eq!(status.seal_status(seal), Some(SealStatus::Closed));
+        assert_eq!(status.unclosed_seals().count(), 0);
+    }
+
+    #[test]
+    fn burn_release() {
+        const BURN: TransitionType = TransitionType::with(1);
+        const RELEASE: TransitionType = TransitionType::with(2);
+        let types = global_state_types();
+        let sem_id = *types.resolve("RGBCommit.Layer1Cap").unwrap();
+        let asset = AssignmentType::with(1);
+        let caps = GlobalStateType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema.global_types = tiny_bmap! {
+            caps => GlobalStateSchema { kind: default!(), sem_id, max_items: u24::with(2) }
+        };
+        schema.owned_types =
+            tiny_bmap! { asset => OwnedStateSchema::Fungible(FungibleType::Unsigned64Bit) };
+        let transition_schema = TransitionSchema {
+            inputs: tiny_bmap! { asset => Occurrences::OnceOrMore },
+            assignments: tiny_bmap! { asset => Occurrences::OnceOrMore },
+            ..default!()
+        };
+        schema.transitions = tiny_bmap! {
+            BURN => transition_schema.clone(),
+            RELEASE => transition_schema,
+        };
+        schema.ext.insert(ChainSupplySchema {
+            assignment: asset,
+            caps,
+            bridges: tiny_bmap! { BURN => RELEASE },
+        });
+        let mut genesis = Genesis::strict_dumb();
+        genesis.schema_id = schema.schema_id();
+        genesis.alt_layers1 = AltLayer1Set::from(tiny_bset! { AltLayer1::Liquid });
+
+        let liquid_seal =
+            GraphSeal::with_blinding(Method::TapretFirst, Txid::from([2u8; 32]), 0, 0);
+        let mut burn = Transition::strict_dumb();
+        burn.transition_type = BURN;
+        burn.assignments
+            .insert(
+                asset,
+                TypedAssigns::Fungible(small_vec![Assign::revealed(
+                    XChain::Liquid(liquid_seal),
+                    RevealedValue::new_random_blinding(100u64, AssetTag::from([1u8; 32])),
+                )]),
+            )
+            .unwrap();
+        let burn_id = burn.id();
+        let spending = |transition_type, nonce| {
+            let mut transition = Transition::strict_dumb();
+            transition.transition_type = transition_type;
+            transition.nonce = nonce;
+            transition
+                .inputs
+                .push(Input::with(Opout::new(burn_id, asset, 0)))
+                .unwrap();
+            transition
+        };
+        let validate = |transition: Transition, resolver: &dyn Fn(XWitnessId) -> WitnessOrd| {
+            let opid = transition.id();
+            let mut ledger = Ledger::with_bundles(schema.clone(), [
+                (XWitnessId::with(Layer1::Bitcoin, Txid::from([1u8; 32])), bundle([burn.clone()])),
+                (XWitnessId::with(Layer1::Liquid, Txid::from([3u8; 32])), bundle([transition])),
+            ]);
+            ledger.types = types.as_types().clone();
+            ledger.genesis = genesis.clone();
+            (opid, ledger.validate(&Ordered(|id| Some(resolver(id)))).failures)
+        };
+        let mined = |id| Mined.resolve_pub_witness_ord(id).unwrap();
+        let released = |failures: &[Failure]| {
+            !failures.iter().any(|f| {
+                matches!(
+                    f.code(),
+                    "HistoryLayer1Mixed"
+                        | "ReleaseTypeMismatch"
+                        | "ReleaseWithoutBurn"
+                        | "BurnUnconfirmed"
+                )
+            })
+        };
+
+        let (_, failures) = validate(spending(RELEASE, 0), &mined);
+        assert!(released(&failures));
+
+        let (opid, failures) = validate(spending(BURN, 0), &mined);
+        assert!(failures.contains(&Failure::ReleaseTypeMismatch(opid, burn_id, RELEASE)));
+
+        let (opid, failures) = validate(spending(RELEASE, 0), &|id: XWitnessId| {
+            if id.layer1() == Layer1::Bitcoin { WitnessOrd::Tentative } else { mined(id) }
+        });
+        assert!(failures.contains(&Failure::BurnUnconfirmed(opid, burn_id)));
+
+        let mut unbacked = Transition::strict_dumb();
+        unbacked.transition_type = RELEASE;
+        let (opid, failures) = validate(unbacked, &mined);
+        assert!(failures.contains(&Failure::ReleaseWithoutBurn(opid)));
+
+        // Nothing was issued on Bitcoin, so there is no supply to burn there
+        let witness_id = XWitnessId::with(Layer1::Bitcoin, Txid::from([1u8; 32]));
+        let bundle = bundle([burn]);
+        let mut cache = WitnessCache::new();
+        cache.cache_anchor(witness_id, bundle.bundle_id());
+        let mut ledger = Ledger::with_bundles(schema, [(witness_id, bundle)]);
+        ledger.types = types.as_types().clone();
+        ledger.genesis = genesis;
+        let status = ledger.validate(&cache.resolver(&Mined));
+        assert!(status
+            .failures
+            .contains(&Failure::ChainSupplyUnderflow(burn_id, Layer1::Bitcoin, 100, 0)));
+    }
+
+    #[test]
+    fn contract_features() {
+        const SEND: TransitionType = TransitionType::with(1);
+        const BURN: TransitionType = TransitionType::with(2);
+        const ISSUE: TransitionType = TransitionType::with(3);
+        let types = global_state_types();
+        let sem_id = *types.resolve("RGBCommit.ContractFeatures").unwrap();
+        let asset = AssignmentType::with(1);
+        let flags = GlobalStateType::with(1);
+        let mut schema = Schema::strict_dumb();
+        schema.global_types = tiny_bmap! { flags => GlobalStateSchema::once(sem_id) };
+        schema.owned_types =
+            tiny_bmap! { asset => OwnedStateSchema::Fungible(FungibleType::Unsigned64Bit) };
+        schema.transitions = tiny_bmap! {
+            SEND => schema.blank_transition(),
+            BURN => schema.blank_transition(),
+            ISSUE => schema.blank_transition(),
+        };
+        schema.ext.insert(FeatureSchema {
+            flags,
+            assignment: asset,
+            burns: tiny_bset! { BURN },
+            inflation: tiny_bset! { ISSUE },
+        });
+
+        let tag = AssetTag::from([1u8; 32]);
+        let revealed = Assign::revealed(
+            XChain::Bitcoin(GraphSeal::new_random_vout(Method::OpretFirst, 0)),
+            RevealedValue::new_random_blinding(100u64, tag),
+        );
+        let disclosed = Assign::ConfidentialSeal {
+            seal: revealed.to_confidential_seal(),
+            state: RevealedValue::new_random_blinding(100u64, tag),
+            lock: none!(),
+        };
+        let validate = |features: &[u16], transition_type, assign: &AssignFungible<GraphSeal>| {
+            let mut genesis = Genesis::strict_dumb();
+            genesis.schema_id = schema.schema_id();
+            for bits in features {
+                let data = ContractFeatures::from_bits(*bits)
+                    .to_strict_serialized::<{ u16::MAX as usize }>()
+                    .unwrap();
+                genesis
+                    .globals
+                    .add_state(flags, DataState::from(data))
+                    .unwrap();
+            }
+            let mut transition = Transition::strict_dumb();
+            transition.contract_id = genesis.contract_id();
+            transition.transition_type = transition_type;
+            transition
+                .assignments
+                .insert(asset, TypedAssigns::Fungible(small_vec![assign.clone()]))
+                .unwrap();
+            let opid = transition.id();
+            let bundle = bundle([transition]);
+            let bundle_id = bundle.bundle_id();
+            let witness_id = XWitnessId::with(Layer1::Bitcoin, Txid::from([1u8; 32]));
+            let mut ledger = Ledger::with_bundles(schema.clone(), [(witness_id, bundle)]);
+            ledger.types = types.as_types().clone();
+            ledger.genesis = genesis;
+            // Skip witness verification, which is irrelevant for the features
+            let mut cache = WitnessCache::new();
+            cache.cache_anchor(witness_id, bundle_id);
+            let status = ledger.validate(&cache.resolver(&Mined));
+            let failures = status
+                .failures
+                .into_iter()
+                .filter(|f| f.code().contains("Feature"))
+                .collect::<Vec<_>>();
+            let warnings = status
+                .warnings
+                .into_iter()
+                .filter(|w| matches!(w, Warning::AmountDisclosed(..)))
+                .collect::<Vec<_>>();
+            (opid, failures, warnings)
+        };
+        let burn = ContractFeatures::BURN.bits();
+        let confidential = ContractFeatures::CONFIDENTIAL_AMOUNTS.bits();
+
+        let (_, failures, _) = validate(&[burn], BURN, &revealed);
+        assert_eq!(failures, vec![]);
+        let (opid, failures, _) = validate(&[], BURN, &revealed);
+        assert_eq!(failures, vec![Failure::FeatureDisabled(opid, ContractFeatures::BURN)]);
+        let (opid, failures, _) = validate(&[burn], ISSUE, &revealed);
+        assert_eq!(failures, vec![Failure::FeatureDisabled(opid, ContractFeatures::INFLATION)]);
+
+        // Amounts of other parties may be disclosed; if the contract requires
+        // confidential amounts this is reported, but doesn't invalidate it
+        let (_, failures, warnings) = validate(&[], SEND, &disclosed);
+        assert_eq!((failures, warnings), (vec![], vec![]));
+        let (_, failures, warnings) = validate(&[confidential], SEND, &revealed);
+        assert_eq!((failures, warnings), (vec![], vec![]));
+        let (opid, failures, warnings) = validate(&[confidential], SEND, &disclosed);
+        assert_eq!(failures, vec![]);
+        assert_eq!(warnings, vec![Warning::AmountDisclosed(opid, asset)]);
+
+        // Features unknown to the validator can't be enforced
+        let (_, failures, _) = validate(&[0x8000 | burn], BURN, &revealed);
+        assert!(failures.contains(&Failure::FeatureFlagsInvalid(flags)));
+        let (_, failures, _) = validate(&[burn, burn], BURN, &revealed);
+        assert!(failures.contains(&Failure::FeatureFlagsInvalid(flags)));
+    }
 }
@@ -26,6 +26,7 @@ use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::str::FromStr;
 
 use amplify::confinement;
 use amplify::num::u24;
@@ -33,49 +34,113 @@ use bp::seals::txout::{CloseMethod, ExplicitSeal, VerifyError, Witness};
 use bp::{dbc, Tx, Txid};
 use commit_verify::mpc;
 use single_use_seals::SealWitness;
-use strict_encoding::{StrictDecode, StrictDumb, StrictEncode};
+use strict_encoding::{
+    DecodeError, ReadTuple, ReadUnion, StrictDecode, StrictDumb, StrictEncode, StrictSum, TypedRead,
+};
 
 use crate::{
-    AssetTags, AssignmentType, Assignments, AssignmentsRef, AttachState, ContractId, DataState,
-    ExposedSeal, Extension, ExtensionType, FungibleState, Genesis, GlobalState, GlobalStateType,
-    GraphSeal, Impossible, Inputs, Metadata, OpFullType, OpId, OpType, Operation, Transition,
-    TransitionType, TxoSeal, TypedAssigns, Valencies, XChain, XOutpoint, XOutputSeal,
+    AssetTags, AssignmentType, Assignments, AssignmentsRef, AttachState, AuxProofs,
+    CommitmentPreimage, ContractId, DataState, ExposedSeal, Extension, ExtensionType, FungibleState,
+    Genesis, GlobalState, GlobalStateType, GraphSeal, Impossible, Inputs, Layer1, Metadata,
+    OpFullType, OpId, OpType, Operation, Transition, TransitionType, TxoSeal, TypedAssigns,
+    Valencies, XChain, XChainParseError, XOutpoint, XOutputSeal, LIB_NAME_RGB_COMMIT,
     LIB_NAME_RGB_LOGIC,
 };
 
-pub type XWitnessId = XChain<Txid>;
+/// Identifier of a witness transaction on one of the supported layer 1
+/// chains.
+///
+/// The canonical textual representation is `<chain>:<txid>`, where chain
+/// prefix is `bc` for Bitcoin and `lq` for Liquid (see [`XChain`] `Display`
+/// implementation). Parsing requires the prefix to be present, such that the
+/// same witness id always has a single textual form. With `serde`
+/// human-readable serializers the id is serialized using the same textual
+/// representation; for backward compatibility deserialization also accepts the
+/// legacy `{ "chain": ..., "data": ... }` form of [`XChain`].
+///
+/// Witness ids are ordered first by their layer 1, with Bitcoin ids being
+/// always greater than Liquid ones, and then by the transaction id within the
+/// same layer 1.
+#[derive(Wrapper, WrapperMut, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, From)]
+#[wrapper(Deref, Display)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_COMMIT)]
+pub struct XWitnessId(XChain<Txid>);
 
-pub type XWitnessTx<X = Impossible> = XChain<Tx, X>;
+impl XWitnessId {
+    /// Constructs witness id for a transaction on a given layer 1.
+    #[inline]
+    pub fn with(layer1: Layer1, txid: Txid) -> Self { Self(XChain::with(layer1, txid)) }
+}
 
-impl XWitnessTx {
-    pub fn witness_id(&self) -> XWitnessId {
-        match self {
-            Self::Bitcoin(tx) => XWitnessId::Bitcoin(tx.txid()),
-            Self::Liquid(tx) => XWitnessId::Liquid(tx.txid()),
-            Self::Other(_) => unreachable!(),
+impl FromStr for XWitnessId {
+    type Err = XChainParseError<<Txid as FromStr>::Err>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.contains(':') {
+            return Err(XChainParseError::NoPrefix);
         }
+        XChain::from_str(s).map(Self)
     }
 }
 
-impl<Dbc: dbc::Proof> XChain<Witness<Dbc>> {
-    pub fn witness_id(&self) -> XWitnessId {
-        match self {
-            Self::Bitcoin(w) => XWitnessId::Bitcoin(w.txid),
-            Self::Liquid(w) => XWitnessId::Liquid(w.txid),
-            Self::Other(_) => unreachable!(),
+#[cfg(feature = "serde")]
+mod _serde {
+    use serde_crate::de::Error;
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    impl Serialize for XWitnessId {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer {
+            if serializer.is_human_readable() {
+                serializer.serialize_str(&self.to_string())
+            } else {
+                self.0.serialize(serializer)
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(crate = "serde_crate", untagged)]
+    enum HumanReadable {
+        Canonical(String),
+        Legacy(XChain<Txid>),
+    }
+
+    impl<'de> Deserialize<'de> for XWitnessId {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de> {
+            if deserializer.is_human_readable() {
+                match HumanReadable::deserialize(deserializer)? {
+                    HumanReadable::Canonical(s) => Self::from_str(&s).map_err(D::Error::custom),
+                    HumanReadable::Legacy(id) => Ok(Self(id)),
+                }
+            } else {
+                XChain::<Txid>::deserialize(deserializer).map(Self)
+            }
         }
     }
 }
 
+pub type XWitnessTx<X = Impossible> = XChain<Tx, X>;
+
+impl XWitnessTx {
+    pub fn witness_id(&self) -> XWitnessId { self.map_ref(Tx::txid).into() }
+}
+
+impl<Dbc: dbc::Proof> XChain<Witness<Dbc>> {
+    pub fn witness_id(&self) -> XWitnessId { self.map_ref(|w| w.txid).into() }
+}
+
 impl<Dbc: dbc::Proof, Seal: TxoSeal> SealWitness<Seal> for XChain<Witness<Dbc>> {
     type Message = mpc::Commitment;
     type Error = VerifyError<Dbc::Error>;
 
     fn verify_seal(&self, seal: &Seal, msg: &Self::Message) -> Result<(), Self::Error> {
-        match self {
-            Self::Bitcoin(witness) | Self::Liquid(witness) => witness.verify_seal(seal, msg),
-            Self::Other(_) => unreachable!(),
-        }
+        self.as_reduced_unsafe().verify_seal(seal, msg)
     }
 
     fn verify_many_seals<'seal>(
@@ -86,50 +151,30 @@ impl<Dbc: dbc::Proof, Seal: TxoSeal> SealWitness<Seal> for XChain<Witness<Dbc>>
     where
         Seal: 'seal,
     {
-        match self {
-            Self::Bitcoin(witness) | Self::Liquid(witness) => witness.verify_many_seals(seals, msg),
-            Self::Other(_) => unreachable!(),
-        }
+        self.as_reduced_unsafe().verify_many_seals(seals, msg)
     }
 }
 
 impl<U: ExposedSeal> XChain<U> {
     pub fn method(self) -> CloseMethod
     where U: TxoSeal {
-        match self {
-            XChain::Bitcoin(seal) => seal.method(),
-            XChain::Liquid(seal) => seal.method(),
-            XChain::Other(_) => unreachable!(),
-        }
+        self.as_reduced_unsafe().method()
     }
 
     #[inline]
     pub fn to_output_seal(self) -> Option<XOutputSeal>
     where U: TxoSeal {
-        Some(match self {
-            XChain::Bitcoin(seal) => {
-                let outpoint = seal.outpoint()?;
-                XChain::Bitcoin(ExplicitSeal::new(seal.method(), outpoint))
-            }
-            XChain::Liquid(seal) => {
-                let outpoint = seal.outpoint()?;
-                XChain::Liquid(ExplicitSeal::new(seal.method(), outpoint))
-            }
-            XChain::Other(_) => unreachable!(),
-        })
+        self.maybe_map(|seal| Some(ExplicitSeal::new(seal.method(), seal.outpoint()?)))
     }
 
     pub fn try_to_output_seal(self, witness_id: XWitnessId) -> Result<XOutputSeal, Self>
     where U: TxoSeal {
         self.to_output_seal()
-            .or(match (self, witness_id) {
-                (XChain::Bitcoin(seal), XWitnessId::Bitcoin(txid)) => {
-                    Some(XChain::Bitcoin(ExplicitSeal::new(seal.method(), seal.outpoint_or(txid))))
-                }
-                (XChain::Liquid(seal), XWitnessId::Liquid(txid)) => {
-                    Some(XChain::Liquid(ExplicitSeal::new(seal.method(), seal.outpoint_or(txid))))
-                }
-                _ => None,
+            .or_else(|| {
+                let (layer1, txid) = witness_id.into_parts();
+                (self.layer1() == layer1).then(|| {
+                    self.map(|seal| ExplicitSeal::new(seal.method(), seal.outpoint_or(txid)))
+                })
             })
             .ok_or(self)
     }
@@ -209,6 +254,14 @@ impl<'op> Operation for OrdOpRef<'op> {
         }
     }
 
+    fn commitment_preimage(&self) -> CommitmentPreimage {
+        match self {
+            OrdOpRef::Genesis(op) => op.commitment_preimage(),
+            OrdOpRef::Transition(op, ..) => op.commitment_preimage(),
+            OrdOpRef::Extension(op, ..) => op.commitment_preimage(),
+        }
+    }
+
     fn contract_id(&self) -> ContractId {
         match self {
             OrdOpRef::Genesis(op) => op.contract_id(),
@@ -249,6 +302,14 @@ impl<'op> Operation for OrdOpRef<'op> {
         }
     }
 
+    fn aux_proofs(&self) -> &AuxProofs {
+        match self {
+            OrdOpRef::Genesis(op) => op.aux_proofs(),
+            OrdOpRef::Transition(op, ..) => op.aux_proofs(),
+            OrdOpRef::Extension(op, ..) => op.aux_proofs(),
+        }
+    }
+
     fn globals(&self) -> &GlobalState {
         match self {
             OrdOpRef::Genesis(op) => op.globals(),
@@ -313,6 +374,8 @@ impl WitnessPos {
     }
 
     pub fn height(&self) -> NonZeroU32 { NonZeroU32::new(self.height).expect("invariant") }
+
+    pub fn timestamp(&self) -> i64 { self.timestamp }
 }
 
 impl PartialOrd for WitnessPos {
@@ -324,10 +387,18 @@ impl Ord for WitnessPos {
     /// timestamp information and not height. The timestamp data are consistent
     /// across multiple blockchains, while height evolves with a different
     /// speed and can't be used in comparisons.
+    ///
+    /// Positions with the same timestamp are ordered by their height, such
+    /// that the ordering is total and consistent with the equality. For
+    /// witnesses on different layers 1 this tie-breaker is arbitrary, yet
+    /// deterministic; operations with equal witness positions are further
+    /// ordered as described in [`OpOrd`].
     fn cmp(&self, other: &Self) -> Ordering {
         assert!(self.timestamp > 0);
         assert!(other.timestamp > 0);
-        self.timestamp.cmp(&other.timestamp)
+        self.timestamp
+            .cmp(&other.timestamp)
+            .then(self.height.cmp(&other.height))
     }
 }
 
@@ -338,7 +409,7 @@ impl Ord for WitnessPos {
 /// users.
 #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash, Debug, Display, From)]
 #[display(lowercase)]
-#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[derive(StrictType, StrictDumb, StrictEncode)]
 #[strict_type(lib = LIB_NAME_RGB_LOGIC, tags = order)]
 #[cfg_attr(
     feature = "serde",
@@ -382,12 +453,73 @@ pub enum WitnessOrd {
     ///   set to [`Self::Archived`]);
     /// - transaction is an RBF replacement prepared to be broadcast (with the previous transaction
     ///   set to [`Self::Archived`] at the same moment).
+    ///
+    /// All inputs of the transaction must spend already mined outputs; otherwise
+    /// [`Self::TentativeChained`] must be used.
     Tentative,
+
+    /// Valid witness transaction which is not (yet) included into a layer 1
+    /// blockchain and spends outputs of other unconfirmed transactions.
+    ///
+    /// The value is the depth of the unconfirmed ancestry: `1` for a transaction
+    /// spending from an unconfirmed parent which inputs are all mined, `2` if
+    /// that parent itself spends an unconfirmed output, etc. Such transactions
+    /// are processed after [`Self::Tentative`] ones, in the order of their
+    /// ancestry depth.
+    ///
+    /// The depth must not be zero, since such witnesses are [`Self::Tentative`]
+    /// (see [`Self::with_unconfirmed_ancestry`]); zero depth is rejected during
+    /// deserialization.
+    #[display("tentative({0})")]
+    TentativeChained(u8),
+}
+
+impl StrictDecode for WitnessOrd {
+    fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+        reader.read_union(|field, r| match field.as_str() {
+            x if x == Self::ALL_VARIANTS[0].1 => Ok(Self::Archived),
+            x if x == Self::ALL_VARIANTS[1].1 => r.read_tuple(|r| r.read_field().map(Self::Mined)),
+            x if x == Self::ALL_VARIANTS[2].1 => Ok(Self::Tentative),
+            x if x == Self::ALL_VARIANTS[3].1 => r.read_tuple(|r| match r.read_field()? {
+                0 => Err(DecodeError::DataIntegrityError(s!(
+                    "tentative witness with zero-depth unconfirmed ancestry"
+                ))),
+                depth => Ok(Self::TentativeChained(depth)),
+            }),
+            x => Err(DecodeError::DataIntegrityError(format!("unknown witness status `{x}`"))),
+        })
+    }
 }
 
 impl WitnessOrd {
+    /// Constructs status of a not yet mined witness transaction with a given
+    /// depth of its unconfirmed ancestry: [`Self::Tentative`] for the zero
+    /// depth and [`Self::TentativeChained`] otherwise.
+    pub fn with_unconfirmed_ancestry(depth: u8) -> Self {
+        match depth {
+            0 => Self::Tentative,
+            depth => Self::TentativeChained(depth),
+        }
+    }
+
     #[inline]
     pub fn is_valid(self) -> bool { self != Self::Archived }
+
+    /// Detects whether the witness transaction is not yet mined.
+    #[inline]
+    pub fn is_tentative(self) -> bool {
+        matches!(self, Self::Tentative | Self::TentativeChained(_))
+    }
+
+    /// Returns depth of the unconfirmed ancestry of the witness transaction
+    /// (see [`Self::TentativeChained`]), which is zero for all other cases.
+    #[inline]
+    pub fn unconfirmed_ancestry(self) -> u8 {
+        match self {
+            Self::TentativeChained(depth) => depth,
+            Self::Archived | Self::Mined(_) | Self::Tentative => 0,
+        }
+    }
 }
 
 /// Operation ordering priority for contract state computation according to
@@ -401,6 +533,9 @@ impl WitnessOrd {
 ///   seals defined in the extension, but are processed before that state transition.
 /// - If two or more operations share the same witness transaction ordering, they are first ordered
 ///   basing on their `nonce` value, and if it is also the same, basing on their operation id value.
+///   This includes operations with witnesses on different layers 1 mined at the same position:
+///   layer 1 of a witness never takes part in the ordering, which keeps it total and independent
+///   from the witness ids.
 ///
 /// [RCP-240731A]: https://github.com/RGB-WG/RFC/issues/10
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -548,7 +683,7 @@ impl<I: GlobalStateIter> GlobalContractState<I> {
 
     fn prev_checked(&mut self) -> Option<(GlobalOrd, I::Data)> {
         let (ord, item) = self.iter.prev()?;
-        if self.last_ord.map(|last| ord <= last).unwrap_or_default() {
+        if self.last_ord.map(|last| ord >= last).unwrap_or_default() {
             panic!(
                 "global contract state iterator has invalid implementation: it fails to order \
                  global state according to the consensus ordering"
@@ -570,21 +705,22 @@ impl<I: GlobalStateIter> GlobalContractState<I> {
             return None;
         }
         if depth >= self.checked_depth {
-            self.iter.reset(depth);
-        } else {
-            self.iter.reset(self.checked_depth);
+            self.iter.reset(self.checked_depth - u24::ONE);
+            if self.last_ord.is_none() {
+                self.last_ord = self.iter.last().map(|(ord, _)| ord);
+            }
             let size = self.iter.size();
-            let to = (depth - self.checked_depth).to_u32();
-            for inc in 0..to {
+            while self.checked_depth <= depth {
                 if self.prev_checked().is_none() {
                     panic!(
                         "global contract state iterator has invalid implementation: it reports \
                          more global state items {size} than the contract has ({})",
-                        self.checked_depth + inc
+                        self.checked_depth
                     );
                 }
             }
         }
+        self.iter.reset(depth);
         self.iter.last().map(|(_, item)| item)
     }
 }
@@ -646,6 +782,7 @@ pub struct OpInfo<'op> {
     pub id: OpId,
     pub ty: OpFullType,
     pub metadata: &'op Metadata,
+    pub aux_proofs: &'op AuxProofs,
     pub prev_state: &'op Assignments<GraphSeal>,
     pub owned_state: AssignmentsRef<'op>,
     pub redeemed: &'op Valencies,
@@ -664,6 +801,7 @@ impl<'op> OpInfo<'op> {
             id,
             ty: op.full_type(),
             metadata: op.metadata(),
+            aux_proofs: op.aux_proofs(),
             prev_state,
             owned_state: op.assignments(),
             redeemed,
@@ -672,3 +810,132 @@ impl<'op> OpInfo<'op> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use amplify::hex::FromHex;
+    use amplify::ByteArray;
+    use strict_encoding::{StreamWriter, StrictReader};
+
+    use super::*;
+
+    const TXID: &str = "646ca5c1062619e2a2d60771c9dfd820551fb773e4dc8c4ed67965a8d1fae839";
+
+    #[test]
+    fn witness_id_display_from_str() {
+        let txid = Txid::from_hex(TXID).unwrap();
+        let bc = XWitnessId::with(Layer1::Bitcoin, txid);
+        let lq = XWitnessId::with(Layer1::Liquid, txid);
+        assert_eq!(bc.to_string(), format!("bc:{TXID}"));
+        assert_eq!(lq.to_string(), format!("lq:{TXID}"));
+        assert_eq!(XWitnessId::from_str(&bc.to_string()).unwrap(), bc);
+        assert_eq!(XWitnessId::from_str(&lq.to_string()).unwrap(), lq);
+        assert!(matches!(XWitnessId::from_str(TXID), Err(XChainParseError::NoPrefix)));
+        assert!(XWitnessId::from_str(&format!("tb:{TXID}")).is_err());
+    }
+
+    #[test]
+    fn witness_id_ord() {
+        let txid1 = Txid::from_hex(TXID).unwrap();
+        let txid2 = Txid::from_byte_array([0xFF; 32]);
+        assert!(txid1 < txid2);
+        assert!(XWitnessId::with(Layer1::Bitcoin, txid1) > XWitnessId::with(Layer1::Liquid, txid2));
+        assert!(
+            XWitnessId::with(Layer1::Bitcoin, txid1) < XWitnessId::with(Layer1::Bitcoin, txid2)
+        );
+        assert!(XWitnessId::with(Layer1::Liquid, txid1) < XWitnessId::with(Layer1::Liquid, txid2));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn witness_id_serde() {
+        use serde_crate::de::value::{Error, MapDeserializer, StrDeserializer};
+        use serde_crate::Deserialize;
+
+        let bc = XWitnessId::with(Layer1::Bitcoin, Txid::from_hex(TXID).unwrap());
+        let canonical = format!("bc:{TXID}");
+        let canonical = StrDeserializer::<Error>::new(&canonical);
+        assert_eq!(XWitnessId::deserialize(canonical).unwrap(), bc);
+        let legacy = [("chain", "bitcoin"), ("data", TXID)];
+        let legacy = MapDeserializer::<_, Error>::new(legacy.into_iter());
+        assert_eq!(XWitnessId::deserialize(legacy).unwrap(), bc);
+        assert!(XWitnessId::deserialize(StrDeserializer::<Error>::new(TXID)).is_err());
+    }
+
+    #[test]
+    fn witness_ord_ancestry() {
+        let mined = WitnessOrd::Mined(WitnessPos::new(1, 1231006505).unwrap());
+        let chained1 = WitnessOrd::TentativeChained(1);
+        let chained2 = WitnessOrd::TentativeChained(2);
+        assert!(mined < WitnessOrd::Tentative);
+        assert!(WitnessOrd::Tentative < chained1);
+        assert!(chained1 < chained2);
+
+        assert!(!mined.is_tentative());
+        assert!(chained1.is_tentative());
+        assert_eq!(WitnessOrd::Tentative.unconfirmed_ancestry(), 0);
+        assert_eq!(chained2.unconfirmed_ancestry(), 2);
+        assert_eq!(chained2.to_string(), "tentative(2)");
+
+        // Zero depth is the same as a tentative witness and has a single representation
+        assert_eq!(WitnessOrd::with_unconfirmed_ancestry(0), WitnessOrd::Tentative);
+        assert_eq!(WitnessOrd::with_unconfirmed_ancestry(1), chained1);
+        let decode =
+            |data: &[u8]| WitnessOrd::strict_decode(&mut StrictReader::in_memory::<64>(data.to_vec()));
+        let mut data = vec![];
+        chained1
+            .strict_write(StreamWriter::new::<64>(&mut data))
+            .unwrap();
+        assert_eq!(data, [3, 1]);
+        assert_eq!(decode(&data).unwrap(), chained1);
+        data[1] = 0;
+        assert!(decode(&data).is_err());
+    }
+
+    #[test]
+    fn witness_pos_ord() {
+        let pos = |height, timestamp| WitnessPos::new(height, timestamp).unwrap();
+        let early = pos(100, 1231006505);
+        let late = pos(90, 1231006506);
+        let same_time = pos(101, 1231006505);
+        assert!(early < late);
+        assert!(early < same_time);
+        assert!(same_time < late);
+        for (a, b) in [(early, late), (early, same_time), (early, early)] {
+            assert_eq!(a.cmp(&b) == Ordering::Equal, a == b);
+        }
+    }
+
+    #[test]
+    fn witness_ord_stable_encoding() {
+        let txid = Txid::from_hex(TXID).unwrap();
+        let ids = [
+            XWitnessId::with(Layer1::Liquid, txid),
+            XWitnessId::with(Layer1::Bitcoin, txid),
+            XWitnessId::with(Layer1::Bitcoin, Txid::from_byte_array([0xFF; 32])),
+        ];
+        let ords = [
+            WitnessOrd::Archived,
+            WitnessOrd::Mined(WitnessPos::new(200, 1231006505).unwrap()),
+            WitnessOrd::Mined(WitnessPos::new(201, 1231006505).unwrap()),
+            WitnessOrd::Tentative,
+            WitnessOrd::TentativeChained(1),
+        ];
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        assert!(ords.windows(2).all(|w| w[0] < w[1]));
+
+        fn roundtrip<T: StrictEncode + StrictDecode>(val: &T) -> T {
+            let mut data = vec![];
+            val.strict_write(StreamWriter::new::<64>(&mut data)).unwrap();
+            T::strict_decode(&mut StrictReader::in_memory::<64>(data)).unwrap()
+        }
+        let decoded_ids = ids.iter().map(roundtrip).collect::<Vec<_>>();
+        let decoded_ords = ords.iter().map(roundtrip).collect::<Vec<_>>();
+        assert_eq!(decoded_ids, ids);
+        assert_eq!(decoded_ords, ords);
+        assert!(decoded_ids.windows(2).all(|w| w[0] < w[1]));
+        assert!(decoded_ords.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -38,7 +38,11 @@ macro_rules! isa_instr {
     (pcps $no:ident) => {{ RgbIsa::Contract(ContractOp::Pcps($no)) }};
     (cng $t:ident,a8[$a_idx:literal]) => {{ RgbIsa::Contract(ContractOp::CnG($t, Reg32::from(u5::with($a_idx)))) }};
     (cnc $t:ident,a16[$a_idx:literal]) => {{ RgbIsa::Contract(ContractOp::CnC($t, Reg32::from(u5::with($a_idx)))) }};
+    (cnx $t:ident,a8[$a_idx:literal]) => {{ RgbIsa::Contract(ContractOp::CnX($t, Reg32::from(u5::with($a_idx)))) }};
+    (ldx $t:ident,a8[$a_idx:literal],s16[$s_idx:literal]) => {{ RgbIsa::Contract(ContractOp::LdX($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx))) }};
     (ldm $t:ident,s16[$s_idx:literal]) => {{ RgbIsa::Contract(ContractOp::LdM($t, RegS::from($s_idx))) }};
+    (ldc $t:ident,a32[$a_idx:literal],s16[$s_idx:literal]) => {{ RgbIsa::Contract(ContractOp::LdC($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx))) }};
+    (nxc $t:ident,a32[$a_idx:literal],s16[$s_idx:literal]) => {{ RgbIsa::Contract(ContractOp::NxC($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx))) }};
     (ldg $t:ident,a8[$a_idx:literal],s16[$s_idx:literal]) => {{ RgbIsa::Contract(ContractOp::LdG($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx))) }};
     (ldp $t:ident,a16[$a_idx:literal],s16[$s_idx:literal]) => {{ RgbIsa::Contract(ContractOp::LdP($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx))) }};
     (lds $t:ident,a16[$a_idx:literal],s16[$s_idx:literal]) => {{ RgbIsa::Contract(ContractOp::LdS($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx))) }};
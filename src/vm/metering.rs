@@ -0,0 +1,132 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2024 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Metering of the resources used by the script execution, allowing schema
+//! authors to profile their validation logic.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeInclusive;
+
+use aluvm::isa::{Bytecode, BytecodeError, ExecStep, InstructionSet};
+use aluvm::library::{CodeEofError, IsaSeg, LibSite, Read, Write};
+use aluvm::reg::{CoreRegs, NumericRegister, Reg};
+
+/// Resources used by the instructions executed with a [`MeteredContext`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub(crate) struct Meter {
+    /// Number of the executed instructions.
+    pub instructions: u64,
+    /// Total complexity of the executed instructions.
+    pub complexity: u64,
+    /// Total size of the registers written by the instructions, in bytes.
+    pub memory: usize,
+}
+
+/// Execution context of the [`Metered`] instructions, wrapping the context of
+/// the underlying instruction set.
+pub(crate) struct MeteredContext<'ctx, I: InstructionSet> {
+    inner: I::Context<'ctx>,
+    meter: RefCell<Meter>,
+    /// Maximal size of the value ever put into each of the written registers.
+    written: RefCell<BTreeMap<Reg, usize>>,
+}
+
+impl<'ctx, I: InstructionSet> MeteredContext<'ctx, I> {
+    pub fn new(inner: I::Context<'ctx>) -> Self {
+        Self {
+            inner,
+            meter: default!(),
+            written: default!(),
+        }
+    }
+
+    /// Returns resources used by all the instructions executed so far.
+    pub fn meter(&self) -> Meter {
+        let mut meter = *self.meter.borrow();
+        meter.memory = self.written.borrow().values().sum();
+        meter
+    }
+
+    /// Returns the wrapped context.
+    pub fn into_inner(self) -> I::Context<'ctx> { self.inner }
+}
+
+/// Instruction set accounting the resources used by the execution of the
+/// wrapped instructions into the [`MeteredContext`].
+#[derive(Clone, Debug, Display)]
+#[display(inner)]
+pub(crate) struct Metered<I: InstructionSet>(I);
+
+impl<I: InstructionSet> InstructionSet for Metered<I> {
+    type Context<'ctx> = MeteredContext<'ctx, I>;
+
+    fn isa_ids() -> IsaSeg { I::isa_ids() }
+
+    fn src_regs(&self) -> BTreeSet<Reg> { self.0.src_regs() }
+
+    fn dst_regs(&self) -> BTreeSet<Reg> { self.0.dst_regs() }
+
+    fn complexity(&self) -> u64 { self.0.complexity() }
+
+    fn exec(&self, regs: &mut CoreRegs, site: LibSite, context: &Self::Context<'_>) -> ExecStep {
+        let step = self.0.exec(regs, site, &context.inner);
+
+        let mut meter = context.meter.borrow_mut();
+        meter.instructions += 1;
+        meter.complexity = meter.complexity.saturating_add(self.0.complexity());
+        let mut written = context.written.borrow_mut();
+        for reg in self.0.dst_regs() {
+            let size = match reg {
+                Reg::A(reg, _) => reg.bytes() as usize,
+                Reg::F(reg, _) => reg.bytes() as usize,
+                Reg::R(reg, _) => reg.bytes() as usize,
+                Reg::S(reg) => regs.get_s(reg).map(|s| s.len() as usize).unwrap_or_default(),
+            };
+            let max = written.entry(reg).or_default();
+            *max = (*max).max(size);
+        }
+        step
+    }
+}
+
+impl<I: InstructionSet> Bytecode for Metered<I> {
+    fn instr_range() -> RangeInclusive<u8> { I::instr_range() }
+
+    fn instr_byte(&self) -> u8 { self.0.instr_byte() }
+
+    fn call_site(&self) -> Option<LibSite> { self.0.call_site() }
+
+    fn encode_args<W>(&self, writer: &mut W) -> Result<(), BytecodeError>
+    where W: Write {
+        self.0.encode_args(writer)
+    }
+
+    fn decode<R>(reader: &mut R) -> Result<Self, CodeEofError>
+    where
+        Self: Sized,
+        R: Read,
+    {
+        I::decode(reader).map(Self)
+    }
+}
@@ -31,6 +31,7 @@ mod op_timechain;
 #[macro_use]
 mod macroasm;
 mod contract;
+mod metering;
 
 pub use aluvm::aluasm_isa;
 pub use contract::{
@@ -39,5 +40,6 @@ pub use contract::{
 };
 pub(crate) use contract::{OpInfo, VmContext};
 pub use isa::RgbIsa;
+pub(crate) use metering::{Metered, MeteredContext};
 pub use op_contract::ContractOp;
 pub use op_timechain::TimechainOp;
@@ -28,6 +28,7 @@ use std::collections::BTreeSet;
 use std::marker::PhantomData;
 use std::ops::RangeInclusive;
 
+use aluvm::data::ByteStr;
 use aluvm::isa::{Bytecode, BytecodeError, ExecStep, InstructionSet};
 use aluvm::library::{CodeEofError, IsaSeg, LibSite, Read, Write};
 use aluvm::reg::{CoreRegs, Reg, Reg16, Reg32, RegA, RegS};
@@ -38,8 +39,8 @@ use commit_verify::CommitVerify;
 use super::opcodes::*;
 use super::{ContractStateAccess, VmContext};
 use crate::{
-    Assign, AssignmentType, BlindingFactor, GlobalStateType, MetaType, PedersenCommitment,
-    RevealedValue, TypedAssigns,
+    Assign, AssignmentType, AuxProofType, BlindingFactor, GlobalStateType, MetaType,
+    PedersenCommitment, RevealedValue, TypedAssigns,
 };
 
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
@@ -128,6 +129,20 @@ pub enum ContractOp<S: ContractStateAccess> {
     #[display("ldc     {0},a32{1},{2}")]
     LdC(GlobalStateType, Reg16, RegS),
 
+    /// Iterates over the contract global state with type id from the first
+    /// argument, moving from the most recent value back in history. Loads the
+    /// value at the depth from the second argument `a32` register into a
+    /// register provided in the third argument and increments the depth
+    /// register, such that the next call loads the preceding value.
+    ///
+    /// Once the iteration goes beyond the global state history retained by
+    /// the contract, sets destination to `None` and leaves the depth register
+    /// unchanged. If the contract doesn't have the provided global state type,
+    /// or the depth register is not set, sets `st0` to fail state and
+    /// terminates the program.
+    #[display("nxc     {0},a32{1},{2}")]
+    NxC(GlobalStateType, Reg16, RegS),
+
     /// Loads operation metadata with a type id from the first argument into a
     /// register provided in the second argument.
     ///
@@ -137,6 +152,22 @@ pub enum ContractOp<S: ContractStateAccess> {
     #[display("ldm     {0},{1}")]
     LdM(MetaType, RegS),
 
+    /// Counts number of auxiliary proofs of the provided type in the current
+    /// operation and puts the number to the destination `a8` register.
+    ///
+    /// If the operation doesn't contain proofs of a given type, sets
+    /// destination index to zero. Does not change `st0` register.
+    #[display("cnx     {0},a8{1}")]
+    CnX(AuxProofType, Reg32),
+
+    /// Loads auxiliary proof of the current operation with type id from the
+    /// first argument and index from the second argument `a8` register into a
+    /// register provided in the third argument.
+    ///
+    /// If the proof is absent sets `st0` to `false` and terminates the program.
+    #[display("ldx     {0},a8{1},{2}")]
+    LdX(AuxProofType, Reg16, RegS),
+
     /// Verify sum of pedersen commitments from inputs and outputs.
     ///
     /// The only argument specifies owned state type for the sum operation. If
@@ -192,13 +223,18 @@ impl<S: ContractStateAccess> InstructionSet for ContractOp<S> {
             ContractOp::LdP(_, reg, _)
             | ContractOp::LdF(_, reg, _)
             | ContractOp::LdS(_, reg, _) => bset![Reg::A(RegA::A16, (*reg).into())],
-            ContractOp::LdG(_, reg, _) => bset![Reg::A(RegA::A8, (*reg).into())],
-            ContractOp::LdC(_, reg, _) => bset![Reg::A(RegA::A32, (*reg).into())],
+            ContractOp::LdG(_, reg, _) | ContractOp::LdX(_, reg, _) => {
+                bset![Reg::A(RegA::A8, (*reg).into())]
+            }
+            ContractOp::LdC(_, reg, _) | ContractOp::NxC(_, reg, _) => {
+                bset![Reg::A(RegA::A32, (*reg).into())]
+            }
 
             ContractOp::CnP(_, _)
             | ContractOp::CnS(_, _)
             | ContractOp::CnG(_, _)
             | ContractOp::CnC(_, _)
+            | ContractOp::CnX(_, _)
             | ContractOp::LdM(_, _) => bset![],
             ContractOp::Pcvs(_) => bset![],
             ContractOp::Pcas(_) | ContractOp::Pcps(_) => bset![Reg::A(RegA::A64, Reg32::Reg0)],
@@ -208,7 +244,7 @@ impl<S: ContractStateAccess> InstructionSet for ContractOp<S> {
 
     fn dst_regs(&self) -> BTreeSet<Reg> {
         match self {
-            ContractOp::CnG(_, reg) => {
+            ContractOp::CnG(_, reg) | ContractOp::CnX(_, reg) => {
                 bset![Reg::A(RegA::A8, *reg)]
             }
             ContractOp::CnP(_, reg) | ContractOp::CnS(_, reg) | ContractOp::CnC(_, reg) => {
@@ -217,10 +253,14 @@ impl<S: ContractStateAccess> InstructionSet for ContractOp<S> {
             ContractOp::LdF(_, _, reg) => {
                 bset![Reg::A(RegA::A64, (*reg).into())]
             }
+            ContractOp::NxC(_, reg_a, reg_s) => {
+                bset![Reg::A(RegA::A32, (*reg_a).into()), Reg::S(*reg_s)]
+            }
             ContractOp::LdG(_, _, reg)
             | ContractOp::LdS(_, _, reg)
             | ContractOp::LdP(_, _, reg)
             | ContractOp::LdC(_, _, reg)
+            | ContractOp::LdX(_, _, reg)
             | ContractOp::LdM(_, reg) => {
                 bset![Reg::S(*reg)]
             }
@@ -236,12 +276,15 @@ impl<S: ContractStateAccess> InstructionSet for ContractOp<S> {
             ContractOp::CnP(_, _)
             | ContractOp::CnS(_, _)
             | ContractOp::CnG(_, _)
-            | ContractOp::CnC(_, _) => 2,
+            | ContractOp::CnC(_, _)
+            | ContractOp::CnX(_, _) => 2,
             ContractOp::LdP(_, _, _)
             | ContractOp::LdS(_, _, _)
             | ContractOp::LdF(_, _, _)
             | ContractOp::LdG(_, _, _)
-            | ContractOp::LdC(_, _, _) => 8,
+            | ContractOp::LdC(_, _, _)
+            | ContractOp::NxC(_, _, _)
+            | ContractOp::LdX(_, _, _) => 8,
             ContractOp::LdM(_, _) => 6,
             ContractOp::Pcvs(_) => 1024,
             ContractOp::Pcas(_) | ContractOp::Pcps(_) => 512,
@@ -409,12 +452,57 @@ impl<S: ContractStateAccess> InstructionSet for ContractOp<S> {
                 };
                 regs.set_s(*reg_s, Some(state.borrow().as_inner()));
             }
+            ContractOp::NxC(state_type, reg_32, reg_s) => {
+                let state = RefCell::borrow(&context.contract_state);
+                let Ok(mut global) = state.global(*state_type) else {
+                    fail!()
+                };
+                let Some(depth) = *regs.get_n(RegA::A32, *reg_32) else {
+                    fail!()
+                };
+                let depth: u32 = depth.into();
+                let Ok(depth) = u24::try_from(depth) else {
+                    fail!()
+                };
+                let next = global.nth(depth).map(|state| ByteStr::from(state.borrow().as_inner()));
+                if next.is_some() {
+                    regs.set_n(RegA::A32, *reg_32, Some(depth.to_u32() + 1));
+                }
+                regs.set_s(*reg_s, next);
+            }
             ContractOp::LdM(type_id, reg) => {
                 let Some(meta) = context.op_info.metadata.get(type_id) else {
                     fail!()
                 };
                 regs.set_s(*reg, Some(meta.to_inner()));
             }
+            ContractOp::CnX(proof_type, reg) => {
+                regs.set_n(
+                    RegA::A8,
+                    *reg,
+                    context
+                        .op_info
+                        .aux_proofs
+                        .get(proof_type)
+                        .map(|p| p.len() as u8),
+                );
+            }
+            ContractOp::LdX(proof_type, reg_8, reg_s) => {
+                let Some(reg_8) = *regs.get_n(RegA::A8, *reg_8) else {
+                    fail!()
+                };
+                let index: u8 = reg_8.into();
+
+                let Some(proof) = context
+                    .op_info
+                    .aux_proofs
+                    .get(proof_type)
+                    .and_then(|p| p.get(index as usize))
+                else {
+                    fail!()
+                };
+                regs.set_s(*reg_s, Some(proof.as_inner()));
+            }
 
             ContractOp::Pcvs(state_type) => {
                 let inputs = load_inputs!(state_type);
@@ -495,7 +583,10 @@ impl<S: ContractStateAccess> Bytecode for ContractOp<S> {
             ContractOp::LdP(_, _, _) => INSTR_LDP,
             ContractOp::LdF(_, _, _) => INSTR_LDF,
             ContractOp::LdC(_, _, _) => INSTR_LDC,
+            ContractOp::NxC(_, _, _) => INSTR_NXC,
             ContractOp::LdM(_, _) => INSTR_LDM,
+            ContractOp::CnX(_, _) => INSTR_CNX,
+            ContractOp::LdX(_, _, _) => INSTR_LDX,
 
             ContractOp::Pcvs(_) => INSTR_PCVS,
             ContractOp::Pcas(_) => INSTR_PCAS,
@@ -553,11 +644,26 @@ impl<S: ContractStateAccess> Bytecode for ContractOp<S> {
                 writer.write_u4(reg_a)?;
                 writer.write_u4(reg_s)?;
             }
+            ContractOp::NxC(state_type, reg_a, reg_s) => {
+                writer.write_u16(*state_type)?;
+                writer.write_u4(reg_a)?;
+                writer.write_u4(reg_s)?;
+            }
             ContractOp::LdM(state_type, reg) => {
                 writer.write_u16(*state_type)?;
                 writer.write_u4(reg)?;
                 writer.write_u4(u4::ZERO)?;
             }
+            ContractOp::CnX(proof_type, reg) => {
+                writer.write_u16(*proof_type)?;
+                writer.write_u5(reg)?;
+                writer.write_u3(u3::ZERO)?;
+            }
+            ContractOp::LdX(proof_type, reg_a, reg_s) => {
+                writer.write_u16(*proof_type)?;
+                writer.write_u4(reg_a)?;
+                writer.write_u4(reg_s)?;
+            }
 
             ContractOp::Pcvs(state_type) => writer.write_u16(*state_type)?,
             ContractOp::Pcas(owned_type) => writer.write_u16(*owned_type)?,
@@ -620,11 +726,26 @@ impl<S: ContractStateAccess> Bytecode for ContractOp<S> {
                 reader.read_u4()?.into(),
                 reader.read_u4()?.into(),
             ),
+            INSTR_NXC => Self::NxC(
+                reader.read_u16()?.into(),
+                reader.read_u4()?.into(),
+                reader.read_u4()?.into(),
+            ),
             INSTR_LDM => {
                 let i = Self::LdM(reader.read_u16()?.into(), reader.read_u4()?.into());
                 reader.read_u4()?; // Discard garbage bits
                 i
             }
+            INSTR_CNX => {
+                let i = Self::CnX(reader.read_u16()?.into(), reader.read_u5()?.into());
+                reader.read_u3()?; // Discard garbage bits
+                i
+            }
+            INSTR_LDX => Self::LdX(
+                reader.read_u16()?.into(),
+                reader.read_u4()?.into(),
+                reader.read_u4()?.into(),
+            ),
 
             INSTR_PCVS => Self::Pcvs(reader.read_u16()?.into()),
             INSTR_PCAS => Self::Pcas(reader.read_u16()?.into()),